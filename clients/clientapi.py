@@ -2,7 +2,7 @@
 from fastapi import FastAPI, Depends, HTTPException, status, Header, Body, Path, Form, Query, \
     security, BackgroundTasks
 from fastapi.security import APIKeyHeader, HTTPBasic, HTTPBasicCredentials
-from fastapi.responses import PlainTextResponse, JSONResponse, Response, FileResponse
+from fastapi.responses import PlainTextResponse, JSONResponse, Response, FileResponse, StreamingResponse
 from fastapi.middleware.cors import CORSMiddleware
 from starlette.concurrency import run_in_threadpool
 import smtplib
@@ -23,6 +23,7 @@ from fastapi.middleware.gzip import GZipMiddleware
 from starlette.middleware.sessions import SessionMiddleware
 from starlette.requests import Request
 import secrets
+import hashlib
 from pydantic import BaseModel, Field, HttpUrl
 from typing import Dict
 from typing import List
@@ -47,6 +48,7 @@ sys.path.append('/pinepods')
 
 import database_functions.functions
 import database_functions.auth_functions
+from database_functions.app_functions import feed_auth_headers, split_feed_credentials
 
 database_type = str(os.getenv('DB_TYPE', 'mariadb'))
 if database_type == "postgresql":
@@ -79,6 +81,27 @@ origins = [
 #     allow_headers=["*"],
 # )
 
+# Lets clients revalidate JSON lists with If-None-Match instead of downloading them again.
+# Registered before GZip so the tag is computed on the uncompressed body.
+@app.middleware("http")
+async def add_etag(request: Request, call_next):
+    response = await call_next(request)
+    if (request.method != "GET" or response.status_code != 200
+            or not request.url.path.startswith("/api/data/")
+            or not response.headers.get("content-type", "").startswith("application/json")):
+        return response
+
+    body = b"".join([chunk async for chunk in response.body_iterator])
+    etag = 'W/"' + hashlib.md5(body).hexdigest() + '"'
+    # The response depends on the API key, so shared caches must not keep it
+    cache_headers = {"ETag": etag, "Cache-Control": "private, no-cache"}
+    if etag in request.headers.get("if-none-match", ""):
+        return Response(status_code=304, headers=cache_headers)
+
+    headers = dict(response.headers)
+    headers.update(cache_headers)
+    return Response(content=body, status_code=200, headers=headers)
+
 app.add_middleware(GZipMiddleware, minimum_size=1000)
 app.add_middleware(SessionMiddleware, secret_key=secret_key_middle)
 
@@ -468,7 +491,8 @@ async def api_verify_password(data: VerifyPasswordInput, cnx=Depends(get_databas
 
 @app.get("/api/data/return_episodes/{user_id}")
 async def api_return_episodes(user_id: int, cnx=Depends(get_database_connection),
-                              api_key: str = Depends(get_api_key_from_header)):
+                              api_key: str = Depends(get_api_key_from_header),
+                              limit: Optional[int] = Query(None, ge=1), offset: int = Query(0, ge=0)):
     is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
     if not is_valid_key:
         raise HTTPException(status_code=403,
@@ -481,7 +505,7 @@ async def api_return_episodes(user_id: int, cnx=Depends(get_database_connection)
 
     # Allow the action if the API key belongs to the user, or it's the web API key
     if key_id == user_id or is_web_key:
-        episodes = database_functions.functions.return_episodes(database_type, cnx, user_id)
+        episodes = database_functions.functions.return_episodes(database_type, cnx, user_id, limit, offset)
         if episodes is None:
             episodes = []  # Return an empty list instead of raising an exception
         return {"episodes": episodes}
@@ -491,12 +515,21 @@ async def api_return_episodes(user_id: int, cnx=Depends(get_database_connection)
 
 
 @app.get("/api/data/podcast_episodes")
-async def api_podcast_episodes(cnx=Depends(get_database_connection), api_key: str = Depends(get_api_key_from_header), user_id: int = Query(...), podcast_id: int = Query(...)):
+async def api_podcast_episodes(cnx=Depends(get_database_connection), api_key: str = Depends(get_api_key_from_header), user_id: int = Query(...), podcast_id: int = Query(...),
+                               limit: Optional[int] = Query(None, ge=1), offset: int = Query(0, ge=0),
+                               sort: str = Query('newest'), season: Optional[int] = Query(None),
+                               episode_type: Optional[str] = Query(None), unplayed: bool = Query(False),
+                               downloaded: bool = Query(False)):
     is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
     if not is_valid_key:
         raise HTTPException(status_code=403,
                             detail="Your API key is either invalid or does not have correct permission")
 
+    if sort not in database_functions.functions.PODCAST_EPISODE_ORDERS:
+        raise HTTPException(status_code=400, detail=f"Unknown sort: {sort}")
+    if episode_type not in (None, 'full', 'trailer', 'bonus'):
+        raise HTTPException(status_code=400, detail=f"Unknown episode type: {episode_type}")
+
     # Check if the provided API key is the web key
     is_web_key = api_key == base_webkey.web_key
 
@@ -504,7 +537,8 @@ async def api_podcast_episodes(cnx=Depends(get_database_connection), api_key: st
 
     # Allow the action if the API key belongs to the user, or it's the web API key
     if key_id == user_id or is_web_key:
-        episodes = database_functions.functions.return_podcast_episodes(database_type, cnx, user_id, podcast_id)
+        episodes = database_functions.functions.return_podcast_episodes(database_type, cnx, user_id, podcast_id, limit, offset,
+                                                                        sort, season, episode_type, unplayed, downloaded)
         if episodes is None:
             episodes = []  # Return an empty list instead of raising an exception
         # logging.error(f"Episodes returned: {episodes}")
@@ -514,6 +548,27 @@ async def api_podcast_episodes(cnx=Depends(get_database_connection), api_key: st
                             detail="You can only return episodes of your own!")
 
 
+@app.get("/api/data/podcast_seasons")
+async def api_podcast_seasons(cnx=Depends(get_database_connection), api_key: str = Depends(get_api_key_from_header),
+                              user_id: int = Query(...), podcast_id: int = Query(...)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user, or it's the web API key
+    if key_id == user_id or is_web_key:
+        return database_functions.functions.get_podcast_seasons(database_type, cnx, user_id, podcast_id)
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only return episodes of your own!")
+
+
 
 @app.get("/api/data/get_podcast_id")
 async def api_podcast_id(cnx=Depends(get_database_connection),
@@ -617,16 +672,36 @@ class PodcastFeedData(BaseModel):
 
 @app.get("/api/data/fetch_podcast_feed")
 async def fetch_podcast_feed(podcast_feed: str = Query(...), cnx=Depends(get_database_connection),
-                             api_key: str = Depends(get_api_key_from_header)):
+                             api_key: str = Depends(get_api_key_from_header),
+                             feed_username: Optional[str] = Header(None, alias="Feed-Username"),
+                             feed_password: Optional[str] = Header(None, alias="Feed-Password"),
+                             feed_header_name: Optional[str] = Header(None, alias="Feed-Header-Name"),
+                             feed_header_value: Optional[str] = Header(None, alias="Feed-Header-Value")):
     is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
     if not is_valid_key:
         raise HTTPException(status_code=403, detail="Invalid API key or insufficient permissions")
 
+    # Feeds being previewed before they're added bring their own credentials, in headers or the
+    # URL. Feeds the user added with credentials are fetched with the stored ones.
+    podcast_feed, url_auth = split_feed_credentials(podcast_feed)
+    feed_auth = {
+        'username': feed_username,
+        'password': feed_password,
+        'header_name': feed_header_name,
+        'header_value': feed_header_value,
+    }
+    if url_auth and not feed_username:
+        feed_auth.update(url_auth)
+    if not any(feed_auth.values()):
+        key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+        feed_auth = database_functions.functions.get_feed_auth_for_url(cnx, database_type, key_id, podcast_feed) if key_id else None
+
     # Fetch the podcast feed data using httpx
     async with httpx.AsyncClient(follow_redirects=True) as client:
-        response = await client.get(podcast_feed)
+        response = await client.get(podcast_feed, headers=feed_auth_headers(feed_auth))
         response.raise_for_status()  # Will raise an httpx.HTTPStatusError for 4XX/5XX responses
-        return Response(content=response.content, media_type="application/xml")
+        # The feed may be Atom or JSON Feed, keep whatever type the publisher sent
+        return Response(content=response.content, media_type=response.headers.get("content-type", "application/xml"))
 
 
 @app.post("/api/data/check_episode_playback")
@@ -742,17 +817,21 @@ async def api_add_podcast(podcast_values: PodcastValuesModel,
 
     # Allow the action if the API key belongs to the user or it's the web API key
     if key_id == podcast_values.user_id or is_web_key:
+        # Feeds imported or pasted with user:password@ in the URL keep the credentials encrypted
+        # with the podcast, not in the URL
+        podcast_dict = podcast_values.dict()
+        podcast_dict['pod_feed_url'], podcast_dict['feed_auth'] = split_feed_credentials(podcast_values.pod_feed_url)
         # Check if user has nextcloud enabled and add to subscription list
         if database_functions.functions.check_gpodder_settings(database_type, cnx, podcast_values.user_id):
             gpodder_url, gpodder_token, gpodder_login = database_functions.functions.get_nextcloud_settings(database_type, cnx, podcast_values.user_id)
-            print(f"Adding podcast to Nextcloud: {gpodder_url}, {gpodder_login}, {gpodder_token}, {podcast_values.pod_feed_url}")
+            print(f"Adding podcast to Nextcloud: {gpodder_url}, {gpodder_login}, {gpodder_token}, {podcast_dict['pod_feed_url']}")
             gpod_type = database_functions.functions.get_gpodder_type(cnx, database_type, podcast_values.user_id)
             print(f"Type of podsync {gpod_type}")
             if gpod_type == "nextcloud":
-                database_functions.functions.add_podcast_to_nextcloud(cnx, database_type, gpodder_url, gpodder_login, gpodder_token, podcast_values.pod_feed_url)
+                database_functions.functions.add_podcast_to_nextcloud(cnx, database_type, gpodder_url, gpodder_login, gpodder_token, podcast_dict['pod_feed_url'])
             else:
-                database_functions.functions.add_podcast_to_opodsync(cnx, database_type, gpodder_url, gpodder_login, gpodder_token, podcast_values.pod_feed_url, "pinepods")
-        result = database_functions.functions.add_podcast(cnx, database_type, podcast_values.dict(), podcast_values.user_id)
+                database_functions.functions.add_podcast_to_opodsync(cnx, database_type, gpodder_url, gpodder_login, gpodder_token, podcast_dict['pod_feed_url'], "pinepods")
+        result = database_functions.functions.add_podcast(cnx, database_type, podcast_dict, podcast_values.user_id)
         if result:
             return {"success": True}
         else:
@@ -807,6 +886,34 @@ async def api_increment_listen_time(user_id: int, cnx=Depends(get_database_conne
                             detail="You can only increment your own listen time.")
 
 
+class TimeSavedRequest(BaseModel):
+    seconds: int
+
+
+@app.put("/api/data/increment_time_saved/{user_id}")
+async def api_increment_time_saved(user_id: int, data: TimeSavedRequest, cnx=Depends(get_database_connection),
+                                   api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user, or it's the web API key
+    if key_id == user_id or is_web_key:
+        if data.seconds <= 0:
+            raise HTTPException(status_code=400, detail="Time saved must be positive.")
+        database_functions.functions.increment_time_saved(cnx, database_type, user_id, data.seconds)
+        return {"detail": "Time saved incremented."}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only increment your own time saved.")
+
+
 @app.put("/api/data/increment_played/{user_id}")
 async def api_increment_played(user_id: int, cnx=Depends(get_database_connection),
                                api_key: str = Depends(get_api_key_from_header)):
@@ -1125,6 +1232,65 @@ async def api_get_auto_skip_times(data: AutoSkipTimesRequest, cnx=Depends(get_da
     return AutoSkipTimesResponse(start_skip=start_skip, end_skip=end_skip)
 
 
+class PlaybackProfileRequest(BaseModel):
+    podcast_id: int
+    user_id: int
+    playback_speed: float = 1.0
+    volume_gain: float = 1.0
+    skip_forward: int = 15
+    skip_backward: int = 15
+    smart_speed: bool = False
+
+@app.post("/api/data/adjust_playback_profile")
+async def api_adjust_playback_profile(data: PlaybackProfileRequest, cnx=Depends(get_database_connection),
+                                      api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403, detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    if key_id == data.user_id or is_web_key:
+        if not 0.5 <= data.playback_speed <= 2.0 or not 0.0 < data.volume_gain <= 3.0:
+            raise HTTPException(status_code=400, detail="Playback speed or volume gain is out of range.")
+        if data.skip_forward <= 0 or data.skip_backward <= 0:
+            raise HTTPException(status_code=400, detail="Skip steps must be positive.")
+        database_functions.functions.adjust_playback_profile(cnx, database_type, data.podcast_id, data.user_id,
+                                                             data.playback_speed, data.volume_gain,
+                                                             data.skip_forward, data.skip_backward,
+                                                             data.smart_speed)
+        return {"detail": "Playback profile updated."}
+    else:
+        raise HTTPException(status_code=403, detail="You can only modify your own podcasts.")
+
+class PlaybackProfileResponse(BaseModel):
+    playback_speed: float
+    volume_gain: float
+    skip_forward: int
+    skip_backward: int
+    smart_speed: bool
+
+@app.post("/api/data/get_playback_profile")
+async def api_get_playback_profile(data: AutoSkipTimesRequest, cnx=Depends(get_database_connection),
+                                   api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403, detail="Your API key is either invalid or does not have correct permission")
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+    if key_id != data.user_id:
+        raise HTTPException(status_code=403, detail="You can only get the playback profile for your own podcast.")
+
+    profile = database_functions.functions.get_playback_profile(cnx, database_type, data.podcast_id, data.user_id)
+    if profile is None:
+        raise HTTPException(status_code=404, detail="Podcast not found")
+
+    return PlaybackProfileResponse(**profile)
+
+
 class SaveEpisodeData(BaseModel):
     episode_id: int
     user_id: int
@@ -1181,6 +1347,73 @@ async def api_remove_saved_episode(data: RemoveSavedEpisodeData, cnx=Depends(get
                             detail="Your API key is either invalid or does not have correct permission")
 
 
+class AddBookmarkData(BaseModel):
+    episode_id: int
+    user_id: int
+    timestamp: int
+    note: Optional[str] = None
+
+
+@app.post("/api/data/add_bookmark")
+async def api_add_bookmark(data: AddBookmarkData, cnx=Depends(get_database_connection),
+                           api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403, detail="Your API key is either invalid or does not have correct permission")
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+    if key_id != data.user_id:
+        raise HTTPException(status_code=403, detail="You can only add bookmarks for yourself!")
+    if data.timestamp < 0:
+        raise HTTPException(status_code=400, detail="Bookmark timestamp can't be negative.")
+
+    note = data.note.strip() if data.note else None
+    bookmark_id = database_functions.functions.add_bookmark(cnx, database_type, data.user_id, data.episode_id,
+                                                            data.timestamp, note or None)
+    return {"detail": "Bookmark added.", "bookmark_id": bookmark_id}
+
+
+class GetBookmarksData(BaseModel):
+    episode_id: int
+    user_id: int
+
+
+@app.post("/api/data/get_bookmarks")
+async def api_get_bookmarks(data: GetBookmarksData, cnx=Depends(get_database_connection),
+                            api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403, detail="Your API key is either invalid or does not have correct permission")
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+    if key_id != data.user_id:
+        raise HTTPException(status_code=403, detail="You can only view your own bookmarks!")
+
+    bookmarks = database_functions.functions.get_bookmarks(cnx, database_type, data.user_id, data.episode_id)
+    return {"bookmarks": bookmarks}
+
+
+class DeleteBookmarkData(BaseModel):
+    bookmark_id: int
+    user_id: int
+
+
+@app.post("/api/data/delete_bookmark")
+async def api_delete_bookmark(data: DeleteBookmarkData, cnx=Depends(get_database_connection),
+                              api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403, detail="Your API key is either invalid or does not have correct permission")
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+    if key_id != data.user_id:
+        raise HTTPException(status_code=403, detail="You can only remove your own bookmarks!")
+
+    if not database_functions.functions.delete_bookmark(cnx, database_type, data.user_id, data.bookmark_id):
+        raise HTTPException(status_code=404, detail="Bookmark not found")
+    return {"detail": "Bookmark removed."}
+
+
 class RecordListenDurationData(BaseModel):
     episode_id: int
     user_id: int
@@ -1420,7 +1653,8 @@ async def api_return_pods(user_id: int, cnx=Depends(get_database_connection),
 
 @app.get("/api/data/user_history/{user_id}")
 async def api_user_history(user_id: int, cnx=Depends(get_database_connection),
-                           api_key: str = Depends(get_api_key_from_header)):
+                           api_key: str = Depends(get_api_key_from_header),
+                           limit: Optional[int] = Query(None, ge=1), offset: int = Query(0, ge=0)):
     is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
     if not is_valid_key:
         raise HTTPException(status_code=403,
@@ -1433,7 +1667,7 @@ async def api_user_history(user_id: int, cnx=Depends(get_database_connection),
 
     # Allow the action if the API key belongs to the user or it's the web API key
     if key_id == user_id or is_web_key:
-        history = database_functions.functions.user_history(cnx, database_type, user_id)
+        history = database_functions.functions.user_history(cnx, database_type, user_id, limit, offset)
         return {"data": history}
     else:
         raise HTTPException(status_code=403,
@@ -1686,6 +1920,162 @@ async def api_set_theme(user_id: int = Body(...), new_theme: str = Body(...), cn
                             detail="You can only set your own theme!")
 
 
+@app.get("/api/data/get_keybindings/{user_id}")
+async def api_get_keybindings(user_id: int, cnx=Depends(get_database_connection),
+                              api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user, or it's the web API key
+    if key_id == user_id or is_web_key:
+        keybindings = database_functions.functions.get_keybindings(cnx, database_type, user_id)
+        return {"keybindings": keybindings}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only get your own keyboard shortcuts!")
+
+
+@app.put("/api/data/user/set_keybindings")
+async def api_set_keybindings(user_id: int = Body(...), keybindings: Dict[str, str] = Body(...),
+                              cnx=Depends(get_database_connection),
+                              api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        database_functions.functions.set_keybindings(cnx, database_type, user_id, keybindings)
+        return {"message": "Keyboard shortcuts updated successfully"}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only set your own keyboard shortcuts!")
+
+
+class EqBand(BaseModel):
+    frequency: float
+    gain: float
+    q: float
+
+
+class AudioProcessingSettings(BaseModel):
+    enabled: bool = False
+    compressor: bool = True
+    loudness_target: Optional[float] = None
+    eq_preset: str = "flat"
+    eq_bands: List[EqBand] = []
+
+
+@app.get("/api/data/get_audio_processing/{user_id}")
+async def api_get_audio_processing(user_id: int, cnx=Depends(get_database_connection),
+                                   api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user, or it's the web API key
+    if key_id == user_id or is_web_key:
+        audio_processing = database_functions.functions.get_audio_processing(cnx, database_type, user_id)
+        return {"audio_processing": audio_processing}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only get your own audio processing settings!")
+
+
+@app.put("/api/data/user/set_audio_processing")
+async def api_set_audio_processing(user_id: int = Body(...), audio_processing: AudioProcessingSettings = Body(...),
+                                   cnx=Depends(get_database_connection),
+                                   api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        if audio_processing.loudness_target is not None and not -31.0 <= audio_processing.loudness_target <= -10.0:
+            raise HTTPException(status_code=400, detail="Loudness target is out of range.")
+        database_functions.functions.set_audio_processing(cnx, database_type, user_id, audio_processing.dict())
+        return {"message": "Audio processing settings updated successfully"}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only set your own audio processing settings!")
+
+
+class QueueSettings(BaseModel):
+    auto_advance: bool = True
+    remove_completed: bool = True
+
+
+@app.get("/api/data/get_queue_settings/{user_id}")
+async def api_get_queue_settings(user_id: int, cnx=Depends(get_database_connection),
+                                 api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user, or it's the web API key
+    if key_id == user_id or is_web_key:
+        return database_functions.functions.get_queue_settings(cnx, database_type, user_id)
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only get your own queue settings!")
+
+
+@app.put("/api/data/user/set_queue_settings")
+async def api_set_queue_settings(user_id: int = Body(...), queue_settings: QueueSettings = Body(...),
+                                 cnx=Depends(get_database_connection),
+                                 api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == user_id or is_web_key:
+        database_functions.functions.set_queue_settings(cnx, database_type, user_id, queue_settings.auto_advance,
+                                                        queue_settings.remove_completed)
+        return {"message": "Queue settings updated successfully"}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only set your own queue settings!")
+
+
 @app.get("/api/data/user/check_downloaded")
 async def api_check_downloaded(user_id: int, title: str, url: str, cnx=Depends(get_database_connection),
                                api_key: str = Depends(get_api_key_from_header)):
@@ -2544,6 +2934,38 @@ async def remove_queued_pod(data: QueueRmData, cnx=Depends(get_database_connecti
                             detail="You can only remove episodes for your own queue!")
 
 
+class QueuePositionData(BaseModel):
+    episode_id: int
+    user_id: int
+    position: Optional[int] = None
+    after_episode_id: Optional[int] = None
+
+
+@app.post("/api/data/queue_pod_at")
+async def queue_pod_at(data: QueuePositionData, cnx=Depends(get_database_connection),
+                       api_key: str = Depends(get_api_key_from_header)):
+    is_valid_key = database_functions.functions.verify_api_key(cnx, database_type, api_key)
+    if not is_valid_key:
+        raise HTTPException(status_code=403,
+                            detail="Your API key is either invalid or does not have correct permission")
+
+    # Check if the provided API key is the web key
+    is_web_key = api_key == base_webkey.web_key
+
+    key_id = database_functions.functions.id_from_api_key(cnx, database_type, api_key)
+
+    # Allow the action if the API key belongs to the user or it's the web API key
+    if key_id == data.user_id or is_web_key:
+        if data.position is not None and data.position < 1:
+            raise HTTPException(status_code=400, detail="Queue positions start at 1.")
+        position = database_functions.functions.queue_pod_at(database_type, cnx, data.episode_id, data.user_id,
+                                                             data.position, data.after_episode_id)
+        return {"data": "Episode queued", "position": position}
+    else:
+        raise HTTPException(status_code=403,
+                            detail="You can only add episodes to your own queue!")
+
+
 # class QueuedEpisodesData(BaseModel):
 #     user_id: int
 
@@ -2869,18 +3291,31 @@ def refresh_nextcloud_subscription_for_user(database_type, user_id, gpodder_url,
         else:
             cnx.close()
 
-def check_valid_feed(feed_url: str):
+def check_valid_feed(feed_url: str, feed_auth=None):
     import feedparser
-    parsed_feed = feedparser.parse(feed_url)
+    parsed_feed = feedparser.parse(feed_url, request_headers=feed_auth_headers(feed_auth))
+    if parsed_feed.get('status') == 401:
+        raise ValueError("The feed needs a username and password or an auth header.")
     if not parsed_feed.get('version'):
         raise ValueError("Invalid podcast feed URL or content.")
-    if not ('title' in parsed_feed.feed and 'link' in parsed_feed.feed and 'description' in parsed_feed.feed):
-        raise ValueError("Feed missing required attributes: title, link, or description.")
+    # Atom and JSON Feed only require a title, the link and description are RSS requirements
+    if parsed_feed.version.startswith(('atom', 'json')):
+        required = ('title',)
+    else:
+        required = ('title', 'link', 'description')
+    missing = [attr for attr in required if attr not in parsed_feed.feed]
+    if missing:
+        raise ValueError(f"Feed missing required attributes: {', '.join(missing)}.")
     return parsed_feed
 
 class CustomPodcast(BaseModel):
     feed_url: str
     user_id: int
+    # Private feeds, either HTTP basic auth or a header like Authorization: Bearer ...
+    username: Optional[str] = None
+    password: Optional[str] = None
+    header_name: Optional[str] = None
+    header_value: Optional[str] = None
 
 @app.post("/api/data/add_custom_podcast")
 async def queue_bump(data: CustomPodcast, cnx=Depends(get_database_connection),
@@ -2897,15 +3332,28 @@ async def queue_bump(data: CustomPodcast, cnx=Depends(get_database_connection),
 
     # Allow the action if the API key belongs to the user or it's the web API key
     if key_id == data.user_id or is_web_key:
+        # Credentials typed into the URL are stored with the others rather than in the URL
+        feed_url, url_auth = split_feed_credentials(data.feed_url)
+        feed_auth = {
+            'username': data.username,
+            'password': data.password,
+            'header_name': data.header_name,
+            'header_value': data.header_value,
+        }
+        if url_auth and not data.username:
+            feed_auth.update(url_auth)
+        if not any(feed_auth.values()):
+            feed_auth = None
+
         try:
-            parsed_feed = check_valid_feed(data.feed_url)
+            parsed_feed = check_valid_feed(feed_url, feed_auth)
         except ValueError as e:
             logger.error(f"Failed to parse: {str(e)}")
             raise HTTPException(status_code=400, detail=str(e))
 
         # Assuming the rest of the code processes the podcast correctly
         try:
-            result = database_functions.functions.add_custom_podcast(database_type, cnx, data.feed_url, data.user_id)
+            result = database_functions.functions.add_custom_podcast(database_type, cnx, feed_url, data.user_id, feed_auth)
             return {"data": result}
         except Exception as e:
             logger.error(f"Failed to process the podcast: {str(e)}")
@@ -2946,6 +3394,7 @@ async def queue_bump(data: QueueBump, cnx=Depends(get_database_connection),
 @app.get("/api/data/stream/{episode_id}")
 async def stream_episode(
     episode_id: int,
+    request: Request,
     cnx=Depends(get_database_connection),
     api_key: str = Query(..., alias='api_key'),  # Change here
     user_id: int = Query(..., alias='user_id')   # Change here
@@ -2964,12 +3413,41 @@ async def stream_episode(
         print(file_path)
         if file_path:
             return FileResponse(path=file_path, media_type='audio/mpeg', filename=os.path.basename(file_path))
-        else:
-            raise HTTPException(status_code=404, detail="Episode not found or not downloaded")
+        # A player can't send a private feed's credentials, so its episodes play through here
+        private_episode = database_functions.functions.get_episode_feed_auth(cnx, database_type, episode_id, user_id)
+        if private_episode:
+            episode_url, feed_auth = private_episode
+            return await proxy_feed_media(episode_url, feed_auth, request.headers.get('range'))
+        raise HTTPException(status_code=404, detail="Episode not found or not downloaded")
     else:
         raise HTTPException(status_code=403, detail="You do not have permission to access this episode")
 
 
+async def proxy_feed_media(url, feed_auth, range_header):
+    headers = feed_auth_headers(feed_auth)
+    # Pass seeking through to the host
+    if range_header:
+        headers['Range'] = range_header
+    client = httpx.AsyncClient(follow_redirects=True, timeout=None)
+    upstream = await client.send(client.build_request("GET", url, headers=headers), stream=True)
+    if upstream.status_code >= 400:
+        await upstream.aclose()
+        await client.aclose()
+        raise HTTPException(status_code=502, detail=f"The feed's host answered {upstream.status_code}")
+
+    async def body():
+        try:
+            async for chunk in upstream.aiter_bytes():
+                yield chunk
+        finally:
+            await upstream.aclose()
+            await client.aclose()
+
+    passthrough = ('content-type', 'content-length', 'content-range', 'accept-ranges')
+    response_headers = {name: upstream.headers[name] for name in passthrough if name in upstream.headers}
+    return StreamingResponse(body(), status_code=upstream.status_code, headers=response_headers)
+
+
 class BackupUser(BaseModel):
     user_id: int
 
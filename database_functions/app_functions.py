@@ -108,16 +108,364 @@ def sync_episode_actions(nextcloud_url, headers):
     # Implement fetching and creating episode actions
     # Similar to the sync_subscriptions method
 
-def get_podcast_values(feed_url, user_id):
+# Query parameters private feeds commonly carry their access token in
+SECRET_QUERY_PARAMS = {'token', 'auth', 'key', 'api_key', 'apikey', 'access_token', 'secret',
+                       'password', 'pass', 'sig', 'signature'}
+
+
+def feed_auth_headers(feed_auth):
+    """
+    The request headers for a private feed. feed_auth is a dict with optional username,
+    password, header_name and header_value keys, holding plain text.
+    """
+    import base64
+    headers = {}
+    if not feed_auth:
+        return headers
+    if feed_auth.get('username'):
+        credentials = f"{feed_auth['username']}:{feed_auth.get('password') or ''}"
+        headers['Authorization'] = 'Basic ' + base64.b64encode(credentials.encode()).decode()
+    if feed_auth.get('header_name') and feed_auth.get('header_value'):
+        headers[feed_auth['header_name']] = feed_auth['header_value']
+    return headers
+
+
+def split_feed_credentials(feed_url):
+    """
+    Moves a user:password@ prefix out of a feed URL, returns the clean URL and the credentials
+    as a feed_auth dict, or None if the URL had none.
+    """
+    from urllib.parse import urlsplit, urlunsplit, unquote
+    parts = urlsplit(feed_url)
+    if not parts.username:
+        return feed_url, None
+    netloc = parts.hostname or ''
+    if parts.port:
+        netloc += f":{parts.port}"
+    feed_auth = {'username': unquote(parts.username), 'password': unquote(parts.password or '')}
+    return urlunsplit((parts.scheme, netloc, parts.path, parts.query, parts.fragment)), feed_auth
+
+
+def redact_feed_url(feed_url):
+    # For exports, drops any user:password@ prefix and blanks out token query parameters
+    from urllib.parse import urlsplit, urlunsplit, parse_qsl, urlencode
+    feed_url, _ = split_feed_credentials(feed_url)
+    parts = urlsplit(feed_url)
+    if not parts.query:
+        return feed_url
+    query = [(name, 'REDACTED' if name.lower() in SECRET_QUERY_PARAMS else value)
+             for name, value in parse_qsl(parts.query, keep_blank_values=True)]
+    return urlunsplit((parts.scheme, parts.netloc, parts.path, urlencode(query), parts.fragment))
+
+
+def feed_artwork(feed):
+    # RSS and Atom images come as a dict with an href, a JSON Feed icon is just the URL
+    image = feed.get('image')
+    if isinstance(image, str):
+        return image
+    if image and image.get('href'):
+        return image['href']
+    return feed.get('logo') or feed.get('icon')
+
+
+def entry_description(entry):
+    # Atom and JSON Feed entries can have full content and no summary
+    content = entry.get('content')
+    if content:
+        return content[0].get('value', '')
+    return entry.get('summary', '')
+
+
+def entry_published(entry):
+    # Atom entries only have to say when they were last updated
+    return entry.get('published') or entry.get('updated')
+
+
+DURATION_UNITS = {
+    'h': 3600, 'hr': 3600, 'hrs': 3600, 'hour': 3600, 'hours': 3600,
+    'm': 60, 'min': 60, 'mins': 60, 'minute': 60, 'minutes': 60,
+    's': 1, 'sec': 1, 'secs': 1, 'second': 1, 'seconds': 1,
+}
+
+# Assumed bitrates for estimating a duration from the enclosure size, podcasts are nearly
+# always constant bitrate. Video varies too much to guess.
+ENCLOSURE_KBPS = {
+    'audio/mpeg': 128, 'audio/mp3': 128, 'audio/mpeg3': 128, 'audio/x-mpeg': 128, 'audio/x-mp3': 128,
+    'audio/aac': 96, 'audio/mp4': 96, 'audio/m4a': 96, 'audio/x-m4a': 96, 'audio/aacp': 96,
+    'audio/ogg': 64, 'audio/opus': 64, 'audio/webm': 64,
+}
+
+
+def parse_feed_duration(value):
+    # Same rules as parse_duration in the web client: HH:MM:SS with overflowing parts and
+    # fractional seconds, plain seconds, ISO 8601 (PT1H2M3S) and spelled out units (45 min)
+    import re
+    if value is None:
+        return None
+    value = str(value).strip().lower()
+    if not value:
+        return None
+    if re.fullmatch(r'(\d+\s*:\s*){0,2}\d+([.,]\d+)?', value):
+        seconds = 0.0
+        for part in value.replace(',', '.').split(':'):
+            seconds = seconds * 60 + float(part)
+        return int(seconds)
+    number = r'(\d+(?:[.,]\d+)?)'
+    iso = re.fullmatch(rf'p(?:{number}w)?(?:{number}d)?(?:t(?:{number}h)?(?:{number}m)?(?:{number}s)?)?', value)
+    if iso and any(iso.groups()):
+        weeks, days, hours, minutes, seconds = (float((group or '0').replace(',', '.')) for group in iso.groups())
+        return int(weeks * 604800 + days * 86400 + hours * 3600 + minutes * 60 + seconds)
+    if re.fullmatch(r'((and\s+)?\d+(\.\d+)?\s*[a-z]+[\s,]*)+', value):
+        parts = re.findall(r'(\d+(?:\.\d+)?)\s*([a-z]+)', value)
+        if all(unit in DURATION_UNITS or unit == 'and' for _, unit in parts):
+            return int(sum(float(amount) * DURATION_UNITS[unit] for amount, unit in parts if unit != 'and'))
+    return None
+
+
+def entry_enclosure_length(entry):
+    # The size in bytes the feed gives for the episode's media, None when it's missing or junk
+    enclosures = entry.get('enclosures') or []
+    try:
+        length = int(str(enclosures[0].get('length') or 0).strip()) if enclosures else 0
+    except ValueError:
+        return None
+    return length if length > 0 else None
+
+
+def entry_enclosure_type(entry):
+    # The MIME type the feed gives for the episode's media without parameters, None when missing
+    enclosures = entry.get('enclosures') or []
+    media_type = (enclosures[0].get('type') or '').split(';')[0].strip().lower() if enclosures else ''
+    return media_type[:255] or None
+
+
+def entry_chapters_url(entry):
+    # The <podcast:chapters> link, feedparser keeps the attributes of tags it doesn't know
+    chapters = entry.get('podcast_chapters')
+    url = chapters.get('url') if isinstance(chapters, dict) else None
+    return url.strip() if url and url.strip() else None
+
+
+def entry_season(entry):
+    # itunes:season, or the Podcasting 2.0 podcast:season
+    for key in ('itunes_season', 'podcast_season'):
+        try:
+            season = int(str(entry.get(key) or '').strip())
+        except ValueError:
+            continue
+        if season >= 0:
+            return season
+    return None
+
+
+def entry_episode_number(entry):
+    # itunes:episode is a whole number, podcast:episode can be 10.5 for an episode in between
+    for key in ('itunes_episode', 'podcast_episode'):
+        try:
+            number = float(str(entry.get(key) or '').strip())
+        except ValueError:
+            continue
+        if number >= 0:
+            return number
+    return None
+
+
+def entry_episode_type(entry):
+    # itunes:episodeType, anything but the three the spec allows counts as a full episode
+    episode_type = (entry.get('itunes_episodetype') or '').strip().lower()
+    return episode_type if episode_type in ('full', 'trailer', 'bonus') else None
+
+
+def read_feed(feed_url, feed_auth=None):
+    """
+    Fetches and parses a feed, returns feedparser's result and the document itself. feedparser
+    loses most of the Podcasting 2.0 tags, feed_extras reads them from the document.
+    """
+    import feedparser
+    import requests
+    headers = {'User-Agent': feedparser.USER_AGENT, **feed_auth_headers(feed_auth)}
+    try:
+        response = requests.get(feed_url, headers=headers, timeout=60)
+        response.raise_for_status()
+    except requests.RequestException as e:
+        print(f"Error fetching feed {redact_feed_url(feed_url)}: {e}")
+        return feedparser.parse(b''), b''
+    # The final URL after redirects, relative links in the feed resolve against it
+    response_headers = {'content-location': response.url,
+                        'content-type': response.headers.get('content-type', '')}
+    return feedparser.parse(response.content, response_headers=response_headers), response.content
+
+
+PODCAST_NAMESPACES = ('https://podcastindex.org/namespace/1.0', 'http://podcastindex.org/namespace/1.0')
+ITUNES_NAMESPACE = 'http://www.itunes.com/dtds/podcast-1.0.dtd'
+
+
+def _podcast_tags(element, name):
+    # Every <podcast:NAME> directly under the element, whatever prefix the feed declared
+    tags = {f'{{{namespace}}}{name}' for namespace in PODCAST_NAMESPACES}
+    return [child for child in element if child.tag in tags]
+
+
+def _podcast_tag(element, name):
+    tags = _podcast_tags(element, name)
+    return tags[0] if tags else None
+
+
+def _text(element):
+    text = (element.text or '').strip() if element is not None else ''
+    return text or None
+
+
+def _attr(element, name):
+    value = (element.get(name) or '').strip()
+    return value or None
+
+
+def _number(value, kind):
+    # None for anything that isn't a number, the web client reads these as unsigned
+    try:
+        number = kind(value) if value is not None else None
+    except ValueError:
+        return None
+    return number if number is not None and number >= 0 else None
+
+
+def _is_yes(element):
+    return (_text(element) or '').lower() == 'yes'
+
+
+def _persons(element):
+    # "host" and "cast" when the feed doesn't say, same as the web client
+    return [{'name': _text(person), 'role': _attr(person, 'role') or 'host',
+             'group': _attr(person, 'group') or 'cast', 'img': _attr(person, 'img'),
+             'href': _attr(person, 'href')}
+            for person in _podcast_tags(element, 'person') if _text(person)]
+
+
+def _location(element):
+    location = _podcast_tag(element, 'location')
+    if _text(location) is None:
+        return None
+    return {'name': _text(location), 'geo': _attr(location, 'geo'), 'osm': _attr(location, 'osm')}
+
+
+def _value(element):
+    value = _podcast_tag(element, 'value')
+    if value is None or not _attr(value, 'type'):
+        return None
+    recipients = [{'name': _attr(recipient, 'name'), 'kind': _attr(recipient, 'type') or '',
+                   'address': _attr(recipient, 'address'),
+                   'split': _number(_attr(recipient, 'split'), int) or 0,
+                   'fee': (_attr(recipient, 'fee') or '').lower() == 'true'}
+                  for recipient in _podcast_tags(value, 'valueRecipient') if _attr(recipient, 'address')]
+    return {'kind': _attr(value, 'type'), 'method': _attr(value, 'method') or '',
+            'suggested': _attr(value, 'suggested'), 'recipients': recipients}
+
+
+def _channel_extras(channel):
+    locked = _podcast_tag(channel, 'locked')
+    trailers = [{'title': _text(trailer) or 'Trailer', 'url': _attr(trailer, 'url'),
+                 'pub_date': _attr(trailer, 'pubdate'), 'length': _number(_attr(trailer, 'length'), int),
+                 'mime_type': _attr(trailer, 'type'), 'season': _number(_attr(trailer, 'season'), int)}
+                for trailer in _podcast_tags(channel, 'trailer') if _attr(trailer, 'url')]
+    return {
+        'itunes_type': (_text(channel.find(f'{{{ITUNES_NAMESPACE}}}type')) or '').lower() or None,
+        'locked': _is_yes(locked),
+        'locked_owner': _attr(locked, 'owner') if locked is not None else None,
+        'persons': _persons(channel),
+        'funding': [{'url': _attr(funding, 'url'), 'label': _text(funding)}
+                    for funding in _podcast_tags(channel, 'funding') if _attr(funding, 'url')],
+        'trailers': trailers,
+        'location': _location(channel),
+        'value': _value(channel),
+    }
+
+
+def _item_extras(item):
+    season = _podcast_tag(item, 'season')
+    episode = _podcast_tag(item, 'episode')
+    soundbites = []
+    for soundbite in _podcast_tags(item, 'soundbite'):
+        start = _number(_attr(soundbite, 'startTime'), float)
+        duration = _number(_attr(soundbite, 'duration'), float)
+        if start is not None and duration is not None:
+            soundbites.append({'start_time': start, 'duration': duration, 'title': _text(soundbite)})
+    extras = {
+        'transcripts': [{'url': _attr(transcript, 'url'), 'mime_type': _attr(transcript, 'type') or '',
+                         'language': _attr(transcript, 'language'), 'rel': _attr(transcript, 'rel')}
+                        for transcript in _podcast_tags(item, 'transcript') if _attr(transcript, 'url')],
+        'persons': _persons(item),
+        'season_name': _attr(season, 'name') if season is not None else None,
+        'episode_display': _attr(episode, 'display') if episode is not None else None,
+        'block': _is_yes(item.find(f'{{{ITUNES_NAMESPACE}}}block')),
+        'location': _location(item),
+        'soundbites': soundbites,
+        'value': _value(item),
+    }
+    # Most items have none of it, they store nothing
+    return extras if any(extras.values()) else None
+
+
+def entry_feed_extras(entry, items):
+    # The entry's Podcasting 2.0 details from feed_extras as JSON, None when it has none
+    import json
+    enclosures = entry.get('enclosures') or []
+    extras = items.get(enclosures[0].get('href')) if enclosures else None
+    return json.dumps(extras) if extras else None
+
+
+def feed_extras(content):
+    """
+    The Podcasting 2.0 details of a feed document that the web client shows, the channel's as a
+    dict and each item's keyed by its enclosure URL. Both are empty when the document isn't RSS.
+    """
+    import xml.etree.ElementTree as ET
+    try:
+        channel = ET.fromstring(content).find('channel')
+    except ET.ParseError:
+        channel = None
+    if channel is None:
+        return {}, {}
+    items = {}
+    for item in channel.findall('item'):
+        enclosure = item.find('enclosure')
+        url = _attr(enclosure, 'url') if enclosure is not None else None
+        if url and url not in items:
+            items[url] = _item_extras(item)
+    return _channel_extras(channel), items
+
+
+def entry_duration(entry):
+    # The feed's duration when it has a usable one, otherwise an estimate from the enclosure
+    # size. Zero means unknown, the player fills it in from the media.
+    for key in ('itunes_duration', 'duration'):
+        seconds = parse_feed_duration(entry.get(key))
+        if seconds:
+            return seconds
+    enclosures = entry.get('enclosures') or []
+    if not enclosures:
+        return 0
+    try:
+        length = int(str(enclosures[0].get('length') or 0).strip())
+    except ValueError:
+        return 0
+    kbps = ENCLOSURE_KBPS.get((enclosures[0].get('type') or 'audio/mpeg').strip().lower())
+    # Lengths like 0 or 1 are placeholders
+    if not kbps or length < 100_000:
+        return 0
+    return length * 8 // (kbps * 1000)
+
+
+def get_podcast_values(feed_url, user_id, feed_auth=None):
     import feedparser
     import json
     # Parse the feed
-    d = feedparser.parse(feed_url)
+    d = feedparser.parse(feed_url, request_headers=feed_auth_headers(feed_auth))
 
     # Initialize podcast_values as a dictionary
     podcast_values = {
         'pod_title': d.feed.title if hasattr(d.feed, 'title') else None,
-        'pod_artwork': d.feed.image.href if hasattr(d.feed, 'image') and hasattr(d.feed.image, 'href') else None,
+        'pod_artwork': feed_artwork(d.feed),
         'pod_author': d.feed.author if hasattr(d.feed, 'author') else None,
         'categories': [],
         'pod_description': d.feed.description if hasattr(d.feed, 'description') else None,
@@ -125,7 +473,8 @@ def get_podcast_values(feed_url, user_id):
         'pod_feed_url': feed_url,
         'pod_website': d.feed.link if hasattr(d.feed, 'link') else None,
         'pod_explicit': False,
-        'user_id': user_id
+        'user_id': user_id,
+        'feed_auth': feed_auth
     }
 
     if not podcast_values['pod_artwork'] and hasattr(d.feed, 'itunes_image'):
@@ -161,13 +510,13 @@ def get_podcast_values(feed_url, user_id):
 
 
 
-def check_valid_feed(feed_url: str):
+def check_valid_feed(feed_url: str, feed_auth=None):
     import feedparser
     """
     Check if the provided URL points to a valid podcast feed.
     Raises ValueError if the feed is invalid.
     """
-    parsed_feed = feedparser.parse(feed_url)
+    parsed_feed = feedparser.parse(feed_url, request_headers=feed_auth_headers(feed_auth))
 
     # Check for basic RSS or Atom feed structure
     if not parsed_feed.get('version'):
@@ -175,8 +524,14 @@ def check_valid_feed(feed_url: str):
         raise ValueError("Invalid podcast feed URL or content.")
 
     # Check for essential elements in the feed
-    if not ('title' in parsed_feed.feed and 'link' in parsed_feed.feed and 'description' in parsed_feed.feed):
-        raise ValueError("Feed missing required attributes: title, link, or description.")
+    # Atom and JSON Feed only require a title, the link and description are RSS requirements
+    if parsed_feed.version.startswith(('atom', 'json')):
+        required = ('title',)
+    else:
+        required = ('title', 'link', 'description')
+    missing = [attr for attr in required if attr not in parsed_feed.feed]
+    if missing:
+        raise ValueError(f"Feed missing required attributes: {', '.join(missing)}.")
 
     # If it passes the above checks, it's likely a valid feed
     return parsed_feed
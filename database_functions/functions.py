@@ -10,6 +10,8 @@ import datetime
 import time
 import appdirs
 import base64
+import html
+import json
 import subprocess
 import psycopg
 from psycopg.rows import dict_row
@@ -21,7 +23,7 @@ from mysql.connector import ProgrammingError
 # app_root = os.environ.get('APP_ROOT')
 sys.path.append('/pinepods/'),
 # Import the functions directly from app_functions.py located in the database_functions directory
-from database_functions.app_functions import sync_subscription_change, get_podcast_values, check_valid_feed, sync_subscription_change_gpodder
+from database_functions.app_functions import sync_subscription_change, get_podcast_values, check_valid_feed, sync_subscription_change_gpodder, entry_description, entry_published, entry_duration, entry_enclosure_length, entry_enclosure_type, entry_chapters_url, entry_season, entry_episode_number, entry_episode_type, entry_feed_extras, feed_extras, read_feed, feed_auth_headers, redact_feed_url
 
 
 def pascal_case(snake_str):
@@ -99,9 +101,9 @@ def get_web_key(cnx, database_type):
     else:
         return None
 
-def add_custom_podcast(database_type, cnx, feed_url, user_id):
+def add_custom_podcast(database_type, cnx, feed_url, user_id, feed_auth=None):
     # Proceed to extract and use podcast details if the feed is valid
-    podcast_values = get_podcast_values(feed_url, user_id)
+    podcast_values = get_podcast_values(feed_url, user_id, feed_auth)
     print("Adding podcast custom")
 
     try:
@@ -165,15 +167,17 @@ def add_podcast(cnx, database_type, podcast_values, user_id):
         if database_type == "postgresql":
             add_podcast_query = """
                 INSERT INTO "Podcasts"
-                (PodcastName, ArtworkURL, Author, Categories, Description, EpisodeCount, FeedURL, WebsiteURL, Explicit, UserID)
-                VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s) RETURNING PodcastID
+                (PodcastName, ArtworkURL, Author, Categories, Description, EpisodeCount, FeedURL, WebsiteURL, Explicit, UserID,
+                 FeedUsername, FeedPassword, FeedHeaderName, FeedHeaderValue)
+                VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s) RETURNING PodcastID
             """
             explicit = podcast_values['pod_explicit']
         else:  # MySQL or MariaDB
             add_podcast_query = """
                 INSERT INTO Podcasts
-                (PodcastName, ArtworkURL, Author, Categories, Description, EpisodeCount, FeedURL, WebsiteURL, Explicit, UserID)
-                VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s)
+                (PodcastName, ArtworkURL, Author, Categories, Description, EpisodeCount, FeedURL, WebsiteURL, Explicit, UserID,
+                 FeedUsername, FeedPassword, FeedHeaderName, FeedHeaderValue)
+                VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s)
             """
             explicit = 1 if podcast_values['pod_explicit'] else 0
 
@@ -188,6 +192,8 @@ def add_podcast(cnx, database_type, podcast_values, user_id):
         print(podcast_values['pod_website'])
         print(explicit)
         print(user_id)
+        # Only custom feeds come with credentials
+        feed_auth_values = encrypt_feed_auth(cnx, database_type, podcast_values.get('feed_auth'))
         try:
             cursor.execute(add_podcast_query, (
                 podcast_values['pod_title'],
@@ -199,7 +205,8 @@ def add_podcast(cnx, database_type, podcast_values, user_id):
                 podcast_values['pod_feed_url'],
                 podcast_values['pod_website'],
                 explicit,
-                user_id
+                user_id,
+                *feed_auth_values
             ))
 
             if database_type == "postgresql":
@@ -386,76 +393,35 @@ def get_first_episode_id(cnx, database_type, podcast_id, user_id):
 
 def add_episodes(cnx, database_type, podcast_id, feed_url, artwork_url, auto_download):
     import datetime
-    import feedparser
     import dateutil.parser
     import re
 
-    episode_dump = feedparser.parse(feed_url)
+    episode_dump, content = read_feed(feed_url, get_feed_auth(cnx, database_type, podcast_id))
+    channel_extras, item_extras = feed_extras(content)
 
     cursor = cnx.cursor()
-
-    def parse_duration(duration_string: str) -> int:
-        # First, check if duration is in seconds (no colons)
-        if ':' not in duration_string:
-            try:
-                # Directly return seconds if no colon is found
-                return int(duration_string)
-            except ValueError:
-                print(f'Error parsing duration from pure seconds: {duration_string}')
-                return 0  # Return 0 or some default value in case of error
-        else:
-            # Handle HH:MM:SS format
-            parts = duration_string.split(':')
-            if len(parts) == 1:
-                # If there's only one part, it's in seconds
-                return int(parts[0])
-            else:
-                while len(parts) < 3:
-                    parts.insert(0, '0')  # Prepend zeros if any parts are missing (ensuring HH:MM:SS format)
-                h, m, s = map(int, parts)
-                return h * 3600 + m * 60 + s
-
-
+    store_podcast_extras(cursor, database_type, podcast_id, channel_extras)
 
     for entry in episode_dump.entries:
-        # Check necessary fields are present
-        if not all(hasattr(entry, attr) for attr in ["title", "summary", "enclosures"]):
+        # Check necessary fields are present, Atom and JSON Feed entries may not have a summary
+        if not all(hasattr(entry, attr) for attr in ["title", "enclosures"]):
             continue
 
         # Extract necessary information
         parsed_title = entry.title
-        parsed_description = entry.get('content', [{}])[0].get('value', entry.summary)
+        parsed_description = entry_description(entry)
         parsed_audio_url = entry.enclosures[0].href if entry.enclosures else ""
-        parsed_release_datetime = dateutil.parser.parse(entry.published).strftime("%Y-%m-%d %H:%M:%S")
+        published = entry_published(entry)
+        release_datetime = dateutil.parser.parse(published) if published else datetime.datetime.now()
+        parsed_release_datetime = release_datetime.strftime("%Y-%m-%d %H:%M:%S")
 
         # Artwork prioritizing episode-specific artwork, then falling back to the feed's artwork if necessary
         parsed_artwork_url = (entry.get('itunes_image', {}).get('href') or
                             getattr(entry, 'image', {}).get('href') or
                             artwork_url)
 
-        # Duration parsing
-        parsed_duration = 0
-        duration_str = getattr(entry, 'itunes_duration', '')
-        if ':' in duration_str:
-            # If duration contains ":", then process as HH:MM:SS or MM:SS
-            time_parts = list(map(int, duration_str.split(':')))
-            while len(time_parts) < 3:
-                time_parts.insert(0, 0)  # Pad missing values with zeros
-            h, m, s = time_parts
-            parsed_duration = h * 3600 + m * 60 + s
-        elif duration_str.isdigit():
-            # If duration is all digits (no ":"), treat as seconds directly
-            parsed_duration = int(duration_str)
-        elif hasattr(entry, 'itunes_duration_seconds'):
-            # Additional format as fallback, if explicitly provided as seconds
-            parsed_duration = int(entry.itunes_duration_seconds)
-        elif hasattr(entry, 'duration'):
-            # Other specified duration formats (assume they are in correct format or seconds)
-            parsed_duration = parse_duration(entry.duration)
-        elif hasattr(entry, 'length'):
-            # If duration not specified but length is, use length (assuming it's in seconds)
-            parsed_duration = int(entry.length)
-
+        # Falls back to an estimate from the enclosure size, see entry_duration
+        parsed_duration = entry_duration(entry)
 
         # Check for existing episode
         if database_type == "postgresql":
@@ -465,23 +431,24 @@ def add_episodes(cnx, database_type, podcast_id, feed_url, artwork_url, auto_dow
 
         cursor.execute(episode_check_query, (podcast_id, parsed_title))
         if cursor.fetchone():
+            backfill_episode_fields(cursor, database_type, podcast_id, parsed_title, entry, entry_feed_extras(entry, item_extras))
             continue  # Episode already exists
         print("inserting now")
         # Insert the new episode
         if database_type == "postgresql":
             episode_insert_query = """
                 INSERT INTO "Episodes"
-                (PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration)
-                VALUES (%s, %s, %s, %s, %s, %s, %s)
+                (PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration, EnclosureLength, EnclosureType, ChaptersURL, Season, EpisodeNumber, EpisodeType, FeedExtras)
+                VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s)
             """
         else:  # MySQL or MariaDB
             episode_insert_query = """
                 INSERT INTO Episodes
-                (PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration)
-                VALUES (%s, %s, %s, %s, %s, %s, %s)
+                (PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration, EnclosureLength, EnclosureType, ChaptersURL, Season, EpisodeNumber, EpisodeType, FeedExtras)
+                VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s)
             """
 
-        cursor.execute(episode_insert_query, (podcast_id, parsed_title, parsed_description, parsed_audio_url, parsed_artwork_url, parsed_release_datetime, parsed_duration))
+        cursor.execute(episode_insert_query, (podcast_id, parsed_title, parsed_description, parsed_audio_url, parsed_artwork_url, parsed_release_datetime, parsed_duration, entry_enclosure_length(entry), entry_enclosure_type(entry), entry_chapters_url(entry), entry_season(entry), entry_episode_number(entry), entry_episode_type(entry), entry_feed_extras(entry, item_extras)))
         print('episodes inserted')
         # Get the EpisodeID for the newly added episode
         if cursor.rowcount > 0:
@@ -495,6 +462,43 @@ def add_episodes(cnx, database_type, podcast_id, feed_url, artwork_url, auto_dow
     cnx.commit()
 
 
+def backfill_episode_fields(cursor, database_type, podcast_id, title, entry, extras):
+    # Episodes added before these were stored pick them up on the next refresh, values already
+    # there are kept. The Podcasting 2.0 details follow the feed instead, transcripts and people
+    # are often added after an episode is out.
+    fields = {
+        'ChaptersURL': entry_chapters_url(entry),
+        'Season': entry_season(entry),
+        'EpisodeNumber': entry_episode_number(entry),
+        'EpisodeType': entry_episode_type(entry),
+    }
+    fields = {column: value for column, value in fields.items() if value is not None}
+    assignments = [f"{column} = COALESCE({column}, %s)" for column in fields]
+    changed = [f"{column} IS NULL" for column in fields]
+    params = list(fields.values())
+    changed_params = []
+    if extras is not None:
+        assignments.append("FeedExtras = %s")
+        changed.append("FeedExtras IS NULL OR FeedExtras <> %s")
+        params.append(extras)
+        changed_params.append(extras)
+    if not assignments:
+        return
+    table = '"Episodes"' if database_type == "postgresql" else "Episodes"
+    query = (f"UPDATE {table} SET {', '.join(assignments)} "
+             f"WHERE PodcastID = %s AND EpisodeTitle = %s AND ({' OR '.join(changed)})")
+    cursor.execute(query, (*params, podcast_id, title, *changed_params))
+
+
+def store_podcast_extras(cursor, database_type, podcast_id, extras):
+    # Replaced on every refresh, a feed that couldn't be read keeps what was there
+    import json
+    if not extras:
+        return
+    table = '"Podcasts"' if database_type == "postgresql" else "Podcasts"
+    cursor.execute(f"UPDATE {table} SET FeedExtras = %s WHERE PodcastID = %s", (json.dumps(extras), podcast_id))
+
+
 def remove_podcast(cnx, database_type, podcast_name, podcast_url, user_id):
     cursor = cnx.cursor()
     print('got to remove')
@@ -609,7 +613,14 @@ def remove_podcast_id(cnx, database_type, podcast_id, user_id):
     finally:
         cursor.close()
 
-def return_episodes(database_type, cnx, user_id):
+# Appends LIMIT/OFFSET when a page was asked for. Works the same on PostgreSQL and MySQL.
+def paginate(query, params, limit, offset):
+    if limit is None:
+        return query, params
+    return query + " LIMIT %s OFFSET %s", params + (limit, offset)
+
+
+def return_episodes(database_type, cnx, user_id, limit=None, offset=0):
     if database_type == "postgresql":
         cnx.row_factory = dict_row
         cursor = cnx.cursor()
@@ -626,7 +637,7 @@ def return_episodes(database_type, cnx, user_id):
             'LEFT JOIN "UserEpisodeHistory" ON "Episodes".EpisodeID = "UserEpisodeHistory".EpisodeID AND "UserEpisodeHistory".UserID = %s '
             'WHERE "Episodes".EpisodePubDate >= NOW() - INTERVAL \'30 days\' '
             'AND "Podcasts".UserID = %s '
-            'ORDER BY "Episodes".EpisodePubDate DESC'
+            'ORDER BY "Episodes".EpisodePubDate DESC, "Episodes".EpisodeID DESC'
         )
     else:  # MySQL or MariaDB
         query = (
@@ -638,10 +649,11 @@ def return_episodes(database_type, cnx, user_id):
             "LEFT JOIN UserEpisodeHistory ON Episodes.EpisodeID = UserEpisodeHistory.EpisodeID AND UserEpisodeHistory.UserID = %s "
             "WHERE Episodes.EpisodePubDate >= DATE_SUB(NOW(), INTERVAL 30 DAY) "
             "AND Podcasts.UserID = %s "
-            "ORDER BY Episodes.EpisodePubDate DESC"
+            "ORDER BY Episodes.EpisodePubDate DESC, Episodes.EpisodeID DESC"
         )
 
-    cursor.execute(query, (user_id, user_id))
+    query, params = paginate(query, (user_id, user_id), limit, offset)
+    cursor.execute(query, params)
     rows = cursor.fetchall()
 
     cursor.close()
@@ -657,7 +669,18 @@ def return_episodes(database_type, cnx, user_id):
 
 
 
-def return_podcast_episodes(database_type, cnx, user_id, podcast_id):
+# How the podcast page can order an added podcast's episodes. Episodes without a date or duration
+# go last, ties keep the order they were added in.
+PODCAST_EPISODE_ORDERS = {
+    'newest': "{e}.EpisodePubDate DESC, {e}.EpisodeID DESC",
+    'oldest': "{e}.EpisodePubDate IS NULL, {e}.EpisodePubDate ASC, {e}.EpisodeNumber IS NULL, {e}.EpisodeNumber ASC, {e}.EpisodeID ASC",
+    'longest': "COALESCE({e}.EpisodeDuration, 0) DESC, {e}.EpisodeID DESC",
+    'shortest': "COALESCE({e}.EpisodeDuration, 0) = 0, {e}.EpisodeDuration ASC, {e}.EpisodeID ASC",
+}
+
+
+def return_podcast_episodes(database_type, cnx, user_id, podcast_id, limit=None, offset=0, sort='newest',
+                            season=None, episode_type=None, unplayed=False, downloaded=False):
     if database_type == "postgresql":
         cnx.row_factory = dict_row
         cursor = cnx.cursor()
@@ -665,34 +688,54 @@ def return_podcast_episodes(database_type, cnx, user_id, podcast_id):
         cursor = cnx.cursor(dictionary=True)
 
     if database_type == "postgresql":
-        query = (
-            'SELECT "Podcasts".PodcastID, "Podcasts".PodcastName, "Episodes".EpisodeID, '
-            '"Episodes".EpisodeTitle, "Episodes".EpisodePubDate, "Episodes".EpisodeDescription, '
-            '"Episodes".EpisodeArtwork, "Episodes".EpisodeURL, "Episodes".EpisodeDuration, '
-            '"UserEpisodeHistory".ListenDuration, CAST("Episodes".EpisodeID AS VARCHAR) AS guid '
-            'FROM "Episodes" '
-            'INNER JOIN "Podcasts" ON "Episodes".PodcastID = "Podcasts".PodcastID '
-            'LEFT JOIN "UserEpisodeHistory" ON "Episodes".EpisodeID = "UserEpisodeHistory".EpisodeID AND "UserEpisodeHistory".UserID = %s '
-            'WHERE "Podcasts".PodcastID = %s AND "Podcasts".UserID = %s '
-            'ORDER BY "Episodes".EpisodePubDate DESC'
-        )
+        episodes, podcasts, history, downloads = '"Episodes"', '"Podcasts"', '"UserEpisodeHistory"', '"DownloadedEpisodes"'
+        guid_type, not_completed = 'VARCHAR', 'FALSE'
     else:  # MySQL or MariaDB
-        query = (
-            "SELECT Podcasts.PodcastID, Podcasts.PodcastName, Episodes.EpisodeID, "
-            "Episodes.EpisodeTitle, Episodes.EpisodePubDate, Episodes.EpisodeDescription, "
-            "Episodes.EpisodeArtwork, Episodes.EpisodeURL, Episodes.EpisodeDuration, "
-            "UserEpisodeHistory.ListenDuration, CAST(Episodes.EpisodeID AS CHAR) AS guid "
-            "FROM Episodes "
-            "INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
-            "LEFT JOIN UserEpisodeHistory ON Episodes.EpisodeID = UserEpisodeHistory.EpisodeID AND UserEpisodeHistory.UserID = %s "
-            "WHERE Podcasts.PodcastID = %s AND Podcasts.UserID = %s "
-            "ORDER BY Episodes.EpisodePubDate DESC"
-        )
+        episodes, podcasts, history, downloads = 'Episodes', 'Podcasts', 'UserEpisodeHistory', 'DownloadedEpisodes'
+        guid_type, not_completed = 'CHAR', '0'
+    is_downloaded = (f'EXISTS (SELECT 1 FROM {downloads} WHERE {downloads}.EpisodeID = {episodes}.EpisodeID '
+                     f'AND {downloads}.UserID = %s)')
+
+    conditions = [f'{podcasts}.PodcastID = %s', f'{podcasts}.UserID = %s']
+    params = [user_id, user_id, podcast_id, user_id]
+    if season is not None:
+        conditions.append(f'{episodes}.Season = %s')
+        params.append(season)
+    # Episodes the feed gave no type are full episodes
+    if episode_type is not None:
+        conditions.append(f"COALESCE({episodes}.EpisodeType, 'full') = %s")
+        params.append(episode_type)
+    if unplayed:
+        conditions.append(f'COALESCE({episodes}.Completed, {not_completed}) = {not_completed}')
+    if downloaded:
+        conditions.append(is_downloaded)
+        params.append(user_id)
 
-    cursor.execute(query, (user_id, podcast_id, user_id))
+    query = (
+        f'SELECT {podcasts}.PodcastID, {podcasts}.PodcastName, {episodes}.EpisodeID, '
+        f'{episodes}.EpisodeTitle, {episodes}.EpisodePubDate, {episodes}.EpisodeDescription, '
+        f'{episodes}.EpisodeArtwork, {episodes}.EpisodeURL, {episodes}.EpisodeDuration, '
+        f'{history}.ListenDuration, CAST({episodes}.EpisodeID AS {guid_type}) AS guid, '
+        f'{episodes}.Completed, {episodes}.Season, {episodes}.EpisodeNumber, {episodes}.EpisodeType, '
+        f'{episodes}.FeedExtras, {is_downloaded} AS Downloaded '
+        f'FROM {episodes} '
+        f'INNER JOIN {podcasts} ON {episodes}.PodcastID = {podcasts}.PodcastID '
+        f'LEFT JOIN {history} ON {episodes}.EpisodeID = {history}.EpisodeID AND {history}.UserID = %s '
+        f'WHERE {" AND ".join(conditions)} '
+        f'ORDER BY {PODCAST_EPISODE_ORDERS[sort].format(e=episodes)}'
+    )
+
+    query, params = paginate(query, tuple(params), limit, offset)
+    cursor.execute(query, params)
     rows = cursor.fetchall()
     cursor.close()
 
+    # MySQL hands back 0/1 for both flags
+    for row in rows:
+        for key in list(row):
+            if key.lower() in ('completed', 'downloaded'):
+                row[key] = bool(row[key])
+
     logging.error(f"Raw rows before normalization: {rows}")
 
     # Normalize keys
@@ -704,6 +747,29 @@ def return_podcast_episodes(database_type, cnx, user_id, podcast_id):
 
     return rows or None
 
+
+def get_podcast_seasons(database_type, cnx, user_id, podcast_id):
+    # What the podcast page's tabs need, trailers and bonus episodes get tabs of their own
+    cursor = cnx.cursor()
+    table = '"Episodes"' if database_type == "postgresql" else "Episodes"
+    podcasts = '"Podcasts"' if database_type == "postgresql" else "Podcasts"
+    query = (
+        f"SELECT DISTINCT {table}.Season, COALESCE({table}.EpisodeType, 'full') FROM {table} "
+        f"INNER JOIN {podcasts} ON {table}.PodcastID = {podcasts}.PodcastID "
+        f"WHERE {podcasts}.PodcastID = %s AND {podcasts}.UserID = %s"
+    )
+    cursor.execute(query, (podcast_id, user_id))
+    rows = cursor.fetchall()
+    cursor.close()
+
+    rows = [tuple(row.values()) if isinstance(row, dict) else row for row in rows]
+    seasons = sorted({season for season, episode_type in rows if season is not None and episode_type == 'full'})
+    return {
+        'seasons': seasons,
+        'trailers': any(episode_type == 'trailer' for _, episode_type in rows),
+        'bonus': any(episode_type == 'bonus' for _, episode_type in rows),
+    }
+
 def get_podcast_details(database_type, cnx, user_id, podcast_id):
     if database_type == "postgresql":
         cnx.row_factory = dict_row
@@ -731,7 +797,7 @@ def get_podcast_details(database_type, cnx, user_id, podcast_id):
     lower_row = lowercase_keys(details)
     bool_fix = convert_bools(lower_row, database_type)
 
-    return bool_fix
+    return strip_feed_auth(bool_fix)
 
 
 def get_podcast_id(database_type, cnx, user_id, podcast_feed, podcast_name):
@@ -1075,7 +1141,9 @@ def refresh_single_pod(cnx, database_type, podcast_id):
     feed_url, artwork_url = cursor.fetchone()
 
     # parse the podcast feed
-    episode_dump = feedparser.parse(feed_url)
+    episode_dump, content = read_feed(feed_url, get_feed_auth(cnx, database_type, podcast_id))
+    channel_extras, item_extras = feed_extras(content)
+    store_podcast_extras(cursor, database_type, podcast_id, channel_extras)
 
     # get the list of episode titles already in the database
     if database_type == "postgresql":
@@ -1086,16 +1154,18 @@ def refresh_single_pod(cnx, database_type, podcast_id):
 
     # insert any new episodes into the database
     for entry in episode_dump.entries:
-        if hasattr(entry, "title") and hasattr(entry, "summary") and hasattr(entry, "enclosures"):
+        if hasattr(entry, "title") and hasattr(entry, "enclosures"):
             title = entry.title
 
             # skip episodes that are already in the database
             if title in existing_titles:
+                backfill_episode_fields(cursor, database_type, podcast_id, title, entry, entry_feed_extras(entry, item_extras))
                 continue
 
-            description = entry.summary
+            description = entry_description(entry)
             audio_url = entry.enclosures[0].href if entry.enclosures else ""
-            release_date = dateutil.parser.parse(entry.published).strftime("%Y-%m-%d")
+            published = entry_published(entry)
+            release_date = (dateutil.parser.parse(published) if published else datetime.datetime.now()).strftime("%Y-%m-%d")
 
             # get the URL of the episode artwork, or use the podcast image URL if not available
             artwork_url = entry.get('itunes_image', {}).get('href', None) or entry.get('image', {}).get('href',
@@ -1104,13 +1174,13 @@ def refresh_single_pod(cnx, database_type, podcast_id):
             # insert the episode into the database
             if database_type == "postgresql":
                 add_episode = ('INSERT INTO "Episodes" '
-                               '(PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration) '
-                               'VALUES (%s, %s, %s, %s, %s, %s, %s)')
+                               '(PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration, EnclosureLength, EnclosureType, ChaptersURL, Season, EpisodeNumber, EpisodeType, FeedExtras) '
+                               'VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s)')
             else:  # MySQL or MariaDB
                 add_episode = ("INSERT INTO Episodes "
-                               "(PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration) "
-                               "VALUES (%s, %s, %s, %s, %s, %s, %s)")
-            episode_values = (podcast_id, title, description, audio_url, artwork_url, release_date, 0)
+                               "(PodcastID, EpisodeTitle, EpisodeDescription, EpisodeURL, EpisodeArtwork, EpisodePubDate, EpisodeDuration, EnclosureLength, EnclosureType, ChaptersURL, Season, EpisodeNumber, EpisodeType, FeedExtras) "
+                               "VALUES (%s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s, %s)")
+            episode_values = (podcast_id, title, description, audio_url, artwork_url, release_date, entry_duration(entry), entry_enclosure_length(entry), entry_enclosure_type(entry), entry_chapters_url(entry), entry_season(entry), entry_episode_number(entry), entry_episode_type(entry), entry_feed_extras(entry, item_extras))
             cursor.execute(add_episode, episode_values)
 
     cnx.commit()
@@ -1275,7 +1345,7 @@ def get_user_details_id(cnx, database_type, user_id):
         return None
 
 
-def user_history(cnx, database_type, user_id):
+def user_history(cnx, database_type, user_id, limit=None, offset=0):
     if not cnx:
         logging.error("Database connection is None.")
         return []
@@ -1290,7 +1360,7 @@ def user_history(cnx, database_type, user_id):
                         'JOIN "Episodes" ON "UserEpisodeHistory".EpisodeID = "Episodes".EpisodeID '
                         'JOIN "Podcasts" ON "Episodes".PodcastID = "Podcasts".PodcastID '
                         'WHERE "UserEpisodeHistory".UserID = %s '
-                        'ORDER BY "UserEpisodeHistory".ListenDate DESC')
+                        'ORDER BY "UserEpisodeHistory".ListenDate DESC, "Episodes".EpisodeID DESC')
         else:  # MySQL or MariaDB
             cursor = cnx.cursor(dictionary=True)  # Ensure dictionary mode
             query = ("SELECT Episodes.EpisodeID, UserEpisodeHistory.ListenDate, UserEpisodeHistory.ListenDuration, "
@@ -1300,9 +1370,10 @@ def user_history(cnx, database_type, user_id):
                         "JOIN Episodes ON UserEpisodeHistory.EpisodeID = Episodes.EpisodeID "
                         "JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
                         "WHERE UserEpisodeHistory.UserID = %s "
-                        "ORDER BY UserEpisodeHistory.ListenDate DESC")
+                        "ORDER BY UserEpisodeHistory.ListenDate DESC, Episodes.EpisodeID DESC")
 
-        cursor.execute(query, (user_id,))
+        query, params = paginate(query, (user_id,), limit, offset)
+        cursor.execute(query, params)
         results = cursor.fetchall()
 
         if not results:
@@ -1403,7 +1474,9 @@ def download_podcast(cnx, database_type, episode_id, user_id):
     # Generate the episode filename based on episode ID and user ID
     filename = f"{user_id}-{episode_id}.mp3"
     file_path = os.path.join(download_dir, filename)
-    response = requests.get(episode_url, stream=True)
+    # Private feeds usually protect their media with the same credentials
+    feed_headers = feed_auth_headers(get_feed_auth(cnx, database_type, podcast_id))
+    response = requests.get(episode_url, stream=True, headers=feed_headers)
     response.raise_for_status()
     # Get the current date and time for DownloadedDate
     downloaded_date = datetime.datetime.now()
@@ -1617,6 +1690,67 @@ def get_auto_skip_times(cnx, database_type, podcast_id, user_id):
         cursor.close()
 
 
+def adjust_playback_profile(cnx, database_type, podcast_id, user_id, playback_speed, volume_gain, skip_forward, skip_backward, smart_speed):
+    cursor = cnx.cursor()
+    try:
+        if database_type == "postgresql":
+            query = """
+                UPDATE "Podcasts"
+                SET PlaybackSpeed = %s, VolumeGain = %s, SkipForward = %s, SkipBackward = %s, SmartSpeed = %s
+                WHERE PodcastID = %s AND UserID = %s
+            """
+        else:  # MySQL or MariaDB
+            query = """
+                UPDATE Podcasts
+                SET PlaybackSpeed = %s, VolumeGain = %s, SkipForward = %s, SkipBackward = %s, SmartSpeed = %s
+                WHERE PodcastID = %s AND UserID = %s
+            """
+        cursor.execute(query, (playback_speed, volume_gain, skip_forward, skip_backward, smart_speed, podcast_id, user_id))
+        cnx.commit()
+    except Exception as e:
+        cnx.rollback()
+        raise e
+    finally:
+        cursor.close()
+
+def get_playback_profile(cnx, database_type, podcast_id, user_id):
+    cursor = cnx.cursor()
+    try:
+        if database_type == "postgresql":
+            query = """
+                SELECT PlaybackSpeed, VolumeGain, SkipForward, SkipBackward, SmartSpeed
+                FROM "Podcasts"
+                WHERE PodcastID = %s AND UserID = %s
+            """
+        else:  # MySQL or MariaDB
+            query = """
+                SELECT PlaybackSpeed, VolumeGain, SkipForward, SkipBackward, SmartSpeed
+                FROM Podcasts
+                WHERE PodcastID = %s AND UserID = %s
+            """
+        cursor.execute(query, (podcast_id, user_id))
+        result = cursor.fetchone()
+
+        if not result:
+            return None
+        if isinstance(result, dict):
+            values = (result.get("playbackspeed"), result.get("volumegain"),
+                      result.get("skipforward"), result.get("skipbackward"), result.get("smartspeed"))
+        else:
+            values = tuple(result[:5])
+        playback_speed, volume_gain, skip_forward, skip_backward, smart_speed = values
+        # Podcasts added before profiles existed have NULLs until they are edited
+        return {
+            "playback_speed": float(playback_speed) if playback_speed is not None else 1.0,
+            "volume_gain": float(volume_gain) if volume_gain is not None else 1.0,
+            "skip_forward": skip_forward if skip_forward is not None else 15,
+            "skip_backward": skip_backward if skip_backward is not None else 15,
+            "smart_speed": bool(smart_speed),
+        }
+    finally:
+        cursor.close()
+
+
 def check_downloaded(cnx, database_type, user_id, episode_id):
     cursor = None
     try:
@@ -1811,6 +1945,103 @@ def get_encryption_key(cnx, database_type):
     # Convert the bytearray to a base64 encoded string before returning.
     return base64.b64encode(result_dict['encryptionkey']).decode()
 
+# The Podcasts columns holding a private feed's credentials, these never go back to a client
+FEED_AUTH_COLUMNS = ('feedusername', 'feedpassword', 'feedheadername', 'feedheadervalue')
+
+
+def feed_auth_cipher(cnx, database_type):
+    from cryptography.fernet import Fernet
+    return Fernet(base64.b64decode(get_encryption_key(cnx, database_type)))
+
+
+def encrypt_feed_auth(cnx, database_type, feed_auth):
+    # The column values for a feed_auth dict, with the password and header value encrypted
+    if not feed_auth:
+        return (None, None, None, None)
+    cipher_suite = feed_auth_cipher(cnx, database_type)
+
+    def encrypt(value):
+        return cipher_suite.encrypt(value.encode()).decode() if value else None
+
+    return (feed_auth.get('username') or None, encrypt(feed_auth.get('password')),
+            feed_auth.get('header_name') or None, encrypt(feed_auth.get('header_value')))
+
+
+def get_feed_auth(cnx, database_type, podcast_id):
+    # A private feed's credentials as a feed_auth dict, None for public feeds
+    cursor = cnx.cursor()
+    if database_type == "postgresql":
+        query = 'SELECT FeedUsername, FeedPassword, FeedHeaderName, FeedHeaderValue FROM "Podcasts" WHERE PodcastID = %s'
+    else:  # MySQL or MariaDB
+        query = "SELECT FeedUsername, FeedPassword, FeedHeaderName, FeedHeaderValue FROM Podcasts WHERE PodcastID = %s"
+    cursor.execute(query, (podcast_id,))
+    row = cursor.fetchone()
+    cursor.close()
+    if not row:
+        return None
+    username, password, header_name, header_value = list(row.values()) if isinstance(row, dict) else row
+    if not username and not header_name:
+        return None
+    cipher_suite = feed_auth_cipher(cnx, database_type)
+
+    def decrypt(value):
+        return cipher_suite.decrypt(value.encode()).decode() if value else None
+
+    return {
+        'username': username,
+        'password': decrypt(password),
+        'header_name': header_name,
+        'header_value': decrypt(header_value),
+    }
+
+
+def get_feed_auth_for_url(cnx, database_type, user_id, feed_url):
+    # The credentials a user stored for a feed, None if they haven't added it or it's public
+    cursor = cnx.cursor()
+    if database_type == "postgresql":
+        query = 'SELECT PodcastID FROM "Podcasts" WHERE FeedURL = %s AND UserID = %s'
+    else:  # MySQL or MariaDB
+        query = "SELECT PodcastID FROM Podcasts WHERE FeedURL = %s AND UserID = %s"
+    cursor.execute(query, (feed_url, user_id))
+    row = cursor.fetchone()
+    cursor.close()
+    if not row:
+        return None
+    podcast_id = list(row.values())[0] if isinstance(row, dict) else row[0]
+    return get_feed_auth(cnx, database_type, podcast_id)
+
+
+def get_episode_feed_auth(cnx, database_type, episode_id, user_id):
+    # The media URL of an episode from a private feed and the credentials to fetch it with,
+    # None if the episode isn't the user's or its feed is public
+    cursor = cnx.cursor()
+    if database_type == "postgresql":
+        query = ('SELECT "Episodes".EpisodeURL, "Podcasts".PodcastID FROM "Episodes" '
+                 'INNER JOIN "Podcasts" ON "Episodes".PodcastID = "Podcasts".PodcastID '
+                 'WHERE "Episodes".EpisodeID = %s AND "Podcasts".UserID = %s')
+    else:  # MySQL or MariaDB
+        query = ("SELECT Episodes.EpisodeURL, Podcasts.PodcastID FROM Episodes "
+                 "INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
+                 "WHERE Episodes.EpisodeID = %s AND Podcasts.UserID = %s")
+    cursor.execute(query, (episode_id, user_id))
+    row = cursor.fetchone()
+    cursor.close()
+    if not row:
+        return None
+    episode_url, podcast_id = list(row.values()) if isinstance(row, dict) else row
+    feed_auth = get_feed_auth(cnx, database_type, podcast_id)
+    if not feed_auth:
+        return None
+    return episode_url, feed_auth
+
+
+def strip_feed_auth(row):
+    # Swaps the credentials in a lowercased Podcasts row for a flag saying there are some
+    if row:
+        row['feedauth'] = any([row.pop(column, None) for column in FEED_AUTH_COLUMNS])
+    return row
+
+
 def get_email_settings(cnx, database_type):
     if database_type == "postgresql":
         cursor = cnx.cursor(row_factory=dict_row)
@@ -2228,6 +2459,148 @@ def set_theme(cnx, database_type, user_id, theme):
             # cnx.close(
 
 
+def get_keybindings(cnx, database_type, user_id):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        if database_type == 'postgresql':
+            query = 'SELECT KeyBindings FROM "UserSettings" WHERE UserID = %s'
+        else:
+            query = "SELECT KeyBindings FROM UserSettings WHERE UserID = %s"
+        cursor.execute(query, (user_id,))
+        result = cursor.fetchone()
+        if not result:
+            return {}
+        if isinstance(result, dict):
+            keybindings = result["keybindings"]
+        else:
+            keybindings = result[0]
+
+        # Only the bindings the user changed are stored, as a JSON object of action -> keys
+        if not keybindings:
+            return {}
+        try:
+            return json.loads(keybindings)
+        except ValueError:
+            return {}
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
+def set_keybindings(cnx, database_type, user_id, keybindings):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        if database_type == 'postgresql':
+            query = 'UPDATE "UserSettings" SET KeyBindings = %s WHERE UserID = %s'
+        else:
+            query = "UPDATE UserSettings SET KeyBindings = %s WHERE UserID = %s"
+        cursor.execute(query, (json.dumps(keybindings), user_id))
+        cnx.commit()
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
+def get_audio_processing(cnx, database_type, user_id):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        if database_type == 'postgresql':
+            query = 'SELECT AudioProcessing FROM "UserSettings" WHERE UserID = %s'
+        else:
+            query = "SELECT AudioProcessing FROM UserSettings WHERE UserID = %s"
+        cursor.execute(query, (user_id,))
+        result = cursor.fetchone()
+        if not result:
+            return None
+        if isinstance(result, dict):
+            audio_processing = result["audioprocessing"]
+        else:
+            audio_processing = result[0]
+
+        # Stored as a JSON object, None until the user saves their settings once
+        if not audio_processing:
+            return None
+        try:
+            return json.loads(audio_processing)
+        except ValueError:
+            return None
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
+def set_audio_processing(cnx, database_type, user_id, audio_processing):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        if database_type == 'postgresql':
+            query = 'UPDATE "UserSettings" SET AudioProcessing = %s WHERE UserID = %s'
+        else:
+            query = "UPDATE UserSettings SET AudioProcessing = %s WHERE UserID = %s"
+        cursor.execute(query, (json.dumps(audio_processing), user_id))
+        cnx.commit()
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
+def get_queue_settings(cnx, database_type, user_id):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        if database_type == 'postgresql':
+            query = 'SELECT QueueAutoAdvance, QueueRemoveCompleted FROM "UserSettings" WHERE UserID = %s'
+        else:
+            query = "SELECT QueueAutoAdvance, QueueRemoveCompleted FROM UserSettings WHERE UserID = %s"
+        cursor.execute(query, (user_id,))
+        result = cursor.fetchone()
+        if not result:
+            auto_advance, remove_completed = None, None
+        elif isinstance(result, dict):
+            auto_advance, remove_completed = result["queueautoadvance"], result["queueremovecompleted"]
+        else:
+            auto_advance, remove_completed = result[0], result[1]
+
+        # Both default to on, which is how the queue behaved before they were settings
+        return {
+            "auto_advance": True if auto_advance is None else bool(auto_advance),
+            "remove_completed": True if remove_completed is None else bool(remove_completed),
+        }
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
+def set_queue_settings(cnx, database_type, user_id, auto_advance, remove_completed):
+    cursor = None
+    try:
+        cursor = cnx.cursor()
+
+        if database_type == 'postgresql':
+            query = 'UPDATE "UserSettings" SET QueueAutoAdvance = %s, QueueRemoveCompleted = %s WHERE UserID = %s'
+        else:
+            query = "UPDATE UserSettings SET QueueAutoAdvance = %s, QueueRemoveCompleted = %s WHERE UserID = %s"
+        cursor.execute(query, (auto_advance, remove_completed, user_id))
+        cnx.commit()
+
+    finally:
+        if cursor:
+            cursor.close()
+
+
 def get_user_info(database_type, cnx):
     try:
         if database_type == "postgresql":
@@ -2829,9 +3202,9 @@ def get_stats(cnx, database_type, user_id):
     logging.info(f"Fetching stats for user ID: {user_id}, database type: {database_type}")
     cursor = cnx.cursor()
     if database_type == "postgresql":
-        query = 'SELECT UserCreated, PodcastsPlayed, TimeListened, PodcastsAdded, EpisodesSaved, EpisodesDownloaded FROM "UserStats" WHERE UserID = %s'
+        query = 'SELECT UserCreated, PodcastsPlayed, TimeListened, PodcastsAdded, EpisodesSaved, EpisodesDownloaded, TimeSaved FROM "UserStats" WHERE UserID = %s'
     else:  # MySQL or MariaDB
-        query = "SELECT UserCreated, PodcastsPlayed, TimeListened, PodcastsAdded, EpisodesSaved, EpisodesDownloaded FROM UserStats WHERE UserID = %s"
+        query = "SELECT UserCreated, PodcastsPlayed, TimeListened, PodcastsAdded, EpisodesSaved, EpisodesDownloaded, TimeSaved FROM UserStats WHERE UserID = %s"
     print('gettings stats')
     cursor.execute(query, (user_id,))
     results = cursor.fetchall()
@@ -2851,7 +3224,8 @@ def get_stats(cnx, database_type, user_id):
             "TimeListened": result['timelistened'],
             "PodcastsAdded": result['podcastsadded'],
             "EpisodesSaved": result['episodessaved'],
-            "EpisodesDownloaded": result['episodesdownloaded']
+            "EpisodesDownloaded": result['episodesdownloaded'],
+            "TimeSaved": result['timesaved'] or 0
         }
     else:  # MySQL or MariaDB
         stats = {
@@ -2860,7 +3234,8 @@ def get_stats(cnx, database_type, user_id):
             "TimeListened": result[2],
             "PodcastsAdded": result[3],
             "EpisodesSaved": result[4],
-            "EpisodesDownloaded": result[5]
+            "EpisodesDownloaded": result[5],
+            "TimeSaved": result[6] or 0
         }
     logging.info(f"Fetched stats: {stats}")
 
@@ -3027,6 +3402,85 @@ def remove_saved_episode(cnx, database_type, episode_id, user_id):
         cursor.close()
 
 
+def add_bookmark(cnx, database_type, user_id, episode_id, timestamp, note):
+    cursor = cnx.cursor()
+    try:
+        if database_type == "postgresql":
+            query = 'INSERT INTO "Bookmarks" (UserID, EpisodeID, Timestamp, Note) VALUES (%s, %s, %s, %s) RETURNING BookmarkID'
+            cursor.execute(query, (user_id, episode_id, timestamp, note))
+            result = cursor.fetchone()
+            bookmark_id = result["bookmarkid"] if isinstance(result, dict) else result[0]
+        else:  # MySQL or MariaDB
+            query = "INSERT INTO Bookmarks (UserID, EpisodeID, Timestamp, Note) VALUES (%s, %s, %s, %s)"
+            cursor.execute(query, (user_id, episode_id, timestamp, note))
+            bookmark_id = cursor.lastrowid
+        cnx.commit()
+        return bookmark_id
+    except Exception as e:
+        cnx.rollback()
+        raise e
+    finally:
+        cursor.close()
+
+
+def get_bookmarks(cnx, database_type, user_id, episode_id):
+    cursor = cnx.cursor()
+    try:
+        if database_type == "postgresql":
+            query = """
+                SELECT BookmarkID, EpisodeID, Timestamp, Note, CreatedDate
+                FROM "Bookmarks"
+                WHERE UserID = %s AND EpisodeID = %s
+                ORDER BY Timestamp
+            """
+        else:  # MySQL or MariaDB
+            query = """
+                SELECT BookmarkID, EpisodeID, Timestamp, Note, CreatedDate
+                FROM Bookmarks
+                WHERE UserID = %s AND EpisodeID = %s
+                ORDER BY Timestamp
+            """
+        cursor.execute(query, (user_id, episode_id))
+        rows = cursor.fetchall()
+
+        bookmarks = []
+        for row in rows:
+            if isinstance(row, dict):
+                row = {key.lower(): value for key, value in row.items()}
+                values = (row["bookmarkid"], row["episodeid"], row["timestamp"], row["note"], row["createddate"])
+            else:
+                values = tuple(row[:5])
+            bookmark_id, bookmark_episode_id, timestamp, note, created = values
+            bookmarks.append({
+                "bookmark_id": bookmark_id,
+                "episode_id": bookmark_episode_id,
+                "timestamp": timestamp,
+                "note": note,
+                "created": created.isoformat() if created else None,
+            })
+        return bookmarks
+    finally:
+        cursor.close()
+
+
+def delete_bookmark(cnx, database_type, user_id, bookmark_id):
+    cursor = cnx.cursor()
+    try:
+        if database_type == "postgresql":
+            query = 'DELETE FROM "Bookmarks" WHERE BookmarkID = %s AND UserID = %s'
+        else:  # MySQL or MariaDB
+            query = "DELETE FROM Bookmarks WHERE BookmarkID = %s AND UserID = %s"
+        cursor.execute(query, (bookmark_id, user_id))
+        deleted = cursor.rowcount > 0
+        cnx.commit()
+        return deleted
+    except Exception as e:
+        cnx.rollback()
+        raise e
+    finally:
+        cursor.close()
+
+
 def increment_played(cnx, database_type, user_id):
     cursor = cnx.cursor()
     if database_type == "postgresql":
@@ -3055,6 +3509,22 @@ def increment_listen_time(cnx, database_type, user_id):
 
 
 
+def increment_time_saved(cnx, database_type, user_id, seconds):
+    cursor = cnx.cursor()
+
+    # Update UserStats table with the seconds Smart Speed trimmed from silences
+    if database_type == "postgresql":
+        query = ('UPDATE "UserStats" SET TimeSaved = COALESCE(TimeSaved, 0) + %s '
+                "WHERE UserID = %s")
+    else:
+        query = ("UPDATE UserStats SET TimeSaved = COALESCE(TimeSaved, 0) + %s "
+                "WHERE UserID = %s")
+    cursor.execute(query, (seconds, user_id))
+    cnx.commit()
+
+    cursor.close()
+
+
 def get_user_episode_count(cnx, database_type, user_id):
     cursor = cnx.cursor()
     if database_type == "postgresql":
@@ -3467,7 +3937,8 @@ def get_episode_metadata(database_type, cnx, episode_id, user_id):
         query = (
             'SELECT "Podcasts".PodcastID, "Podcasts".PodcastName, "Podcasts".ArtworkURL, "Episodes".EpisodeTitle, "Episodes".EpisodePubDate, '
             '"Episodes".EpisodeDescription, "Episodes".EpisodeArtwork, "Episodes".EpisodeURL, "Episodes".EpisodeDuration, "Episodes".EpisodeID, '
-            '"Podcasts".WebsiteURL, "UserEpisodeHistory".ListenDuration, "Episodes".Completed '
+            '"Podcasts".WebsiteURL, "UserEpisodeHistory".ListenDuration, "Episodes".Completed, "Episodes".EnclosureLength, "Episodes".EnclosureType, "Episodes".ChaptersURL, '
+            '"Episodes".Season, "Episodes".EpisodeNumber, "Episodes".EpisodeType, "Episodes".FeedExtras, "Podcasts".FeedExtras AS PodcastExtras '
             'FROM "Episodes" '
             'INNER JOIN "Podcasts" ON "Episodes".PodcastID = "Podcasts".PodcastID '
             'LEFT JOIN "UserEpisodeHistory" ON "Episodes".EpisodeID = "UserEpisodeHistory".EpisodeID AND "Podcasts".UserID = "UserEpisodeHistory".UserID '
@@ -3478,7 +3949,8 @@ def get_episode_metadata(database_type, cnx, episode_id, user_id):
         query = (
             "SELECT Podcasts.PodcastID, Podcasts.PodcastName, Podcasts.ArtworkURL, Episodes.EpisodeTitle, Episodes.EpisodePubDate, "
             "Episodes.EpisodeDescription, Episodes.EpisodeArtwork, Episodes.EpisodeURL, Episodes.EpisodeDuration, Episodes.EpisodeID, "
-            "Podcasts.WebsiteURL, UserEpisodeHistory.ListenDuration, Episodes.Completed "
+            "Podcasts.WebsiteURL, UserEpisodeHistory.ListenDuration, Episodes.Completed, Episodes.EnclosureLength, Episodes.EnclosureType, Episodes.ChaptersURL, "
+            "Episodes.Season, Episodes.EpisodeNumber, Episodes.EpisodeType, Episodes.FeedExtras, Podcasts.FeedExtras AS PodcastExtras "
             "FROM Episodes "
             "INNER JOIN Podcasts ON Episodes.PodcastID = Podcasts.PodcastID "
             "LEFT JOIN UserEpisodeHistory ON Episodes.EpisodeID = UserEpisodeHistory.EpisodeID AND Podcasts.UserID = UserEpisodeHistory.UserID "
@@ -3864,7 +4336,7 @@ def search_data(database_type, cnx, search_term, user_id):
             return []
 
         # Convert column names to lowercase for MySQL
-        result = lowercase_keys(result)
+        result = [strip_feed_auth(row) for row in lowercase_keys(result)]
 
         # Post-process the results to cast boolean to integer for the 'explicit' field
         if database_type == "postgresql":
@@ -4017,6 +4489,57 @@ def remove_queued_pod(database_type, cnx, episode_id, user_id):
 
 
 
+def queue_pod_at(database_type, cnx, episode_id, user_id, position=None, after_episode_id=None):
+    """
+    Places an episode in the user's queue, moving it if it's already queued. It goes right after
+    after_episode_id when that's queued, otherwise at the 1-based position, otherwise at the end.
+    """
+    cursor = cnx.cursor()
+    try:
+        if database_type == "postgresql":
+            select_query = 'SELECT EpisodeID FROM "EpisodeQueue" WHERE UserID = %s ORDER BY QueuePosition ASC'
+        else:  # MySQL or MariaDB
+            select_query = "SELECT EpisodeID FROM EpisodeQueue WHERE UserID = %s ORDER BY QueuePosition ASC"
+        cursor.execute(select_query, (user_id,))
+        rows = cursor.fetchall()
+        queue = [row["episodeid"] if isinstance(row, dict) else row[0] for row in rows]
+
+        already_queued = episode_id in queue
+        if already_queued:
+            queue.remove(episode_id)
+
+        if after_episode_id is not None:
+            # With nothing playing from the queue, "next" means the front of it
+            index = queue.index(after_episode_id) + 1 if after_episode_id in queue else 0
+        elif position is not None:
+            index = min(max(position - 1, 0), len(queue))
+        else:
+            index = len(queue)
+        queue.insert(index, episode_id)
+
+        if not already_queued:
+            insert_query = (
+                'INSERT INTO "EpisodeQueue"(UserID, EpisodeID, QueuePosition) VALUES (%s, %s, %s)'
+                if database_type == "postgresql" else
+                "INSERT INTO EpisodeQueue(UserID, EpisodeID, QueuePosition) VALUES (%s, %s, %s)"
+            )
+            cursor.execute(insert_query, (user_id, episode_id, index + 1))
+
+        update_query = (
+            'UPDATE "EpisodeQueue" SET QueuePosition = %s WHERE UserID = %s AND EpisodeID = %s'
+            if database_type == "postgresql" else
+            "UPDATE EpisodeQueue SET QueuePosition = %s WHERE UserID = %s AND EpisodeID = %s"
+        )
+        cursor.executemany(update_query, [(pos + 1, user_id, queued_id) for pos, queued_id in enumerate(queue)])
+        cnx.commit()
+        return index + 1
+    except Exception as e:
+        cnx.rollback()
+        raise e
+    finally:
+        cursor.close()
+
+
 def get_queued_episodes(database_type, cnx, user_id):
     if database_type == "postgresql":
         from psycopg.rows import dict_row
@@ -4833,12 +5356,15 @@ def backup_user(database_type, cnx, user_id):
 
     opml_content = '<?xml version="1.0" encoding="UTF-8"?>\n<opml version="2.0">\n  <head>\n    <title>Podcast Subscriptions</title>\n  </head>\n  <body>\n'
 
+    # Tokens in feed URLs are as good as passwords, so they're left out of the export
     if database_type == "postgresql":
         for podcast in podcasts:
-            opml_content += f'    <outline text="{podcast["podcastname"]}" title="{podcast["podcastname"]}" type="rss" xmlUrl="{podcast["feedurl"]}" />\n'
+            feed_url = html.escape(redact_feed_url(podcast["feedurl"]))
+            opml_content += f'    <outline text="{podcast["podcastname"]}" title="{podcast["podcastname"]}" type="rss" xmlUrl="{feed_url}" />\n'
     else:
         for podcast in podcasts:
-            opml_content += f'    <outline text="{podcast["PodcastName"]}" title="{podcast["PodcastName"]}" type="rss" xmlUrl="{podcast["FeedURL"]}" />\n'
+            feed_url = html.escape(redact_feed_url(podcast["FeedURL"]))
+            opml_content += f'    <outline text="{podcast["PodcastName"]}" title="{podcast["PodcastName"]}" type="rss" xmlUrl="{feed_url}" />\n'
 
     opml_content += '  </body>\n</opml>'
 
@@ -112,9 +112,17 @@ try:
                         PodcastsAdded INT DEFAULT 0,
                         EpisodesSaved INT DEFAULT 0,
                         EpisodesDownloaded INT DEFAULT 0,
+                        TimeSaved INT DEFAULT 0,
                         FOREIGN KEY (UserID) REFERENCES Users(UserID)
                     )""")
 
+    # Check if the TimeSaved column exists, and add it if it doesn't
+    cursor.execute("SHOW COLUMNS FROM UserStats LIKE 'TimeSaved'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("ALTER TABLE UserStats ADD COLUMN TimeSaved INT DEFAULT 0")
+        logging.info("TimeSaved column added to UserStats table.")
+
     # Generate a key
     key = Fernet.generate_key()
 
@@ -291,6 +299,16 @@ try:
                         AutoDownload TINYINT(1) DEFAULT 0,
                         StartSkip INT DEFAULT 0,
                         EndSkip INT DEFAULT 0,
+                        PlaybackSpeed FLOAT DEFAULT 1.0,
+                        VolumeGain FLOAT DEFAULT 1.0,
+                        SkipForward INT DEFAULT 15,
+                        SkipBackward INT DEFAULT 15,
+                        SmartSpeed TINYINT(1) DEFAULT 0,
+                        FeedUsername TEXT,
+                        FeedPassword TEXT,
+                        FeedHeaderName TEXT,
+                        FeedHeaderValue TEXT,
+                        FeedExtras TEXT,
                         FOREIGN KEY (UserID) REFERENCES Users(UserID)
                     )""")
     logging.info("Podcasts table checked/created.")
@@ -307,6 +325,46 @@ try:
         """)
         logging.info("AutoDownload, StartSkip, and EndSkip columns added to Podcasts table.")
 
+    # Check if the playback profile columns exist, and add them if they don't
+    cursor.execute("SHOW COLUMNS FROM Podcasts LIKE 'PlaybackSpeed'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("""
+            ALTER TABLE Podcasts
+            ADD COLUMN PlaybackSpeed FLOAT DEFAULT 1.0,
+            ADD COLUMN VolumeGain FLOAT DEFAULT 1.0,
+            ADD COLUMN SkipForward INT DEFAULT 15,
+            ADD COLUMN SkipBackward INT DEFAULT 15
+        """)
+        logging.info("PlaybackSpeed, VolumeGain, SkipForward, and SkipBackward columns added to Podcasts table.")
+
+    cursor.execute("SHOW COLUMNS FROM Podcasts LIKE 'SmartSpeed'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("ALTER TABLE Podcasts ADD COLUMN SmartSpeed TINYINT(1) DEFAULT 0")
+        logging.info("SmartSpeed column added to Podcasts table.")
+
+    # Credentials for private feeds, kept apart from the feed URL. The password and header value
+    # are stored encrypted.
+    cursor.execute("SHOW COLUMNS FROM Podcasts LIKE 'FeedUsername'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("""
+            ALTER TABLE Podcasts
+            ADD COLUMN FeedUsername TEXT,
+            ADD COLUMN FeedPassword TEXT,
+            ADD COLUMN FeedHeaderName TEXT,
+            ADD COLUMN FeedHeaderValue TEXT
+        """)
+        logging.info("Feed credential columns added to Podcasts table.")
+
+    # The Podcasting 2.0 details of the feed as JSON, hosts, trailers and support links for the
+    # podcast page
+    cursor.execute("SHOW COLUMNS FROM Podcasts LIKE 'FeedExtras'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("ALTER TABLE Podcasts ADD COLUMN FeedExtras TEXT")
+
     cursor.execute("""CREATE TABLE IF NOT EXISTS Episodes (
                         EpisodeID INT AUTO_INCREMENT PRIMARY KEY,
                         PodcastID INT,
@@ -317,6 +375,13 @@ try:
                         EpisodePubDate DATETIME,
                         EpisodeDuration INT,
                         Completed TINYINT(1) DEFAULT 0,
+                        EnclosureLength BIGINT,
+                        EnclosureType VARCHAR(255),
+                        ChaptersURL TEXT,
+                        Season INT,
+                        EpisodeNumber DOUBLE,
+                        EpisodeType VARCHAR(16),
+                        FeedExtras TEXT,
                         FOREIGN KEY (PodcastID) REFERENCES Podcasts(PodcastID)
                     )""")
     # Check if the Completed column exists, and add it if it doesn't
@@ -328,6 +393,47 @@ try:
             ADD COLUMN Completed TINYINT(1) DEFAULT 0
         """)
 
+    # The enclosure size from the feed, desktop downloads check the finished file against it
+    cursor.execute("SHOW COLUMNS FROM Episodes LIKE 'EnclosureLength'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("""
+            ALTER TABLE Episodes
+            ADD COLUMN EnclosureLength BIGINT
+        """)
+
+    # The enclosure's MIME type from the feed, desktop downloads name their files by it
+    cursor.execute("SHOW COLUMNS FROM Episodes LIKE 'EnclosureType'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("""
+            ALTER TABLE Episodes
+            ADD COLUMN EnclosureType VARCHAR(255)
+        """)
+
+    # The <podcast:chapters> link from the feed, so playing an episode doesn't mean reading the feed
+    cursor.execute("SHOW COLUMNS FROM Episodes LIKE 'ChaptersURL'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("""
+            ALTER TABLE Episodes
+            ADD COLUMN ChaptersURL TEXT
+        """)
+
+    # Seasons, numbering and trailers from the feed, the podcast page sorts and filters by them
+    for column, definition in (('Season', 'INT'), ('EpisodeNumber', 'DOUBLE'), ('EpisodeType', 'VARCHAR(16)')):
+        cursor.execute(f"SHOW COLUMNS FROM Episodes LIKE '{column}'")
+        result = cursor.fetchone()
+        if not result:
+            cursor.execute(f"ALTER TABLE Episodes ADD COLUMN {column} {definition}")
+
+    # The Podcasting 2.0 details of the item as JSON, people, transcripts and soundbites for the
+    # episode page
+    cursor.execute("SHOW COLUMNS FROM Episodes LIKE 'FeedExtras'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("ALTER TABLE Episodes ADD COLUMN FeedExtras TEXT")
+
 
     def create_index_if_not_exists(cursor, index_name, table_name, column_name):
         cursor.execute(f"SELECT COUNT(1) IndexIsThere FROM INFORMATION_SCHEMA.STATISTICS WHERE table_schema = DATABASE() AND index_name = '{index_name}'")
@@ -344,9 +450,41 @@ try:
                         UserSettingID INT AUTO_INCREMENT PRIMARY KEY,
                         UserID INT UNIQUE,
                         Theme VARCHAR(255) DEFAULT 'nordic',
+                        KeyBindings TEXT,
+                        AudioProcessing TEXT,
+                        QueueAutoAdvance TINYINT(1) DEFAULT 1,
+                        QueueRemoveCompleted TINYINT(1) DEFAULT 1,
                         FOREIGN KEY (UserID) REFERENCES Users(UserID)
                     )""")
 
+    # Check if the KeyBindings column exists, and add it if it doesn't
+    cursor.execute("SHOW COLUMNS FROM UserSettings LIKE 'KeyBindings'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("""
+            ALTER TABLE UserSettings
+            ADD COLUMN KeyBindings TEXT
+        """)
+
+    # Check if the AudioProcessing column exists, and add it if it doesn't
+    cursor.execute("SHOW COLUMNS FROM UserSettings LIKE 'AudioProcessing'")
+    result = cursor.fetchone()
+    if not result:
+        cursor.execute("""
+            ALTER TABLE UserSettings
+            ADD COLUMN AudioProcessing TEXT
+        """)
+
+    # Check if the queue columns exist, and add them if they don't
+    for column in ("QueueAutoAdvance", "QueueRemoveCompleted"):
+        cursor.execute(f"SHOW COLUMNS FROM UserSettings LIKE '{column}'")
+        result = cursor.fetchone()
+        if not result:
+            cursor.execute(f"""
+                ALTER TABLE UserSettings
+                ADD COLUMN {column} TINYINT(1) DEFAULT 1
+            """)
+
     cursor.execute("""INSERT IGNORE INTO UserSettings (UserID, Theme) VALUES ('1', 'nordic')""")
     cursor.execute("""INSERT IGNORE INTO UserSettings (UserID, Theme) VALUES ('2', 'nordic')""")
 
@@ -369,6 +507,17 @@ try:
                         FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID)
                     )""")
 
+    cursor.execute("""CREATE TABLE IF NOT EXISTS Bookmarks (
+                        BookmarkID INT AUTO_INCREMENT PRIMARY KEY,
+                        UserID INT,
+                        EpisodeID INT,
+                        Timestamp INT NOT NULL,
+                        Note TEXT,
+                        CreatedDate TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                        FOREIGN KEY (UserID) REFERENCES Users(UserID) ON DELETE CASCADE,
+                        FOREIGN KEY (EpisodeID) REFERENCES Episodes(EpisodeID) ON DELETE CASCADE
+                    )""")
+
 
     # Create the DownloadedEpisodes table
     cursor.execute("""CREATE TABLE IF NOT EXISTS DownloadedEpisodes (
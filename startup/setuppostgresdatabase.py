@@ -106,8 +106,10 @@ try:
                         PodcastsAdded INT DEFAULT 0,
                         EpisodesSaved INT DEFAULT 0,
                         EpisodesDownloaded INT DEFAULT 0,
+                        TimeSaved INT DEFAULT 0,
                         FOREIGN KEY (UserID) REFERENCES "Users"(UserID)
                     )""")
+    cursor.execute('ALTER TABLE "UserStats" ADD COLUMN IF NOT EXISTS TimeSaved INT DEFAULT 0')
 
 
     # Generate a key
@@ -295,9 +297,32 @@ try:
                 AutoDownload BOOLEAN DEFAULT FALSE,
                 StartSkip INT DEFAULT 0,
                 EndSkip INT DEFAULT 0,
+                PlaybackSpeed REAL DEFAULT 1.0,
+                VolumeGain REAL DEFAULT 1.0,
+                SkipForward INT DEFAULT 15,
+                SkipBackward INT DEFAULT 15,
+                SmartSpeed BOOLEAN DEFAULT FALSE,
+                FeedUsername TEXT,
+                FeedPassword TEXT,
+                FeedHeaderName TEXT,
+                FeedHeaderValue TEXT,
+                FeedExtras TEXT,
                 FOREIGN KEY (UserID) REFERENCES "Users"(UserID)
             )
         """)
+        cursor.execute("""
+            ALTER TABLE "Podcasts"
+            ADD COLUMN IF NOT EXISTS PlaybackSpeed REAL DEFAULT 1.0,
+            ADD COLUMN IF NOT EXISTS VolumeGain REAL DEFAULT 1.0,
+            ADD COLUMN IF NOT EXISTS SkipForward INT DEFAULT 15,
+            ADD COLUMN IF NOT EXISTS SkipBackward INT DEFAULT 15,
+            ADD COLUMN IF NOT EXISTS SmartSpeed BOOLEAN DEFAULT FALSE,
+            ADD COLUMN IF NOT EXISTS FeedUsername TEXT,
+            ADD COLUMN IF NOT EXISTS FeedPassword TEXT,
+            ADD COLUMN IF NOT EXISTS FeedHeaderName TEXT,
+            ADD COLUMN IF NOT EXISTS FeedHeaderValue TEXT,
+            ADD COLUMN IF NOT EXISTS FeedExtras TEXT
+        """)
         cnx.commit()  # Ensure changes are committed
     except Exception as e:
         print(f"Error adding Podcasts table: {e}")
@@ -322,9 +347,29 @@ try:
                 EpisodePubDate TIMESTAMP,
                 EpisodeDuration INT,
                 Completed BOOLEAN DEFAULT FALSE,
+                EnclosureLength BIGINT,
+                EnclosureType VARCHAR(255),
+                ChaptersURL TEXT,
+                Season INT,
+                EpisodeNumber DOUBLE PRECISION,
+                EpisodeType VARCHAR(16),
+                FeedExtras TEXT,
                 FOREIGN KEY (PodcastID) REFERENCES "Podcasts"(PodcastID)
             )
         """)
+        # The enclosure size from the feed, desktop downloads check the finished file against it
+        cursor.execute('ALTER TABLE "Episodes" ADD COLUMN IF NOT EXISTS EnclosureLength BIGINT')
+        # The enclosure's MIME type from the feed, desktop downloads name their files by it
+        cursor.execute('ALTER TABLE "Episodes" ADD COLUMN IF NOT EXISTS EnclosureType VARCHAR(255)')
+        # The <podcast:chapters> link from the feed, so playing an episode doesn't mean reading the feed
+        cursor.execute('ALTER TABLE "Episodes" ADD COLUMN IF NOT EXISTS ChaptersURL TEXT')
+        # Seasons, numbering and trailers from the feed, the podcast page sorts and filters by them
+        cursor.execute('ALTER TABLE "Episodes" ADD COLUMN IF NOT EXISTS Season INT')
+        cursor.execute('ALTER TABLE "Episodes" ADD COLUMN IF NOT EXISTS EpisodeNumber DOUBLE PRECISION')
+        cursor.execute('ALTER TABLE "Episodes" ADD COLUMN IF NOT EXISTS EpisodeType VARCHAR(16)')
+        # The Podcasting 2.0 details of the item as JSON, people, transcripts and soundbites for
+        # the episode page
+        cursor.execute('ALTER TABLE "Episodes" ADD COLUMN IF NOT EXISTS FeedExtras TEXT')
 
         cnx.commit()  # Ensure changes are committed
     except Exception as e:
@@ -349,8 +394,16 @@ try:
                             UserSettingID SERIAL PRIMARY KEY,
                             UserID INT UNIQUE,
                             Theme VARCHAR(255) DEFAULT 'nordic',
+                            KeyBindings TEXT,
+                            AudioProcessing TEXT,
+                            QueueAutoAdvance BOOLEAN DEFAULT TRUE,
+                            QueueRemoveCompleted BOOLEAN DEFAULT TRUE,
                             FOREIGN KEY (UserID) REFERENCES "Users"(UserID)
                         )""")
+        cursor.execute('ALTER TABLE "UserSettings" ADD COLUMN IF NOT EXISTS KeyBindings TEXT')
+        cursor.execute('ALTER TABLE "UserSettings" ADD COLUMN IF NOT EXISTS AudioProcessing TEXT')
+        cursor.execute('ALTER TABLE "UserSettings" ADD COLUMN IF NOT EXISTS QueueAutoAdvance BOOLEAN DEFAULT TRUE')
+        cursor.execute('ALTER TABLE "UserSettings" ADD COLUMN IF NOT EXISTS QueueRemoveCompleted BOOLEAN DEFAULT TRUE')
     except Exception as e:
         print(f"Error adding UserSettings table: {e}")
     logging.info("created UserSettings table.")
@@ -377,6 +430,17 @@ try:
                         FOREIGN KEY (EpisodeID) REFERENCES "Episodes"(EpisodeID)
                     )""")
 
+    cursor.execute("""CREATE TABLE IF NOT EXISTS "Bookmarks" (
+                        BookmarkID SERIAL PRIMARY KEY,
+                        UserID INT,
+                        EpisodeID INT,
+                        Timestamp INT NOT NULL,
+                        Note TEXT,
+                        CreatedDate TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                        FOREIGN KEY (UserID) REFERENCES "Users"(UserID) ON DELETE CASCADE,
+                        FOREIGN KEY (EpisodeID) REFERENCES "Episodes"(EpisodeID) ON DELETE CASCADE
+                    )""")
+
 
     # Create the DownloadedEpisodes table
     cursor.execute("""CREATE TABLE IF NOT EXISTS "DownloadedEpisodes" (
@@ -0,0 +1,32 @@
+// Prints a user's queue from the command line:
+//   cargo run --example queue -- https://pods.example.com <api key>
+use pinepods_client::{ClientError, PinepodsClient};
+
+#[tokio::main]
+async fn main() -> Result<(), ClientError> {
+    let mut args = std::env::args().skip(1);
+    let (server_name, api_key) = match (args.next(), args.next()) {
+        (Some(server_name), Some(api_key)) => (server_name, api_key),
+        _ => {
+            eprintln!("usage: queue <server url> <api key>");
+            std::process::exit(2);
+        }
+    };
+
+    let client = PinepodsClient::new(server_name, Some(api_key));
+    let user_id = client
+        .user_id()
+        .await?
+        .retrieved_id
+        .ok_or(ClientError::Unauthorized)?;
+
+    for (index, episode) in client.queued_episodes(user_id).await?.iter().enumerate() {
+        println!(
+            "{:>3}. {} - {}",
+            index + 1,
+            episode.podcastname,
+            episode.episodetitle
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,304 @@
+use crate::client::{DetailResponse, PinepodsClient};
+use crate::error::ClientError;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub struct SettingsUser {
+    pub userid: i32,
+    pub fullname: String,
+    pub username: String,
+    pub email: String,
+    pub isadmin: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct AddSettingsUserRequest {
+    pub fullname: String,
+    pub username: String,
+    pub email: String,
+    pub hash_pw: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteUserResponse {
+    pub status: String,
+}
+
+#[derive(Deserialize, PartialEq)]
+pub struct FinalAdminResponse {
+    pub final_admin: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AdminCheckResponse {
+    pub is_admin: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct SuccessResponse {
+    pub success: bool,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct SelfServiceStatusResponse {
+    status: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmailSettings {
+    pub server_name: String,
+    pub server_port: String,
+    pub from_email: String,
+    pub send_mode: String,
+    pub encryption: String,
+    pub auth_required: bool,
+    pub email_username: String,
+    pub email_password: String,
+}
+
+#[derive(Serialize, Debug)]
+struct EmailSettingsRequest<'a> {
+    email_settings: &'a EmailSettings,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestEmailSettings {
+    pub server_name: String,
+    pub server_port: String,
+    pub from_email: String,
+    pub send_mode: String,
+    pub encryption: String,
+    pub auth_required: bool,
+    pub email_username: String,
+    pub email_password: String,
+    pub to_email: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SendEmailSettings {
+    pub to_email: String,
+    pub subject: String,
+    pub message: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EmailSendResponse {
+    pub email_status: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+#[allow(non_snake_case)]
+#[serde(rename_all = "PascalCase")]
+pub struct EmailSettingsResponse {
+    pub Emailsettingsid: i32,
+    pub ServerName: String,
+    pub ServerPort: i32,
+    pub FromEmail: String,
+    pub SendMode: String,
+    pub Encryption: String,
+    pub AuthRequired: i32,
+    pub Username: String,
+    pub Password: String,
+}
+
+#[derive(Serialize, Debug)]
+struct BackupUserRequest {
+    user_id: i32,
+}
+
+#[derive(Serialize, Debug)]
+struct BackupServerRequest<'a> {
+    database_pass: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+struct RestoreServerRequest<'a> {
+    database_pass: &'a str,
+    server_restore_data: &'a str,
+}
+
+impl PinepodsClient {
+    /// Every user on the server. Needs an admin's API key.
+    pub async fn users(&self) -> Result<Vec<SettingsUser>, ClientError> {
+        self.get("/api/data/get_user_info").await
+    }
+
+    /// Whether the user was created.
+    pub async fn add_user(&self, user: &AddSettingsUserRequest) -> Result<bool, ClientError> {
+        let response: DetailResponse = self.post("/api/data/add_user", user).await?;
+        Ok(response.detail == "Success")
+    }
+
+    pub async fn set_fullname(&self, user_id: i32, new_name: &str) -> Result<String, ClientError> {
+        let request = self
+            .request(Method::PUT, &format!("/api/data/set_fullname/{}", user_id))?
+            .query(&[("new_name", new_name)])
+            .header(CONTENT_TYPE, "application/json");
+        let response: DetailResponse = self.send_json(request).await?;
+        Ok(response.detail)
+    }
+
+    /// `hash_pw` is the new password already hashed by the caller.
+    pub async fn set_password(&self, user_id: i32, hash_pw: &str) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .put_for(
+                &format!("/api/data/set_password/{}", user_id),
+                Some(&serde_json::json!({ "hash_pw": hash_pw })),
+            )
+            .await?;
+        Ok(response.detail)
+    }
+
+    pub async fn delete_user(&self, user_id: i32) -> Result<DeleteUserResponse, ClientError> {
+        self.delete::<(), _>(&format!("/api/data/user/delete/{}", user_id), None)
+            .await
+    }
+
+    pub async fn set_email(&self, user_id: i32, new_email: &str) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .put_for(
+                "/api/data/user/set_email",
+                Some(&serde_json::json!({ "user_id": user_id, "new_email": new_email })),
+            )
+            .await?;
+        Ok(response.detail)
+    }
+
+    pub async fn set_username(
+        &self,
+        user_id: i32,
+        new_username: &str,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .put_for(
+                "/api/data/user/set_username",
+                Some(&serde_json::json!({ "user_id": user_id, "new_username": new_username })),
+            )
+            .await?;
+        Ok(response.detail)
+    }
+
+    pub async fn set_isadmin(&self, user_id: i32, isadmin: bool) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .put_for(
+                "/api/data/user/set_isadmin",
+                Some(&serde_json::json!({ "user_id": user_id, "isadmin": isadmin })),
+            )
+            .await?;
+        Ok(response.detail)
+    }
+
+    /// Whether `user_id` is the last admin left, who can't be demoted or deleted.
+    pub async fn final_admin(&self, user_id: i32) -> Result<FinalAdminResponse, ClientError> {
+        self.get(&format!("/api/data/user/final_admin/{}", user_id))
+            .await
+    }
+
+    pub async fn user_admin_check(&self, user_id: i32) -> Result<AdminCheckResponse, ClientError> {
+        self.get(&format!("/api/data/user_admin_check/{}", user_id))
+            .await
+    }
+
+    // The server-wide switches flip on every call rather than taking a value
+    async fn toggle(&self, path: &str) -> Result<SuccessResponse, ClientError> {
+        self.send_json(
+            self.request(Method::POST, path)?
+                .header(CONTENT_TYPE, "application/json"),
+        )
+        .await
+    }
+
+    pub async fn toggle_guest(&self) -> Result<SuccessResponse, ClientError> {
+        self.toggle("/api/data/enable_disable_guest").await
+    }
+
+    pub async fn toggle_downloads(&self) -> Result<SuccessResponse, ClientError> {
+        self.toggle("/api/data/enable_disable_downloads").await
+    }
+
+    pub async fn toggle_self_service(&self) -> Result<SuccessResponse, ClientError> {
+        self.toggle("/api/data/enable_disable_self_service").await
+    }
+
+    pub async fn guest_status(&self) -> Result<bool, ClientError> {
+        self.get("/api/data/guest_status").await
+    }
+
+    pub async fn download_status(&self) -> Result<bool, ClientError> {
+        self.get("/api/data/download_status").await
+    }
+
+    /// Whether people can create their own accounts. Needs no API key.
+    pub async fn self_service_status(&self) -> Result<bool, ClientError> {
+        let response: SelfServiceStatusResponse = self
+            .send_json(self.unauthenticated(Method::GET, "/api/data/self_service_status"))
+            .await?;
+        Ok(response.status)
+    }
+
+    pub async fn save_email_settings(
+        &self,
+        email_settings: &EmailSettings,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .post(
+                "/api/data/save_email_settings",
+                &EmailSettingsRequest { email_settings },
+            )
+            .await?;
+        Ok(response.detail)
+    }
+
+    pub async fn email_settings(&self) -> Result<EmailSettingsResponse, ClientError> {
+        self.get("/api/data/get_email_settings").await
+    }
+
+    /// Sends `message` using settings that haven't been saved yet.
+    pub async fn send_test_email(
+        &self,
+        settings: &TestEmailSettings,
+    ) -> Result<EmailSendResponse, ClientError> {
+        self.post("/api/data/send_test_email", settings).await
+    }
+
+    pub async fn send_email(
+        &self,
+        email: &SendEmailSettings,
+    ) -> Result<EmailSendResponse, ClientError> {
+        self.post("/api/data/send_email", email).await
+    }
+
+    /// The user's podcasts as an OPML document.
+    pub async fn backup_user(&self, user_id: i32) -> Result<String, ClientError> {
+        self.post_for_text("/api/data/backup_user", &BackupUserRequest { user_id })
+            .await
+    }
+
+    /// A dump of the whole database, as SQL.
+    pub async fn backup_server(&self, database_pass: &str) -> Result<String, ClientError> {
+        self.post_for_text(
+            "/api/data/backup_server",
+            &BackupServerRequest { database_pass },
+        )
+        .await
+    }
+
+    pub async fn restore_server(
+        &self,
+        database_pass: &str,
+        server_restore_data: &str,
+    ) -> Result<String, ClientError> {
+        self.post_for_text(
+            "/api/data/restore_server",
+            &RestoreServerRequest {
+                database_pass,
+                server_restore_data,
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,244 @@
+use crate::client::{with_json, PinepodsClient};
+use crate::error::ClientError;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+struct LoginResponse {
+    retrieved_key: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct AddUserRequest {
+    pub fullname: String,
+    pub username: String,
+    pub email: String,
+    pub hash_pw: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+struct FirstLoginResponse {
+    FirstLogin: bool,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct CheckMfaEnabledResponse {
+    mfa_enabled: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GenerateMFAResponse {
+    pub secret: String,
+    pub qr_code_svg: String,
+}
+
+#[derive(Serialize)]
+pub struct VerifyMFABody {
+    pub user_id: i32,
+    pub mfa_code: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct VerifyMFAResponse {
+    verified: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SaveMFASecretRequest {
+    pub user_id: i32,
+    pub mfa_secret: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct SaveMFASecretResponse {
+    pub status: String,
+}
+
+#[derive(Serialize)]
+struct DeleteMFARequest {
+    user_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+struct DeleteMFAResponse {
+    deleted: bool,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub struct APIInfo {
+    pub apikeyid: i32,
+    pub userid: i32,
+    pub username: String,
+    pub lastfourdigits: String,
+    pub created: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct APIInfoResponse {
+    api_info: Vec<APIInfo>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct CreateAPIKeyResponse {
+    api_key: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DeleteAPIRequest {
+    pub api_id: String,
+    pub user_id: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct DeleteAPIKeyResponse {
+    pub detail: String,
+}
+
+#[derive(Serialize)]
+pub struct ResetCodePayload {
+    pub email: String,
+    pub username: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct ResetCodeResponse {
+    code_created: bool,
+}
+
+#[derive(Serialize)]
+pub struct ResetForgotPasswordPayload {
+    pub reset_code: String,
+    pub email: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct ForgotResetPasswordResponse {
+    pub message: String,
+}
+
+impl PinepodsClient {
+    /// Signs in with a username and password and returns the user's API key. Needs no API key.
+    pub async fn login(&self, username: &str, password: &str) -> Result<String, ClientError> {
+        let response: LoginResponse = self
+            .send_json(
+                self.unauthenticated(Method::GET, "/api/data/get_key")
+                    .basic_auth(username, Some(password)),
+            )
+            .await?;
+        Ok(response.retrieved_key)
+    }
+
+    /// Creates an account from the login page, when self service sign up is on.
+    pub async fn add_login_user(&self, user: &AddUserRequest) -> Result<(), ClientError> {
+        self.send(with_json(
+            self.unauthenticated(Method::POST, "/api/data/add_login_user"),
+            user,
+        )?)
+        .await
+        .map(|_| ())
+    }
+
+    /// Whether the user has already been through the first login setup.
+    pub async fn first_login_done(&self, user_id: i32) -> Result<bool, ClientError> {
+        let response: FirstLoginResponse = self
+            .get(&format!("/api/data/first_login_done/{}", user_id))
+            .await?;
+        Ok(response.FirstLogin)
+    }
+
+    /// Emails the user a code for resetting a forgotten password. Needs no API key.
+    pub async fn create_reset_code(&self, payload: &ResetCodePayload) -> Result<bool, ClientError> {
+        let response: ResetCodeResponse = self
+            .send_json(with_json(
+                self.unauthenticated(Method::POST, "/api/data/reset_password_create_code"),
+                payload,
+            )?)
+            .await?;
+        Ok(response.code_created)
+    }
+
+    /// Sets a new password if the emailed reset code matches. Needs no API key.
+    pub async fn verify_and_reset_password(
+        &self,
+        payload: &ResetForgotPasswordPayload,
+    ) -> Result<ForgotResetPasswordResponse, ClientError> {
+        self.send_json(with_json(
+            self.unauthenticated(Method::POST, "/api/data/verify_and_reset_password"),
+            payload,
+        )?)
+        .await
+    }
+
+    pub async fn mfa_enabled(&self, user_id: i32) -> Result<bool, ClientError> {
+        let response: CheckMfaEnabledResponse = self
+            .get(&format!("/api/data/check_mfa_enabled/{}", user_id))
+            .await?;
+        Ok(response.mfa_enabled)
+    }
+
+    /// Checks a code at login against the user's saved secret.
+    pub async fn verify_mfa(&self, body: &VerifyMFABody) -> Result<bool, ClientError> {
+        let response: VerifyMFAResponse = self.post("/api/data/verify_mfa", body).await?;
+        Ok(response.verified)
+    }
+
+    /// A new secret, held by the server until a code from it is verified.
+    pub async fn generate_mfa_secret(
+        &self,
+        user_id: i32,
+    ) -> Result<GenerateMFAResponse, ClientError> {
+        self.get(&format!("/api/data/generate_mfa_secret/{}", user_id))
+            .await
+    }
+
+    /// Checks a code against the secret from [`generate_mfa_secret`](Self::generate_mfa_secret)
+    /// and turns MFA on if it matches.
+    pub async fn verify_temp_mfa(&self, body: &VerifyMFABody) -> Result<bool, ClientError> {
+        let response: VerifyMFAResponse = self.post("/api/data/verify_temp_mfa", body).await?;
+        Ok(response.verified)
+    }
+
+    pub async fn save_mfa_secret(
+        &self,
+        request: &SaveMFASecretRequest,
+    ) -> Result<SaveMFASecretResponse, ClientError> {
+        self.post("/api/data/save_mfa_secret", request).await
+    }
+
+    /// Whether MFA was turned off.
+    pub async fn disable_mfa(&self, user_id: i32) -> Result<bool, ClientError> {
+        let response: DeleteMFAResponse = self
+            .delete("/api/data/delete_mfa", Some(&DeleteMFARequest { user_id }))
+            .await?;
+        Ok(response.deleted)
+    }
+
+    /// The user's API keys, with only their last four digits.
+    pub async fn api_keys(&self, user_id: i32) -> Result<Vec<APIInfo>, ClientError> {
+        let response: APIInfoResponse = self
+            .get(&format!("/api/data/get_api_info/{}", user_id))
+            .await?;
+        Ok(response.api_info)
+    }
+
+    /// Returns the new key, the only time it's shown in full.
+    pub async fn create_api_key(&self, user_id: i32) -> Result<String, ClientError> {
+        let response: CreateAPIKeyResponse = self
+            .post(
+                "/api/data/create_api_key",
+                &serde_json::json!({ "user_id": user_id }),
+            )
+            .await?;
+        Ok(response.api_key)
+    }
+
+    pub async fn delete_api_key(
+        &self,
+        request: &DeleteAPIRequest,
+    ) -> Result<DeleteAPIKeyResponse, ClientError> {
+        self.delete("/api/data/delete_api_key", Some(request)).await
+    }
+}
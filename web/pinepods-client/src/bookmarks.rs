@@ -0,0 +1,76 @@
+use crate::client::PinepodsClient;
+use crate::episodes::EpisodeRequest;
+use crate::error::ClientError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub bookmark_id: i32,
+    pub episode_id: i32,
+    // Seconds into the episode
+    pub timestamp: i32,
+    pub note: Option<String>,
+    pub created: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AddBookmarkRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+    pub timestamp: i32,
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AddBookmarkResponse {
+    bookmark_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+struct BookmarksResponse {
+    bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Serialize, Debug)]
+struct DeleteBookmarkRequest {
+    bookmark_id: i32,
+    user_id: i32,
+}
+
+impl PinepodsClient {
+    /// Returns the id of the new bookmark.
+    pub async fn add_bookmark(&self, request: &AddBookmarkRequest) -> Result<i32, ClientError> {
+        let response: AddBookmarkResponse = self.post("/api/data/add_bookmark", request).await?;
+        Ok(response.bookmark_id)
+    }
+
+    /// The user's bookmarks in an episode, earliest first.
+    pub async fn bookmarks(
+        &self,
+        user_id: i32,
+        episode_id: i32,
+    ) -> Result<Vec<Bookmark>, ClientError> {
+        let response: BookmarksResponse = self
+            .post(
+                "/api/data/get_bookmarks",
+                &EpisodeRequest {
+                    episode_id,
+                    user_id,
+                },
+            )
+            .await?;
+        Ok(response.bookmarks)
+    }
+
+    pub async fn delete_bookmark(&self, user_id: i32, bookmark_id: i32) -> Result<(), ClientError> {
+        self.post_for_text(
+            "/api/data/delete_bookmark",
+            &DeleteBookmarkRequest {
+                bookmark_id,
+                user_id,
+            },
+        )
+        .await
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Remembers GET responses along with their `ETag` and `Last-Modified` validators, so asking
+/// for an unchanged list again costs the server a bodyless 304.
+///
+/// Clones share the same entries. Attach one with [`PinepodsClient::with_cache`].
+///
+/// [`PinepodsClient::with_cache`]: crate::PinepodsClient::with_cache
+#[derive(Clone, Debug, Default)]
+pub struct ResponseCache {
+    entries: Arc<Mutex<HashMap<String, CachedResponse>>>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CachedResponse {
+    pub(crate) body: String,
+    pub(crate) etag: Option<String>,
+    pub(crate) last_modified: Option<String>,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        ResponseCache::default()
+    }
+
+    pub(crate) fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.lock().get(url).cloned()
+    }
+
+    pub(crate) fn insert(&self, url: String, response: CachedResponse) {
+        self.lock().insert(url, response);
+    }
+
+    /// Forgets every response whose URL contains `path`, e.g. `/api/data/return_pods`.
+    pub fn invalidate(&self, path: &str) {
+        self.lock().retain(|url, _| !url.contains(path));
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedResponse>> {
+        // Entries are only ever replaced whole, so a panic mid-update can't leave one half written
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,286 @@
+use crate::cache::{CachedResponse, ResponseCache};
+use crate::error::ClientError;
+use reqwest::header::{
+    HeaderName, HeaderValue, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+
+// Long enough for a slow server, short enough that a dead connection gets noticed
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A connection to one PinePods server, optionally signed in with an API key.
+///
+/// Cloning is cheap and clones share the underlying HTTP connection pool.
+#[derive(Clone, Debug)]
+pub struct PinepodsClient {
+    server_name: String,
+    api_key: Option<String>,
+    timeout: Duration,
+    cache: Option<ResponseCache>,
+    http: reqwest::Client,
+}
+
+impl PinepodsClient {
+    /// `server_name` is the base URL of the server, e.g. `https://pods.example.com`.
+    pub fn new(server_name: impl Into<String>, api_key: Option<String>) -> Self {
+        let server_name = server_name.into().trim_end_matches('/').to_string();
+        PinepodsClient {
+            server_name,
+            api_key,
+            timeout: DEFAULT_TIMEOUT,
+            cache: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// How long to wait for each response before giving up with a `Network` error.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Revalidates GET requests against `cache` instead of always downloading them in full.
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn server_name(&self) -> &str {
+        &self.server_name
+    }
+
+    pub fn api_key(&self) -> Option<&str> {
+        self.api_key.as_deref()
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.server_name, path)
+    }
+
+    // A request carrying the API key, which every /api/data endpoint needs
+    pub(crate) fn request(
+        &self,
+        method: Method,
+        path: &str,
+    ) -> Result<RequestBuilder, ClientError> {
+        let api_key = self.api_key.as_deref().ok_or(ClientError::Unauthorized)?;
+        Ok(self
+            .unauthenticated(method, path)
+            .header("Api-Key", api_key))
+    }
+
+    pub(crate) fn unauthenticated(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, self.url(path))
+            .timeout(self.timeout)
+    }
+
+    // Sends the request and returns the body of a successful response
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<String, ClientError> {
+        let response = request.send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if status.is_success() {
+            Ok(body)
+        } else {
+            Err(ClientError::from_status(status.as_u16(), body))
+        }
+    }
+
+    // Like `send`, but asks the server to skip the body when the cached copy is still current
+    async fn send_cached(&self, request: RequestBuilder) -> Result<String, ClientError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.send(request).await,
+        };
+        let mut request = request.build()?;
+        let url = request.url().to_string();
+        let cached = cache.get(&url);
+        if let Some(cached) = &cached {
+            let validators = [
+                (IF_NONE_MATCH, &cached.etag),
+                (IF_MODIFIED_SINCE, &cached.last_modified),
+            ];
+            for (name, value) in validators {
+                if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                    request.headers_mut().insert(name, value);
+                }
+            }
+        }
+
+        let response = self.http.execute(request).await?;
+        let status = response.status();
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (status, cached) {
+            return Ok(cached.body);
+        }
+        let etag = header(&response, ETAG);
+        let last_modified = header(&response, LAST_MODIFIED);
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(ClientError::from_status(status.as_u16(), body));
+        }
+        if etag.is_some() || last_modified.is_some() {
+            cache.insert(
+                url,
+                CachedResponse {
+                    body: body.clone(),
+                    etag,
+                    last_modified,
+                },
+            );
+        }
+        Ok(body)
+    }
+
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, ClientError> {
+        decode(self.send(request).await?)
+    }
+
+    pub(crate) async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        decode(self.send_cached(self.request(Method::GET, path)?).await?)
+    }
+
+    pub(crate) async fn get_with_query<T: DeserializeOwned, Q: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        query: &Q,
+    ) -> Result<T, ClientError> {
+        decode(
+            self.send_cached(self.request(Method::GET, path)?.query(query))
+                .await?,
+        )
+    }
+
+    pub(crate) async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ClientError> {
+        self.send_json(with_json(self.request(Method::POST, path)?, body)?)
+            .await
+    }
+
+    // For the endpoints that answer with plain text rather than JSON
+    pub(crate) async fn post_for_text<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<String, ClientError> {
+        self.send(with_json(self.request(Method::POST, path)?, body)?)
+            .await
+    }
+
+    pub(crate) async fn put<B: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<(), ClientError> {
+        let request = with_optional_json(self.request(Method::PUT, path)?, body)?;
+        self.send(request).await.map(|_| ())
+    }
+
+    // Like `put`, for the endpoints whose reply is worth reading
+    pub(crate) async fn put_for<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, ClientError> {
+        self.send_json(with_optional_json(self.request(Method::PUT, path)?, body)?)
+            .await
+    }
+
+    pub(crate) async fn delete<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, ClientError> {
+        self.send_json(with_optional_json(
+            self.request(Method::DELETE, path)?,
+            body,
+        )?)
+        .await
+    }
+}
+
+fn decode<T: DeserializeOwned>(body: String) -> Result<T, ClientError> {
+    serde_json::from_str(&body).map_err(|e| {
+        // Enough of the body to tell an HTML error page from a changed API
+        let excerpt: String = body.chars().take(200).collect();
+        ClientError::Decode(format!("{} in {}", e, excerpt))
+    })
+}
+
+fn header(response: &Response, name: HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+pub(crate) fn with_json<B: Serialize + ?Sized>(
+    request: RequestBuilder,
+    body: &B,
+) -> Result<RequestBuilder, ClientError> {
+    Ok(request
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(body)?))
+}
+
+fn with_optional_json<B: Serialize + ?Sized>(
+    request: RequestBuilder,
+    body: Option<&B>,
+) -> Result<RequestBuilder, ClientError> {
+    match body {
+        Some(body) => with_json(request, body),
+        None => Ok(request.header(CONTENT_TYPE, "application/json")),
+    }
+}
+
+/// A slice of a long list: `limit` items, skipping the first `offset`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Page {
+    pub fn first(limit: usize) -> Self {
+        Page { offset: 0, limit }
+    }
+
+    /// The page that follows the `loaded` items already fetched.
+    pub fn after(loaded: usize, limit: usize) -> Self {
+        Page {
+            offset: loaded,
+            limit,
+        }
+    }
+
+    /// Whether a page that came back with `received` items was the last one.
+    pub fn is_last(&self, received: usize) -> bool {
+        received < self.limit
+    }
+}
+
+// Replies that only carry a status message
+#[derive(serde::Deserialize)]
+pub(crate) struct DetailResponse {
+    pub(crate) detail: String,
+}
+
+#[derive(serde::Deserialize)]
+pub(crate) struct DataEnvelope<T> {
+    pub(crate) data: T,
+}
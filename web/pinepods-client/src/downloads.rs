@@ -0,0 +1,81 @@
+use crate::client::{DetailResponse, PinepodsClient};
+use crate::error::ClientError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct EpisodeDownloadResponse {
+    pub episodes: Vec<EpisodeDownload>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[allow(non_snake_case)]
+#[serde(rename_all = "lowercase")]
+pub struct EpisodeDownload {
+    pub episodetitle: String,
+    pub podcastname: String,
+    pub episodepubdate: String,
+    pub episodedescription: String,
+    pub episodeartwork: String,
+    pub episodeurl: String,
+    pub episodeduration: i32,
+    pub listenduration: Option<i32>,
+    pub episodeid: i32,
+    pub downloadedlocation: String,
+    pub podcastid: i32,
+    pub completed: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct DownloadDataResponse {
+    #[serde(rename = "downloaded_episodes")]
+    pub episodes: Vec<EpisodeDownload>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DownloadEpisodeRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DownloadAllPodcastRequest {
+    pub podcast_id: i32,
+    pub user_id: i32,
+}
+
+impl PinepodsClient {
+    /// Episodes the server has downloaded for the user.
+    pub async fn episode_downloads(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<EpisodeDownload>, ClientError> {
+        let response: DownloadDataResponse = self
+            .get_with_query("/api/data/download_episode_list", &[("user_id", user_id)])
+            .await?;
+        Ok(response.episodes)
+    }
+
+    pub async fn download_episode(
+        &self,
+        request: &DownloadEpisodeRequest,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self.post("/api/data/download_podcast", request).await?;
+        Ok(response.detail)
+    }
+
+    pub async fn download_all_podcast(
+        &self,
+        request: &DownloadAllPodcastRequest,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self.post("/api/data/download_all_podcast", request).await?;
+        Ok(response.detail)
+    }
+
+    pub async fn remove_downloaded_episode(
+        &self,
+        request: &DownloadEpisodeRequest,
+    ) -> Result<String, ClientError> {
+        self.post_for_text("/api/data/delete_episode", request)
+            .await
+    }
+}
@@ -0,0 +1,416 @@
+use crate::client::{DataEnvelope, DetailResponse, Page, PinepodsClient};
+use crate::error::ClientError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+#[serde(rename_all = "lowercase")]
+pub struct Episode {
+    pub podcastname: String,
+    pub episodetitle: String,
+    pub episodepubdate: String,
+    pub episodedescription: String,
+    pub episodeartwork: String,
+    pub episodeurl: String,
+    pub episodeduration: i32,
+    pub listenduration: Option<i32>,
+    pub episodeid: i32,
+    pub completed: bool,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct RecentEps {
+    pub episodes: Option<Vec<Episode>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct EpisodeInfo {
+    pub episodetitle: String,
+    pub podcastname: String,
+    pub podcastid: i32,
+    pub episodepubdate: String,
+    pub episodedescription: String,
+    pub episodeartwork: String,
+    pub episodeurl: String,
+    pub episodeduration: i32,
+    pub listenduration: Option<i32>,
+    pub episodeid: i32,
+    pub completed: bool,
+    // Size of the enclosure in bytes as the feed listed it, when it listed one
+    #[serde(default)]
+    pub enclosurelength: Option<i64>,
+    // MIME type of the enclosure as the feed listed it, e.g. audio/mp4
+    #[serde(default)]
+    pub enclosuretype: Option<String>,
+    // The <podcast:chapters> link from the feed
+    #[serde(default)]
+    pub chaptersurl: Option<String>,
+    #[serde(default)]
+    pub season: Option<i32>,
+    #[serde(default)]
+    pub episodenumber: Option<f64>,
+    #[serde(default)]
+    pub episodetype: Option<String>,
+    // The Podcasting 2.0 details of the episode and of its podcast, JSON text the server stores
+    // from the feed
+    #[serde(default)]
+    pub feedextras: Option<String>,
+    #[serde(default)]
+    pub podcastextras: Option<String>,
+}
+
+// The body of most calls that act on one of the user's episodes
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EpisodeRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EpisodeMetadataResponse {
+    pub episode: EpisodeInfo,
+}
+
+#[derive(Deserialize, Debug)]
+struct EpisodeInDbResponse {
+    episode_in_db: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MarkEpisodeCompletedRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Serialize)]
+pub struct RecordListenDurationRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+    pub listen_duration: f64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RecordListenDurationResponse {
+    pub detail: String,
+}
+
+// Saved episodes
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct SavedEpisodesResponse {
+    pub episodes: Vec<SavedEpisode>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[allow(non_snake_case)]
+#[serde(rename_all = "lowercase")]
+pub struct SavedEpisode {
+    pub episodetitle: String,
+    pub podcastname: String,
+    pub episodepubdate: String,
+    pub episodedescription: String,
+    pub episodeartwork: String,
+    pub episodeurl: String,
+    pub episodeduration: i32,
+    pub listenduration: Option<i32>,
+    pub episodeid: i32,
+    pub websiteurl: String,
+    pub completed: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct SavedDataResponse {
+    pub saved_episodes: Vec<SavedEpisode>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SavePodcastRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+}
+
+// History
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct HistoryEpisodesResponse {
+    pub episodes: Vec<HistoryEpisode>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[allow(non_snake_case)]
+#[serde(rename_all = "lowercase")]
+pub struct HistoryEpisode {
+    pub episodetitle: String,
+    pub podcastname: String,
+    pub episodepubdate: String,
+    pub episodedescription: String,
+    pub episodeartwork: String,
+    pub episodeurl: String,
+    pub episodeduration: i32,
+    pub listenduration: Option<i32>,
+    pub episodeid: i32,
+    pub completed: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct HistoryDataResponse {
+    pub data: Vec<HistoryEpisode>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HistoryAddRequest {
+    pub episode_id: i32,
+    pub episode_pos: f32,
+    pub user_id: i32,
+}
+
+// Keys come back capitalized the way the server's capitalize_keys leaves them
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct PodcastEpisode {
+    pub Episodeid: i32,
+    pub Episodetitle: Option<String>,
+    pub Episodedescription: Option<String>,
+    pub Episodepubdate: Option<String>,
+    pub Episodeurl: Option<String>,
+    pub Episodeartwork: Option<String>,
+    pub Episodeduration: Option<i32>,
+    #[serde(default)]
+    pub Listenduration: Option<i32>,
+    #[serde(default)]
+    pub Completed: bool,
+    #[serde(default)]
+    pub Downloaded: bool,
+    #[serde(default)]
+    pub Season: Option<i32>,
+    #[serde(default)]
+    pub Episodenumber: Option<f64>,
+    // "full", "trailer" or "bonus", unset when the feed didn't say
+    #[serde(default)]
+    pub Episodetype: Option<String>,
+    // The Podcasting 2.0 details the server stores from the feed, as JSON text
+    #[serde(default)]
+    pub Feedextras: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PodcastEpisodesResponse {
+    episodes: Option<Vec<PodcastEpisode>>,
+}
+
+/// The order to list an added podcast's episodes in. Episodes without a date or duration go last.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EpisodeOrder {
+    #[default]
+    Newest,
+    Oldest,
+    Longest,
+    Shortest,
+}
+
+/// Episodes the feed gave no type count as full episodes.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EpisodeType {
+    Full,
+    Trailer,
+    Bonus,
+}
+
+/// Which of an added podcast's episodes to list, the default is all of them newest first.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct EpisodeFilter {
+    pub sort: EpisodeOrder,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode_type: Option<EpisodeType>,
+    pub unplayed: bool,
+    pub downloaded: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct PodcastEpisodesQuery<'a> {
+    user_id: i32,
+    podcast_id: i32,
+    limit: usize,
+    offset: usize,
+    #[serde(flatten)]
+    filter: &'a EpisodeFilter,
+}
+
+/// The seasons of an added podcast's full episodes and whether it has trailers or bonus
+/// episodes, for the tabs above its episodes.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PodcastSeasons {
+    pub seasons: Vec<i32>,
+    pub trailers: bool,
+    pub bonus: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct PodcastQuery {
+    user_id: i32,
+    podcast_id: i32,
+}
+
+impl PinepodsClient {
+    /// The newest episodes across every podcast the user follows.
+    pub async fn recent_episodes(&self, user_id: i32) -> Result<Vec<Episode>, ClientError> {
+        let response: RecentEps = self
+            .get(&format!("/api/data/return_episodes/{}", user_id))
+            .await?;
+        Ok(response.episodes.unwrap_or_default())
+    }
+
+    /// One page of [`recent_episodes`](Self::recent_episodes), newest first.
+    pub async fn recent_episodes_page(
+        &self,
+        user_id: i32,
+        page: Page,
+    ) -> Result<Vec<Episode>, ClientError> {
+        let response: RecentEps = self
+            .get_with_query(&format!("/api/data/return_episodes/{}", user_id), &page)
+            .await?;
+        Ok(response.episodes.unwrap_or_default())
+    }
+
+    pub async fn episode_metadata(
+        &self,
+        request: &EpisodeRequest,
+    ) -> Result<EpisodeInfo, ClientError> {
+        let response: EpisodeMetadataResponse =
+            self.post("/api/data/get_episode_metadata", request).await?;
+        Ok(response.episode)
+    }
+
+    pub async fn check_episode_in_db(
+        &self,
+        user_id: i32,
+        episode_title: &str,
+        episode_url: &str,
+    ) -> Result<bool, ClientError> {
+        let response: EpisodeInDbResponse = self
+            .get_with_query(
+                &format!("/api/data/check_episode_in_db/{}", user_id),
+                &[
+                    ("episode_title", episode_title),
+                    ("episode_url", episode_url),
+                ],
+            )
+            .await?;
+        Ok(response.episode_in_db)
+    }
+
+    pub async fn mark_episode_completed(
+        &self,
+        request: &MarkEpisodeCompletedRequest,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .post("/api/data/mark_episode_completed", request)
+            .await?;
+        Ok(response.detail)
+    }
+
+    pub async fn mark_episode_uncompleted(
+        &self,
+        request: &MarkEpisodeCompletedRequest,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .post("/api/data/mark_episode_uncompleted", request)
+            .await?;
+        Ok(response.detail)
+    }
+
+    pub async fn record_listen_duration(
+        &self,
+        request: &RecordListenDurationRequest,
+    ) -> Result<RecordListenDurationResponse, ClientError> {
+        self.post("/api/data/record_listen_duration", request).await
+    }
+
+    pub async fn saved_episodes(&self, user_id: i32) -> Result<Vec<SavedEpisode>, ClientError> {
+        let response: SavedDataResponse = self
+            .get(&format!("/api/data/saved_episode_list/{}", user_id))
+            .await?;
+        Ok(response.saved_episodes)
+    }
+
+    pub async fn save_episode(&self, request: &SavePodcastRequest) -> Result<String, ClientError> {
+        let response: DetailResponse = self.post("/api/data/save_episode", request).await?;
+        Ok(response.detail)
+    }
+
+    pub async fn remove_saved_episode(
+        &self,
+        request: &SavePodcastRequest,
+    ) -> Result<String, ClientError> {
+        self.post_for_text("/api/data/remove_saved_episode", request)
+            .await
+    }
+
+    pub async fn user_history(&self, user_id: i32) -> Result<Vec<HistoryEpisode>, ClientError> {
+        let response: DataEnvelope<Vec<HistoryEpisode>> = self
+            .get(&format!("/api/data/user_history/{}", user_id))
+            .await?;
+        Ok(response.data)
+    }
+
+    /// One page of the user's history, most recently played first.
+    pub async fn user_history_page(
+        &self,
+        user_id: i32,
+        page: Page,
+    ) -> Result<Vec<HistoryEpisode>, ClientError> {
+        let response: DataEnvelope<Vec<HistoryEpisode>> = self
+            .get_with_query(&format!("/api/data/user_history/{}", user_id), &page)
+            .await?;
+        Ok(response.data)
+    }
+
+    pub async fn add_history(&self, request: &HistoryAddRequest) -> Result<(), ClientError> {
+        self.post_for_text("/api/data/record_podcast_history", request)
+            .await
+            .map(|_| ())
+    }
+
+    /// One page of the added podcast's episodes that pass `filter`, in its order.
+    pub async fn podcast_episodes(
+        &self,
+        user_id: i32,
+        podcast_id: i32,
+        filter: &EpisodeFilter,
+        page: Page,
+    ) -> Result<Vec<PodcastEpisode>, ClientError> {
+        let response: PodcastEpisodesResponse = self
+            .get_with_query(
+                "/api/data/podcast_episodes",
+                &PodcastEpisodesQuery {
+                    user_id,
+                    podcast_id,
+                    limit: page.limit,
+                    offset: page.offset,
+                    filter,
+                },
+            )
+            .await?;
+        Ok(response.episodes.unwrap_or_default())
+    }
+
+    pub async fn podcast_seasons(
+        &self,
+        user_id: i32,
+        podcast_id: i32,
+    ) -> Result<PodcastSeasons, ClientError> {
+        self.get_with_query(
+            "/api/data/podcast_seasons",
+            &PodcastQuery {
+                user_id,
+                podcast_id,
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,89 @@
+use serde::Deserialize;
+use std::fmt;
+
+/// Everything that can go wrong talking to a PinePods server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientError {
+    /// No API key was set, or the server rejected it (401/403)
+    Unauthorized,
+    /// The endpoint or the item asked for doesn't exist (404)
+    NotFound,
+    /// Any other non-success status, with the response body
+    Server(u16, String),
+    /// The response didn't have the shape we expected
+    Decode(String),
+    /// The request never got a response
+    Network(String),
+}
+
+// FastAPI wraps error messages as {"detail": "..."}
+#[derive(Deserialize)]
+struct ErrorDetail {
+    detail: String,
+}
+
+impl ClientError {
+    pub(crate) fn from_status(status: u16, body: String) -> Self {
+        match status {
+            401 | 403 => ClientError::Unauthorized,
+            404 => ClientError::NotFound,
+            _ => ClientError::Server(status, body),
+        }
+    }
+
+    /// The status code the server answered with, if it answered at all.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Unauthorized => Some(401),
+            ClientError::NotFound => Some(404),
+            ClientError::Server(status, _) => Some(*status),
+            ClientError::Decode(_) | ClientError::Network(_) => None,
+        }
+    }
+
+    /// Whether the same request might work if sent again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::Network(_) => true,
+            ClientError::Server(status, _) => *status >= 500 || *status == 408,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Unauthorized => {
+                write!(f, "Not authorized, the API key is missing or invalid")
+            }
+            ClientError::NotFound => write!(f, "Not found"),
+            ClientError::Server(status, body) => match serde_json::from_str::<ErrorDetail>(body) {
+                Ok(error) => write!(f, "Server error ({}): {}", status, error.detail),
+                Err(_) => write!(f, "Server error ({}): {}", status, body),
+            },
+            ClientError::Decode(message) => write!(f, "Failed to read response: {}", message),
+            ClientError::Network(message) => write!(f, "Network error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_decode() {
+            ClientError::Decode(error.to_string())
+        } else if error.is_timeout() {
+            ClientError::Network("the server took too long to respond".to_string())
+        } else {
+            ClientError::Network(error.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        ClientError::Decode(error.to_string())
+    }
+}
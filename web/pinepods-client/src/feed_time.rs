@@ -0,0 +1,606 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+// Feeds are supposed to use RFC 2822 dates and HH:MM:SS durations, in practice they use
+// whatever their CMS printed. These accept what shows up in real feeds.
+
+/// Parses a feed duration into whole seconds.
+///
+/// Accepts `HH:MM:SS`, `MM:SS` and plain seconds with fractional seconds and surrounding
+/// whitespace, minutes or seconds past 59 (`1:75:00`), ISO 8601 durations (`PT1H2M3S`) and
+/// spelled out units (`45 min`, `1h 30m`). A zero duration is returned as `Some(0)`, feeds
+/// write that when they don't know.
+pub fn parse_duration(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let seconds = clock_seconds(value)
+        .or_else(|| iso_duration_seconds(value))
+        .or_else(|| unit_seconds(value))?;
+    (seconds.is_finite() && seconds >= 0.0 && seconds < u32::MAX as f64).then_some(seconds as u32)
+}
+
+/// Formats seconds the way feeds write durations, `HH:MM:SS`.
+pub fn format_duration(seconds: u32) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// Guesses a duration from the enclosure's size in bytes and type, for feeds that leave the
+/// duration out. Podcasts are nearly always encoded at a constant bitrate, so this lands within
+/// a few minutes. Returns `None` for video, whose bitrates vary too much to guess, and for the
+/// placeholder lengths like 0 or 1 that many feeds write.
+pub fn estimate_duration(length_bytes: u64, mime_type: Option<&str>) -> Option<u32> {
+    if length_bytes < 100_000 {
+        return None;
+    }
+    let mime_type = mime_type
+        .unwrap_or("audio/mpeg")
+        .trim()
+        .to_ascii_lowercase();
+    let kbps: u64 = match mime_type.as_str() {
+        "" | "audio/mpeg" | "audio/mp3" | "audio/mpeg3" | "audio/x-mpeg" | "audio/x-mp3" => 128,
+        "audio/aac" | "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/aacp" => 96,
+        "audio/ogg" | "audio/opus" | "audio/webm" => 64,
+        _ => return None,
+    };
+    Some((length_bytes * 8 / (kbps * 1000)) as u32)
+}
+
+/// Parses a feed date.
+///
+/// Besides strict RFC 2822 and RFC 3339 this takes RFC 2822 style dates with a wrong or missing
+/// weekday, full day and month names, missing seconds, two digit years, AM/PM times and named
+/// zones like `EST` or `CEST`, plus ISO 8601 dates with or without a time or zone. Dates
+/// without a zone are taken as UTC.
+pub fn parse_feed_date(value: &str) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .or_else(|| DateTime::parse_from_rfc3339(value).ok())
+        .or_else(|| parse_iso_like(value))
+        .or_else(|| parse_rfc2822_like(value))
+}
+
+// A number with an optional decimal part, no signs, exponents or infinities
+fn decimal(value: &str) -> Option<f64> {
+    let value = value.replace(',', ".");
+    let mut parts = value.splitn(2, '.');
+    let whole = parts.next()?;
+    let fraction = parts.next().unwrap_or("0");
+    let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() || !digits(whole) || !digits(fraction) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn clock_seconds(value: &str) -> Option<f64> {
+    let parts: Vec<&str> = value.split(':').map(str::trim).collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let (last, leading) = parts.split_last()?;
+    let mut seconds = 0.0;
+    for part in leading {
+        // Only the last part can have a fraction
+        if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds * 60.0 + decimal(last)?)
+}
+
+// PT1H2M3S, P1DT2H, PT45.5S
+fn iso_duration_seconds(value: &str) -> Option<f64> {
+    let value = value.to_ascii_uppercase();
+    let rest = value.strip_prefix('P')?;
+    let mut seconds = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut any = false;
+    for c in rest.chars() {
+        match c {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(c),
+            unit => {
+                let amount = decimal(&number)?;
+                number.clear();
+                seconds += amount
+                    * match (in_time, unit) {
+                        (false, 'W') => 604_800.0,
+                        (false, 'D') => 86_400.0,
+                        (true, 'H') => 3600.0,
+                        (true, 'M') => 60.0,
+                        (true, 'S') => 1.0,
+                        _ => return None,
+                    };
+                any = true;
+            }
+        }
+    }
+    (any && number.is_empty()).then_some(seconds)
+}
+
+// 45 min, 1 hr 30 mins, 1h30m, 2 hours and 5 minutes
+fn unit_seconds(value: &str) -> Option<f64> {
+    let value = value.to_ascii_lowercase();
+    let mut seconds = 0.0;
+    let mut any = false;
+    let mut rest = value.as_str();
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        rest = rest.strip_prefix("and ").unwrap_or(rest).trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+            .unwrap_or(rest.len());
+        let amount = decimal(&rest[..number_end])?;
+        rest = rest[number_end..].trim_start();
+        let unit_end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let unit = match &rest[..unit_end] {
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60.0,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            _ => return None,
+        };
+        rest = &rest[unit_end..];
+        seconds += amount * unit;
+        any = true;
+    }
+    any.then_some(seconds)
+}
+
+fn parse_iso_like(value: &str) -> Option<DateTime<FixedOffset>> {
+    let bytes = value.as_bytes();
+    if bytes.len() < 10 || !bytes[..4].iter().all(u8::is_ascii_digit) || bytes[4] != b'-' {
+        return None;
+    }
+    let utc = FixedOffset::east_opt(0)?;
+    // A trailing Z or UTC is the same as +00:00
+    let value = match value
+        .strip_suffix('Z')
+        .or_else(|| value.strip_suffix(" UTC"))
+        .or_else(|| value.strip_suffix(" GMT"))
+    {
+        Some(rest) => format!("{}+00:00", rest.trim_end()),
+        None => value.to_string(),
+    };
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f%#z",
+        "%Y-%m-%d %H:%M:%S%.f%#z",
+        "%Y-%m-%d %H:%M:%S%.f %#z",
+        "%Y-%m-%dT%H:%M%#z",
+        "%Y-%m-%d %H:%M%#z",
+    ] {
+        if let Ok(date) = DateTime::parse_from_str(&value, format) {
+            return Some(date);
+        }
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(&value, format) {
+            return utc.from_local_datetime(&date).single();
+        }
+    }
+    let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()?;
+    utc.from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .single()
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+// Matches Jan, January and Sept, but not Janus
+fn month_number(token: &str) -> Option<u32> {
+    let token = token.trim_end_matches('.').to_ascii_lowercase();
+    if token == "sept" {
+        return Some(9);
+    }
+    MONTHS
+        .iter()
+        .position(|month| token.len() >= 3 && month.starts_with(token.as_str()))
+        .filter(|index| token.len() == 3 || token == MONTHS[*index])
+        .map(|index| index as u32 + 1)
+}
+
+fn is_weekday(token: &str) -> bool {
+    let token = token.trim_end_matches('.').to_ascii_lowercase();
+    token.len() >= 3
+        && token.bytes().all(|b| b.is_ascii_alphabetic())
+        && WEEKDAYS.iter().any(|day| token.starts_with(day))
+        && (token.len() == 3
+            || token.ends_with("day")
+            || token == "tues"
+            || token == "thur"
+            || token == "thurs")
+}
+
+// Offsets in seconds east of UTC for the zone names feeds use. RFC 2822 only allows the US ones,
+// the rest turn up anyway.
+fn named_zone(name: &str) -> Option<i32> {
+    let hours = match name.to_ascii_uppercase().as_str() {
+        "GMT" | "UT" | "UTC" | "Z" | "WET" => 0.0,
+        "BST" | "CET" | "WEST" | "MET" => 1.0,
+        "CEST" | "EET" | "MEST" | "SAST" | "CAT" => 2.0,
+        "EEST" | "MSK" | "EAT" => 3.0,
+        "IST" => 5.5,
+        "SGT" | "HKT" | "AWST" | "PHT" => 8.0,
+        "JST" | "KST" => 9.0,
+        "ACST" => 9.5,
+        "AEST" => 10.0,
+        "ACDT" => 10.5,
+        "AEDT" => 11.0,
+        "NZST" => 12.0,
+        "NZDT" => 13.0,
+        "NST" => -3.5,
+        "NDT" => -2.5,
+        "AST" => -4.0,
+        "ADT" => -3.0,
+        "EST" => -5.0,
+        "EDT" => -4.0,
+        "CST" => -6.0,
+        "CDT" => -5.0,
+        "MST" => -7.0,
+        "MDT" => -6.0,
+        "PST" => -8.0,
+        "PDT" => -7.0,
+        "AKST" => -9.0,
+        "AKDT" => -8.0,
+        "HST" => -10.0,
+        _ => return None,
+    };
+    Some((hours * 3600.0) as i32)
+}
+
+// +0100, -05:00, +1, GMT+2, UTC-05:30 or a zone name
+fn zone_offset(token: &str) -> Option<i32> {
+    if let Some(offset) = named_zone(token) {
+        return Some(offset);
+    }
+    let upper = token.to_ascii_uppercase();
+    let numeric = upper
+        .strip_prefix("GMT")
+        .or_else(|| upper.strip_prefix("UTC"))
+        .unwrap_or(&upper);
+    let (sign, digits) = match numeric.as_bytes().first()? {
+        b'+' => (1, &numeric[1..]),
+        b'-' => (-1, &numeric[1..]),
+        _ => return None,
+    };
+    let digits: String = digits.chars().filter(|c| *c != ':').collect();
+    if digits.is_empty() || digits.len() > 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        1 | 2 => (digits.parse::<i32>().ok()?, 0),
+        3 => (
+            digits[..1].parse::<i32>().ok()?,
+            digits[1..].parse::<i32>().ok()?,
+        ),
+        _ => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+    };
+    (hours <= 14 && minutes < 60).then_some(sign * (hours * 3600 + minutes * 60))
+}
+
+// 10:00, 10:00:00 or 10:00:00.5, seconds of 60 are leap seconds and get clamped
+fn clock_time(token: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = token.split(':');
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next()?.parse().ok()?;
+    let second = match parts.next() {
+        Some(second) => decimal(second)? as u32,
+        None => 0,
+    };
+    if parts.next().is_some() || hour > 24 || minute > 59 || second > 60 {
+        return None;
+    }
+    Some((hour, minute, second.min(59)))
+}
+
+fn parse_rfc2822_like(value: &str) -> Option<DateTime<FixedOffset>> {
+    let mut day = None;
+    let mut month = None;
+    let mut year = None;
+    let mut time = None;
+    let mut pm = None;
+    let mut offset = None;
+
+    let cleaned = value.replace(['(', ')'], " ");
+    let mut tokens: Vec<String> = Vec::new();
+    for token in cleaned.split(|c: char| c.is_whitespace() || c == ',') {
+        if token.is_empty() {
+            continue;
+        }
+        // 05-Mar-2024
+        if token.contains('-')
+            && token.bytes().any(|b| b.is_ascii_alphabetic())
+            && !token.starts_with('-')
+        {
+            let upper = token.to_ascii_uppercase();
+            if !upper.starts_with("GMT") && !upper.starts_with("UTC") {
+                tokens.extend(token.split('-').map(str::to_string));
+                continue;
+            }
+        }
+        // 10:00:00+0100 with the zone stuck to the time
+        if token.contains(':') && token.starts_with(|c: char| c.is_ascii_digit()) {
+            if let Some(split) = token[1..].find(['+', '-']).map(|index| index + 1) {
+                tokens.push(token[..split].to_string());
+                tokens.push(token[split..].to_string());
+                continue;
+            }
+        }
+        tokens.push(token.to_string());
+    }
+
+    for token in &tokens {
+        let lower = token.to_ascii_lowercase();
+        if lower == "am" || lower == "a.m." {
+            pm = Some(false);
+        } else if lower == "pm" || lower == "p.m." {
+            pm = Some(true);
+        } else if token.contains(':') && time.is_none() && !token.starts_with(['+', '-']) {
+            time = Some(clock_time(token)?);
+        } else if let Some(number) = month_number(token).filter(|_| month.is_none()) {
+            month = Some(number);
+        } else if is_weekday(token) {
+            continue;
+        } else if token.bytes().all(|b| b.is_ascii_digit()) {
+            let number: i32 = token.parse().ok()?;
+            if token.len() <= 2 && day.is_none() && year.is_none() {
+                day = Some(number as u32);
+            } else if year.is_none() {
+                year = Some(match (token.len(), number) {
+                    (2, number) if number < 50 => 2000 + number,
+                    (2, number) => 1900 + number,
+                    _ => number,
+                });
+            } else if day.is_none() && token.len() <= 2 {
+                day = Some(number as u32);
+            } else {
+                return None;
+            }
+        } else if let Some(zone) = zone_offset(token).filter(|_| offset.is_none()) {
+            offset = Some(zone);
+        } else {
+            return None;
+        }
+    }
+
+    let (mut hour, minute, second) = time.unwrap_or((0, 0, 0));
+    match pm {
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    let date = NaiveDate::from_ymd_opt(year?, month?, day?)?;
+    let naive = if hour == 24 {
+        date.succ_opt()?.and_hms_opt(0, minute, second)?
+    } else {
+        date.and_hms_opt(hour, minute, second)?
+    };
+    FixedOffset::east_opt(offset.unwrap_or(0))?
+        .from_local_datetime(&naive)
+        .single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Durations copied from real feeds
+    #[test]
+    fn durations() {
+        let cases: &[(&str, Option<u32>)] = &[
+            ("1:02:03", Some(3723)),
+            ("01:02:03", Some(3723)),
+            ("62:03", Some(3723)),
+            ("3723", Some(3723)),
+            (" 3723 \n", Some(3723)),
+            ("3600.0", Some(3600)),
+            ("1:02:03.5", Some(3723)),
+            ("00:45:00.000", Some(2700)),
+            ("45:00,5", Some(2700)),
+            ("1:75:00", Some(8100)),
+            ("90:00", Some(5400)),
+            ("0:00:90", Some(90)),
+            ("PT1H2M3S", Some(3723)),
+            ("PT45M", Some(2700)),
+            ("pt3723.5s", Some(3723)),
+            ("P0DT1H", Some(3600)),
+            ("45 min", Some(2700)),
+            ("45 mins", Some(2700)),
+            ("1 hr 30 min", Some(5400)),
+            ("1h30m", Some(5400)),
+            ("2 hours and 5 minutes", Some(7500)),
+            ("90 Minutes", Some(5400)),
+            ("0", Some(0)),
+            ("00:00:00", Some(0)),
+            ("", None),
+            ("   ", None),
+            ("abc", None),
+            ("1:2:3:4", None),
+            ("-5", None),
+            ("1:-2", None),
+            ("NaN", None),
+            ("inf", None),
+            ("1e3", None),
+            ("12 apples", None),
+            ("P", None),
+            ("PT", None),
+            ("1.2.3", None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(parse_duration(value), *expected, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(0), "00:00:00");
+        assert_eq!(format_duration(3723), "01:02:03");
+        assert_eq!(format_duration(400_000), "111:06:40");
+    }
+
+    #[test]
+    fn estimates_from_enclosure_size() {
+        // An hour at 128 kbit/s
+        assert_eq!(
+            estimate_duration(57_600_000, Some("audio/mpeg")),
+            Some(3600)
+        );
+        assert_eq!(estimate_duration(57_600_000, None), Some(3600));
+        assert_eq!(
+            estimate_duration(43_200_000, Some("audio/x-m4a")),
+            Some(3600)
+        );
+        assert_eq!(estimate_duration(28_800_000, Some("Audio/Ogg")), Some(3600));
+        assert_eq!(estimate_duration(0, Some("audio/mpeg")), None);
+        assert_eq!(estimate_duration(1, None), None);
+        assert_eq!(estimate_duration(500_000_000, Some("video/mp4")), None);
+    }
+
+    fn utc(value: &str) -> Option<String> {
+        parse_feed_date(value).map(|date| date.naive_utc().format("%Y-%m-%d %H:%M:%S").to_string())
+    }
+
+    // pubDates copied from real feeds
+    #[test]
+    fn dates() {
+        let cases: &[(&str, Option<&str>)] = &[
+            (
+                "Tue, 05 Mar 2024 10:00:00 +0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            ("Tue, 05 Mar 2024 10:00:00 GMT", Some("2024-03-05 10:00:00")),
+            ("Tue, 5 Mar 2024 10:00:00 EST", Some("2024-03-05 15:00:00")),
+            ("Tue, 05 Mar 2024 10:00:00 PDT", Some("2024-03-05 17:00:00")),
+            // Wrong weekday
+            (
+                "Mon, 05 Mar 2024 10:00:00 +0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            (
+                "Tuesday, 05 March 2024 10:00:00 +0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            (
+                "Tues, 05 Mar 2024 10:00:00 +0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            ("05 Mar 2024 10:00:00 +0000", Some("2024-03-05 10:00:00")),
+            ("Tue, 05 Mar 2024 10:00 +0000", Some("2024-03-05 10:00:00")),
+            ("Tue, 05 Mar 2024 10:00:00", Some("2024-03-05 10:00:00")),
+            (
+                "Tue, 05 Mar 2024 10:00:00 CEST",
+                Some("2024-03-05 08:00:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:00 AEDT",
+                Some("2024-03-04 23:00:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:00 +01:00",
+                Some("2024-03-05 09:00:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:00 GMT+1",
+                Some("2024-03-05 09:00:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:00 UTC-05:30",
+                Some("2024-03-05 15:30:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:00 +0000 (UTC)",
+                Some("2024-03-05 10:00:00"),
+            ),
+            ("Tue, 05 Mar 24 10:00:00 +0000", Some("2024-03-05 10:00:00")),
+            (
+                "Thu, 05 Sept 2024 10:00:00 +0000",
+                Some("2024-09-05 10:00:00"),
+            ),
+            (
+                "Tue,05 Mar 2024 10:00:00 +0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:00+0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 24:00:00 +0000",
+                Some("2024-03-06 00:00:00"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:60 CEST",
+                Some("2024-03-05 08:00:59"),
+            ),
+            (
+                "Tue, 05 Mar 2024 10:00:00.250 +0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            (
+                "Tue,  05  Mar  2024  10:00:00  +0000",
+                Some("2024-03-05 10:00:00"),
+            ),
+            ("05-Mar-2024 10:00:00 +0000", Some("2024-03-05 10:00:00")),
+            ("March 5, 2024 3:00 PM", Some("2024-03-05 15:00:00")),
+            ("Mar 5 2024 12:30 am EST", Some("2024-03-05 05:30:00")),
+            ("2024-03-05T10:00:00Z", Some("2024-03-05 10:00:00")),
+            ("2024-03-05T10:00:00.123Z", Some("2024-03-05 10:00:00")),
+            ("2024-03-05T10:00:00+01:00", Some("2024-03-05 09:00:00")),
+            ("2024-03-05T10:00:00+0100", Some("2024-03-05 09:00:00")),
+            ("2024-03-05T10:00:00-05", Some("2024-03-05 15:00:00")),
+            ("2024-03-05T10:00+01:00", Some("2024-03-05 09:00:00")),
+            ("2024-03-05T10:00:00", Some("2024-03-05 10:00:00")),
+            ("2024-03-05 10:00:00", Some("2024-03-05 10:00:00")),
+            ("2024-03-05 10:00:00 UTC", Some("2024-03-05 10:00:00")),
+            ("2024-03-05 10:00:00 +0100", Some("2024-03-05 09:00:00")),
+            ("2024-03-05T10:00", Some("2024-03-05 10:00:00")),
+            ("2024-03-05", Some("2024-03-05 00:00:00")),
+            ("", None),
+            ("yesterday", None),
+            ("Tue, 32 Mar 2024 10:00:00 +0000", None),
+            ("Tue, 05 Foo 2024 10:00:00 +0000", None),
+            ("Tue, 05 Mar 2024 10:61:00 +0000", None),
+            ("2024-13-05T10:00:00Z", None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(utc(value).as_deref(), *expected, "{:?}", value);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! A typed client for the PinePods server API.
+//!
+//! The web app uses it from the browser and it works the same from native Rust, so scripts and
+//! tools can talk to a server without reimplementing each call:
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), pinepods_client::ClientError> {
+//! let client = pinepods_client::PinepodsClient::new("https://pods.example.com", None)
+//!     .with_api_key("my-api-key");
+//! let user_id = client.user_id().await?.retrieved_id.unwrap_or_default();
+//! for episode in client.queued_episodes(user_id).await? {
+//!     println!("{}: {}", episode.podcastname, episode.episodetitle);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod admin;
+mod auth;
+mod bookmarks;
+mod cache;
+mod client;
+mod downloads;
+mod episodes;
+mod error;
+mod feed_time;
+mod feeds;
+mod podcasts;
+mod queue;
+mod search;
+mod settings;
+mod sync;
+mod user;
+
+pub use admin::*;
+pub use auth::*;
+pub use bookmarks::*;
+pub use cache::ResponseCache;
+pub use client::{Page, PinepodsClient};
+pub use downloads::*;
+pub use episodes::*;
+pub use error::ClientError;
+pub use feed_time::{estimate_duration, format_duration, parse_duration, parse_feed_date};
+pub use feeds::*;
+pub use podcasts::*;
+pub use queue::*;
+pub use search::*;
+pub use settings::*;
+pub use sync::*;
+pub use user::*;
@@ -0,0 +1,387 @@
+use crate::client::{DetailResponse, PinepodsClient};
+use crate::error::ClientError;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+// MySQL hands booleans back as 0/1
+fn bool_from_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct BoolOrIntVisitor;
+
+    impl<'de> serde::de::Visitor<'de> for BoolOrIntVisitor {
+        type Value = bool;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a boolean or an integer")
+        }
+
+        fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+            Ok(value)
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(value != 0)
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(value != 0)
+        }
+    }
+
+    deserializer.deserialize_any(BoolOrIntVisitor)
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct PodcastResponse {
+    pub pods: Option<Vec<Podcast>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+#[serde(rename_all = "lowercase")]
+pub struct Podcast {
+    pub podcastid: i32,
+    pub podcastname: String,
+    pub artworkurl: Option<String>,
+    pub description: Option<String>,
+    pub episodecount: i32,
+    pub websiteurl: Option<String>,
+    pub feedurl: String,
+    pub author: Option<String>,
+    pub categories: String, // Keeping as String since it's handled as empty string "{}" or "{}"
+    #[serde(deserialize_with = "bool_from_int")]
+    pub explicit: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PodcastValues {
+    pub pod_title: String,
+    pub pod_artwork: String,
+    pub pod_author: String,
+    pub categories: HashMap<String, String>,
+    pub pod_description: String,
+    pub pod_episode_count: i32,
+    pub pod_feed_url: String,
+    pub pod_website: String,
+    pub pod_explicit: bool,
+    pub user_id: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemovePodcastValues {
+    pub podcast_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemovePodcastValuesName {
+    pub user_id: i32,
+    pub podcast_name: String,
+    pub podcast_url: String,
+}
+
+#[derive(Deserialize)]
+struct PodcastStatusResponse {
+    success: bool,
+}
+
+#[derive(Default, Deserialize, Debug)]
+pub struct CheckPodcastResponse {
+    pub exists: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct PodcastIdResponse {
+    pub episodes: i32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct PodcastIdEpResponse {
+    pub podcast_id: i32,
+}
+
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct PodcastDetails {
+    pub podcastid: i32,
+    pub podcastname: String,
+    pub artworkurl: String,
+    pub author: String,
+    pub categories: String,
+    pub description: String,
+    pub episodecount: i32,
+    pub feedurl: String,
+    pub websiteurl: String,
+    pub explicit: bool,
+    pub userid: i32,
+    // Whether the feed was added with credentials, the credentials themselves stay on the server
+    #[serde(default)]
+    pub feedauth: bool,
+    // The Podcasting 2.0 details the server stores from the feed, as JSON text
+    #[serde(default)]
+    pub feedextras: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PodcastDetailsResponse {
+    details: PodcastDetails,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AutoDownloadRequest {
+    pub podcast_id: i32,
+    pub user_id: i32,
+    pub auto_download: bool,
+}
+
+// The body of every per-podcast settings lookup
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PodcastSettingsRequest {
+    pub podcast_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AutoDownloadStatusResponse {
+    pub auto_download: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SkipTimesRequest {
+    pub podcast_id: i32,
+    pub start_skip: i32,
+    pub end_skip: i32,
+    pub user_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AutoSkipTimesResponse {
+    pub start_skip: i32,
+    pub end_skip: i32,
+}
+
+// Per-podcast playback defaults applied whenever an episode from the podcast starts
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlaybackProfile {
+    pub playback_speed: f64,
+    pub volume_gain: f64,
+    pub skip_forward: i32,
+    pub skip_backward: i32,
+    // Speeds through silent stretches
+    #[serde(default)]
+    pub smart_speed: bool,
+}
+
+impl Default for PlaybackProfile {
+    fn default() -> Self {
+        PlaybackProfile {
+            playback_speed: 1.0,
+            volume_gain: 1.0,
+            skip_forward: 15,
+            skip_backward: 15,
+            smart_speed: false,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct PlaybackProfileRequest {
+    pub podcast_id: i32,
+    pub user_id: i32,
+    #[serde(flatten)]
+    pub profile: PlaybackProfile,
+}
+
+impl PinepodsClient {
+    /// Every podcast the user is subscribed to.
+    pub async fn podcasts(&self, user_id: i32) -> Result<Vec<Podcast>, ClientError> {
+        let response: PodcastResponse = self
+            .get(&format!("/api/data/return_pods/{}", user_id))
+            .await?;
+        Ok(response.pods.unwrap_or_default())
+    }
+
+    pub async fn add_podcast(&self, podcast: &PodcastValues) -> Result<bool, ClientError> {
+        let response: PodcastStatusResponse = self.post("/api/data/add_podcast", podcast).await?;
+        Ok(response.success)
+    }
+
+    pub async fn remove_podcast(&self, request: &RemovePodcastValues) -> Result<bool, ClientError> {
+        let response: PodcastStatusResponse =
+            self.post("/api/data/remove_podcast_id", request).await?;
+        Ok(response.success)
+    }
+
+    pub async fn remove_podcast_by_name(
+        &self,
+        request: &RemovePodcastValuesName,
+    ) -> Result<bool, ClientError> {
+        let response: PodcastStatusResponse =
+            self.post("/api/data/remove_podcast", request).await?;
+        Ok(response.success)
+    }
+
+    /// Whether the user is already subscribed to the feed.
+    pub async fn check_podcast(
+        &self,
+        user_id: i32,
+        podcast_name: &str,
+        podcast_url: &str,
+    ) -> Result<CheckPodcastResponse, ClientError> {
+        self.get_with_query(
+            "/api/data/check_podcast",
+            &[
+                ("user_id", user_id.to_string().as_str()),
+                ("podcast_name", podcast_name),
+                ("podcast_url", podcast_url),
+            ],
+        )
+        .await
+    }
+
+    pub async fn podcast_id(
+        &self,
+        user_id: i32,
+        podcast_feed: &str,
+        podcast_title: &str,
+    ) -> Result<i32, ClientError> {
+        let response: PodcastIdResponse = self
+            .get_with_query(
+                "/api/data/get_podcast_id",
+                &[
+                    ("user_id", user_id.to_string().as_str()),
+                    ("podcast_feed", podcast_feed),
+                    ("podcast_title", podcast_title),
+                ],
+            )
+            .await?;
+        Ok(response.episodes)
+    }
+
+    pub async fn podcast_id_from_episode(
+        &self,
+        episode_id: i32,
+        user_id: i32,
+    ) -> Result<i32, ClientError> {
+        let response: PodcastIdEpResponse = self
+            .get_with_query(
+                "/api/data/get_podcast_id_from_ep_id",
+                &[("episode_id", episode_id), ("user_id", user_id)],
+            )
+            .await?;
+        Ok(response.podcast_id)
+    }
+
+    pub async fn podcast_id_from_episode_name(
+        &self,
+        episode_name: &str,
+        episode_url: &str,
+        user_id: i32,
+    ) -> Result<i32, ClientError> {
+        let response: PodcastIdEpResponse = self
+            .get_with_query(
+                "/api/data/get_podcast_id_from_ep_name",
+                &[
+                    ("episode_name", episode_name),
+                    ("episode_url", episode_url),
+                    ("user_id", user_id.to_string().as_str()),
+                ],
+            )
+            .await?;
+        Ok(response.podcast_id)
+    }
+
+    pub async fn podcast_details(
+        &self,
+        user_id: i32,
+        podcast_id: i32,
+    ) -> Result<PodcastDetails, ClientError> {
+        let response: PodcastDetailsResponse = self
+            .get_with_query(
+                "/api/data/get_podcast_details",
+                &[("user_id", user_id), ("podcast_id", podcast_id)],
+            )
+            .await?;
+        Ok(response.details)
+    }
+
+    pub async fn enable_auto_download(
+        &self,
+        request: &AutoDownloadRequest,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self.post("/api/data/enable_auto_download", request).await?;
+        Ok(response.detail)
+    }
+
+    pub async fn auto_download_status(
+        &self,
+        user_id: i32,
+        podcast_id: i32,
+    ) -> Result<bool, ClientError> {
+        let response: AutoDownloadStatusResponse = self
+            .post(
+                "/api/data/get_auto_download_status",
+                &PodcastSettingsRequest {
+                    podcast_id,
+                    user_id,
+                },
+            )
+            .await?;
+        Ok(response.auto_download)
+    }
+
+    pub async fn adjust_skip_times(
+        &self,
+        request: &SkipTimesRequest,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self.post("/api/data/adjust_skip_times", request).await?;
+        Ok(response.detail)
+    }
+
+    pub async fn auto_skip_times(
+        &self,
+        user_id: i32,
+        podcast_id: i32,
+    ) -> Result<AutoSkipTimesResponse, ClientError> {
+        self.post(
+            "/api/data/get_auto_skip_times",
+            &PodcastSettingsRequest {
+                podcast_id,
+                user_id,
+            },
+        )
+        .await
+    }
+
+    pub async fn adjust_playback_profile(
+        &self,
+        request: &PlaybackProfileRequest,
+    ) -> Result<String, ClientError> {
+        let response: DetailResponse = self
+            .post("/api/data/adjust_playback_profile", request)
+            .await?;
+        Ok(response.detail)
+    }
+
+    pub async fn playback_profile(
+        &self,
+        user_id: i32,
+        podcast_id: i32,
+    ) -> Result<PlaybackProfile, ClientError> {
+        self.post(
+            "/api/data/get_playback_profile",
+            &PodcastSettingsRequest {
+                podcast_id,
+                user_id,
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,123 @@
+use crate::client::{DataEnvelope, PinepodsClient};
+use crate::error::ClientError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QueuePodcastRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+struct QueueResponse {
+    data: String,
+}
+
+// Where `queue_episode_at` puts an episode. An episode that's already queued is moved.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct QueuePositionRequest {
+    pub episode_id: i32,
+    pub user_id: i32,
+    // 1-based position in the queue, None for the end
+    pub position: Option<i32>,
+    // Takes precedence over `position`, used for "play next"
+    pub after_episode_id: Option<i32>,
+}
+
+impl QueuePositionRequest {
+    pub fn play_next(episode_id: i32, user_id: i32, playing_episode_id: Option<i32>) -> Self {
+        QueuePositionRequest {
+            episode_id,
+            user_id,
+            position: if playing_episode_id.is_none() {
+                Some(1)
+            } else {
+                None
+            },
+            after_episode_id: playing_episode_id,
+        }
+    }
+
+    pub fn play_last(episode_id: i32, user_id: i32) -> Self {
+        QueuePositionRequest {
+            episode_id,
+            user_id,
+            position: None,
+            after_episode_id: None,
+        }
+    }
+
+    pub fn move_to(episode_id: i32, user_id: i32, position: i32) -> Self {
+        QueuePositionRequest {
+            episode_id,
+            user_id,
+            position: Some(position),
+            after_episode_id: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct QueuePositionResponse {
+    position: i32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct QueuedEpisodesResponse {
+    pub episodes: Vec<QueuedEpisode>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[allow(non_snake_case)]
+#[serde(rename_all = "lowercase")]
+pub struct QueuedEpisode {
+    pub episodetitle: String,
+    pub podcastname: String,
+    pub episodepubdate: String,
+    pub episodedescription: String,
+    pub episodeartwork: String,
+    pub episodeurl: String,
+    #[serde(default)]
+    pub queueposition: Option<i32>,
+    pub episodeduration: i32,
+    pub queuedate: String,
+    pub listenduration: Option<i32>,
+    pub episodeid: i32,
+    pub completed: bool,
+}
+
+impl PinepodsClient {
+    /// Adds the episode to the end of the queue and returns the server's message.
+    pub async fn queue_episode(
+        &self,
+        request: &QueuePodcastRequest,
+    ) -> Result<String, ClientError> {
+        let response: QueueResponse = self.post("/api/data/queue_pod", request).await?;
+        Ok(response.data)
+    }
+
+    /// Returns the 1-based position the episode ended up at.
+    pub async fn queue_episode_at(
+        &self,
+        request: &QueuePositionRequest,
+    ) -> Result<i32, ClientError> {
+        let response: QueuePositionResponse = self.post("/api/data/queue_pod_at", request).await?;
+        Ok(response.position)
+    }
+
+    pub async fn remove_queued_episode(
+        &self,
+        request: &QueuePodcastRequest,
+    ) -> Result<String, ClientError> {
+        self.post_for_text("/api/data/remove_queued_pod", request)
+            .await
+    }
+
+    /// The user's queue, in play order.
+    pub async fn queued_episodes(&self, user_id: i32) -> Result<Vec<QueuedEpisode>, ClientError> {
+        let response: DataEnvelope<Vec<QueuedEpisode>> = self
+            .get_with_query("/api/data/get_queued_episodes", &[("user_id", user_id)])
+            .await?;
+        Ok(response.data)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::client::{DataEnvelope, PinepodsClient};
+use crate::error::ClientError;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchRequest {
+    pub search_term: String,
+    pub user_id: i32,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub struct SearchEpisode {
+    pub podcastid: i32,
+    pub podcastname: String,
+    pub artworkurl: String,
+    pub author: String,
+    pub categories: String,
+    pub description: String,
+    pub episodecount: i32,
+    pub feedurl: String,
+    pub websiteurl: String,
+    pub explicit: i32,
+    pub userid: i32,
+    pub episodeid: i32,
+    pub episodetitle: String,
+    pub episodedescription: String,
+    pub episodeurl: String,
+    pub episodeartwork: String,
+    pub episodepubdate: String,
+    pub episodeduration: i32,
+    pub listenduration: Option<i32>,
+    pub completed: bool,
+}
+
+/// What the search API found, in the shape of whichever directory it asked.
+#[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
+#[allow(non_snake_case)]
+pub struct PodcastSearchResult {
+    pub status: Option<String>,               // for PodcastIndex
+    pub resultCount: Option<i32>,             // for iTunes
+    pub feeds: Option<Vec<PodcastIndexFeed>>, // for PodcastIndex
+    pub results: Option<Vec<ITunesPodcast>>,  // for iTunes
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
+#[allow(non_snake_case)]
+pub struct PodcastIndexFeed {
+    pub id: i64,
+    pub title: String,
+    pub url: String,
+    pub originalUrl: String,
+    pub link: String,
+    pub description: String,
+    pub author: String,
+    pub ownerName: String,
+    pub image: String,
+    pub artwork: String,
+    pub lastUpdateTime: i64,
+    pub categories: Option<HashMap<String, String>>,
+    pub explicit: bool,
+    pub episodeCount: i32,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
+#[allow(non_snake_case)]
+pub struct ITunesPodcast {
+    pub wrapperType: String,
+    pub kind: String,
+    pub collectionId: i64,
+    pub trackId: i64,
+    pub artistName: String,
+    pub trackName: String,
+    pub collectionViewUrl: String,
+    pub feedUrl: String,
+    pub artworkUrl100: String,
+    pub releaseDate: String,
+    pub genres: Vec<String>,
+    pub collectionExplicitness: String,
+    pub trackCount: Option<i32>,
+}
+
+impl PinepodsClient {
+    /// Searches the episodes of the podcasts the user follows.
+    pub async fn search_data(
+        &self,
+        request: &SearchRequest,
+    ) -> Result<Vec<SearchEpisode>, ClientError> {
+        let response: DataEnvelope<Vec<SearchEpisode>> =
+            self.post("/api/data/search_data", request).await?;
+        Ok(response.data)
+    }
+
+    /// Looks `query` up in a podcast directory, `index` being `podcastindex` or `itunes`.
+    ///
+    /// Unlike the other methods this is for a client pointed at the search API, the server's
+    /// `search_api_url`, rather than at the server itself.
+    pub async fn search_podcasts(
+        &self,
+        query: &str,
+        index: &str,
+    ) -> Result<PodcastSearchResult, ClientError> {
+        self.send_json(
+            self.unauthenticated(Method::GET, "")
+                .query(&[("query", query), ("index", index)]),
+        )
+        .await
+    }
+
+    /// Whether the search API this client points at answers at all.
+    pub async fn check_search_api(&self) -> Result<(), ClientError> {
+        self.send(self.unauthenticated(Method::GET, ""))
+            .await
+            .map(|_| ())
+    }
+}
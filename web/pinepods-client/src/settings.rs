@@ -0,0 +1,265 @@
+use crate::client::PinepodsClient;
+use crate::error::ClientError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct GetThemeResponse {
+    theme: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct SetThemeRequest {
+    pub user_id: i32,
+    pub new_theme: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct SetThemeResponse {
+    message: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct GetKeyBindingsResponse {
+    keybindings: HashMap<String, String>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct SetKeyBindingsRequest {
+    pub user_id: i32,
+    pub keybindings: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EqPreset {
+    Flat,
+    VoiceBoost,
+    BassCut,
+    // Bands the user adjusted by hand
+    #[serde(other)]
+    Custom,
+}
+
+impl EqPreset {
+    pub const ALL: [EqPreset; 4] = [
+        EqPreset::Flat,
+        EqPreset::VoiceBoost,
+        EqPreset::BassCut,
+        EqPreset::Custom,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            EqPreset::Flat => "flat",
+            EqPreset::VoiceBoost => "voice_boost",
+            EqPreset::BassCut => "bass_cut",
+            EqPreset::Custom => "custom",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        EqPreset::ALL
+            .iter()
+            .copied()
+            .find(|preset| preset.id() == id)
+            .unwrap_or(EqPreset::Custom)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EqPreset::Flat => "Flat",
+            EqPreset::VoiceBoost => "Voice Boost",
+            EqPreset::BassCut => "Bass Cut",
+            EqPreset::Custom => "Custom",
+        }
+    }
+
+    /// Gains in dB for the low shelf, presence and high shelf bands, `None` for custom.
+    pub fn gains(&self) -> Option<[f64; 3]> {
+        match self {
+            EqPreset::Flat => Some([0.0, 0.0, 0.0]),
+            EqPreset::VoiceBoost => Some([-4.0, 4.0, 2.0]),
+            EqPreset::BassCut => Some([-10.0, 0.0, 0.0]),
+            EqPreset::Custom => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct EqBand {
+    pub frequency: f64,
+    pub gain: f64,
+    pub q: f64,
+}
+
+/// The EQ is a low shelf, a presence peak and a high shelf, in that order.
+pub const EQ_BAND_LABELS: [&str; 3] = ["Bass", "Presence", "Treble"];
+
+pub fn eq_bands_with_gains(gains: [f64; 3]) -> Vec<EqBand> {
+    let frequencies = [150.0, 2800.0, 8000.0];
+    gains
+        .iter()
+        .zip(frequencies.iter())
+        .map(|(gain, frequency)| EqBand {
+            frequency: *frequency,
+            gain: *gain,
+            q: 1.0,
+        })
+        .collect()
+}
+
+/// The per-user processing chain the player applies to episodes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AudioProcessing {
+    pub enabled: bool,
+    pub compressor: bool,
+    // Level in dB the chain evens episodes out to, None leaves loudness alone
+    pub loudness_target: Option<f64>,
+    pub eq_preset: EqPreset,
+    #[serde(default)]
+    pub eq_bands: Vec<EqBand>,
+}
+
+impl Default for AudioProcessing {
+    fn default() -> Self {
+        AudioProcessing {
+            enabled: false,
+            compressor: true,
+            loudness_target: Some(-16.0),
+            eq_preset: EqPreset::Flat,
+            eq_bands: eq_bands_with_gains([0.0, 0.0, 0.0]),
+        }
+    }
+}
+
+impl AudioProcessing {
+    /// The stored bands, or the preset's when none were stored.
+    pub fn bands(&self) -> Vec<EqBand> {
+        if self.eq_bands.len() == EQ_BAND_LABELS.len() {
+            self.eq_bands.clone()
+        } else {
+            eq_bands_with_gains(self.eq_preset.gains().unwrap_or([0.0, 0.0, 0.0]))
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+struct GetAudioProcessingResponse {
+    audio_processing: Option<AudioProcessing>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct SetAudioProcessingRequest {
+    pub user_id: i32,
+    pub audio_processing: AudioProcessing,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub struct QueueSettings {
+    // Start the next queued episode when one finishes
+    pub auto_advance: bool,
+    // Take finished episodes out of the queue
+    pub remove_completed: bool,
+}
+
+impl Default for QueueSettings {
+    fn default() -> Self {
+        QueueSettings {
+            auto_advance: true,
+            remove_completed: true,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct SetQueueSettingsRequest {
+    pub user_id: i32,
+    pub queue_settings: QueueSettings,
+}
+
+#[derive(Serialize, Debug, Deserialize, PartialEq, Clone)]
+pub struct TimeZoneInfo {
+    pub user_id: i32,
+    pub timezone: String,
+    pub hour_pref: i32,
+    pub date_format: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SetupTimeZoneInfoResponse {
+    pub success: bool,
+}
+
+impl PinepodsClient {
+    pub async fn theme(&self, user_id: i32) -> Result<String, ClientError> {
+        let response: GetThemeResponse = self
+            .get(&format!("/api/data/get_theme/{}", user_id))
+            .await?;
+        Ok(response.theme)
+    }
+
+    /// Whether the server accepted the new theme.
+    pub async fn set_theme(&self, request: &SetThemeRequest) -> Result<bool, ClientError> {
+        let response: SetThemeResponse = self
+            .put_for("/api/data/user/set_theme", Some(request))
+            .await?;
+        Ok(response.message == "Success")
+    }
+
+    /// Only the shortcuts the user has rebound, keyed by action id.
+    pub async fn keybindings(&self, user_id: i32) -> Result<HashMap<String, String>, ClientError> {
+        let response: GetKeyBindingsResponse = self
+            .get(&format!("/api/data/get_keybindings/{}", user_id))
+            .await?;
+        Ok(response.keybindings)
+    }
+
+    pub async fn set_keybindings(
+        &self,
+        request: &SetKeyBindingsRequest,
+    ) -> Result<(), ClientError> {
+        self.put("/api/data/user/set_keybindings", Some(request))
+            .await
+    }
+
+    /// `None` until the user has saved their processing settings once.
+    pub async fn audio_processing(
+        &self,
+        user_id: i32,
+    ) -> Result<Option<AudioProcessing>, ClientError> {
+        let response: GetAudioProcessingResponse = self
+            .get(&format!("/api/data/get_audio_processing/{}", user_id))
+            .await?;
+        Ok(response.audio_processing)
+    }
+
+    pub async fn set_audio_processing(
+        &self,
+        request: &SetAudioProcessingRequest,
+    ) -> Result<(), ClientError> {
+        self.put("/api/data/user/set_audio_processing", Some(request))
+            .await
+    }
+
+    pub async fn queue_settings(&self, user_id: i32) -> Result<QueueSettings, ClientError> {
+        self.get(&format!("/api/data/get_queue_settings/{}", user_id))
+            .await
+    }
+
+    pub async fn set_queue_settings(
+        &self,
+        request: &SetQueueSettingsRequest,
+    ) -> Result<(), ClientError> {
+        self.put("/api/data/user/set_queue_settings", Some(request))
+            .await
+    }
+
+    /// Saves the time zone and date preferences picked on first login.
+    pub async fn setup_time_info(
+        &self,
+        time_zone_info: &TimeZoneInfo,
+    ) -> Result<SetupTimeZoneInfoResponse, ClientError> {
+        self.post("/api/data/setup_time_info", time_zone_info).await
+    }
+}
@@ -0,0 +1,121 @@
+use crate::client::{with_json, DataEnvelope, PinepodsClient};
+use crate::error::ClientError;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct Poll {
+    pub token: String,
+    pub endpoint: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct NextcloudInitiateResponse {
+    pub poll: Poll,
+    pub login: String,
+}
+
+#[derive(Serialize, Debug)]
+struct LoginInitiateRequest<'a> {
+    user_id: i32,
+    nextcloud_url: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct NextcloudAuthRequest {
+    pub user_id: i32,
+    pub token: String,
+    pub poll_endpoint: String,
+    pub nextcloud_url: String,
+}
+
+#[derive(Serialize)]
+pub struct GpodderCheckRequest {
+    pub gpodder_url: String,
+    pub gpodder_username: String,
+    pub gpodder_password: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GpodderCheckResponse {
+    pub status: String,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct GpodderAuthRequest {
+    pub user_id: i32,
+    pub gpodder_url: String,
+    pub gpodder_username: String,
+    pub gpodder_password: String,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct GpodderData {
+    pub gpodderurl: String,
+    pub gpoddertoken: String,
+}
+
+impl PinepodsClient {
+    /// Starts a Nextcloud login flow. The user signs in at `login` while the server polls for
+    /// the result.
+    pub async fn initiate_nextcloud_login(
+        &self,
+        user_id: i32,
+        nextcloud_url: &str,
+    ) -> Result<NextcloudInitiateResponse, ClientError> {
+        self.post(
+            "/api/data/initiate_nextcloud_login",
+            &LoginInitiateRequest {
+                user_id,
+                nextcloud_url,
+            },
+        )
+        .await
+    }
+
+    pub async fn add_nextcloud_server(
+        &self,
+        request: &NextcloudAuthRequest,
+    ) -> Result<(), ClientError> {
+        self.post_for_text("/api/data/add_nextcloud_server", request)
+            .await
+            .map(|_| ())
+    }
+
+    /// Tries the credentials against the gPodder server without saving them. Needs no API key.
+    pub async fn verify_gpodder_auth(
+        &self,
+        request: &GpodderCheckRequest,
+    ) -> Result<GpodderCheckResponse, ClientError> {
+        self.send_json(with_json(
+            self.unauthenticated(Method::POST, "/api/data/verify_gpodder_auth"),
+            request,
+        )?)
+        .await
+    }
+
+    pub async fn add_gpodder_server(
+        &self,
+        request: &GpodderAuthRequest,
+    ) -> Result<(), ClientError> {
+        self.post_for_text("/api/data/add_gpodder_server", request)
+            .await
+            .map(|_| ())
+    }
+
+    /// Whether the user syncs with a Nextcloud or gPodder server.
+    pub async fn gpodder_sync_enabled(&self, user_id: i32) -> Result<bool, ClientError> {
+        let response: DataEnvelope<bool> = self
+            .get(&format!("/api/data/check_gpodder_settings/{}", user_id))
+            .await?;
+        Ok(response.data)
+    }
+
+    pub async fn gpodder_settings(&self, user_id: i32) -> Result<GpodderData, ClientError> {
+        let response: DataEnvelope<GpodderData> = self
+            .get(&format!("/api/data/get_gpodder_settings/{}", user_id))
+            .await?;
+        Ok(response.data)
+    }
+}
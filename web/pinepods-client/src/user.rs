@@ -0,0 +1,131 @@
+use crate::client::PinepodsClient;
+use crate::error::ClientError;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct PinepodsCheckResponse {
+    pub pinepods_instance: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct KeyVerification {
+    pub status: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GetUserIdResponse {
+    pub status: String,
+    pub retrieved_id: Option<i32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub struct GetUserDetails {
+    pub UserID: i32,
+    pub Fullname: Option<String>,
+    pub Username: Option<String>,
+    pub Email: Option<String>,
+    pub Hashed_PW: Option<String>,
+    pub Salt: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GetApiDetails {
+    pub api_url: Option<String>,
+    pub proxy_url: Option<String>,
+    pub proxy_host: Option<String>,
+    pub proxy_port: Option<String>,
+    pub proxy_protocol: Option<String>,
+    pub reverse_proxy: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TimeInfoResponse {
+    pub timezone: String,
+    pub hour_pref: i16,
+    pub date_format: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[allow(non_snake_case)]
+pub struct UserStats {
+    pub UserCreated: String,
+    pub PodcastsPlayed: i32,
+    pub TimeListened: i32,
+    pub PodcastsAdded: i32,
+    pub EpisodesSaved: i32,
+    pub EpisodesDownloaded: i32,
+    // Seconds trimmed from silences by Smart Speed
+    #[serde(default)]
+    pub TimeSaved: i32,
+}
+
+#[derive(Serialize, Debug)]
+struct TimeSavedRequest {
+    seconds: i32,
+}
+
+impl PinepodsClient {
+    /// Whether the server at `server_name` is a PinePods server at all. Needs no API key.
+    pub async fn is_pinepods_instance(&self) -> Result<bool, ClientError> {
+        let response: PinepodsCheckResponse = self
+            .send_json(self.unauthenticated(Method::GET, "/api/pinepods_check"))
+            .await?;
+        Ok(response.pinepods_instance.unwrap_or(false))
+    }
+
+    pub async fn verify_key(&self) -> Result<KeyVerification, ClientError> {
+        self.get("/api/data/verify_key").await
+    }
+
+    /// Looks up which user the API key belongs to.
+    pub async fn user_id(&self) -> Result<GetUserIdResponse, ClientError> {
+        self.get("/api/data/get_user").await
+    }
+
+    pub async fn user_details(&self, user_id: i32) -> Result<GetUserDetails, ClientError> {
+        self.get(&format!("/api/data/user_details_id/{}", user_id))
+            .await
+    }
+
+    pub async fn api_config(&self) -> Result<GetApiDetails, ClientError> {
+        self.get("/api/data/config").await
+    }
+
+    pub async fn time_info(&self, user_id: i32) -> Result<TimeInfoResponse, ClientError> {
+        self.get_with_query("/api/data/get_time_info", &[("user_id", user_id)])
+            .await
+    }
+
+    pub async fn stats(&self, user_id: i32) -> Result<UserStats, ClientError> {
+        self.get_with_query("/api/data/get_stats", &[("user_id", user_id)])
+            .await
+    }
+
+    pub async fn increment_listen_time(&self, user_id: i32) -> Result<(), ClientError> {
+        self.put::<()>(
+            &format!("/api/data/increment_listen_time/{}", user_id),
+            None,
+        )
+        .await
+    }
+
+    pub async fn increment_played(&self, user_id: i32) -> Result<(), ClientError> {
+        self.put::<()>(&format!("/api/data/increment_played/{}", user_id), None)
+            .await
+    }
+
+    /// Adds the seconds Smart Speed trimmed from silences to the user's stats.
+    pub async fn increment_time_saved(
+        &self,
+        user_id: i32,
+        seconds: i32,
+    ) -> Result<(), ClientError> {
+        self.put(
+            &format!("/api/data/increment_time_saved/{}", user_id),
+            Some(&TimeSavedRequest { seconds }),
+        )
+        .await
+    }
+}
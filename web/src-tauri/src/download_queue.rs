@@ -0,0 +1,427 @@
+// Works through the queued episode downloads in the background: a few at a time, spaced out per
+// host, retried with backoff and only started inside the schedule the user picked.
+
+use crate::downloads::{DownloadManager, CANCELLED, PROGRESS_EVENT};
+use crate::local_db::{DownloadJob, DownloadSettings, JobState, LocalDb};
+use crate::{get_project_dirs, EpisodeInfo, PodcastDetails};
+use chrono::Timelike;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+pub const JOBS_EVENT: &str = "download-jobs";
+// How long the queue sleeps when nothing wakes it, so schedule windows get noticed
+const IDLE_CHECK: Duration = Duration::from_secs(60);
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 60 * 60;
+
+#[derive(Default)]
+pub struct DownloadQueue {
+    wake: Notify,
+    // When a download from each host last started, for DownloadSettings::hostinterval
+    host_starts: Mutex<HashMap<String, Instant>>,
+}
+
+impl DownloadQueue {
+    // Called after anything that could let another job start
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
+
+pub async fn run(app: AppHandle) {
+    // Jobs that were running when the app closed resume from their part files
+    if let Err(e) = app
+        .state::<LocalDb>()
+        .set_jobs_state(&[JobState::Running], JobState::Queued)
+    {
+        println!("Failed to requeue downloads: {}", e);
+    }
+    loop {
+        let next_check = start_ready_jobs(&app).unwrap_or_else(|e| {
+            println!("Failed to start queued downloads: {}", e);
+            IDLE_CHECK
+        });
+        let queue = app.state::<DownloadQueue>();
+        tokio::select! {
+            _ = queue.wake.notified() => {}
+            _ = tokio::time::sleep(next_check) => {}
+        }
+    }
+}
+
+// Starts what the limits allow and returns how long until one of them could let more through
+fn start_ready_jobs(app: &AppHandle) -> Result<Duration, String> {
+    let db = app.state::<LocalDb>();
+    let settings = db.download_settings()?;
+    // The schedule only holds back new downloads, ones already running finish
+    if !schedule_allows(&settings) {
+        return Ok(IDLE_CHECK);
+    }
+
+    let jobs = db.download_jobs()?;
+    let mut running = jobs
+        .iter()
+        .filter(|job| job.state == JobState::Running)
+        .count() as u32;
+    let now = unix_now();
+    let interval = Duration::from_secs(settings.hostinterval.into());
+    let mut next_check = IDLE_CHECK;
+    let mut started = false;
+    let queue = app.state::<DownloadQueue>();
+    let mut host_starts = queue.host_starts.lock().map_err(|e| e.to_string())?;
+
+    for job in jobs.into_iter().filter(|job| job.state == JobState::Queued) {
+        if running >= settings.maxconcurrent.max(1) {
+            break;
+        }
+        if job.nextattempt > now {
+            next_check = next_check.min(Duration::from_secs((job.nextattempt - now) as u64));
+            continue;
+        }
+        let host = reqwest::Url::parse(&job.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        if let Some(since) = host_starts.get(&host).map(Instant::elapsed) {
+            if since < interval {
+                next_check = next_check.min(interval - since);
+                continue;
+            }
+        }
+
+        host_starts.insert(host, Instant::now());
+        db.set_job_state(job.id, JobState::Running, job.attempts, 0, None)?;
+        running += 1;
+        started = true;
+        // A job paused mid-download still has its connection, it carries on in its own task
+        if app
+            .state::<DownloadManager>()
+            .resume(&job.filename)
+            .is_err()
+        {
+            tauri::async_runtime::spawn(run_job(app.clone(), job));
+        }
+    }
+
+    if started {
+        emit_jobs(app);
+    }
+    Ok(next_check)
+}
+
+async fn run_job(app: AppHandle, job: DownloadJob) {
+    let result = download_job(&app, &job).await;
+    let db = app.state::<LocalDb>();
+    let recorded = match result {
+        Ok(()) => Ok(()),
+        Err(e) if e == CANCELLED => db.remove_job(job.id),
+        Err(e) => {
+            let attempts = job.attempts + 1;
+            let max_attempts = db
+                .download_settings()
+                .map(|settings| settings.maxattempts)
+                .unwrap_or(1);
+            // The user may have paused the job while it ran, a pause outlasts the failure
+            let current = find_job(&app, job.id).ok();
+            if let Some(current) = current.filter(|current| current.state == JobState::Paused) {
+                db.set_job_state(
+                    job.id,
+                    JobState::Paused,
+                    attempts,
+                    current.nextattempt,
+                    Some(&e),
+                )
+            } else if attempts >= max_attempts {
+                db.set_job_state(job.id, JobState::Failed, attempts, 0, Some(&e))
+            } else {
+                db.set_job_state(
+                    job.id,
+                    JobState::Queued,
+                    attempts,
+                    unix_now() + backoff_secs(attempts),
+                    Some(&e),
+                )
+            }
+        }
+    };
+    if let Err(e) = recorded {
+        println!("Failed to record download of {}: {}", job.title, e);
+    }
+    emit_jobs(&app);
+    app.state::<DownloadQueue>().wake();
+}
+
+async fn download_job(app: &AppHandle, job: &DownloadJob) -> Result<(), String> {
+    let downloads = app.state::<DownloadManager>();
+    let proj_dirs = get_project_dirs()?;
+    let app_dir = proj_dirs.data_dir();
+
+    let emit = |progress| {
+        if let Err(e) = app.emit_all(PROGRESS_EVENT, progress) {
+            println!("Failed to send download progress: {}", e);
+        }
+    };
+    let listed_type = job.mediatype.as_deref().unwrap_or("audio/mpeg");
+    let path = downloads
+        .download(
+            &job.url,
+            app_dir,
+            &job.filename,
+            job.expectedsize,
+            listed_type,
+            emit,
+        )
+        .await?;
+
+    // The episode plays fine without its artwork
+    let artwork_filename = format!("artwork_{}", job.episodeid);
+    let artwork = downloads
+        .download(
+            &job.artworkurl,
+            app_dir,
+            &artwork_filename,
+            None,
+            "image/jpeg",
+            |_| {},
+        )
+        .await;
+    if let Err(e) = &artwork {
+        println!("Failed to download artwork for {}: {}", job.title, e);
+    }
+
+    // Removing the job while its artwork downloads has nothing left to cancel, so check it's
+    // still wanted before the episode joins the library
+    if find_job(app, job.id).is_err() {
+        let _ = tokio::fs::remove_file(&path).await;
+        if let Ok(artwork) = artwork {
+            let _ = tokio::fs::remove_file(artwork).await;
+        }
+        return Err(CANCELLED.to_string());
+    }
+
+    let mut episode: EpisodeInfo = serde_json::from_str(&job.episode).map_err(|e| e.to_string())?;
+    episode.downloadedlocation = Some(path.to_string_lossy().into_owned());
+    let podcast: PodcastDetails = serde_json::from_str(&job.podcast).map_err(|e| e.to_string())?;
+    app.state::<LocalDb>()
+        .finish_job(job.id, &episode, &podcast)
+}
+
+pub fn emit_jobs(app: &AppHandle) {
+    match app.state::<LocalDb>().download_jobs() {
+        Ok(jobs) => {
+            if let Err(e) = app.emit_all(JOBS_EVENT, jobs) {
+                println!("Failed to send download jobs: {}", e);
+            }
+        }
+        Err(e) => println!("Failed to load download jobs: {}", e),
+    }
+}
+
+fn find_job(app: &AppHandle, id: i64) -> Result<DownloadJob, String> {
+    app.state::<LocalDb>()
+        .download_jobs()?
+        .into_iter()
+        .find(|job| job.id == id)
+        .ok_or_else(|| format!("Download {} is not queued", id))
+}
+
+pub fn pause_job(app: &AppHandle, id: i64) -> Result<(), String> {
+    let job = find_job(app, id)?;
+    match job.state {
+        // Between the audio and the artwork there's nothing left to pause
+        JobState::Running => {
+            let _ = app.state::<DownloadManager>().pause(&job.filename);
+        }
+        JobState::Queued => {}
+        JobState::Paused | JobState::Failed => return Ok(()),
+    }
+    app.state::<LocalDb>()
+        .set_job_state(id, JobState::Paused, job.attempts, job.nextattempt, None)
+}
+
+// Resumed jobs go back in the queue, so they wait for a free slot like any other. One still
+// connected picks up where it paused once start_ready_jobs lets it.
+pub fn resume_job(app: &AppHandle, id: i64) -> Result<(), String> {
+    let job = find_job(app, id)?;
+    if !matches!(job.state, JobState::Paused | JobState::Failed) {
+        return Ok(());
+    }
+    // A failed job asked for again gets its full set of attempts back
+    let attempts = if job.state == JobState::Failed {
+        0
+    } else {
+        job.attempts
+    };
+    app.state::<LocalDb>()
+        .set_job_state(id, JobState::Queued, attempts, 0, None)?;
+    app.state::<DownloadQueue>().wake();
+    Ok(())
+}
+
+pub async fn remove_job(app: &AppHandle, id: i64) -> Result<(), String> {
+    let job = find_job(app, id)?;
+    let proj_dirs = get_project_dirs()?;
+    app.state::<DownloadManager>()
+        .cancel(proj_dirs.data_dir(), &job.filename)
+        .await?;
+    app.state::<LocalDb>().remove_job(id)?;
+    app.state::<DownloadQueue>().wake();
+    Ok(())
+}
+
+pub fn pause_all(app: &AppHandle) -> Result<(), String> {
+    for job in app.state::<LocalDb>().download_jobs()? {
+        pause_job(app, job.id)?;
+    }
+    Ok(())
+}
+
+pub fn resume_all(app: &AppHandle) -> Result<(), String> {
+    for job in app.state::<LocalDb>().download_jobs()? {
+        if job.state == JobState::Paused {
+            resume_job(app, job.id)?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn clear_failed(app: &AppHandle) -> Result<(), String> {
+    for job in app.state::<LocalDb>().download_jobs()? {
+        if job.state == JobState::Failed {
+            remove_job(app, job.id).await?;
+        }
+    }
+    Ok(())
+}
+
+fn schedule_allows(settings: &DownloadSettings) -> bool {
+    if settings.schedule == "night"
+        && !in_night_window(
+            chrono::Local::now().hour(),
+            settings.nightstart,
+            settings.nightend,
+        )
+    {
+        return false;
+    }
+    !(settings.unmeteredonly && connection_metered() == Some(true))
+}
+
+// A window like 23 to 6 runs across midnight, one that starts and ends on the same hour is the
+// whole day rather than none of it
+fn in_night_window(hour: u32, start: u32, end: u32) -> bool {
+    if start < end {
+        hour >= start && hour < end
+    } else if start > end {
+        hour >= start || hour < end
+    } else {
+        true
+    }
+}
+
+// Doubles with every failed attempt up to MAX_BACKOFF_SECS
+fn backoff_secs(attempts: u32) -> i64 {
+    (BASE_BACKOFF_SECS << attempts.saturating_sub(1).min(16)).min(MAX_BACKOFF_SECS)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+// None when the system doesn't say, which doesn't hold downloads back
+#[cfg(target_os = "linux")]
+fn connection_metered() -> Option<bool> {
+    use std::process::Command;
+    // NetworkManager's Metered property: 1 yes, 2 no, 3 guessed yes, 4 guessed no
+    let output = Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "u 1" | "u 3" => Some(true),
+        "u 2" | "u 4" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn connection_metered() -> Option<bool> {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+    // CREATE_NO_WINDOW, so no console flashes up on every check
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "[Windows.Networking.Connectivity.NetworkInformation, Windows.Networking.Connectivity, \
+             ContentType=WindowsRuntime]::GetInternetConnectionProfile().GetConnectionCost().NetworkCostType",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "Unrestricted" => Some(false),
+        "Fixed" | "Variable" => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn connection_metered() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn night_window_within_a_day() {
+        assert!(!in_night_window(0, 1, 5));
+        assert!(in_night_window(1, 1, 5));
+        assert!(in_night_window(4, 1, 5));
+        assert!(!in_night_window(5, 1, 5));
+    }
+
+    #[test]
+    fn night_window_across_midnight() {
+        assert!(in_night_window(23, 23, 6));
+        assert!(in_night_window(0, 23, 6));
+        assert!(in_night_window(5, 23, 6));
+        assert!(!in_night_window(6, 23, 6));
+        assert!(!in_night_window(12, 23, 6));
+        assert!(!in_night_window(22, 23, 6));
+    }
+
+    #[test]
+    fn night_window_starting_and_ending_together_is_all_day() {
+        for hour in 0..24 {
+            assert!(in_night_window(hour, 3, 3));
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_ceiling() {
+        assert_eq!(backoff_secs(1), BASE_BACKOFF_SECS);
+        assert_eq!(backoff_secs(2), BASE_BACKOFF_SECS * 2);
+        assert_eq!(backoff_secs(3), BASE_BACKOFF_SECS * 4);
+        assert_eq!(backoff_secs(8), MAX_BACKOFF_SECS);
+        // Large attempt counts can't overflow the shift
+        assert_eq!(backoff_secs(100), MAX_BACKOFF_SECS);
+        assert_eq!(backoff_secs(u32::MAX), MAX_BACKOFF_SECS);
+        assert_eq!(backoff_secs(0), BASE_BACKOFF_SECS);
+    }
+}
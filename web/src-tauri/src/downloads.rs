@@ -0,0 +1,463 @@
+// Streams downloads into the app's data directory. Each one is written to a `.part` file that
+// survives dropped connections and pauses, and only takes its real name once its size checks out.
+// The extension it gets comes from what the file turned out to be, see media_type.
+
+use crate::media_type::{self, SNIFF_LEN};
+use reqwest::header::{HeaderMap, CONTENT_RANGE, CONTENT_TYPE, RANGE};
+use reqwest::{Client, Response, StatusCode};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::watch;
+
+pub const PROGRESS_EVENT: &str = "download-progress";
+// How many times in a row a dropped connection is picked up again from where it stopped
+const MAX_RETRIES: u32 = 5;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+// Feeds often list a placeholder enclosure length, anything smaller isn't checked against
+const MIN_ENCLOSURE_LENGTH: u64 = 100_000;
+pub const CANCELLED: &str = "Download cancelled";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadState {
+    Downloading,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// The payload of PROGRESS_EVENT, total is None when neither the server nor the feed gave a size
+#[derive(Clone, Debug, Serialize)]
+pub struct DownloadProgress {
+    pub filename: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub state: DownloadState,
+    pub error: Option<String>,
+}
+
+pub struct DownloadManager {
+    client: Client,
+    // The control channel of every download in flight, by filename
+    downloads: Mutex<HashMap<String, watch::Sender<Control>>>,
+}
+
+impl DownloadManager {
+    pub fn new() -> Result<Self, String> {
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(30))
+            // A stalled connection counts as dropped and gets resumed
+            .read_timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(DownloadManager {
+            client,
+            downloads: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn pause(&self, filename: &str) -> Result<(), String> {
+        self.send(filename, Control::Pause)
+    }
+
+    pub fn resume(&self, filename: &str) -> Result<(), String> {
+        self.send(filename, Control::Run)
+    }
+
+    // Downloads left behind by an earlier run aren't in flight, their part file is just removed
+    pub async fn cancel(&self, dir: &Path, filename: &str) -> Result<(), String> {
+        if self.send(filename, Control::Cancel).is_ok() {
+            return Ok(());
+        }
+        let part = part_path(dir, filename)?;
+        if part.exists() {
+            fs::remove_file(part).await.map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn send(&self, filename: &str, control: Control) -> Result<(), String> {
+        let downloads = self.downloads.lock().map_err(|e| e.to_string())?;
+        let sender = downloads
+            .get(filename)
+            .ok_or_else(|| format!("{} is not downloading", filename))?;
+        sender.send_replace(control);
+        Ok(())
+    }
+
+    // filename has no extension yet, listed_type is the type the feed gave for the file. Returns
+    // where the finished file ended up.
+    pub async fn download(
+        &self,
+        url: &str,
+        dir: &Path,
+        filename: &str,
+        expected_size: Option<u64>,
+        listed_type: &str,
+        emit: impl Fn(DownloadProgress),
+    ) -> Result<PathBuf, String> {
+        let part = part_path(dir, filename)?;
+        let mut control = {
+            let mut downloads = self.downloads.lock().map_err(|e| e.to_string())?;
+            if downloads.contains_key(filename) {
+                return Err(format!("{} is already downloading", filename));
+            }
+            let (sender, receiver) = watch::channel(Control::Run);
+            downloads.insert(filename.to_string(), sender);
+            receiver
+        };
+
+        let mut progress = Progress {
+            filename: filename.to_string(),
+            downloaded: 0,
+            total: None,
+            state: DownloadState::Downloading,
+            content_type: None,
+            last_emit: None,
+            emit,
+        };
+        progress.report(DownloadState::Downloading, None);
+
+        let result = async {
+            transfer(&self.client, url, &part, &mut control, &mut progress).await?;
+            verify(&progress, expected_size)?;
+            finish(&part, dir, filename, &progress, listed_type).await
+        }
+        .await;
+
+        if let Ok(mut downloads) = self.downloads.lock() {
+            downloads.remove(filename);
+        }
+        match &result {
+            Ok(_) => progress.report(DownloadState::Completed, None),
+            Err(e) if e == CANCELLED => {
+                if part.exists() {
+                    fs::remove_file(&part).await.map_err(|e| e.to_string())?;
+                }
+                progress.report(DownloadState::Cancelled, None);
+            }
+            // The part file stays so the next attempt resumes it
+            Err(e) => progress.report(DownloadState::Failed, Some(e.clone())),
+        }
+        result
+    }
+}
+
+struct Progress<F: Fn(DownloadProgress)> {
+    filename: String,
+    downloaded: u64,
+    total: Option<u64>,
+    state: DownloadState,
+    // The Content-Type of the last response that sent any of the file
+    content_type: Option<String>,
+    last_emit: Option<Instant>,
+    emit: F,
+}
+
+impl<F: Fn(DownloadProgress)> Progress<F> {
+    fn advance(&mut self, bytes: usize) {
+        self.downloaded += bytes as u64;
+        self.report(DownloadState::Downloading, None);
+    }
+
+    // Changes of state always go out, byte counts at most every PROGRESS_INTERVAL
+    fn report(&mut self, state: DownloadState, error: Option<String>) {
+        let due = state != self.state
+            || error.is_some()
+            || !matches!(self.last_emit, Some(last) if last.elapsed() < PROGRESS_INTERVAL);
+        self.state = state;
+        if due {
+            self.last_emit = Some(Instant::now());
+            (self.emit)(DownloadProgress {
+                filename: self.filename.clone(),
+                downloaded: self.downloaded,
+                total: self.total,
+                state,
+                error,
+            });
+        }
+    }
+}
+
+enum Streamed {
+    Finished,
+    // Paused or cancelled, the loop in transfer sorts out which
+    Interrupted,
+    Dropped(String),
+}
+
+async fn transfer<F: Fn(DownloadProgress)>(
+    client: &Client,
+    url: &str,
+    part: &Path,
+    control: &mut watch::Receiver<Control>,
+    progress: &mut Progress<F>,
+) -> Result<(), String> {
+    let mut retries = 0;
+    // Retries only start over once a connection gets further than any before it, a server that
+    // drops at the same spot and ignores Range would otherwise be retried forever
+    let mut furthest = 0;
+    loop {
+        wait_until_running(control, progress).await?;
+        let offset = match fs::metadata(part).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+        progress.downloaded = offset;
+
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+        let dropped = match request.send().await {
+            Err(e) => e.to_string(),
+            Ok(response) if response.status() == StatusCode::RANGE_NOT_SATISFIABLE => {
+                // Either the part file already holds everything or the file changed on the server
+                if content_range_total(response.headers()) == Some(offset) {
+                    progress.total = Some(offset);
+                    return Ok(());
+                }
+                fs::remove_file(part).await.map_err(|e| e.to_string())?;
+                continue;
+            }
+            Ok(response) if !response.status().is_success() => {
+                return Err(format!("Server responded with {}", response.status()));
+            }
+            Ok(response) => {
+                progress.content_type = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                // Servers that ignore Range send the whole file again
+                let resumed = response.status() == StatusCode::PARTIAL_CONTENT;
+                if resumed {
+                    progress.total = content_range_total(response.headers())
+                        .or_else(|| response.content_length().map(|length| offset + length));
+                } else {
+                    progress.downloaded = 0;
+                    progress.total = response.content_length();
+                }
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(resumed)
+                    .truncate(!resumed)
+                    .open(part)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let streamed = stream(response, &mut file, control, progress).await;
+                file.flush().await.map_err(|e| e.to_string())?;
+                if progress.downloaded > furthest {
+                    furthest = progress.downloaded;
+                    retries = 0;
+                }
+                match streamed? {
+                    Streamed::Finished => match progress.total {
+                        Some(total) if progress.downloaded < total => format!(
+                            "Connection closed after {} of {} bytes",
+                            progress.downloaded, total
+                        ),
+                        _ => return Ok(()),
+                    },
+                    Streamed::Interrupted => continue,
+                    Streamed::Dropped(e) => e,
+                }
+            }
+        };
+
+        retries += 1;
+        if retries > MAX_RETRIES {
+            return Err(dropped);
+        }
+        println!(
+            "Download of {} dropped ({}), retrying",
+            progress.filename, dropped
+        );
+        tokio::time::sleep(Duration::from_secs(retries as u64)).await;
+    }
+}
+
+async fn stream<F: Fn(DownloadProgress)>(
+    mut response: Response,
+    file: &mut File,
+    control: &mut watch::Receiver<Control>,
+    progress: &mut Progress<F>,
+) -> Result<Streamed, String> {
+    loop {
+        tokio::select! {
+            chunk = response.chunk() => match chunk {
+                Ok(Some(bytes)) => {
+                    file.write_all(&bytes).await.map_err(|e| e.to_string())?;
+                    progress.advance(bytes.len());
+                }
+                Ok(None) => return Ok(Streamed::Finished),
+                Err(e) => return Ok(Streamed::Dropped(e.to_string())),
+            },
+            changed = control.changed() => {
+                if changed.is_err() || *control.borrow() != Control::Run {
+                    return Ok(Streamed::Interrupted);
+                }
+            }
+        }
+    }
+}
+
+async fn wait_until_running<F: Fn(DownloadProgress)>(
+    control: &mut watch::Receiver<Control>,
+    progress: &mut Progress<F>,
+) -> Result<(), String> {
+    loop {
+        let current = *control.borrow_and_update();
+        match current {
+            Control::Run => return Ok(()),
+            Control::Cancel => return Err(CANCELLED.to_string()),
+            Control::Pause => {
+                progress.report(DownloadState::Paused, None);
+                if control.changed().await.is_err() {
+                    return Err(CANCELLED.to_string());
+                }
+            }
+        }
+    }
+}
+
+// The server's size wins when it gave one, dynamic ad insertion makes enclosure lengths go stale.
+// Without it the enclosure length is all there is to tell a truncated download apart.
+fn verify<F: Fn(DownloadProgress)>(
+    progress: &Progress<F>,
+    expected_size: Option<u64>,
+) -> Result<(), String> {
+    let expected = progress
+        .total
+        .or(expected_size.filter(|size| *size >= MIN_ENCLOSURE_LENGTH));
+    match expected {
+        Some(expected) if progress.downloaded < expected => Err(format!(
+            "Downloaded {} of {} bytes",
+            progress.downloaded, expected
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Gives the finished part file its real name, with the extension its contents call for
+async fn finish<F: Fn(DownloadProgress)>(
+    part: &Path,
+    dir: &Path,
+    filename: &str,
+    progress: &Progress<F>,
+    listed_type: &str,
+) -> Result<PathBuf, String> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    File::open(part)
+        .await
+        .map_err(|e| e.to_string())?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)
+        .await
+        .map_err(|e| e.to_string())?;
+    let extension = media_type::extension(&head, progress.content_type.as_deref(), listed_type);
+    let path = dir.join(format!("{}.{}", filename, extension));
+    fs::rename(part, &path).await.map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+// "bytes 0-99/1234" and "bytes */1234" both end in the full size
+fn content_range_total(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn part_path(dir: &Path, filename: &str) -> Result<PathBuf, String> {
+    // Only bare names, so a download can't write outside the data directory
+    if Path::new(filename)
+        .file_name()
+        .and_then(|name| name.to_str())
+        != Some(filename)
+    {
+        return Err(format!("Invalid download filename: {}", filename));
+    }
+    Ok(dir.join(format!("{}.part", filename)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn content_range(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_RANGE, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    fn progress(downloaded: u64, total: Option<u64>) -> Progress<impl Fn(DownloadProgress)> {
+        Progress {
+            filename: "episode_1".to_string(),
+            downloaded,
+            total,
+            state: DownloadState::Downloading,
+            content_type: None,
+            last_emit: None,
+            emit: |_| {},
+        }
+    }
+
+    #[test]
+    fn content_range_total_reads_the_full_size() {
+        assert_eq!(
+            content_range_total(&content_range("bytes 0-99/100")),
+            Some(100)
+        );
+        assert_eq!(
+            content_range_total(&content_range("bytes */100")),
+            Some(100)
+        );
+        // The server doesn't know the full size
+        assert_eq!(content_range_total(&content_range("bytes 0-99/*")), None);
+        assert_eq!(content_range_total(&content_range("bytes 0-99")), None);
+        assert_eq!(content_range_total(&content_range("garbage")), None);
+        assert_eq!(content_range_total(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn verify_prefers_the_server_size() {
+        assert!(verify(&progress(500_000, Some(500_000)), None).is_ok());
+        assert!(verify(&progress(499_999, Some(500_000)), None).is_err());
+        // Ad insertion makes the feed's enclosure length stale either way
+        assert!(verify(&progress(500_000, Some(500_000)), Some(900_000)).is_ok());
+        assert!(verify(&progress(500_000, Some(600_000)), Some(400_000)).is_err());
+    }
+
+    #[test]
+    fn verify_falls_back_to_the_enclosure_length() {
+        assert!(verify(&progress(200_000, None), Some(200_000)).is_ok());
+        assert!(verify(&progress(300_000, None), Some(200_000)).is_ok());
+        assert_eq!(
+            verify(&progress(150_000, None), Some(200_000)),
+            Err("Downloaded 150000 of 200000 bytes".to_string())
+        );
+        // Placeholder lengths and missing sizes can't tell a truncated file apart
+        assert!(verify(&progress(10, None), Some(1)).is_ok());
+        assert!(verify(&progress(10, None), None).is_ok());
+    }
+}
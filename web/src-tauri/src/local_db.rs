@@ -0,0 +1,746 @@
+// The desktop app's library of downloaded episodes and their podcasts, kept in SQLite next to
+// the downloads. Every command goes through the one connection behind the mutex, so concurrent
+// downloads can't overwrite each other's writes.
+
+use crate::{EpisodeInfo, Podcast, PodcastDetails};
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+const DB_FILE: &str = "local_library.sqlite";
+// Where the library lived before, read once by import_json
+const EPISODES_JSON: &str = "local_episodes.json";
+const PODCASTS_JSON: &str = "local_podcasts.json";
+
+// One entry per schema version, PRAGMA user_version records how many have run. Only append.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE podcasts (
+        podcastid INTEGER PRIMARY KEY,
+        podcastname TEXT NOT NULL,
+        artworkurl TEXT,
+        author TEXT,
+        categories TEXT NOT NULL DEFAULT '',
+        description TEXT,
+        episodecount INTEGER NOT NULL DEFAULT 0,
+        explicit INTEGER NOT NULL DEFAULT 0,
+        feedurl TEXT NOT NULL,
+        userid INTEGER NOT NULL DEFAULT 0,
+        websiteurl TEXT
+    );
+    CREATE INDEX podcasts_feedurl ON podcasts (feedurl);
+    CREATE TABLE episodes (
+        episodeid INTEGER PRIMARY KEY,
+        podcastid INTEGER NOT NULL,
+        episodetitle TEXT NOT NULL,
+        podcastname TEXT NOT NULL,
+        episodepubdate TEXT NOT NULL,
+        episodedescription TEXT NOT NULL,
+        episodeartwork TEXT NOT NULL,
+        episodeurl TEXT NOT NULL,
+        episodeduration INTEGER NOT NULL,
+        listenduration INTEGER,
+        completed INTEGER NOT NULL DEFAULT 0,
+        downloadedlocation TEXT
+    );
+    CREATE INDEX episodes_podcastid ON episodes (podcastid);
+    CREATE INDEX episodes_pubdate ON episodes (episodepubdate);",
+    // The episode and podcast are kept as JSON until the download finishes and they join the library
+    "CREATE TABLE download_jobs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        episodeid INTEGER NOT NULL UNIQUE,
+        title TEXT NOT NULL,
+        filename TEXT NOT NULL,
+        url TEXT NOT NULL,
+        artworkurl TEXT NOT NULL,
+        expectedsize INTEGER,
+        episode TEXT NOT NULL,
+        podcast TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        state TEXT NOT NULL DEFAULT 'queued',
+        attempts INTEGER NOT NULL DEFAULT 0,
+        nextattempt INTEGER NOT NULL DEFAULT 0,
+        error TEXT
+    );
+    CREATE TABLE download_settings (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        maxconcurrent INTEGER NOT NULL DEFAULT 2,
+        hostinterval INTEGER NOT NULL DEFAULT 2,
+        maxattempts INTEGER NOT NULL DEFAULT 5,
+        schedule TEXT NOT NULL DEFAULT 'anytime',
+        nightstart INTEGER NOT NULL DEFAULT 1,
+        nightend INTEGER NOT NULL DEFAULT 6,
+        unmeteredonly INTEGER NOT NULL DEFAULT 0
+    );
+    INSERT INTO download_settings (id) VALUES (1);",
+    // Job filenames lose their extension, it's picked once the file's type is known
+    "ALTER TABLE download_jobs ADD COLUMN mediatype TEXT;
+    UPDATE download_jobs SET filename = 'episode_' || episodeid;",
+];
+
+const EPISODE_COLUMNS: &str = "episodetitle, podcastname, podcastid, episodepubdate, \
+    episodedescription, episodeartwork, episodeurl, episodeduration, listenduration, episodeid, \
+    completed, downloadedlocation";
+
+const JOB_COLUMNS: &str =
+    "id, episodeid, title, filename, url, artworkurl, expectedsize, episode, \
+    podcast, position, state, attempts, nextattempt, error, mediatype";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+    Failed,
+}
+
+impl JobState {
+    fn as_str(self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Paused => "paused",
+            JobState::Failed => "failed",
+        }
+    }
+
+    fn parse(state: &str) -> Self {
+        match state {
+            "running" => JobState::Running,
+            "paused" => JobState::Paused,
+            "failed" => JobState::Failed,
+            _ => JobState::Queued,
+        }
+    }
+}
+
+// A queued episode download, the queue runs them in position order
+#[derive(Clone, Debug, Serialize)]
+pub struct DownloadJob {
+    pub id: i64,
+    pub episodeid: i32,
+    pub title: String,
+    // The name in the data directory without an extension, the finished file gets the one that
+    // matches its type
+    pub filename: String,
+    #[serde(skip)]
+    pub url: String,
+    #[serde(skip)]
+    pub artworkurl: String,
+    #[serde(skip)]
+    pub expectedsize: Option<u64>,
+    #[serde(skip)]
+    pub episode: String,
+    #[serde(skip)]
+    pub podcast: String,
+    pub position: i64,
+    pub state: JobState,
+    pub attempts: u32,
+    // Unix time before which a retry doesn't start
+    pub nextattempt: i64,
+    pub error: Option<String>,
+    // The enclosure's MIME type from the feed
+    #[serde(skip)]
+    pub mediatype: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DownloadSettings {
+    pub maxconcurrent: u32,
+    // Seconds between starting two downloads from the same host
+    pub hostinterval: u32,
+    pub maxattempts: u32,
+    // "anytime" or "night", night runs from nightstart to nightend in local hours
+    pub schedule: String,
+    pub nightstart: u32,
+    pub nightend: u32,
+    pub unmeteredonly: bool,
+}
+
+pub struct LocalDb {
+    conn: Mutex<Connection>,
+}
+
+impl LocalDb {
+    pub fn open(data_dir: &Path) -> Result<Self, String> {
+        fs::create_dir_all(data_dir).map_err(|e| e.to_string())?;
+        let mut conn = Connection::open(data_dir.join(DB_FILE)).map_err(|e| e.to_string())?;
+        // WAL keeps reads working while a download is being recorded
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| e.to_string())?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(|e| e.to_string())?;
+        migrate(&mut conn).map_err(|e| e.to_string())?;
+        import_json(&mut conn, data_dir)?;
+        Ok(LocalDb {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn with_transaction<T>(
+        &self,
+        work: impl FnOnce(&Transaction) -> rusqlite::Result<T>,
+    ) -> Result<T, String> {
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let result = work(&tx).map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(result)
+    }
+
+    pub fn remove_episode(&self, episodeid: i32) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            tx.execute(
+                "DELETE FROM episodes WHERE episodeid = ?1",
+                params![episodeid],
+            )
+            .map(|_| ())
+        })
+    }
+
+    pub fn set_downloaded_location(&self, episodeid: i32, location: &str) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            tx.execute(
+                "UPDATE episodes SET downloadedlocation = ?2 WHERE episodeid = ?1",
+                params![episodeid, location],
+            )
+            .map(|_| ())
+        })
+    }
+
+    pub fn episodes(&self) -> Result<Vec<EpisodeInfo>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {} FROM episodes ORDER BY episodepubdate DESC",
+                EPISODE_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+        let episodes = statement
+            .query_map([], episode_from_row)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        Ok(episodes)
+    }
+
+    pub fn episode(&self, episodeid: i32) -> Result<Option<EpisodeInfo>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM episodes WHERE episodeid = ?1",
+                EPISODE_COLUMNS
+            ),
+            params![episodeid],
+            episode_from_row,
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn download_jobs(&self) -> Result<Vec<DownloadJob>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {} FROM download_jobs ORDER BY position",
+                JOB_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+        let jobs = statement
+            .query_map([], job_from_row)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        Ok(jobs)
+    }
+
+    pub fn queue_download(
+        &self,
+        episode: &EpisodeInfo,
+        podcast: &PodcastDetails,
+        filename: &str,
+        expected_size: Option<u64>,
+        media_type: Option<&str>,
+    ) -> Result<(), String> {
+        let episode_json = serde_json::to_string(episode).map_err(|e| e.to_string())?;
+        let podcast_json = serde_json::to_string(podcast).map_err(|e| e.to_string())?;
+        self.with_transaction(|tx| {
+            let queued: bool = tx.query_row(
+                "SELECT EXISTS (SELECT 1 FROM download_jobs WHERE episodeid = ?1)",
+                params![episode.episodeid],
+                |row| row.get(0),
+            )?;
+            if queued {
+                return Ok(false);
+            }
+            tx.execute(
+                "INSERT INTO download_jobs (episodeid, title, filename, url, artworkurl, \
+                 expectedsize, episode, podcast, mediatype, position)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9,
+                    (SELECT COALESCE(MAX(position), 0) + 1 FROM download_jobs))",
+                params![
+                    episode.episodeid,
+                    episode.episodetitle,
+                    filename,
+                    episode.episodeurl,
+                    episode.episodeartwork,
+                    expected_size,
+                    episode_json,
+                    podcast_json,
+                    media_type,
+                ],
+            )?;
+            Ok(true)
+        })
+        .and_then(|inserted| {
+            if inserted {
+                Ok(())
+            } else {
+                Err(format!("{} is already queued", episode.episodetitle))
+            }
+        })
+    }
+
+    pub fn set_job_state(
+        &self,
+        id: i64,
+        state: JobState,
+        attempts: u32,
+        nextattempt: i64,
+        error: Option<&str>,
+    ) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            tx.execute(
+                "UPDATE download_jobs SET state = ?2, attempts = ?3, nextattempt = ?4, error = ?5 \
+                 WHERE id = ?1",
+                params![id, state.as_str(), attempts, nextattempt, error],
+            )
+            .map(|_| ())
+        })
+    }
+
+    // Moves every job in one of the from states to the to state, for pause and resume all
+    pub fn set_jobs_state(&self, from: &[JobState], to: JobState) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            for state in from {
+                tx.execute(
+                    "UPDATE download_jobs SET state = ?2, nextattempt = 0 WHERE state = ?1",
+                    params![state.as_str(), to.as_str()],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn remove_job(&self, id: i64) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            tx.execute("DELETE FROM download_jobs WHERE id = ?1", params![id])
+                .map(|_| ())
+        })
+    }
+
+    // Puts the job at the given place in the queue, counting from 0
+    pub fn move_job(&self, id: i64, position: usize) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            let mut ids: Vec<i64> = tx
+                .prepare("SELECT id FROM download_jobs WHERE id != ?1 ORDER BY position")?
+                .query_map(params![id], |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?;
+            ids.insert(position.min(ids.len()), id);
+            for (index, job_id) in ids.iter().enumerate() {
+                tx.execute(
+                    "UPDATE download_jobs SET position = ?2 WHERE id = ?1",
+                    params![job_id, index as i64 + 1],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    // The episode and its podcast join the library and the job is done, all or nothing
+    pub fn finish_job(
+        &self,
+        id: i64,
+        episode: &EpisodeInfo,
+        podcast: &PodcastDetails,
+    ) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            upsert_podcast(tx, podcast)?;
+            upsert_episode(tx, episode)?;
+            tx.execute("DELETE FROM download_jobs WHERE id = ?1", params![id])
+                .map(|_| ())
+        })
+    }
+
+    pub fn download_settings(&self) -> Result<DownloadSettings, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT maxconcurrent, hostinterval, maxattempts, schedule, nightstart, nightend, \
+             unmeteredonly FROM download_settings WHERE id = 1",
+            [],
+            |row| {
+                Ok(DownloadSettings {
+                    maxconcurrent: row.get(0)?,
+                    hostinterval: row.get(1)?,
+                    maxattempts: row.get(2)?,
+                    schedule: row.get(3)?,
+                    nightstart: row.get(4)?,
+                    nightend: row.get(5)?,
+                    unmeteredonly: row.get(6)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn save_download_settings(&self, settings: &DownloadSettings) -> Result<(), String> {
+        self.with_transaction(|tx| {
+            tx.execute(
+                "UPDATE download_settings SET maxconcurrent = ?1, hostinterval = ?2, \
+                 maxattempts = ?3, schedule = ?4, nightstart = ?5, nightend = ?6, \
+                 unmeteredonly = ?7 WHERE id = 1",
+                params![
+                    settings.maxconcurrent,
+                    settings.hostinterval,
+                    settings.maxattempts,
+                    settings.schedule,
+                    settings.nightstart,
+                    settings.nightend,
+                    settings.unmeteredonly,
+                ],
+            )
+            .map(|_| ())
+        })
+    }
+
+    pub fn podcasts(&self) -> Result<Vec<Podcast>, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let mut statement = conn
+            .prepare(
+                "SELECT podcastid, podcastname, artworkurl, description, episodecount, \
+                 websiteurl, feedurl, author, categories, explicit \
+                 FROM podcasts ORDER BY podcastname COLLATE NOCASE",
+            )
+            .map_err(|e| e.to_string())?;
+        let podcasts = statement
+            .query_map([], |row| {
+                Ok(Podcast {
+                    podcastid: row.get(0)?,
+                    podcastname: row.get(1)?,
+                    artworkurl: row.get(2)?,
+                    description: row.get(3)?,
+                    episodecount: row.get(4)?,
+                    websiteurl: row.get(5)?,
+                    feedurl: row.get(6)?,
+                    author: row.get(7)?,
+                    categories: row.get(8)?,
+                    explicit: row.get(9)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        Ok(podcasts)
+    }
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+// Moves the JSON files older versions wrote into the database. They're renamed afterwards
+// rather than deleted, and the upserts make running it twice harmless.
+fn import_json(conn: &mut Connection, data_dir: &Path) -> Result<(), String> {
+    let episodes_path = data_dir.join(EPISODES_JSON);
+    let podcasts_path = data_dir.join(PODCASTS_JSON);
+    let episodes: Vec<EpisodeInfo> = read_json(&episodes_path)?;
+    let podcasts: Vec<Podcast> = read_json(&podcasts_path)?;
+    if episodes.is_empty() && podcasts.is_empty() {
+        return Ok(());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for podcast in podcasts {
+        let details = PodcastDetails {
+            podcastid: podcast.podcastid,
+            artworkurl: podcast.artworkurl.unwrap_or_default(),
+            author: podcast.author.unwrap_or_default(),
+            categories: podcast.categories,
+            description: podcast.description.unwrap_or_default(),
+            episodecount: podcast.episodecount,
+            explicit: podcast.explicit,
+            feedurl: podcast.feedurl,
+            podcastname: podcast.podcastname,
+            userid: 0,
+            websiteurl: podcast.websiteurl.unwrap_or_default(),
+        };
+        upsert_podcast(&tx, &details).map_err(|e| e.to_string())?;
+    }
+    // The old file could hold the same episode more than once, the last copy wins
+    for episode in &episodes {
+        upsert_episode(&tx, episode).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    for path in [episodes_path, podcasts_path] {
+        if path.exists() {
+            fs::rename(&path, path.with_extension("json.imported")).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+// A file that doesn't parse is moved aside as .json.bad so it can't stop the app from starting,
+// and the rest of the library still opens
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    match serde_json::from_str(&data) {
+        Ok(items) => Ok(items),
+        Err(e) => {
+            println!("Failed to import {}: {}", path.display(), e);
+            fs::rename(path, path.with_extension("json.bad")).map_err(|e| e.to_string())?;
+            Ok(Vec::new())
+        }
+    }
+}
+
+fn upsert_episode(tx: &Transaction, episode: &EpisodeInfo) -> rusqlite::Result<()> {
+    tx.execute(
+        &format!(
+            "INSERT INTO episodes ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT (episodeid) DO UPDATE SET
+                episodetitle = excluded.episodetitle,
+                podcastname = excluded.podcastname,
+                podcastid = excluded.podcastid,
+                episodepubdate = excluded.episodepubdate,
+                episodedescription = excluded.episodedescription,
+                episodeartwork = excluded.episodeartwork,
+                episodeurl = excluded.episodeurl,
+                episodeduration = excluded.episodeduration,
+                listenduration = excluded.listenduration,
+                completed = excluded.completed,
+                downloadedlocation = excluded.downloadedlocation",
+            EPISODE_COLUMNS
+        ),
+        params![
+            episode.episodetitle,
+            episode.podcastname,
+            episode.podcastid,
+            episode.episodepubdate,
+            episode.episodedescription,
+            episode.episodeartwork,
+            episode.episodeurl,
+            episode.episodeduration,
+            episode.listenduration,
+            episode.episodeid,
+            episode.completed,
+            episode.downloadedlocation,
+        ],
+    )
+    .map(|_| ())
+}
+
+fn upsert_podcast(tx: &Transaction, podcast: &PodcastDetails) -> rusqlite::Result<()> {
+    tx.execute(
+        "INSERT INTO podcasts (podcastid, podcastname, artworkurl, author, categories, \
+         description, episodecount, explicit, feedurl, userid, websiteurl)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT (podcastid) DO UPDATE SET
+            podcastname = excluded.podcastname,
+            artworkurl = excluded.artworkurl,
+            author = excluded.author,
+            categories = excluded.categories,
+            description = excluded.description,
+            episodecount = excluded.episodecount,
+            explicit = excluded.explicit,
+            feedurl = excluded.feedurl,
+            userid = excluded.userid,
+            websiteurl = excluded.websiteurl",
+        params![
+            podcast.podcastid,
+            podcast.podcastname,
+            podcast.artworkurl,
+            podcast.author,
+            podcast.categories,
+            podcast.description,
+            podcast.episodecount,
+            podcast.explicit,
+            podcast.feedurl,
+            podcast.userid,
+            podcast.websiteurl,
+        ],
+    )
+    .map(|_| ())
+}
+
+fn job_from_row(row: &Row) -> rusqlite::Result<DownloadJob> {
+    Ok(DownloadJob {
+        id: row.get(0)?,
+        episodeid: row.get(1)?,
+        title: row.get(2)?,
+        filename: row.get(3)?,
+        url: row.get(4)?,
+        artworkurl: row.get(5)?,
+        expectedsize: row.get(6)?,
+        episode: row.get(7)?,
+        podcast: row.get(8)?,
+        position: row.get(9)?,
+        state: JobState::parse(&row.get::<_, String>(10)?),
+        attempts: row.get(11)?,
+        nextattempt: row.get(12)?,
+        error: row.get(13)?,
+        mediatype: row.get(14)?,
+    })
+}
+
+fn episode_from_row(row: &Row) -> rusqlite::Result<EpisodeInfo> {
+    Ok(EpisodeInfo {
+        episodetitle: row.get(0)?,
+        podcastname: row.get(1)?,
+        podcastid: row.get(2)?,
+        episodepubdate: row.get(3)?,
+        episodedescription: row.get(4)?,
+        episodeartwork: row.get(5)?,
+        episodeurl: row.get(6)?,
+        episodeduration: row.get(7)?,
+        listenduration: row.get(8)?,
+        episodeid: row.get(9)?,
+        completed: row.get(10)?,
+        downloadedlocation: row.get(11)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // A fresh directory per test, tests run in parallel
+    fn data_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("pinepods-local-db-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn episode_json(episodeid: i32) -> String {
+        format!(
+            r#"{{"episodetitle": "Episode {id}", "podcastname": "Podcast", "podcastid": 7,
+            "episodepubdate": "2024-01-0{id}T00:00:00", "episodedescription": "",
+            "episodeartwork": "", "episodeurl": "https://example.com/{id}.mp3",
+            "episodeduration": 60, "listenduration": null, "episodeid": {id},
+            "completed": false, "downloadedlocation": "/data/episode_{id}.mp3"}}"#,
+            id = episodeid
+        )
+    }
+
+    const PODCAST_JSON: &str = r#"[{"podcastid": 7, "podcastname": "Podcast", "artworkurl": null,
+        "description": null, "episodecount": 2, "websiteurl": null,
+        "feedurl": "https://example.com/feed.xml", "author": null, "categories": "",
+        "explicit": false}]"#;
+
+    fn user_version(conn: &Connection) -> usize {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrations_run_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn migrations_drop_job_extensions() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.execute_batch(MIGRATIONS[1]).unwrap();
+        conn.pragma_update(None, "user_version", 2).unwrap();
+        conn.execute(
+            "INSERT INTO download_jobs (episodeid, title, filename, url, artworkurl, episode, \
+             podcast, position) VALUES (5, 'Episode 5', 'episode_5.mp3', '', '', '', '', 1)",
+            [],
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+        let (filename, mediatype): (String, Option<String>) = conn
+            .query_row(
+                "SELECT filename, mediatype FROM download_jobs WHERE episodeid = 5",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(filename, "episode_5");
+        assert_eq!(mediatype, None);
+    }
+
+    #[test]
+    fn imports_legacy_json() {
+        let dir = data_dir("import");
+        // The same episode twice, as older versions could write it
+        let episodes = format!(
+            "[{}, {}, {}]",
+            episode_json(1),
+            episode_json(2),
+            episode_json(2)
+        );
+        fs::write(dir.join(EPISODES_JSON), episodes).unwrap();
+        fs::write(dir.join(PODCASTS_JSON), PODCAST_JSON).unwrap();
+
+        let db = LocalDb::open(&dir).unwrap();
+        let episodes = db.episodes().unwrap();
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[0].episodeid, 2);
+        assert_eq!(
+            episodes[0].downloadedlocation.as_deref(),
+            Some("/data/episode_2.mp3")
+        );
+        assert_eq!(
+            db.podcasts().unwrap()[0].feedurl,
+            "https://example.com/feed.xml"
+        );
+        assert!(!dir.join(EPISODES_JSON).exists());
+        assert!(dir.join("local_episodes.json.imported").exists());
+        assert!(dir.join("local_podcasts.json.imported").exists());
+        drop(db);
+
+        // Opening again finds nothing left to import
+        let db = LocalDb::open(&dir).unwrap();
+        assert_eq!(db.episodes().unwrap().len(), 2);
+        drop(db);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn malformed_json_is_set_aside() {
+        let dir = data_dir("malformed");
+        fs::write(dir.join(EPISODES_JSON), "[{\"episodetitle\": ").unwrap();
+        fs::write(dir.join(PODCASTS_JSON), PODCAST_JSON).unwrap();
+
+        let db = LocalDb::open(&dir).unwrap();
+        assert!(db.episodes().unwrap().is_empty());
+        assert_eq!(db.podcasts().unwrap().len(), 1);
+        assert!(!dir.join(EPISODES_JSON).exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("local_episodes.json.bad")).unwrap(),
+            "[{\"episodetitle\": "
+        );
+        assert!(dir.join("local_podcasts.json.imported").exists());
+        drop(db);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,13 +1,21 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod download_queue;
+mod downloads;
+mod local_db;
+mod media_server;
+mod media_type;
+
 use directories::ProjectDirs;
+use download_queue::DownloadQueue;
+use downloads::DownloadManager;
+use local_db::{DownloadJob, DownloadSettings, LocalDb};
+use media_server::MediaServer;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
-use tauri::command;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, State};
 
 // Define the structure for the file entries
 #[derive(Serialize, Deserialize)]
@@ -51,25 +59,6 @@ fn get_app_dir() -> Result<String, String> {
     Ok(app_dir.display().to_string())
 }
 
-#[command]
-async fn download_file(url: String, filename: String) -> Result<(), String> {
-    let proj_dirs = get_project_dirs()?;
-    let app_dir = proj_dirs.data_dir();
-    if !app_dir.exists() {
-        fs::create_dir_all(app_dir).map_err(|e| e.to_string())?;
-    }
-
-    let response = reqwest::get(&url).await.map_err(|e| e.to_string())?;
-    let content = response.bytes().await.map_err(|e| e.to_string())?;
-
-    tokio::task::block_in_place(|| {
-        let mut file = fs::File::create(app_dir.join(&filename)).map_err(|e| e.to_string())?;
-        file.write_all(&content).map_err(|e| e.to_string())
-    })?;
-
-    Ok(())
-}
-
 #[derive(Debug, Deserialize, Clone, PartialEq, Serialize)]
 pub struct EpisodeInfo {
     pub episodetitle: String,
@@ -87,90 +76,89 @@ pub struct EpisodeInfo {
 }
 
 #[command]
-async fn update_local_db(mut episode_info: EpisodeInfo) -> Result<(), String> {
+async fn remove_from_local_db(db: State<'_, LocalDb>, episodeid: i32) -> Result<(), String> {
     let proj_dirs = get_project_dirs().map_err(|e| e.to_string())?;
-    let db_path = proj_dirs.data_dir().join("local_episodes.json");
-
-    // Calculate the downloaded location
-    let download_dir = proj_dirs
-        .data_dir()
-        .join(format!("episode_{}.mp3", episode_info.episodeid));
-    episode_info.downloadedlocation = Some(download_dir.to_string_lossy().into_owned());
-
-    let mut episodes = if db_path.exists() {
-        let data = std::fs::read_to_string(&db_path).map_err(|e| e.to_string())?;
-        serde_json::from_str::<Vec<EpisodeInfo>>(&data).map_err(|e| e.to_string())?
-    } else {
-        Vec::new()
-    };
-
-    episodes.push(episode_info);
-
-    let file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&db_path)
-        .map_err(|e| e.to_string())?;
-    serde_json::to_writer(file, &episodes).map_err(|e| e.to_string())?;
-
-    Ok(())
-}
-
-#[command]
-async fn remove_from_local_db(episodeid: i32) -> Result<(), String> {
-    let proj_dirs = get_project_dirs().map_err(|e| e.to_string())?;
-    let db_path = proj_dirs.data_dir().join("local_episodes.json");
-
-    let mut episodes = if db_path.exists() {
-        let data = std::fs::read_to_string(&db_path).map_err(|e| e.to_string())?;
-        serde_json::from_str::<Vec<EpisodeInfo>>(&data).map_err(|e| e.to_string())?
-    } else {
-        return Ok(()); // No episodes to remove if file doesn't exist
-    };
-
-    episodes.retain(|episode| episode.episodeid != episodeid);
-
-    let file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&db_path)
-        .map_err(|e| e.to_string())?;
-    serde_json::to_writer(file, &episodes).map_err(|e| e.to_string())?;
-
-    // Delete the audio file and artwork
-    let audio_file_path = proj_dirs
-        .data_dir()
-        .join(format!("episode_{}.mp3", episodeid));
-    let artwork_file_path = proj_dirs
-        .data_dir()
-        .join(format!("artwork_{}.jpg", episodeid));
-
-    if audio_file_path.exists() {
-        std::fs::remove_file(audio_file_path).map_err(|e| e.to_string())?;
+    let location = db
+        .episode(episodeid)?
+        .and_then(|episode| episode.downloadedlocation);
+    db.remove_episode(episodeid)?;
+
+    // Delete the audio file
+    if let Some(location) = location {
+        let audio_file_path = PathBuf::from(location);
+        if audio_file_path.exists() {
+            fs::remove_file(audio_file_path).map_err(|e| e.to_string())?;
+        }
     }
 
-    if artwork_file_path.exists() {
-        std::fs::remove_file(artwork_file_path).map_err(|e| e.to_string())?;
+    // The artwork keeps whatever extension its type called for. Part files belong to the queue.
+    let stems = [
+        format!("episode_{}", episodeid),
+        format!("artwork_{}", episodeid),
+    ];
+    for entry in fs::read_dir(proj_dirs.data_dir()).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let stem = path.file_stem().and_then(|stem| stem.to_str());
+        let is_part = path.extension().map_or(false, |ext| ext == "part");
+        if !is_part && stem.map_or(false, |stem| stems.iter().any(|name| name == stem)) {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
     }
 
     Ok(())
 }
 
 #[command]
-async fn get_local_episodes() -> Result<Vec<EpisodeInfo>, String> {
-    let proj_dirs = get_project_dirs().map_err(|e| e.to_string())?;
-    let db_path = proj_dirs.data_dir().join("local_episodes.json");
+async fn get_local_episodes(db: State<'_, LocalDb>) -> Result<Vec<EpisodeInfo>, String> {
+    db.episodes()
+}
 
-    if !db_path.exists() {
-        return Ok(Vec::new());
-    }
+// Mirrors the JSON chapters format used by the frontend
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LocalChapter {
+    start_time: f64,
+    end_time: Option<f64>,
+    title: Option<String>,
+}
 
-    let data = std::fs::read_to_string(&db_path).map_err(|e| e.to_string())?;
-    let episodes = serde_json::from_str::<Vec<EpisodeInfo>>(&data).map_err(|e| e.to_string())?;
+#[command]
+async fn get_local_chapters(
+    db: State<'_, LocalDb>,
+    episodeid: i32,
+) -> Result<Vec<LocalChapter>, String> {
+    let file_path = db
+        .episode(episodeid)?
+        .and_then(|episode| episode.downloadedlocation)
+        .ok_or_else(|| format!("Episode {} is not downloaded", episodeid))?;
+
+    let tag = match id3::Tag::read_from_path(&file_path) {
+        Ok(tag) => tag,
+        // Files without an ID3 tag simply have no chapters
+        Err(e) if matches!(e.kind, id3::ErrorKind::NoTag) => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
 
-    Ok(episodes)
+    let mut chapters: Vec<LocalChapter> = tag
+        .chapters()
+        .map(|chapter| LocalChapter {
+            start_time: chapter.start_time as f64 / 1000.0,
+            end_time: Some(chapter.end_time as f64 / 1000.0),
+            title: chapter
+                .frames
+                .iter()
+                .find(|frame| frame.id() == "TIT2")
+                .and_then(|frame| frame.content().text())
+                .map(|title| title.to_string()),
+        })
+        .collect();
+    chapters.sort_by(|a, b| {
+        a.start_time
+            .partial_cmp(&b.start_time)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(chapters)
 }
 
 #[command]
@@ -215,33 +203,103 @@ pub struct PodcastDetails {
     pub websiteurl: String,
 }
 
+// Queues the episode's audio, it joins the library with its podcast once downloaded.
+// expected_size and media_type are the enclosure length and type from the feed.
 #[command]
-async fn update_podcast_db(podcast_details: PodcastDetails) -> Result<(), String> {
-    let proj_dirs = get_project_dirs().map_err(|e| e.to_string())?;
-    let db_path = proj_dirs.data_dir().join("local_podcasts.json");
+fn queue_episode_download(
+    app: AppHandle,
+    db: State<'_, LocalDb>,
+    queue: State<'_, DownloadQueue>,
+    episode_info: EpisodeInfo,
+    podcast_details: PodcastDetails,
+    expected_size: Option<u64>,
+    media_type: Option<String>,
+) -> Result<(), String> {
+    let filename = format!("episode_{}", episode_info.episodeid);
+    db.queue_download(
+        &episode_info,
+        &podcast_details,
+        &filename,
+        expected_size,
+        media_type.as_deref(),
+    )?;
+    download_queue::emit_jobs(&app);
+    queue.wake();
+    Ok(())
+}
 
-    let mut podcasts = if db_path.exists() {
-        let data = std::fs::read_to_string(&db_path).map_err(|e| e.to_string())?;
-        serde_json::from_str::<Vec<PodcastDetails>>(&data).map_err(|e| e.to_string())?
-    } else {
-        Vec::new()
-    };
+#[command]
+fn get_download_jobs(db: State<'_, LocalDb>) -> Result<Vec<DownloadJob>, String> {
+    db.download_jobs()
+}
 
-    if !podcasts
-        .iter()
-        .any(|p| p.podcastid == podcast_details.podcastid)
-    {
-        podcasts.push(podcast_details);
-    }
+#[command]
+fn pause_download_job(app: AppHandle, id: i64) -> Result<(), String> {
+    download_queue::pause_job(&app, id)?;
+    download_queue::emit_jobs(&app);
+    Ok(())
+}
+
+#[command]
+fn resume_download_job(app: AppHandle, id: i64) -> Result<(), String> {
+    download_queue::resume_job(&app, id)?;
+    download_queue::emit_jobs(&app);
+    Ok(())
+}
+
+#[command]
+async fn remove_download_job(app: AppHandle, id: i64) -> Result<(), String> {
+    download_queue::remove_job(&app, id).await?;
+    download_queue::emit_jobs(&app);
+    Ok(())
+}
+
+#[command]
+fn move_download_job(
+    app: AppHandle,
+    db: State<'_, LocalDb>,
+    id: i64,
+    position: usize,
+) -> Result<(), String> {
+    db.move_job(id, position)?;
+    download_queue::emit_jobs(&app);
+    Ok(())
+}
+
+#[command]
+fn pause_all_downloads(app: AppHandle) -> Result<(), String> {
+    download_queue::pause_all(&app)?;
+    download_queue::emit_jobs(&app);
+    Ok(())
+}
+
+#[command]
+fn resume_all_downloads(app: AppHandle) -> Result<(), String> {
+    download_queue::resume_all(&app)?;
+    download_queue::emit_jobs(&app);
+    Ok(())
+}
+
+#[command]
+async fn clear_failed_downloads(app: AppHandle) -> Result<(), String> {
+    download_queue::clear_failed(&app).await?;
+    download_queue::emit_jobs(&app);
+    Ok(())
+}
 
-    let file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&db_path)
-        .map_err(|e| e.to_string())?;
-    serde_json::to_writer(file, &podcasts).map_err(|e| e.to_string())?;
+#[command]
+fn get_download_settings(db: State<'_, LocalDb>) -> Result<DownloadSettings, String> {
+    db.download_settings()
+}
 
+#[command]
+fn save_download_settings(
+    db: State<'_, LocalDb>,
+    queue: State<'_, DownloadQueue>,
+    settings: DownloadSettings,
+) -> Result<(), String> {
+    db.save_download_settings(&settings)?;
+    queue.wake();
     Ok(())
 }
 
@@ -261,18 +319,8 @@ pub struct Podcast {
 }
 
 #[command]
-async fn get_local_podcasts() -> Result<Vec<Podcast>, String> {
-    let proj_dirs = get_project_dirs().map_err(|e| e.to_string())?;
-    let db_path = proj_dirs.data_dir().join("local_podcasts.json");
-
-    if !db_path.exists() {
-        return Ok(Vec::new());
-    }
-
-    let data = std::fs::read_to_string(&db_path).map_err(|e| e.to_string())?;
-    let podcasts = serde_json::from_str::<Vec<Podcast>>(&data).map_err(|e| e.to_string())?;
-
-    Ok(podcasts)
+async fn get_local_podcasts(db: State<'_, LocalDb>) -> Result<Vec<Podcast>, String> {
+    db.podcasts()
 }
 
 #[tauri::command]
@@ -288,51 +336,60 @@ async fn get_local_file(filepath: String) -> Result<Vec<u8>, String> {
     Ok(buffer)
 }
 
-#[tauri::command]
-async fn start_file_server(filepath: String) -> Result<String, String> {
-    // Log the file path to ensure it's correct
-    println!("Starting file server with path: {}", filepath);
-
-    // Ensure the path exists and is accessible
-    if !std::path::Path::new(&filepath).exists() {
-        return Err(format!("File path does not exist: {}", filepath));
-    }
-
-    // Get the directory of the file
-    let file_dir = std::path::Path::new(&filepath)
-        .parent()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_string();
-
-    // Log the directory being served
-    println!("Serving files from directory: {}", file_dir);
-
-    // Create the warp filter to serve the directory containing the file
-    let file_route = warp::fs::dir(file_dir);
-
-    // Start the warp server
-    tokio::spawn(warp::serve(file_route).run(([127, 0, 0, 1], 3030)));
-
-    Ok("http://127.0.0.1:3030".to_string())
+// Where the player streams a downloaded episode from, only files in the local library are served
+#[command]
+fn get_episode_media_url(
+    db: State<'_, LocalDb>,
+    server: State<'_, MediaServer>,
+    episodeid: i32,
+) -> Result<String, String> {
+    let location = db
+        .episode(episodeid)?
+        .and_then(|episode| episode.downloadedlocation)
+        .ok_or_else(|| format!("Episode {} is not downloaded", episodeid))?;
+    server.url_for(Path::new(&location))
 }
 
 fn main() {
+    let proj_dirs = get_project_dirs().expect("Cannot determine project directories");
+    let local_db = LocalDb::open(proj_dirs.data_dir()).expect("Failed to open the local library");
+    if let Err(e) = media_type::migrate_downloads(&local_db, proj_dirs.data_dir()) {
+        println!("Failed to update downloaded file names: {}", e);
+    }
+    let downloads = DownloadManager::new().expect("Failed to set up downloads");
+    let media_server = MediaServer::start().expect("Failed to start the local media server");
+
     tauri::Builder::default()
+        .manage(local_db)
+        .manage(downloads)
+        .manage(DownloadQueue::default())
+        .manage(media_server)
+        .setup(|app| {
+            tauri::async_runtime::spawn(download_queue::run(app.handle()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             list_dir,
             get_app_dir,
-            download_file,
             delete_file,
-            update_local_db,
             remove_from_local_db,
-            update_podcast_db,
             get_local_podcasts,
             get_local_episodes,
             list_app_files,
             get_local_file,
-            start_file_server
+            get_local_chapters,
+            get_episode_media_url,
+            queue_episode_download,
+            get_download_jobs,
+            pause_download_job,
+            resume_download_job,
+            remove_download_job,
+            move_download_job,
+            pause_all_downloads,
+            resume_all_downloads,
+            clear_failed_downloads,
+            get_download_settings,
+            save_download_settings
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,252 @@
+// Serves downloaded episodes to the player for as long as the app runs. It listens on an
+// ephemeral port on localhost and only hands out files the app registered, each behind its own
+// random token. Range requests are answered so the player can seek.
+
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+use tokio_util::io::ReaderStream;
+use warp::http::header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE};
+use warp::http::{Response, StatusCode};
+use warp::hyper::Body;
+use warp::{Filter, Rejection};
+
+const TOKEN_LEN: usize = 32;
+// Where the app's own pages are served from on each platform. No other site gets to read the
+// responses, even with a token.
+const APP_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "https://tauri.localhost",
+    "http://tauri.localhost",
+];
+// The devPath in tauri.conf.json
+#[cfg(debug_assertions)]
+const DEV_ORIGIN: &str = "http://localhost:8080";
+
+// Registered files by token
+type Files = Arc<Mutex<HashMap<String, PathBuf>>>;
+
+pub struct MediaServer {
+    addr: SocketAddr,
+    files: Files,
+}
+
+impl MediaServer {
+    // Needs to run outside the async runtime, it blocks until the port is bound
+    pub fn start() -> Result<Self, String> {
+        let files = Files::default();
+        let lookup = files.clone();
+        #[allow(unused_mut)]
+        let mut origins = APP_ORIGINS.to_vec();
+        #[cfg(debug_assertions)]
+        origins.push(DEV_ORIGIN);
+        let route = warp::path!(String / String)
+            .and(warp::get().or(warp::head()).unify())
+            .and(warp::header::optional::<String>("range"))
+            .and_then(move |token, name, range| serve(lookup.clone(), token, name, range))
+            // Lets the player route downloads through Web Audio for Smart Speed
+            .with(
+                warp::cors()
+                    .allow_origins(origins)
+                    .allow_methods(["GET", "HEAD"]),
+            );
+
+        let (addr, server) = tauri::async_runtime::block_on(async move {
+            warp::serve(route).try_bind_ephemeral(([127, 0, 0, 1], 0))
+        })
+        .map_err(|e| e.to_string())?;
+        tauri::async_runtime::spawn(server);
+        Ok(MediaServer { addr, files })
+    }
+
+    // The URL the player loads the file from, a file keeps the same one until the app closes
+    pub fn url_for(&self, path: &Path) -> Result<String, String> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| format!("Invalid media file: {}", path.display()))?;
+        let mut files = self.files.lock().map_err(|e| e.to_string())?;
+        let existing = files
+            .iter()
+            .find(|(_, registered)| registered.as_path() == path)
+            .map(|(token, _)| token.clone());
+        let token = match existing {
+            Some(token) => token,
+            None => {
+                let token: String = rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(TOKEN_LEN)
+                    .map(char::from)
+                    .collect();
+                files.insert(token.clone(), path.to_path_buf());
+                token
+            }
+        };
+        Ok(format!("http://{}/{}/{}", self.addr, token, name))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ByteRange {
+    Whole,
+    // First and last byte, inclusive like the header
+    Part(u64, u64),
+    Unsatisfiable,
+}
+
+async fn serve(
+    files: Files,
+    token: String,
+    name: String,
+    range: Option<String>,
+) -> Result<Response<Body>, Rejection> {
+    // The name in the URL has to match too, so a token is only good for its own file
+    let path = files
+        .lock()
+        .ok()
+        .and_then(|files| files.get(&token).cloned())
+        .filter(|path| path.file_name().and_then(|file| file.to_str()) == Some(name.as_str()))
+        .ok_or_else(warp::reject::not_found)?;
+    let mut file = File::open(&path)
+        .await
+        .map_err(|_| warp::reject::not_found())?;
+    let size = file
+        .metadata()
+        .await
+        .map_err(|_| warp::reject::not_found())?
+        .len();
+
+    // mime_guess calls .m4a audio/m4a, which not every player knows
+    let mime = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("m4a") => "audio/mp4".to_string(),
+        _ => mime_guess::from_path(&path)
+            .first_or_octet_stream()
+            .to_string(),
+    };
+    let response = Response::builder()
+        .header(CONTENT_TYPE, mime)
+        .header(ACCEPT_RANGES, "bytes");
+    let response = match byte_range(range.as_deref(), size) {
+        ByteRange::Whole => response
+            .header(CONTENT_LENGTH, size)
+            .body(Body::wrap_stream(ReaderStream::new(file))),
+        ByteRange::Part(first, last) => {
+            file.seek(SeekFrom::Start(first))
+                .await
+                .map_err(|_| warp::reject::not_found())?;
+            let length = last - first + 1;
+            response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(CONTENT_RANGE, format!("bytes {}-{}/{}", first, last, size))
+                .header(CONTENT_LENGTH, length)
+                .body(Body::wrap_stream(ReaderStream::new(file.take(length))))
+        }
+        ByteRange::Unsatisfiable => response
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(CONTENT_RANGE, format!("bytes */{}", size))
+            .body(Body::empty()),
+    };
+    response.map_err(|_| warp::reject::not_found())
+}
+
+// Only single ranges are answered, which is all media players ask for. Anything the header
+// doesn't make sense as gets the whole file, as the spec allows.
+fn byte_range(header: Option<&str>, size: u64) -> ByteRange {
+    let spec = match header.and_then(|header| header.trim().strip_prefix("bytes=")) {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return ByteRange::Whole,
+    };
+    let (first, last) = match spec.split_once('-') {
+        Some(bounds) => bounds,
+        None => return ByteRange::Whole,
+    };
+    let range = match (first.trim().parse::<u64>(), last.trim()) {
+        // bytes=-500 is the last 500 bytes
+        (Err(_), suffix) if first.trim().is_empty() => match suffix.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(suffix) => (size.saturating_sub(suffix), size.saturating_sub(1)),
+            Err(_) => return ByteRange::Whole,
+        },
+        (Ok(first), "") => (first, size.saturating_sub(1)),
+        (Ok(first), last) => match last.parse::<u64>() {
+            Ok(last) if last >= first => (first, last.min(size.saturating_sub(1))),
+            _ => return ByteRange::Whole,
+        },
+        (Err(_), _) => return ByteRange::Whole,
+    };
+    if size == 0 || range.0 >= size {
+        ByteRange::Unsatisfiable
+    } else {
+        ByteRange::Part(range.0, range.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn open_ended_ranges_run_to_the_end() {
+        assert_eq!(byte_range(Some("bytes=0-"), 1000), ByteRange::Part(0, 999));
+        assert_eq!(
+            byte_range(Some("bytes=400-"), 1000),
+            ByteRange::Part(400, 999)
+        );
+    }
+
+    #[test]
+    fn suffix_ranges_count_from_the_end() {
+        assert_eq!(
+            byte_range(Some("bytes=-500"), 1000),
+            ByteRange::Part(500, 999)
+        );
+        // A suffix longer than the file is the whole file
+        assert_eq!(
+            byte_range(Some("bytes=-5000"), 1000),
+            ByteRange::Part(0, 999)
+        );
+        assert_eq!(byte_range(Some("bytes=-0"), 1000), ByteRange::Unsatisfiable);
+    }
+
+    #[test]
+    fn ranges_are_held_to_the_file() {
+        assert_eq!(byte_range(Some("bytes=0-99"), 1000), ByteRange::Part(0, 99));
+        assert_eq!(
+            byte_range(Some("bytes=500-5000"), 1000),
+            ByteRange::Part(500, 999)
+        );
+        assert_eq!(
+            byte_range(Some("bytes=1000-"), 1000),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            byte_range(Some("bytes=5000-6000"), 1000),
+            ByteRange::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn anything_else_gets_the_whole_file() {
+        assert_eq!(byte_range(None, 1000), ByteRange::Whole);
+        assert_eq!(byte_range(Some("bytes=0-10,20-30"), 1000), ByteRange::Whole);
+        assert_eq!(byte_range(Some("bytes=10-5"), 1000), ByteRange::Whole);
+        assert_eq!(byte_range(Some("bytes=abc"), 1000), ByteRange::Whole);
+        assert_eq!(byte_range(Some("bytes=a-b"), 1000), ByteRange::Whole);
+        assert_eq!(byte_range(Some("bytes=-x"), 1000), ByteRange::Whole);
+        assert_eq!(byte_range(Some("items=0-10"), 1000), ByteRange::Whole);
+        assert_eq!(byte_range(Some(""), 1000), ByteRange::Whole);
+    }
+
+    #[test]
+    fn empty_files_have_no_ranges() {
+        assert_eq!(byte_range(None, 0), ByteRange::Whole);
+        assert_eq!(byte_range(Some("bytes=0-"), 0), ByteRange::Unsatisfiable);
+        assert_eq!(byte_range(Some("bytes=-500"), 0), ByteRange::Unsatisfiable);
+        assert_eq!(byte_range(Some("bytes=0-99"), 0), ByteRange::Unsatisfiable);
+    }
+}
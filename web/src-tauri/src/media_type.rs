@@ -0,0 +1,283 @@
+// Works out the extension a download is saved under, so media players and the local file server
+// see what the file really is. The file's own magic bytes win, then the server's Content-Type,
+// then the type the feed listed for the enclosure.
+
+use crate::local_db::LocalDb;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// Enough of the start of a file for every signature in sniff
+pub const SNIFF_LEN: usize = 64;
+
+pub fn extension(head: &[u8], content_type: Option<&str>, listed_type: &str) -> &'static str {
+    let declared = content_type
+        .and_then(extension_for_mime)
+        .or_else(|| extension_for_mime(listed_type));
+    match (sniff(head), declared) {
+        // Generic MP4 brands hold audio-only files too, the declared type tells those apart
+        (Some("mp4"), Some("m4a")) => "m4a",
+        (Some(sniffed), _) => sniffed,
+        (None, Some(declared)) => declared,
+        (None, None) if listed_type.starts_with("image/") => "jpg",
+        (None, None) => "mp3",
+    }
+}
+
+// None for generic types like application/octet-stream, they say nothing about the file
+fn extension_for_mime(mime: &str) -> Option<&'static str> {
+    let mime = mime.split(';').next()?.trim().to_ascii_lowercase();
+    Some(match mime.as_str() {
+        "audio/mpeg" | "audio/mp3" | "audio/mpeg3" | "audio/x-mpeg" | "audio/x-mp3" => "mp3",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/mp4a-latm" => "m4a",
+        "audio/aac" | "audio/x-aac" | "audio/aacp" => "aac",
+        "audio/ogg" | "application/ogg" | "audio/vorbis" => "ogg",
+        "audio/opus" => "opus",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/wav" | "audio/wave" | "audio/x-wav" => "wav",
+        "audio/webm" | "video/webm" => "webm",
+        "video/mp4" => "mp4",
+        "video/x-m4v" => "m4v",
+        "video/quicktime" => "mov",
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => "jpg",
+        "image/png" => "png",
+        "image/webp" => "webp",
+        "image/gif" => "gif",
+        _ => return None,
+    })
+}
+
+fn sniff(head: &[u8]) -> Option<&'static str> {
+    match head {
+        [b'I', b'D', b'3', ..] => Some("mp3"),
+        [_, _, _, _, b'f', b't', b'y', b'p', brand @ ..] => match brand.get(..3) {
+            Some(b"M4A") | Some(b"M4B") | Some(b"M4P") => Some("m4a"),
+            Some(b"M4V") => Some("m4v"),
+            Some(b"qt ") => Some("mov"),
+            _ => Some("mp4"),
+        },
+        [b'O', b'g', b'g', b'S', ..] => {
+            if head.get(28..36) == Some(b"OpusHead") {
+                Some("opus")
+            } else {
+                Some("ogg")
+            }
+        }
+        [b'f', b'L', b'a', b'C', ..] => Some("flac"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some("wav"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("webp"),
+        [0x1a, 0x45, 0xdf, 0xa3, ..] => Some("webm"),
+        [0xff, 0xd8, 0xff, ..] => Some("jpg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("png"),
+        [b'G', b'I', b'F', b'8', ..] => Some("gif"),
+        // ADTS AAC and MPEG audio frames share the sync word, AAC's layer bits are always zero
+        [0xff, second, ..] if second & 0xf6 == 0xf0 => Some("aac"),
+        [0xff, second, ..] if second & 0xe0 == 0xe0 && second & 0x06 != 0 => Some("mp3"),
+        _ => None,
+    }
+}
+
+fn read_head(path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)?;
+    Ok(head)
+}
+
+// Downloads from before file types were detected were all saved as .mp3 and .jpg. Files already
+// named right are left alone, so this only does work once.
+pub fn migrate_downloads(db: &LocalDb, data_dir: &Path) -> Result<(), String> {
+    for episode in db.episodes()? {
+        if let Some(location) = &episode.downloadedlocation {
+            match fix_extension(Path::new(location)) {
+                Ok(Some(renamed)) => {
+                    db.set_downloaded_location(episode.episodeid, &renamed.to_string_lossy())?
+                }
+                Ok(None) => {}
+                Err(e) => println!("Failed to check the type of {}: {}", location, e),
+            }
+        }
+        let artwork = data_dir.join(format!("artwork_{}.jpg", episode.episodeid));
+        if let Err(e) = fix_extension(&artwork) {
+            println!("Failed to check the type of {}: {}", artwork.display(), e);
+        }
+    }
+
+    // Queued downloads used to carry the .mp3 in their part file's name
+    for job in db.download_jobs()? {
+        let old_part = data_dir.join(format!("{}.mp3.part", job.filename));
+        let part = data_dir.join(format!("{}.part", job.filename));
+        if old_part.exists() && !part.exists() {
+            fs::rename(&old_part, &part).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+// The file's new path when its contents called for another extension
+fn fix_extension(path: &Path) -> std::io::Result<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let is_episode = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map_or(false, |stem| stem.starts_with("episode_"));
+    let detected = match sniff(&read_head(path)?) {
+        // Episodes are audio, a generic MP4 brand on one is an audio-only file
+        Some("mp4") if is_episode => "m4a",
+        Some(detected) => detected,
+        None => return Ok(None),
+    };
+    let current = path.extension().and_then(|ext| ext.to_str());
+    if current == Some(detected) || (current == Some("m4a") && detected == "mp4") {
+        return Ok(None);
+    }
+    let renamed = path.with_extension(detected);
+    fs::rename(path, &renamed)?;
+    Ok(Some(renamed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ftyp(brand: &[u8; 4]) -> Vec<u8> {
+        let mut head = vec![0, 0, 0, 0x20];
+        head.extend_from_slice(b"ftyp");
+        head.extend_from_slice(brand);
+        head.extend_from_slice(&[0; 20]);
+        head
+    }
+
+    fn ogg(codec: &[u8]) -> Vec<u8> {
+        let mut head = b"OggS".to_vec();
+        head.resize(28, 0);
+        head.extend_from_slice(codec);
+        head
+    }
+
+    // A fresh directory per test, tests run in parallel
+    fn data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pinepods-media-type-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn sniffs_mpeg_audio() {
+        assert_eq!(sniff(b"ID3\x04\x00\x00\x00\x00\x00\x00"), Some("mp3"));
+        // MPEG-1 layer III frame sync
+        assert_eq!(sniff(&[0xff, 0xfb, 0x90, 0x64]), Some("mp3"));
+        // ADTS AAC has the same sync word with the layer bits zeroed
+        assert_eq!(sniff(&[0xff, 0xf1, 0x50, 0x80]), Some("aac"));
+    }
+
+    #[test]
+    fn sniffs_mp4_brands() {
+        assert_eq!(sniff(&ftyp(b"M4A ")), Some("m4a"));
+        assert_eq!(sniff(&ftyp(b"M4B ")), Some("m4a"));
+        assert_eq!(sniff(&ftyp(b"M4V ")), Some("m4v"));
+        assert_eq!(sniff(&ftyp(b"qt  ")), Some("mov"));
+        assert_eq!(sniff(&ftyp(b"isom")), Some("mp4"));
+        assert_eq!(sniff(&ftyp(b"mp42")), Some("mp4"));
+    }
+
+    #[test]
+    fn sniffs_other_audio() {
+        assert_eq!(sniff(&ogg(b"OpusHead")), Some("opus"));
+        assert_eq!(sniff(&ogg(b"\x01vorbis")), Some("ogg"));
+        assert_eq!(sniff(b"fLaC\x00\x00\x00\x22"), Some("flac"));
+        assert_eq!(sniff(b"RIFF\x24\x08\x00\x00WAVEfmt "), Some("wav"));
+        assert_eq!(sniff(b"<html><body>"), None);
+        assert_eq!(sniff(b""), None);
+    }
+
+    #[test]
+    fn generic_mp4_takes_the_declared_audio_type() {
+        assert_eq!(extension(&ftyp(b"isom"), None, "audio/mp4"), "m4a");
+        assert_eq!(
+            extension(&ftyp(b"isom"), Some("audio/x-m4a"), "video/mp4"),
+            "m4a"
+        );
+        assert_eq!(extension(&ftyp(b"isom"), None, "video/mp4"), "mp4");
+    }
+
+    #[test]
+    fn magic_bytes_win_over_declared_types() {
+        assert_eq!(
+            extension(b"ID3\x04", Some("audio/mp4"), "audio/x-m4a"),
+            "mp3"
+        );
+        assert_eq!(
+            extension(&ftyp(b"M4A "), Some("audio/mpeg"), "audio/mpeg"),
+            "m4a"
+        );
+    }
+
+    #[test]
+    fn unknown_headers_fall_back_to_the_declared_types() {
+        let unknown = b"\x00\x01\x02\x03";
+        assert_eq!(
+            extension(unknown, Some("audio/ogg; codecs=opus"), "audio/mpeg"),
+            "ogg"
+        );
+        // Generic server types say nothing, the feed's type is used instead
+        assert_eq!(
+            extension(unknown, Some("application/octet-stream"), "audio/x-m4a"),
+            "m4a"
+        );
+        assert_eq!(extension(unknown, None, "AUDIO/FLAC"), "flac");
+        assert_eq!(extension(unknown, None, "image/avif"), "jpg");
+        assert_eq!(extension(unknown, None, "application/octet-stream"), "mp3");
+    }
+
+    #[test]
+    fn fix_extension_renames_by_contents() {
+        let dir = data_dir("fix-extension");
+        let write = |name: &str, contents: &[u8]| {
+            let path = dir.join(name);
+            fs::write(&path, contents).unwrap();
+            path
+        };
+
+        let m4a = write("episode_1.mp3", &ftyp(b"M4A "));
+        assert_eq!(
+            fix_extension(&m4a).unwrap(),
+            Some(dir.join("episode_1.m4a"))
+        );
+        assert!(!m4a.exists());
+        assert!(dir.join("episode_1.m4a").exists());
+
+        // Episodes with a generic MP4 brand are audio
+        let generic = write("episode_2.mp3", &ftyp(b"isom"));
+        assert_eq!(
+            fix_extension(&generic).unwrap(),
+            Some(dir.join("episode_2.m4a"))
+        );
+
+        let png = write("artwork_1.jpg", b"\x89PNG\r\n\x1a\n");
+        assert_eq!(
+            fix_extension(&png).unwrap(),
+            Some(dir.join("artwork_1.png"))
+        );
+
+        // Files already named right, ones that can't be told apart and missing ones stay put
+        let mp3 = write("episode_3.mp3", b"ID3\x04\x00");
+        assert_eq!(fix_extension(&mp3).unwrap(), None);
+        let already_m4a = write("episode_4.m4a", &ftyp(b"isom"));
+        assert_eq!(fix_extension(&already_m4a).unwrap(), None);
+        let unknown = write("episode_5.mp3", b"\x00\x01\x02\x03");
+        assert_eq!(fix_extension(&unknown).unwrap(), None);
+        assert!(unknown.exists());
+        assert_eq!(fix_extension(&dir.join("episode_6.mp3")).unwrap(), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
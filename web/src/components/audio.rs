@@ -1,19 +1,29 @@
+use crate::components::audio_graph;
+use crate::components::bookmarks::{BookmarkControl, ClipRange};
 use crate::components::context::{AppState, UIState};
 #[cfg(not(feature = "server_build"))]
-use crate::components::downloads_tauri::start_local_file_server;
+use crate::components::downloads_tauri::{fetch_local_chapters, fetch_local_episode_url};
+use crate::components::gen_funcs::format_time;
+use crate::components::media_session;
+use crate::components::sleep_timer::{SleepTimerControl, SleepTimerMode, SLEEP_FADE_SECONDS};
+use crate::requests::chapters::{call_get_chapters, current_chapter_index};
 #[cfg(not(feature = "server_build"))]
 use crate::requests::pod_req::EpisodeDownload;
 use crate::requests::pod_req::{
     call_add_history, call_check_episode_in_db, call_get_auto_skip_times,
+    call_get_episode_metadata, call_get_playback_profile, call_get_podcast_details,
     call_get_podcast_id_from_ep, call_get_queued_episodes, call_increment_listen_time,
-    call_increment_played, call_mark_episode_completed, call_queue_episode,
-    call_record_listen_duration, call_remove_queued_episode, HistoryAddRequest,
-    MarkEpisodeCompletedRequest, QueuePodcastRequest, RecordListenDurationRequest,
+    call_increment_played, call_increment_time_saved, call_mark_episode_completed,
+    call_queue_episode, call_record_listen_duration, call_remove_queued_episode, EpisodeRequest,
+    HistoryAddRequest, MarkEpisodeCompletedRequest, QueuePodcastRequest, QueuedEpisode,
+    RecordListenDurationRequest,
 };
+use crate::requests::setting_reqs::{
+    call_get_audio_processing, call_get_queue_settings, AudioProcessing, QueueSettings,
+};
+use gloo_events::EventListener;
 use gloo_timers::callback::Interval;
 use std::cell::Cell;
-#[cfg(not(feature = "server_build"))]
-use std::path::Path;
 use std::rc::Rc;
 use std::string::String;
 use wasm_bindgen::closure::Closure;
@@ -38,6 +48,8 @@ pub struct AudioPlayerProps {
     pub start_pos_sec: f64,
     pub end_pos_sec: f64,
     pub offline: bool,
+    #[prop_or_default]
+    pub podcast_name: Option<String>,
 }
 
 #[function_component(AudioPlayer)]
@@ -104,36 +116,63 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
             || ()
         }
     });
-    // Update playing state when Spacebar is pressed
-    let audio_dispatch_effect = _audio_dispatch.clone();
-    use_effect_with((), move |_| {
-        let keydown_handler = {
-            let audio_info = audio_dispatch_effect.clone();
-            Closure::wrap(Box::new(move |event: KeyboardEvent| {
-                // Check if the event target is not an input or textarea
-                let target = event
-                    .target()
-                    .unwrap()
-                    .dyn_into::<web_sys::HtmlElement>()
-                    .unwrap();
-                if !(target.tag_name().eq_ignore_ascii_case("input")
-                    || target.tag_name().eq_ignore_ascii_case("textarea"))
-                {
-                    if event.key() == " " {
-                        // Prevent the default behavior of the spacebar key
-                        event.prevent_default();
-                        // Toggle `audio_playing` here
-                        audio_info.reduce_mut(|state| state.toggle_playback());
-                    }
+    // Load chapters for the new episode. Downloaded episodes in the desktop app use the ID3 CHAP
+    // frames in the file, everything else uses the <podcast:chapters> link from the feed.
+    use_effect_with((src_clone.clone(), episode_id.clone()), {
+        let audio_dispatch = _audio_dispatch.clone();
+        let feed_chapters_url = state
+            .podcast_feed_results
+            .as_ref()
+            .filter(|results| results.podcast.is_some())
+            .and_then(|results| {
+                results
+                    .episodes
+                    .iter()
+                    .find(|episode| episode.enclosure_url.as_deref() == Some(props.src.as_str()))
+                    .map(|episode| episode.chapters_url.clone())
+            });
+        let server_name = server_name.clone();
+        let api_key = api_key.clone();
+        let user_id = user_id.clone();
+        let offline = props.offline;
+        move |(_, episode_id): &(String, Option<i32>)| {
+            let episode_id = *episode_id;
+            audio_dispatch.reduce_mut(|state| state.chapters = None);
+            if offline {
+                if let Some(episode_id) = episode_id {
+                    load_local_chapters(episode_id, audio_dispatch);
                 }
-            }) as Box<dyn FnMut(_)>)
-        };
-        window()
-            .unwrap()
-            .add_event_listener_with_callback("keydown", keydown_handler.as_ref().unchecked_ref())
-            .unwrap();
-        keydown_handler.forget(); // Note: this will make the listener permanent
-        || ()
+            } else if let (Some(server_name), Some(api_key)) = (server_name, api_key) {
+                wasm_bindgen_futures::spawn_local(async move {
+                    // Played from anywhere but a feed preview, the server has it
+                    let chapters_url = match (feed_chapters_url, episode_id, user_id) {
+                        (Some(chapters_url), _, _) => chapters_url,
+                        (None, Some(episode_id), Some(user_id)) => {
+                            episode_chapters_url(&server_name, &api_key, user_id, episode_id).await
+                        }
+                        (None, _, _) => None,
+                    };
+                    let chapters_url = match chapters_url {
+                        Some(chapters_url) => chapters_url,
+                        None => return,
+                    };
+                    match call_get_chapters(&server_name, &api_key, &chapters_url).await {
+                        Ok(chapters) if !chapters.is_empty() => {
+                            audio_dispatch.reduce_mut(move |state| {
+                                state.chapters = Some(chapters);
+                            });
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            web_sys::console::log_1(
+                                &format!("Error loading chapters: {}", e).into(),
+                            );
+                        }
+                    }
+                });
+            }
+            || ()
+        }
     });
 
     // Effect for setting up an interval to update the current playback time
@@ -230,11 +269,30 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                             0.0
                         };
 
+                        // Pause at the end of a clip, and stop following it once the listener
+                        // seeks away from it
+                        if let Some(clip) = audio_dispatch.get().active_clip {
+                            if clip.reached_end(time_in_seconds) {
+                                audio_element.pause().unwrap_or(());
+                                audio_dispatch.reduce_mut(|state| {
+                                    state.active_clip = None;
+                                    state.audio_playing = Some(false);
+                                });
+                            } else if !clip.contains(time_in_seconds) {
+                                audio_dispatch.reduce_mut(|state| state.active_clip = None);
+                            }
+                        }
+
                         audio_dispatch.reduce_mut(move |state_clone| {
                             // Update the global state with the current time
                             state_clone.current_time_seconds = time_in_seconds;
                             state_clone.current_time_formatted = formatted_time;
                         });
+                        media_session::set_position_state(
+                            duration,
+                            time_in_seconds,
+                            audio_element.playback_rate(),
+                        );
 
                         progress.set(progress_percentage);
                     }
@@ -328,6 +386,104 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
         }
     });
 
+    // Effect for running the sleep timer. It reads the latest state on every tick since the timer
+    // can be started, restarted or cancelled at any point while an episode plays.
+    use_effect_with((offline_status.clone(), episode_id.clone()), {
+        let audio_dispatch = _audio_dispatch.clone();
+        let server_name = server_name.clone();
+        let api_key = api_key.clone().flatten();
+        let user_id = user_id.clone();
+        let offline = offline_status.unwrap_or(false);
+
+        move |(_, episode_id): &(Option<bool>, Option<i32>)| {
+            let episode_id = *episode_id;
+            let interval = {
+                let audio_dispatch = audio_dispatch.clone();
+                Interval::new(1000, move || {
+                    let state = audio_dispatch.get();
+                    let timer = match state.sleep_timer.as_ref() {
+                        Some(timer) => timer,
+                        None => return,
+                    };
+                    let remaining = timer.remaining_seconds(&state);
+                    // End-of-episode timers fire from the ended handler so the queue doesn't advance
+                    if remaining <= 0.0 && timer.mode != SleepTimerMode::EndOfEpisode {
+                        fire_sleep_timer(
+                            &audio_dispatch,
+                            server_name.clone(),
+                            api_key.clone(),
+                            user_id,
+                            episode_id,
+                            offline,
+                        );
+                    } else if remaining < SLEEP_FADE_SECONDS {
+                        let fade = (remaining / SLEEP_FADE_SECONDS).clamp(0.0, 1.0);
+                        audio_dispatch.reduce_mut(move |state| {
+                            if let Some(audio) = &state.audio_element {
+                                audio_graph::set_player_volume(
+                                    audio,
+                                    state.audio_volume / 100.0 * fade,
+                                );
+                            }
+                            if let Some(timer) = state.sleep_timer.as_mut() {
+                                timer.fading = true;
+                            }
+                        });
+                    }
+                })
+            };
+
+            // Interacting with the app while the volume fades out restarts the timer
+            let document = window().unwrap().document().unwrap();
+            let interaction_listeners: Vec<EventListener> = ["pointerdown", "keydown"]
+                .iter()
+                .map(|event_type| {
+                    let audio_dispatch = audio_dispatch.clone();
+                    EventListener::new(&document, *event_type, move |_| {
+                        let fading = audio_dispatch
+                            .get()
+                            .sleep_timer
+                            .as_ref()
+                            .map_or(false, |timer| timer.fading);
+                        if fading {
+                            audio_dispatch.reduce_mut(|state| state.restart_sleep_timer());
+                        }
+                    })
+                })
+                .collect();
+
+            // Shaking the device restarts the timer at any point
+            let last_shake = Rc::new(Cell::new(0.0));
+            let shake_listener = {
+                let audio_dispatch = audio_dispatch.clone();
+                EventListener::new(&window().unwrap(), "devicemotion", move |event| {
+                    let acceleration = event
+                        .dyn_ref::<web_sys::DeviceMotionEvent>()
+                        .and_then(|motion| motion.acceleration_including_gravity());
+                    if let Some(acceleration) = acceleration {
+                        let magnitude = (acceleration.x().unwrap_or_default().powi(2)
+                            + acceleration.y().unwrap_or_default().powi(2)
+                            + acceleration.z().unwrap_or_default().powi(2))
+                        .sqrt();
+                        let now = js_sys::Date::now();
+                        if magnitude > SHAKE_THRESHOLD && now - last_shake.get() > 1000.0 {
+                            last_shake.set(now);
+                            if audio_dispatch.get().sleep_timer.is_some() {
+                                audio_dispatch.reduce_mut(|state| state.restart_sleep_timer());
+                            }
+                        }
+                    }
+                })
+            };
+
+            move || {
+                interval.cancel();
+                drop(interaction_listeners);
+                drop(shake_listener);
+            }
+        }
+    });
+
     // Effect for incrementing user listen time
     let state_increment_clone = audio_state.clone();
     use_effect_with((offline_status.clone(), episode_id.clone()), {
@@ -348,6 +504,27 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                         &"Offline mode enabled. Not incrementing listen time.".into(),
                     );
                 } else {
+                    let seconds_saved = audio_graph::take_time_saved();
+                    if seconds_saved > 0 {
+                        if let (Some(server_name), Some(Some(api_key)), Some(user_id)) =
+                            (server_name.clone(), api_key.clone(), user_id)
+                        {
+                            wasm_bindgen_futures::spawn_local(async move {
+                                if let Err(e) = call_increment_time_saved(
+                                    &server_name,
+                                    &api_key,
+                                    user_id,
+                                    seconds_saved,
+                                )
+                                .await
+                                {
+                                    web_sys::console::log_1(
+                                        &format!("Error recording time saved: {}", e).into(),
+                                    );
+                                }
+                            });
+                        }
+                    }
                     if state_increment_clone.audio_playing.unwrap_or_default() {
                         let server_name = server_name.clone();
                         let api_key = api_key.clone();
@@ -382,15 +559,13 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
     });
 
     // Effect for managing queued episodes
-    use_effect_with(audio_ref.clone(), {
+    // Keyed on the element too, since a new one is created when leaving the Web Audio graph
+    use_effect_with((audio_ref.clone(), audio_state.audio_element.clone()), {
         let audio_dispatch = _audio_dispatch.clone();
         let server_name = server_name.clone();
         let api_key = api_key.clone();
         let user_id = user_id.clone();
-        let current_episode_id = episode_id.clone(); // Assuming this is correctly obtained elsewhere
-        let audio_state = audio_state.clone();
         let audio_state_cloned = audio_state.clone();
-        let offline_status = offline_status.clone();
 
         move |_| {
             if let Some(audio_element) = audio_state_cloned.audio_element.clone() {
@@ -398,86 +573,44 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                 // Clone all necessary data to be used inside the closure to avoid FnOnce limitation.
 
                 let ended_closure = Closure::wrap(Box::new(move || {
-                    let server_name = server_name.clone();
-                    let api_key = api_key.clone();
-                    let user_id = user_id.clone();
-                    let audio_dispatch = audio_dispatch.clone();
-                    let current_episode_id = current_episode_id.clone();
-                    let audio_state = audio_state.clone();
-                    let offline_status_loop = offline_status.unwrap_or(false);
-                    // Closure::wrap(Box::new(move |_| {
+                    // The element outlives the episode it started with, so read what's playing now
+                    let playing = audio_dispatch.get().currently_playing.clone();
+                    let current_episode_id = playing.as_ref().map(|playing| playing.episode_id);
+                    let offline_status_loop =
+                        playing.as_ref().map_or(false, |playing| playing.offline);
+                    // A sleep timer set for the end of the episode stops here instead of moving on
+                    let sleep_at_end = audio_dispatch
+                        .get()
+                        .sleep_timer
+                        .as_ref()
+                        .map_or(false, |timer| timer.mode == SleepTimerMode::EndOfEpisode);
+                    if sleep_at_end {
+                        fire_sleep_timer(
+                            &audio_dispatch,
+                            server_name.clone(),
+                            api_key.clone().flatten(),
+                            user_id,
+                            current_episode_id,
+                            offline_status_loop,
+                        );
+                    }
                     if offline_status_loop {
                         // If offline, do not perform any action
                         web_sys::console::log_1(
                             &"Offline mode enabled. Not managing queue.".into(),
                         );
-                    } else {
-                        wasm_bindgen_futures::spawn_local(async move {
-                            let queued_episodes_result = call_get_queued_episodes(
-                                &server_name.clone().unwrap(),
-                                &api_key.clone().unwrap(),
-                                &user_id.clone().unwrap(),
-                            )
-                            .await;
-                            match queued_episodes_result {
-                                Ok(episodes) => {
-                                    if let Some(current_episode) = episodes
-                                        .iter()
-                                        .find(|ep| ep.episodeid == current_episode_id.unwrap())
-                                    {
-                                        let current_queue_position =
-                                            current_episode.queueposition.unwrap_or_default();
-                                        // Remove the currently playing episode from the queue
-                                        let request = QueuePodcastRequest {
-                                            episode_id: current_episode_id.clone().unwrap(),
-                                            user_id: user_id.clone().unwrap(), // replace with the actual user ID
-                                        };
-                                        let remove_result = call_remove_queued_episode(
-                                            &server_name.clone().unwrap(),
-                                            &api_key.clone().unwrap(),
-                                            &request,
-                                        )
-                                        .await;
-                                        match remove_result {
-                                            Ok(_) => {
-                                                // web_sys::console::log_1(&"Successfully removed episode from queue".into());
-                                            }
-                                            Err(_e) => {
-                                                // web_sys::console::log_1(&format!("Failed to remove episode from queue: {:?}", e).into());
-                                            }
-                                        }
-                                        if let Some(next_episode) = episodes.iter().find(|ep| {
-                                            ep.queueposition == Some(current_queue_position + 1)
-                                        }) {
-                                            on_play_click(
-                                                next_episode.episodeurl.clone(),
-                                                next_episode.episodetitle.clone(),
-                                                next_episode.episodeartwork.clone(),
-                                                next_episode.episodeduration,
-                                                next_episode.episodeid,
-                                                next_episode.listenduration,
-                                                api_key.clone().unwrap().unwrap(),
-                                                user_id.unwrap(),
-                                                server_name.clone().unwrap(),
-                                                audio_dispatch.clone(),
-                                                audio_state.clone(),
-                                                None,
-                                            )
-                                            .emit(MouseEvent::new("click").unwrap());
-                                        } else {
-                                            audio_dispatch.reduce_mut(|state| {
-                                                state.audio_playing = Some(false);
-                                            });
-                                        }
-                                    }
-                                }
-                                Err(_e) => {
-                                    // web_sys::console::log_1(&format!("Failed to fetch queued episodes: {:?}", e).into());
-                                }
-                            }
-                        });
+                    } else if let (Some(server_name), Some(user_id), Some(current_episode_id)) =
+                        (server_name.clone(), user_id, current_episode_id)
+                    {
+                        advance_queue(
+                            server_name,
+                            api_key.clone().flatten(),
+                            user_id,
+                            current_episode_id,
+                            !sleep_at_end,
+                            audio_dispatch.clone(),
+                        );
                     }
-                    // }) as Box<dyn FnMut()>);
                 }) as Box<dyn FnMut()>);
                 // Setting and forgetting the closure must be done within the same scope
                 audio_element.set_onended(Some(ended_closure.as_ref().unchecked_ref()));
@@ -541,6 +674,33 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
         })
     };
 
+    // Turn Smart Speed on or off for the current episode. Turning it on for a source that isn't
+    // routed yet reloads it with CORS, once the host is known to allow that.
+    let toggle_smart_speed = {
+        let audio_dispatch = _audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if audio_dispatch.get().smart_speed {
+                audio_dispatch.reduce_mut(|state| {
+                    state.smart_speed = false;
+                    if let Some(audio) = &state.audio_element {
+                        audio_graph::set_smart_speed(audio, false);
+                    }
+                });
+                return;
+            }
+            let audio_dispatch = audio_dispatch.clone();
+            spawn_local(async move {
+                audio_dispatch.reduce_mut(|state| state.smart_speed = true);
+                if !route_current_episode(&audio_dispatch).await {
+                    audio_dispatch.reduce_mut(|state| {
+                        state.smart_speed = false;
+                        state.smart_speed_unavailable = true;
+                    });
+                }
+            });
+        })
+    };
+
     let volume_dispatch = _audio_dispatch.clone();
 
     // Adjust the volume based on a slider value
@@ -550,7 +710,8 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
             audio_dispatch.reduce_mut(|audio_state| {
                 audio_state.audio_volume = volume;
                 if let Some(audio_element) = &audio_state.audio_element {
-                    audio_element.set_volume(volume / 100.0); // Set volume as a percentage
+                    // Set volume as a percentage, after the processing chain when it's in use
+                    audio_graph::set_player_volume(audio_element, volume / 100.0);
                 }
             });
         })
@@ -575,7 +736,8 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
         let audio_dispatch = _audio_dispatch.clone();
         Callback::from(move |_| {
             if let Some(audio_element) = skip_state.audio_element.as_ref() {
-                let new_time = audio_element.current_time() + 15.0;
+                let step = skip_state.active_playback_profile().skip_forward as f64;
+                let new_time = audio_element.current_time() + step;
                 audio_element.set_current_time(new_time);
                 audio_dispatch.reduce_mut(|state| state.update_current_time(new_time));
             }
@@ -588,7 +750,8 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
         let audio_dispatch = _audio_dispatch.clone();
         Callback::from(move |_| {
             if let Some(audio_element) = backward_state.audio_element.as_ref() {
-                let new_time = audio_element.current_time() - 15.0;
+                let step = backward_state.active_playback_profile().skip_backward as f64;
+                let new_time = audio_element.current_time() - step;
                 audio_element.set_current_time(new_time);
                 audio_dispatch.reduce_mut(|state| state.update_current_time(new_time));
             }
@@ -604,58 +767,155 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
         let audio_state = audio_state.clone();
 
         Callback::from(move |_: MouseEvent| {
-            let server_name = server_name.clone();
-            let api_key = api_key.clone();
-            let audio_dispatch = audio_dispatch.clone();
-            let audio_state = audio_state.clone();
-            wasm_bindgen_futures::spawn_local(async move {
-                let episodes_result = call_get_queued_episodes(
-                    &server_name.clone().unwrap(),
-                    &api_key.clone().unwrap(),
-                    &user_id.clone().unwrap(),
-                )
-                .await;
-                if let Ok(episodes) = episodes_result {
-                    if let Some(current_episode) = episodes
-                        .iter()
-                        .find(|ep| ep.episodeid == current_episode_id.unwrap())
-                    {
-                        let current_queue_position =
-                            current_episode.queueposition.unwrap_or_default();
+            if let (Some(server_name), Some(user_id), Some(current_episode_id)) =
+                (server_name.clone(), user_id, current_episode_id)
+            {
+                play_next_in_queue(
+                    server_name,
+                    api_key.clone().flatten(),
+                    user_id,
+                    current_episode_id,
+                    audio_dispatch.clone(),
+                    audio_state.clone(),
+                );
+            }
+        })
+    };
 
-                        if let Some(next_episode) = episodes
-                            .iter()
-                            .find(|ep| ep.queueposition == Some(current_queue_position + 1))
-                        {
-                            on_play_click(
-                                next_episode.episodeurl.clone(),
-                                next_episode.episodetitle.clone(),
-                                next_episode.episodeartwork.clone(),
-                                next_episode.episodeduration,
-                                next_episode.episodeid,
-                                next_episode.listenduration,
-                                api_key.clone().unwrap().unwrap(),
-                                user_id.unwrap(),
-                                server_name.clone().unwrap(),
-                                audio_dispatch.clone(),
-                                audio_state.clone(),
-                                None,
-                            )
-                            .emit(MouseEvent::new("click").unwrap());
-                        } else {
-                            audio_dispatch.reduce_mut(|state| {
-                                state.audio_playing = Some(false);
-                            });
-                        }
+    let seek_chapter = {
+        let audio_dispatch = _audio_dispatch.clone();
+        Callback::from(move |start_time: f64| {
+            audio_dispatch.reduce_mut(|state| state.seek_to(start_time));
+        })
+    };
+
+    // Jumps to the start of the previous chapter, or back to the start of the current one when
+    // it has been playing for more than a few seconds
+    let previous_chapter = {
+        let audio_dispatch = _audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            audio_dispatch.reduce_mut(|state| {
+                let current_time = state.current_time_seconds;
+                let target = state.chapters.as_ref().and_then(|chapters| {
+                    let index = current_chapter_index(chapters, current_time)?;
+                    if current_time - chapters[index].start_time > 3.0 || index == 0 {
+                        Some(chapters[index].start_time)
+                    } else {
+                        Some(chapters[index - 1].start_time)
                     }
-                } else {
-                    // Handle the error, maybe log it or show a user-facing message
-                    web_sys::console::log_1(&"Failed to fetch queued episodes".into());
+                });
+                if let Some(target) = target {
+                    state.seek_to(target);
+                }
+            });
+        })
+    };
+
+    let next_chapter = {
+        let audio_dispatch = _audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            audio_dispatch.reduce_mut(|state| {
+                let current_time = state.current_time_seconds;
+                let target = state.chapters.as_ref().and_then(|chapters| {
+                    chapters
+                        .iter()
+                        .find(|chapter| chapter.start_time > current_time)
+                        .map(|chapter| chapter.start_time)
+                });
+                if let Some(target) = target {
+                    state.seek_to(target);
                 }
             });
         })
     };
 
+    // Effect for publishing the playing episode to the OS media overlay
+    let media_metadata = audio_state.currently_playing.as_ref().map(|props| {
+        (
+            props.title.clone(),
+            props.podcast_name.clone(),
+            props.artwork_url.clone(),
+        )
+    });
+    use_effect_with(media_metadata, |media_metadata| {
+        if let Some((title, podcast_name, artwork_url)) = media_metadata {
+            media_session::set_metadata(title, podcast_name.as_deref(), artwork_url);
+        }
+        || ()
+    });
+
+    use_effect_with(audio_state.audio_playing, |audio_playing| {
+        media_session::set_playback_state(audio_playing.unwrap_or(false));
+        || ()
+    });
+
+    // Effect for handling lock screen controls, headset buttons and keyboard media keys
+    use_effect_with(episode_id, {
+        let audio_dispatch = _audio_dispatch.clone();
+        let skip_episode = skip_episode.clone();
+        move |_| {
+            let set_playing = |playing: bool| {
+                let audio_dispatch = audio_dispatch.clone();
+                Closure::wrap(Box::new(move |_: JsValue| {
+                    audio_dispatch.reduce_mut(|state| {
+                        if state.audio_playing.unwrap_or(false) != playing {
+                            state.toggle_playback();
+                        }
+                    });
+                }) as Box<dyn FnMut(JsValue)>)
+            };
+            let seek_by = |direction: f64| {
+                let audio_dispatch = audio_dispatch.clone();
+                Closure::wrap(Box::new(move |details: JsValue| {
+                    let requested_offset = media_session::action_detail(&details, "seekOffset");
+                    audio_dispatch.reduce_mut(|state| {
+                        let profile = state.active_playback_profile();
+                        let default_step = if direction > 0.0 {
+                            profile.skip_forward
+                        } else {
+                            profile.skip_backward
+                        };
+                        let offset = requested_offset.unwrap_or(default_step as f64);
+                        let new_time = state.audio_element.as_ref().map(|audio_element| {
+                            (audio_element.current_time() + direction * offset).max(0.0)
+                        });
+                        if let Some(new_time) = new_time {
+                            state.seek_to(new_time);
+                        }
+                    });
+                }) as Box<dyn FnMut(JsValue)>)
+            };
+            let seek_to = {
+                let audio_dispatch = audio_dispatch.clone();
+                Closure::wrap(Box::new(move |details: JsValue| {
+                    if let Some(time) = media_session::action_detail(&details, "seekTime") {
+                        audio_dispatch.reduce_mut(|state| state.seek_to(time));
+                    }
+                }) as Box<dyn FnMut(JsValue)>)
+            };
+            let next_track = Closure::wrap(Box::new(move |_: JsValue| {
+                skip_episode.emit(MouseEvent::new("click").unwrap());
+            }) as Box<dyn FnMut(JsValue)>);
+
+            let handlers = vec![
+                ("play", set_playing(true)),
+                ("pause", set_playing(false)),
+                ("seekbackward", seek_by(-1.0)),
+                ("seekforward", seek_by(1.0)),
+                ("seekto", seek_to),
+                ("nexttrack", next_track),
+            ];
+            for (action, handler) in handlers.iter() {
+                media_session::set_action_handler(action, Some(handler.as_ref().unchecked_ref()));
+            }
+
+            move || {
+                media_session::clear_action_handlers();
+                drop(handlers);
+            }
+        }
+    });
+
     let audio_state = _audio_dispatch.get();
 
     // Check if there is an audio player prop set in AppState
@@ -722,6 +982,44 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                 ""
             }
         );
+        let chapters = audio_state.chapters.clone().unwrap_or_default();
+        let current_chapter = current_chapter_index(&chapters, audio_state.current_time_seconds);
+        let current_chapter_title = current_chapter
+            .and_then(|index| chapters[index].title.clone())
+            .unwrap_or_default();
+        let chapter_marks = if audio_props.duration_sec > 0.0 {
+            html! {
+                <div class="chapter-marks">
+                    { for chapters.iter().filter(|chapter| chapter.start_time > 0.0).map(|chapter| {
+                        let left = (chapter.start_time / audio_props.duration_sec * 100.0).min(100.0);
+                        html! {
+                            <span class="chapter-mark" style={format!("left: {}%;", left)} title={chapter.title.clone().unwrap_or_default()}></span>
+                        }
+                    })}
+                </div>
+            }
+        } else {
+            html! {}
+        };
+        let chapter_list = html! {
+            <ul class="chapter-list">
+                { for chapters.iter().enumerate().map(|(index, chapter)| {
+                    let seek_chapter = seek_chapter.clone();
+                    let start_time = chapter.start_time;
+                    let chapter_class = if Some(index) == current_chapter {
+                        classes!("chapter-list-item", "current-chapter")
+                    } else {
+                        classes!("chapter-list-item")
+                    };
+                    html! {
+                        <li class={chapter_class} onclick={Callback::from(move |_: MouseEvent| seek_chapter.emit(start_time))}>
+                            <span class="chapter-time">{ format_time(start_time) }</span>
+                            <span class="chapter-title">{ chapter.title.clone().unwrap_or_default() }</span>
+                        </li>
+                    }
+                })}
+            </ul>
+        };
         let update_volume_closure = update_playback_volume.clone();
         let update_playback_closure = update_playback_speed.clone();
         html! {
@@ -736,16 +1034,22 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                     </div>
                     <div class="title" onclick={title_click.clone()}>{ &audio_props.title }
                     </div>
+                    if !current_chapter_title.is_empty() {
+                        <div class="chapter-title-display">{ current_chapter_title.clone() }</div>
+                    }
                     <div class="scrub-bar">
                         <span>{audio_state.current_time_formatted.clone()}</span>
-                        <input type="range"
-                            class="flex-grow h-1 cursor-pointer"
-                            min="0.0"
-                            max={audio_props.duration_sec.to_string().clone()}
-                            value={audio_state.current_time_seconds.to_string()}
-                            oninput={update_time.clone()}
-                            style={progress_style}
-                        />
+                        <div class="scrub-bar-track flex-grow">
+                            <input type="range"
+                                class="w-full h-1 cursor-pointer"
+                                min="0.0"
+                                max={audio_props.duration_sec.to_string().clone()}
+                                value={audio_state.current_time_seconds.to_string()}
+                                oninput={update_time.clone()}
+                                style={progress_style}
+                            />
+                            { chapter_marks }
+                        </div>
                         <span>{formatted_duration.clone()}</span>
                     </div>
 
@@ -762,6 +1066,11 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                                 </>
                             }
                         }
+                        if !chapters.is_empty() {
+                            <button onclick={previous_chapter.clone()} title="Previous chapter" class="skip-button audio-top-button selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                                <span class="material-icons">{"first_page"}</span>
+                            </button>
+                        }
                         <button onclick={skip_backward.clone()} class="rewind-button audio-top-button selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
                             <span class="material-icons">{"fast_rewind"}</span>
                         </button>
@@ -773,9 +1082,16 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                         <button onclick={skip_forward.clone()} class="skip-button audio-top-button selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
                             <span class="material-icons">{"fast_forward"}</span>
                         </button>
+                        if !chapters.is_empty() {
+                            <button onclick={next_chapter.clone()} title="Next chapter" class="skip-button audio-top-button selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                                <span class="material-icons">{"last_page"}</span>
+                            </button>
+                        }
                         <button onclick={skip_episode.clone()} class="skip-button audio-top-button selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
                             <span class="material-icons">{"skip_next"}</span>
                         </button>
+                        <SleepTimerControl />
+                        <BookmarkControl />
                     </div>
                     <div class="episode-button-container flex items-center justify-center">
                     // Other buttons as before
@@ -797,6 +1113,19 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                                         update_playback_closure.emit(speed);
                                     })}
                                 />
+                                <button
+                                    onclick={toggle_smart_speed.clone()}
+                                    title="Speed through silences"
+                                    class={classes!("smart-speed-button", audio_state.smart_speed.then_some("active"))}
+                                    disabled={audio_state.smart_speed_unavailable}
+                                >
+                                    { if audio_state.smart_speed { "Smart Speed: On" } else { "Smart Speed: Off" } }
+                                </button>
+                                if audio_state.smart_speed_unavailable {
+                                    <div class="smart-speed-note">{"Smart Speed isn't available because this podcast's host doesn't allow its audio to be analyzed. Download the episode to use it."}</div>
+                                } else if audio_state.smart_speed {
+                                    <div class="smart-speed-note">{format!("Time saved: {}", format_time(audio_graph::session_time_saved()))}</div>
+                                }
                             </div>
                         </div>
                     </div>
@@ -844,6 +1173,9 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
                         </div>
                     </div>
                     </div>
+                    if !chapters.is_empty() {
+                        { chapter_list }
+                    }
                     </div>
 
                 </div>
@@ -884,6 +1216,277 @@ pub fn audio_player(props: &AudioPlayerProps) -> Html {
     }
 }
 
+// The episode queued after `episode_id`, or the front of the queue when it isn't queued
+fn next_in_queue(episodes: &[QueuedEpisode], episode_id: i32) -> Option<&QueuedEpisode> {
+    match episodes.iter().position(|ep| ep.episodeid == episode_id) {
+        Some(index) => episodes.get(index + 1),
+        None => episodes.first(),
+    }
+}
+
+// The user's queue settings, fetched once and then kept in the store
+async fn load_queue_settings(
+    audio_dispatch: &Dispatch<UIState>,
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: i32,
+) -> QueueSettings {
+    if let Some(settings) = audio_dispatch.get().queue_settings {
+        return settings;
+    }
+    let api_key = api_key.clone().unwrap_or_default();
+    match call_get_queue_settings(server_name, &api_key, user_id).await {
+        Ok(settings) => {
+            audio_dispatch.reduce_mut(move |state| state.queue_settings = Some(settings));
+            settings
+        }
+        Err(e) => {
+            web_sys::console::log_1(&format!("Error getting queue settings: {}", e).into());
+            QueueSettings::default()
+        }
+    }
+}
+
+// Runs when an episode finishes, from its real end or the podcast's end skip. Depending on the
+// user's queue settings it takes the episode out of the queue and starts the next one.
+// `keep_playing` is false when a sleep timer stops playback here.
+pub fn advance_queue(
+    server_name: String,
+    api_key: Option<String>,
+    user_id: i32,
+    finished_episode_id: i32,
+    keep_playing: bool,
+    audio_dispatch: Dispatch<UIState>,
+) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let settings = load_queue_settings(&audio_dispatch, &server_name, &api_key, user_id).await;
+        let episodes = match call_get_queued_episodes(&server_name, &api_key, &user_id).await {
+            Ok(episodes) => episodes,
+            Err(e) => {
+                web_sys::console::log_1(&format!("Failed to fetch queued episodes: {}", e).into());
+                return;
+            }
+        };
+
+        if settings.remove_completed
+            && episodes
+                .iter()
+                .any(|ep| ep.episodeid == finished_episode_id)
+        {
+            let request = QueuePodcastRequest {
+                episode_id: finished_episode_id,
+                user_id,
+            };
+            if let Err(e) = call_remove_queued_episode(&server_name, &api_key, &request).await {
+                web_sys::console::log_1(
+                    &format!("Failed to remove episode from queue: {}", e).into(),
+                );
+            }
+        }
+
+        match next_in_queue(&episodes, finished_episode_id)
+            .filter(|_| keep_playing && settings.auto_advance)
+        {
+            Some(next_episode) => on_play_click(
+                next_episode.episodeurl.clone(),
+                next_episode.episodetitle.clone(),
+                next_episode.episodeartwork.clone(),
+                next_episode.episodeduration,
+                next_episode.episodeid,
+                next_episode.listenduration,
+                api_key.clone().unwrap_or_default(),
+                user_id,
+                server_name.clone(),
+                audio_dispatch.clone(),
+                audio_dispatch.get(),
+                None,
+                None,
+            )
+            .emit(MouseEvent::new("click").unwrap()),
+            None => audio_dispatch.reduce_mut(|state| {
+                state.audio_playing = Some(false);
+            }),
+        }
+    });
+}
+
+// Plays the episode queued after `current_episode_id`, or stops when it was the last one
+pub fn play_next_in_queue(
+    server_name: String,
+    api_key: Option<String>,
+    user_id: i32,
+    current_episode_id: i32,
+    audio_dispatch: Dispatch<UIState>,
+    audio_state: Rc<UIState>,
+) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let episodes_result = call_get_queued_episodes(&server_name, &api_key, &user_id).await;
+        if let Ok(episodes) = episodes_result {
+            if let Some(next_episode) = next_in_queue(&episodes, current_episode_id) {
+                on_play_click(
+                    next_episode.episodeurl.clone(),
+                    next_episode.episodetitle.clone(),
+                    next_episode.episodeartwork.clone(),
+                    next_episode.episodeduration,
+                    next_episode.episodeid,
+                    next_episode.listenduration,
+                    api_key.clone().unwrap_or_default(),
+                    user_id,
+                    server_name.clone(),
+                    audio_dispatch.clone(),
+                    audio_state.clone(),
+                    None,
+                    None,
+                )
+                .emit(MouseEvent::new("click").unwrap());
+            } else {
+                audio_dispatch.reduce_mut(|state| {
+                    state.audio_playing = Some(false);
+                });
+            }
+        } else {
+            // Handle the error, maybe log it or show a user-facing message
+            web_sys::console::log_1(&"Failed to fetch queued episodes".into());
+        }
+    });
+}
+
+// Total acceleration (m/s², including gravity) that counts as a shake
+const SHAKE_THRESHOLD: f64 = 25.0;
+
+// Stops playback when the sleep timer runs out and saves where the listener dozed off
+fn fire_sleep_timer(
+    audio_dispatch: &Dispatch<UIState>,
+    server_name: Option<String>,
+    api_key: Option<String>,
+    user_id: Option<i32>,
+    episode_id: Option<i32>,
+    offline: bool,
+) {
+    let mut listen_duration = None;
+    audio_dispatch.reduce_mut(|state| {
+        if let Some(audio) = &state.audio_element {
+            let _ = audio.pause();
+            listen_duration = Some(audio.current_time());
+        }
+        state.audio_playing = Some(false);
+        state.cancel_sleep_timer();
+    });
+
+    if offline {
+        return;
+    }
+    if let (
+        Some(server_name),
+        Some(api_key),
+        Some(user_id),
+        Some(episode_id),
+        Some(listen_duration),
+    ) = (server_name, api_key, user_id, episode_id, listen_duration)
+    {
+        let request_data = RecordListenDurationRequest {
+            episode_id,
+            user_id,
+            listen_duration,
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = call_record_listen_duration(&server_name, &api_key, request_data).await
+            {
+                web_sys::console::log_1(&format!("Error recording listen duration: {}", e).into());
+            }
+        });
+    }
+}
+
+// The chapters link for an episode of an added podcast, from the podcast's feed
+// The server keeps each episode's chapters link from its feed
+async fn episode_chapters_url(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: i32,
+    episode_id: i32,
+) -> Option<String> {
+    let episode_request = EpisodeRequest {
+        episode_id,
+        user_id,
+    };
+    call_get_episode_metadata(server_name, api_key.clone(), &episode_request)
+        .await
+        .ok()?
+        .chaptersurl
+}
+
+#[cfg(not(feature = "server_build"))]
+fn load_local_chapters(episode_id: i32, audio_dispatch: Dispatch<UIState>) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match fetch_local_chapters(episode_id).await {
+            Ok(chapters) if !chapters.is_empty() => {
+                audio_dispatch.reduce_mut(move |state| {
+                    state.chapters = Some(chapters);
+                });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                web_sys::console::log_1(&e);
+            }
+        }
+    });
+}
+
+// Downloaded episodes only exist in the desktop app
+#[cfg(feature = "server_build")]
+fn load_local_chapters(_episode_id: i32, _audio_dispatch: Dispatch<UIState>) {}
+
+// The user's processing chain settings, fetched once and then kept in the store
+async fn load_audio_processing(
+    audio_dispatch: &Dispatch<UIState>,
+    server_name: &str,
+    api_key: &str,
+    user_id: i32,
+) -> AudioProcessing {
+    if let Some(processing) = audio_dispatch.get().audio_processing.clone() {
+        return processing;
+    }
+    match call_get_audio_processing(server_name.to_string(), api_key.to_string(), &user_id).await {
+        Ok(processing) => {
+            let processing = processing.unwrap_or_default();
+            let stored = processing.clone();
+            audio_dispatch.reduce_mut(move |state| state.audio_processing = Some(stored));
+            processing
+        }
+        Err(e) => {
+            web_sys::console::log_1(
+                &format!("Error getting audio processing settings: {}", e).into(),
+            );
+            AudioProcessing::default()
+        }
+    }
+}
+
+// Moves the current episode onto the Web Audio graph and applies the store's settings to it,
+// reloading the episode with CORS if it isn't routed yet. Returns false when the host doesn't
+// allow that.
+pub async fn route_current_episode(audio_dispatch: &Dispatch<UIState>) -> bool {
+    let state = audio_dispatch.get();
+    let routed = state
+        .audio_element
+        .as_ref()
+        .map_or(false, audio_graph::is_routed);
+    if routed {
+        audio_dispatch.reduce_mut(|state| state.apply_audio_graph());
+        return true;
+    }
+    let src = match state.currently_playing.as_ref() {
+        Some(playing) => playing.src.clone(),
+        None => return false,
+    };
+    if !audio_graph::supports_cors(&src).await {
+        return false;
+    }
+    audio_dispatch.reduce_mut(|state| state.reload_through_graph());
+    true
+}
+
 pub fn on_play_click(
     episode_url_for_closure: String,
     episode_title_for_closure: String,
@@ -897,6 +1500,7 @@ pub fn on_play_click(
     audio_dispatch: Dispatch<UIState>,
     _audio_state: Rc<UIState>,
     is_local: Option<bool>,
+    clip: Option<ClipRange>,
 ) -> Callback<MouseEvent> {
     Callback::from(move |_: MouseEvent| {
         web_sys::console::log_1(&JsValue::from_str("Play button clicked"));
@@ -1000,13 +1604,13 @@ pub fn on_play_click(
                 }
             }
         });
+        // Construct the URL for streaming from the local server
+        let stream_src = format!(
+            "{}/api/data/stream/{}?api_key={}&user_id={}",
+            server_name, episode_id, api_key, user_id
+        );
         let src = if let Some(_local) = is_local {
-            // Construct the URL for streaming from the local server
-            let src = format!(
-                "{}/api/data/stream/{}?api_key={}&user_id={}",
-                server_name, episode_id, api_key, user_id
-            );
-            src
+            stream_src.clone()
         } else {
             // Use the provided URL for streaming
             let src = episode_url_for_wasm.clone();
@@ -1023,24 +1627,64 @@ pub fn on_play_click(
             .await
             {
                 Ok(podcast_id) => {
-                    match call_get_auto_skip_times(
-                        &server_name,
-                        &Some(api_key.clone()),
-                        user_id,
-                        podcast_id,
+                    // Everything playback needs from the server is fetched at once
+                    let (details, skip_times, profile, processing) = futures_util::future::join4(
+                        call_get_podcast_details(&server_name, &api_key, user_id, &podcast_id),
+                        call_get_auto_skip_times(
+                            &server_name,
+                            &Some(api_key.clone()),
+                            user_id,
+                            podcast_id,
+                        ),
+                        call_get_playback_profile(
+                            &server_name,
+                            &Some(api_key.clone()),
+                            user_id,
+                            podcast_id,
+                        ),
+                        load_audio_processing(&audio_dispatch, &server_name, &api_key, user_id),
                     )
-                    .await
-                    {
+                    .await;
+                    let profile = profile.unwrap_or_default();
+                    let podcast_name = details
+                        .as_ref()
+                        .ok()
+                        .map(|details| details.podcastname.clone());
+                    // Only the server has a private feed's credentials, so its episodes stream
+                    // through the server
+                    let src = if details.as_ref().is_ok_and(|details| details.feedauth) {
+                        stream_src
+                    } else {
+                        src
+                    };
+                    match skip_times {
                         Ok((start_skip, end_skip)) => {
-                            let start_pos_sec =
-                                listen_duration_for_closure.unwrap_or(0).max(start_skip) as f64;
+                            // A clip starts where it says, not where the listener left off
+                            let start_pos_sec = match clip {
+                                Some(clip) => clip.start,
+                                None => {
+                                    listen_duration_for_closure.unwrap_or(0).max(start_skip) as f64
+                                }
+                            };
                             let end_pos_sec = end_skip as f64;
-
+                            // Only a volume boost, Smart Speed and the processing chain need the
+                            // Web Audio graph
+                            let wants_graph = profile.volume_gain != 1.0
+                                || profile.smart_speed
+                                || processing.enabled;
+                            let smart_speed = profile.smart_speed;
+                            let graph_src = src.clone();
+
+                            // Playback starts straight away, the graph is added once the host's
+                            // CORS support is known
                             audio_dispatch.reduce_mut(move |audio_state| {
                                 audio_state.audio_playing = Some(true);
-                                audio_state.playback_speed = 1.0;
+                                audio_state.playback_speed = profile.playback_speed;
+                                audio_state.playback_profile = Some(profile.clone());
+                                audio_state.smart_speed = false;
+                                audio_state.smart_speed_unavailable = false;
                                 audio_state.audio_volume = 100.0;
-                                audio_state.offline = Some(false);
+                                audio_state.active_clip = clip.filter(|clip| clip.end.is_some());
                                 audio_state.currently_playing = Some(AudioPlayerProps {
                                     src: src.clone(),
                                     title: episode_title_for_wasm.clone(),
@@ -1051,14 +1695,39 @@ pub fn on_play_click(
                                     start_pos_sec,
                                     end_pos_sec: end_pos_sec as f64,
                                     offline: false,
+                                    podcast_name: podcast_name.clone(),
                                 });
+                                audio_state.prepare_audio_element(false);
                                 audio_state.set_audio_source(src.to_string());
                                 if let Some(audio) = &audio_state.audio_element {
                                     audio.set_current_time(start_pos_sec);
+                                    // Loading a new source resets the rate to the default one
+                                    audio.set_default_playback_rate(profile.playback_speed);
+                                    audio.set_playback_rate(profile.playback_speed);
                                     let _ = audio.play();
                                 }
                                 audio_state.audio_playing = Some(true);
                             });
+
+                            if wants_graph {
+                                let cors = audio_graph::supports_cors(&graph_src).await;
+                                audio_dispatch.reduce_mut(move |state| {
+                                    // Another episode may have started while the host was checked
+                                    let still_playing =
+                                        state.currently_playing.as_ref().map_or(false, |playing| {
+                                            playing.episode_id == episode_id_for_wasm
+                                        });
+                                    if !still_playing {
+                                        return;
+                                    }
+                                    if cors {
+                                        state.smart_speed = smart_speed;
+                                        state.reload_through_graph();
+                                    } else {
+                                        state.smart_speed_unavailable = smart_speed;
+                                    }
+                                });
+                            }
                         }
 
                         Err(e) => {
@@ -1086,30 +1755,35 @@ pub fn on_play_click_offline(
         let episode_info_for_closure = episode_info.clone();
         let audio_dispatch = audio_dispatch.clone();
 
-        let file_path = episode_info_for_closure.downloadedlocation.clone();
         let episode_title_for_wasm = episode_info_for_closure.episodetitle.clone();
         let episode_artwork_for_wasm = episode_info_for_closure.episodeartwork.clone();
         let episode_duration_for_wasm = episode_info_for_closure.episodeduration.clone();
         let episode_id_for_wasm = episode_info_for_closure.episodeid.clone();
         let listen_duration_for_closure = episode_info_for_closure.listenduration.clone();
+        let podcast_name_for_wasm = episode_info_for_closure.podcastname.clone();
 
         wasm_bindgen_futures::spawn_local(async move {
-            match start_local_file_server(&file_path).await {
-                Ok(server_url) => {
-                    let file_name = Path::new(&file_path)
-                        .file_name()
-                        .and_then(|name| name.to_str())
-                        .unwrap_or(""); // Extract the file name from the path
-
-                    web_sys::console::log_1(&format!("Server URL: {}", server_url).into());
-                    web_sys::console::log_1(&format!("{}/{}", server_url, file_name).into());
-                    let src = format!("{}/{}", server_url, file_name);
+            match fetch_local_episode_url(episode_id_for_wasm).await {
+                Ok(src) => {
+                    // Downloads have no podcast profile, so Smart Speed follows the player toggle.
+                    // The local media server sends CORS headers, so it can always be analyzed.
+                    let state = audio_dispatch.get();
+                    let wants_graph = state.smart_speed
+                        || state
+                            .audio_processing
+                            .as_ref()
+                            .map_or(false, |processing| processing.enabled);
+                    let route_through_graph = wants_graph && audio_graph::supports_cors(&src).await;
+                    let smart_speed = state.smart_speed && route_through_graph;
 
                     audio_dispatch.reduce_mut(move |audio_state| {
                         audio_state.audio_playing = Some(true);
                         audio_state.playback_speed = 1.0;
+                        audio_state.playback_profile = None;
+                        audio_state.smart_speed = smart_speed;
+                        audio_state.smart_speed_unavailable = false;
                         audio_state.audio_volume = 100.0;
-                        audio_state.offline = Some(true);
+                        audio_state.active_clip = None;
                         audio_state.currently_playing = Some(AudioPlayerProps {
                             src: src.clone(),
                             title: episode_title_for_wasm.clone(),
@@ -1120,9 +1794,15 @@ pub fn on_play_click_offline(
                             start_pos_sec: listen_duration_for_closure.unwrap_or(0) as f64,
                             end_pos_sec: 0.0,
                             offline: true,
+                            podcast_name: Some(podcast_name_for_wasm.clone()),
                         });
+                        audio_state.prepare_audio_element(route_through_graph);
                         audio_state.set_audio_source(src.to_string());
+                        if route_through_graph {
+                            audio_state.apply_audio_graph();
+                        }
                         if let Some(audio) = &audio_state.audio_element {
+                            audio.set_default_playback_rate(1.0);
                             audio.set_current_time(listen_duration_for_closure.unwrap_or(0) as f64);
                             let _ = audio.play();
                         }
@@ -1131,7 +1811,7 @@ pub fn on_play_click_offline(
                 }
                 Err(e) => {
                     web_sys::console::log_1(
-                        &format!("Error starting local file server: {:?}", e).into(),
+                        &format!("Error getting local episode URL: {:?}", e).into(),
                     );
                 }
             }
@@ -0,0 +1,411 @@
+use crate::requests::setting_reqs::AudioProcessing;
+use gloo_net::http::{Method, RequestBuilder};
+use gloo_timers::callback::Interval;
+use std::cell::{Cell, RefCell};
+use web_sys::{
+    AnalyserNode, AudioContext, BiquadFilterNode, BiquadFilterType, DynamicsCompressorNode,
+    GainNode, HtmlAudioElement, RequestMode,
+};
+
+// Volume boost, Smart Speed and the processing chain all need the Web Audio API. Once an element
+// is connected to an AudioContext it can never be disconnected again, and cross-origin audio
+// routed through it is silenced unless it was loaded with CORS. So the graph is only built on
+// demand, for sources that pass `supports_cors`, and a routed element is swapped for a fresh one
+// before playing anything that doesn't.
+//
+// The chain is source -> EQ -> compressor -> loudness -> output -> destination, with an analyser
+// tapped after the compressor so Smart Speed and the loudness meter see the same level regardless
+// of the volume.
+
+// Below this RMS level (about -40 dBFS) the audio counts as silence
+const SILENCE_THRESHOLD: f32 = 0.01;
+// Pauses shorter than this are left alone so speech keeps its rhythm
+const MIN_SILENCE_SECONDS: f64 = 0.25;
+// How much faster than the listener's own speed silences are played
+const SILENCE_SPEEDUP: f64 = 3.0;
+const MAX_PLAYBACK_RATE: f64 = 4.0;
+const TICK_MS: u32 = 50;
+
+// Blocks quieter than this are pauses and don't count towards the measured loudness
+const LOUDNESS_GATE_DB: f64 = -50.0;
+// Roughly how many seconds of audio the loudness measurement averages over
+const LOUDNESS_WINDOW_SECONDS: f64 = 3.0;
+const MAX_LOUDNESS_CORRECTION_DB: f64 = 12.0;
+
+struct AudioGraph {
+    element: HtmlAudioElement,
+    context: AudioContext,
+    eq: Vec<BiquadFilterNode>,
+    compressor: DynamicsCompressorNode,
+    loudness: GainNode,
+    output: GainNode,
+    analyser: AnalyserNode,
+    // Dropping the interval stops Smart Speed and the loudness meter
+    _ticker: Interval,
+    samples: Vec<f32>,
+    last_tick: f64,
+    player_volume: f64,
+    volume_gain: f64,
+    silence: Option<SilenceDetector>,
+    loudness_meter: Option<LoudnessMeter>,
+}
+
+struct SilenceDetector {
+    silent_for: f64,
+    // The listener's speed while a silence is being sped through
+    base_rate: Option<f64>,
+}
+
+struct LoudnessMeter {
+    target_db: f64,
+    average_db: Option<f64>,
+}
+
+thread_local! {
+    static AUDIO_GRAPH: RefCell<Option<AudioGraph>> = RefCell::new(None);
+    // Seconds saved that haven't been reported to the server yet
+    static TIME_SAVED_PENDING: Cell<f64> = Cell::new(0.0);
+    // Seconds saved since the app was opened, shown in the player
+    static TIME_SAVED_SESSION: Cell<f64> = Cell::new(0.0);
+}
+
+fn build_graph(element: &HtmlAudioElement) -> Result<AudioGraph, wasm_bindgen::JsValue> {
+    let context = AudioContext::new()?;
+    let source = context.create_media_element_source(element)?;
+
+    let mut eq = Vec::new();
+    for filter_type in [
+        BiquadFilterType::Lowshelf,
+        BiquadFilterType::Peaking,
+        BiquadFilterType::Highshelf,
+    ] {
+        let filter = context.create_biquad_filter()?;
+        filter.set_type(filter_type);
+        eq.push(filter);
+    }
+    let compressor = context.create_dynamics_compressor()?;
+    let loudness = context.create_gain()?;
+    let output = context.create_gain()?;
+    let analyser = context.create_analyser()?;
+    analyser.set_fft_size(2048);
+
+    source.connect_with_audio_node(&eq[0])?;
+    eq[0].connect_with_audio_node(&eq[1])?;
+    eq[1].connect_with_audio_node(&eq[2])?;
+    eq[2].connect_with_audio_node(&compressor)?;
+    compressor.connect_with_audio_node(&loudness)?;
+    loudness.connect_with_audio_node(&output)?;
+    output.connect_with_audio_node(&context.destination())?;
+    // The analyser only listens, it doesn't need to reach the destination
+    compressor.connect_with_audio_node(&analyser)?;
+
+    let ticker = Interval::new(TICK_MS, || {
+        AUDIO_GRAPH.with(|graph| {
+            if let Some(graph) = graph.borrow_mut().as_mut() {
+                graph.tick();
+            }
+        })
+    });
+
+    // From here on the volume is applied by the output node, after the compressor and loudness
+    // stages have seen the audio at full level
+    let player_volume = element.volume();
+    element.set_volume(1.0);
+
+    let mut graph = AudioGraph {
+        element: element.clone(),
+        context,
+        eq,
+        compressor,
+        loudness,
+        output,
+        samples: vec![0.0; analyser.fft_size() as usize],
+        analyser,
+        _ticker: ticker,
+        last_tick: js_sys::Date::now(),
+        player_volume,
+        volume_gain: 1.0,
+        silence: None,
+        loudness_meter: None,
+    };
+    graph.set_processing(&AudioProcessing::default());
+    graph.update_output();
+    Ok(graph)
+}
+
+// Runs `f` on the graph if `element` is routed through it
+fn with_graph(element: &HtmlAudioElement, f: impl FnOnce(&mut AudioGraph)) {
+    AUDIO_GRAPH.with(|graph| {
+        if let Some(graph) = graph.borrow_mut().as_mut() {
+            if graph.element == *element {
+                f(graph);
+            }
+        }
+    });
+}
+
+// Routes `element` through the graph, building it if needed. The element must have been loaded
+// with `crossorigin="anonymous"`. Returns false if the graph couldn't be built.
+pub fn route(element: &HtmlAudioElement) -> bool {
+    AUDIO_GRAPH.with(|graph| {
+        let mut graph = graph.borrow_mut();
+        let routed = graph
+            .as_ref()
+            .map_or(false, |graph| graph.element == *element);
+        if !routed {
+            match build_graph(element) {
+                Ok(built) => {
+                    // The previous element was replaced, so its context has nothing left to play
+                    if let Some(previous) = graph.replace(built) {
+                        let _ = previous.context.close();
+                    }
+                }
+                Err(e) => {
+                    web_sys::console::log_1(&format!("Error building audio graph: {:?}", e).into());
+                    return false;
+                }
+            }
+        }
+        if let Some(graph) = graph.as_ref() {
+            // Contexts created outside a user gesture start suspended
+            let _ = graph.context.resume();
+        }
+        true
+    })
+}
+
+// Whether `element` already plays through the Web Audio graph
+pub fn is_routed(element: &HtmlAudioElement) -> bool {
+    AUDIO_GRAPH.with(|graph| {
+        graph
+            .borrow()
+            .as_ref()
+            .map_or(false, |graph| graph.element == *element)
+    })
+}
+
+// Tears the graph down once its element is no longer used
+pub fn detach() {
+    AUDIO_GRAPH.with(|graph| {
+        if let Some(previous) = graph.borrow_mut().take() {
+            let _ = previous.context.close();
+        }
+    });
+}
+
+// Checks the host sends CORS headers, so the audio can go through the graph without being muted
+pub async fn supports_cors(src: &str) -> bool {
+    match RequestBuilder::new(src)
+        .method(Method::HEAD)
+        .mode(RequestMode::Cors)
+        .send()
+        .await
+    {
+        Ok(response) => response.ok(),
+        Err(_) => false,
+    }
+}
+
+// The player's volume, from 0 to 1. Routed elements stay at full volume so the processing chain
+// sees the real level.
+pub fn set_player_volume(element: &HtmlAudioElement, volume: f64) {
+    let mut routed = false;
+    with_graph(element, |graph| {
+        routed = true;
+        graph.player_volume = volume;
+        graph.update_output();
+    });
+    if !routed {
+        element.set_volume(volume);
+    }
+}
+
+// The per-podcast volume boost, on top of the player's volume
+pub fn set_volume_gain(element: &HtmlAudioElement, gain: f64) {
+    with_graph(element, |graph| {
+        graph.volume_gain = gain;
+        graph.update_output();
+    });
+}
+
+pub fn set_processing(element: &HtmlAudioElement, processing: &AudioProcessing) {
+    with_graph(element, |graph| graph.set_processing(processing));
+}
+
+// Starts or stops speeding through silences
+pub fn set_smart_speed(element: &HtmlAudioElement, enabled: bool) {
+    with_graph(element, |graph| {
+        if enabled {
+            if graph.silence.is_none() {
+                graph.silence = Some(SilenceDetector {
+                    silent_for: 0.0,
+                    base_rate: None,
+                });
+            }
+        } else if let Some(detector) = graph.silence.take() {
+            // Unless the speed was changed since, leave the silence at the listener's speed
+            if let Some(base_rate) = detector.base_rate {
+                let current_rate = graph.element.playback_rate();
+                if (current_rate - boosted_rate(base_rate)).abs() <= f64::EPSILON {
+                    graph.element.set_playback_rate(base_rate);
+                }
+            }
+        }
+    });
+}
+
+// Whole seconds saved since the last call, the remainder is kept for next time
+pub fn take_time_saved() -> i32 {
+    TIME_SAVED_PENDING.with(|pending| {
+        let seconds = pending.get().floor();
+        pending.set(pending.get() - seconds);
+        seconds as i32
+    })
+}
+
+pub fn session_time_saved() -> f64 {
+    TIME_SAVED_SESSION.with(|session| session.get())
+}
+
+impl AudioGraph {
+    fn update_output(&self) {
+        self.output
+            .gain()
+            .set_value((self.player_volume * self.volume_gain) as f32);
+    }
+
+    // Disabled processing leaves every stage in place but neutral
+    fn set_processing(&mut self, processing: &AudioProcessing) {
+        let bands = processing.bands();
+        for (filter, band) in self.eq.iter().zip(bands.iter()) {
+            let gain = if processing.enabled { band.gain } else { 0.0 };
+            filter.frequency().set_value(band.frequency as f32);
+            filter.q().set_value(band.q as f32);
+            filter.gain().set_value(gain as f32);
+        }
+
+        if processing.enabled && processing.compressor {
+            // Gentle settings for speech, evening out loud and quiet voices
+            self.compressor.threshold().set_value(-24.0);
+            self.compressor.knee().set_value(30.0);
+            self.compressor.ratio().set_value(4.0);
+            self.compressor.attack().set_value(0.003);
+            self.compressor.release().set_value(0.25);
+        } else {
+            self.compressor.threshold().set_value(0.0);
+            self.compressor.knee().set_value(0.0);
+            self.compressor.ratio().set_value(1.0);
+        }
+
+        match processing.loudness_target.filter(|_| processing.enabled) {
+            Some(target_db) => {
+                let meter = self.loudness_meter.get_or_insert(LoudnessMeter {
+                    target_db,
+                    average_db: None,
+                });
+                meter.target_db = target_db;
+            }
+            None => {
+                self.loudness_meter = None;
+                self.loudness.gain().set_value(1.0);
+            }
+        }
+    }
+
+    fn tick(&mut self) {
+        let now = js_sys::Date::now();
+        let elapsed = ((now - self.last_tick) / 1000.0).max(0.0);
+        self.last_tick = now;
+
+        if self.silence.is_none() && self.loudness_meter.is_none() {
+            return;
+        }
+        if self.element.paused() {
+            if let Some(detector) = self.silence.as_mut() {
+                detector.silent_for = 0.0;
+            }
+            return;
+        }
+
+        self.analyser.get_float_time_domain_data(&mut self.samples);
+        let rms = (self.samples.iter().map(|s| s * s).sum::<f32>()
+            / self.samples.len().max(1) as f32)
+            .sqrt();
+
+        self.detect_silence(rms, elapsed);
+        self.measure_loudness(rms, elapsed);
+    }
+
+    fn detect_silence(&mut self, rms: f32, elapsed: f64) {
+        let detector = match self.silence.as_mut() {
+            Some(detector) => detector,
+            None => return,
+        };
+
+        if let Some(base_rate) = detector.base_rate {
+            let boosted = boosted_rate(base_rate);
+            let current_rate = self.element.playback_rate();
+            // The listener changed speed mid-silence, so that becomes the speed to return to
+            let base_rate = if (current_rate - boosted).abs() > f64::EPSILON {
+                current_rate
+            } else {
+                // Covering `elapsed * boosted` of audio at the listener's speed would have
+                // taken `elapsed * boosted / base_rate`
+                let saved = elapsed * (boosted / base_rate - 1.0);
+                TIME_SAVED_PENDING.with(|pending| pending.set(pending.get() + saved));
+                TIME_SAVED_SESSION.with(|session| session.set(session.get() + saved));
+                base_rate
+            };
+
+            if rms >= SILENCE_THRESHOLD {
+                self.element.set_playback_rate(base_rate);
+                detector.base_rate = None;
+                detector.silent_for = 0.0;
+            } else if detector.base_rate != Some(base_rate) {
+                self.element.set_playback_rate(boosted_rate(base_rate));
+                detector.base_rate = Some(base_rate);
+            }
+        } else if rms < SILENCE_THRESHOLD {
+            detector.silent_for += elapsed;
+            if detector.silent_for >= MIN_SILENCE_SECONDS {
+                let base_rate = self.element.playback_rate();
+                self.element.set_playback_rate(boosted_rate(base_rate));
+                detector.base_rate = Some(base_rate);
+            }
+        } else {
+            detector.silent_for = 0.0;
+        }
+    }
+
+    // A running RMS average stands in for integrated LUFS, which is close enough for speech and
+    // cheap to keep up to date. The loudness stage then slowly corrects towards the target.
+    fn measure_loudness(&mut self, rms: f32, elapsed: f64) {
+        let meter = match self.loudness_meter.as_mut() {
+            Some(meter) => meter,
+            None => return,
+        };
+        let level_db = 20.0 * (rms.max(f32::MIN_POSITIVE) as f64).log10();
+        if level_db < LOUDNESS_GATE_DB {
+            return;
+        }
+
+        let weight = (elapsed / LOUDNESS_WINDOW_SECONDS).min(1.0);
+        let average_db = match meter.average_db {
+            Some(average_db) => average_db + (level_db - average_db) * weight,
+            None => level_db,
+        };
+        meter.average_db = Some(average_db);
+
+        let correction_db = (meter.target_db - average_db)
+            .clamp(-MAX_LOUDNESS_CORRECTION_DB, MAX_LOUDNESS_CORRECTION_DB);
+        let gain = 10f64.powf(correction_db / 20.0);
+        let _ =
+            self.loudness
+                .gain()
+                .set_target_at_time(gain as f32, self.context.current_time(), 0.5);
+    }
+}
+
+fn boosted_rate(base_rate: f64) -> f64 {
+    (base_rate * SILENCE_SPEEDUP).min(MAX_PLAYBACK_RATE)
+}
@@ -0,0 +1,421 @@
+use crate::components::context::{AppState, UIState};
+use crate::components::gen_funcs::format_time;
+use crate::requests::pod_req::{
+    call_add_bookmark, call_delete_bookmark, call_get_bookmarks, AddBookmarkRequest, Bookmark,
+};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+// Lengths offered for shared clips, in seconds
+const CLIP_LENGTHS: [(u32, &str); 4] = [
+    (30, "30 sec"),
+    (60, "1 min"),
+    (120, "2 min"),
+    (300, "5 min"),
+];
+
+// A range of an episode to play. Playback pauses once it passes `end`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRange {
+    pub start: f64,
+    pub end: Option<f64>,
+}
+
+impl ClipRange {
+    pub fn starting_at(start: f64) -> Self {
+        ClipRange { start, end: None }
+    }
+
+    pub fn label(&self) -> String {
+        match self.end {
+            Some(end) => format!("{} - {}", format_time(self.start), format_time(end)),
+            None => format_time(self.start),
+        }
+    }
+
+    // True while `time` is still inside the clip. Seeking well outside of it counts as leaving
+    // the clip, so the listener isn't stopped at a point they scrubbed past.
+    pub fn contains(&self, time: f64) -> bool {
+        match self.end {
+            Some(end) => time >= self.start - 2.0 && time < end,
+            None => false,
+        }
+    }
+
+    // The window is wide enough that a one second tick can't step over it, even sped up
+    pub fn reached_end(&self, time: f64) -> bool {
+        self.end
+            .map_or(false, |end| time >= end && time < end + 5.0)
+    }
+}
+
+// Links open the episode page, which offers to play the range
+pub fn clip_link(server_name: &str, episode_id: i32, clip: &ClipRange) -> String {
+    let mut link = format!(
+        "{}/episode?episode_id={}&clip_start={}",
+        server_name.trim_end_matches('/'),
+        episode_id,
+        clip.start.floor() as i64
+    );
+    if let Some(end) = clip.end {
+        link.push_str(&format!("&clip_end={}", end.floor() as i64));
+    }
+    link
+}
+
+// The episode and range from a clip link the app was opened with
+pub fn clip_from_location() -> Option<(i32, ClipRange)> {
+    let search = web_sys::window()?.location().search().ok()?;
+    let mut episode_id = None;
+    let mut start = None;
+    let mut end = None;
+    for pair in search.trim_start_matches('?').split('&') {
+        let (key, value) = pair.split_once('=')?;
+        match key {
+            "episode_id" => episode_id = value.parse::<i32>().ok(),
+            "clip_start" => start = value.parse::<f64>().ok(),
+            "clip_end" => end = value.parse::<f64>().ok(),
+            _ => {}
+        }
+    }
+    let start = start?.max(0.0);
+    Some((
+        episode_id?,
+        ClipRange {
+            start,
+            end: end.filter(|end| *end > start),
+        },
+    ))
+}
+
+// web-sys only exposes the clipboard behind an unstable flag, so it's reached through JS
+fn copy_to_clipboard(text: &str) -> bool {
+    let navigator = match web_sys::window() {
+        Some(window) => window.navigator(),
+        None => return false,
+    };
+    let clipboard = js_sys::Reflect::get(&navigator, &JsValue::from_str("clipboard"))
+        .ok()
+        .filter(|clipboard| !clipboard.is_undefined());
+    let write_text = clipboard.as_ref().and_then(|clipboard| {
+        js_sys::Reflect::get(clipboard, &JsValue::from_str("writeText"))
+            .ok()?
+            .dyn_into::<js_sys::Function>()
+            .ok()
+    });
+    match (clipboard, write_text) {
+        (Some(clipboard), Some(write_text)) => write_text
+            .call1(&clipboard, &JsValue::from_str(text))
+            .is_ok(),
+        _ => false,
+    }
+}
+
+fn insert_sorted(bookmarks: &mut Vec<Bookmark>, bookmark: Bookmark) {
+    let index = bookmarks
+        .iter()
+        .position(|existing| existing.timestamp > bookmark.timestamp)
+        .unwrap_or(bookmarks.len());
+    bookmarks.insert(index, bookmark);
+}
+
+#[function_component(BookmarkControl)]
+pub fn bookmark_control() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    // The moment being bookmarked is taken when the menu opens, not when it's saved
+    let pending: UseStateHandle<Option<f64>> = use_state(|| None);
+    let note = use_state(String::new);
+
+    let toggle_menu = {
+        let pending = pending.clone();
+        let note = note.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if pending.is_some() {
+                pending.set(None);
+            } else if let Some(audio) = &audio_dispatch.get().audio_element {
+                note.set(String::new());
+                pending.set(Some(audio.current_time().floor()));
+            }
+        })
+    };
+
+    let on_note_input = {
+        let note = note.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                note.set(input.value());
+            }
+        })
+    };
+
+    let on_save = {
+        let pending = pending.clone();
+        let note = note.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        let api_key = state
+            .auth_details
+            .as_ref()
+            .and_then(|ud| ud.api_key.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let episode_id = audio_state
+            .currently_playing
+            .as_ref()
+            .map(|props| props.episode_id);
+        Callback::from(move |_: MouseEvent| {
+            let (timestamp, server_name, user_id, episode_id) =
+                match (*pending, server_name.clone(), user_id, episode_id) {
+                    (Some(timestamp), Some(server_name), Some(user_id), Some(episode_id)) => {
+                        (timestamp, server_name, user_id, episode_id)
+                    }
+                    _ => return,
+                };
+            let note = Some(note.trim().to_string()).filter(|note| !note.is_empty());
+            let request = AddBookmarkRequest {
+                episode_id,
+                user_id,
+                timestamp: timestamp as i32,
+                note: note.clone(),
+            };
+            pending.set(None);
+
+            let api_key = api_key.clone();
+            let audio_dispatch = audio_dispatch.clone();
+            spawn_local(async move {
+                match call_add_bookmark(&server_name, &api_key, &request).await {
+                    Ok(bookmark_id) => audio_dispatch.reduce_mut(move |audio_state| {
+                        // Only episodes whose list was loaded are cached, the rest load on demand
+                        if let Some(bookmarks) = audio_state.bookmarks.get_mut(&episode_id) {
+                            insert_sorted(
+                                bookmarks,
+                                Bookmark {
+                                    bookmark_id,
+                                    episode_id,
+                                    timestamp: request.timestamp,
+                                    note,
+                                    created: None,
+                                },
+                            );
+                        }
+                        audio_state.info_message = Some(format!(
+                            "Bookmark added at {}",
+                            format_time(request.timestamp as f64)
+                        ));
+                    }),
+                    Err(e) => audio_dispatch.reduce_mut(|audio_state| {
+                        audio_state.error_message = Some(format!("Error adding bookmark: {}", e))
+                    }),
+                }
+            });
+        })
+    };
+
+    let on_cancel = {
+        let pending = pending.clone();
+        Callback::from(move |_: MouseEvent| pending.set(None))
+    };
+
+    // Bookmarks are tied to the episode on the server, so episodes it doesn't know can't have them
+    let available = audio_state.episode_in_db.unwrap_or(false)
+        && !audio_state.app_offline_mode.unwrap_or(false);
+
+    html! {
+        <div class="bookmark-control">
+            <button onclick={toggle_menu} title="Bookmark this moment" disabled={!available} class="skip-button audio-top-button selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                <span class="material-icons">{"bookmark_add"}</span>
+            </button>
+            if let Some(timestamp) = *pending {
+                <div class="bookmark-menu">
+                    <span class="item_container-text">{ format!("Bookmark at {}", format_time(timestamp)) }</span>
+                    <input
+                        type="text"
+                        autofocus=true
+                        class="search-bar-input border text-sm rounded-lg p-2"
+                        placeholder="Add a note (optional)"
+                        value={(*note).clone()}
+                        oninput={on_note_input}
+                    />
+                    <div class="bookmark-menu-buttons">
+                        <button onclick={on_save} class="theme-submit-button font-bold py-1 px-3 rounded" type="button">{"Save"}</button>
+                        <button onclick={on_cancel} class="theme-submit-button font-bold py-1 px-3 rounded" type="button">{"Cancel"}</button>
+                    </div>
+                </div>
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct BookmarkListProps {
+    pub episode_id: i32,
+    // Episode length in seconds, used to keep shared clips inside the episode
+    pub duration: i32,
+    pub on_play: Callback<ClipRange>,
+}
+
+#[function_component(BookmarkList)]
+pub fn bookmark_list(props: &BookmarkListProps) -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let clip_length = use_state(|| 60u32);
+    // The bookmark whose clip link was last generated, with the link
+    let shared: UseStateHandle<Option<(i32, String)>> = use_state(|| None);
+
+    let api_key = state
+        .auth_details
+        .as_ref()
+        .and_then(|ud| ud.api_key.clone());
+    let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+    let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+
+    {
+        let audio_dispatch = audio_dispatch.clone();
+        let api_key = api_key.clone();
+        let server_name = server_name.clone();
+        use_effect_with(props.episode_id, move |episode_id| {
+            let episode_id = *episode_id;
+            if let (Some(server_name), Some(user_id)) = (server_name, user_id) {
+                spawn_local(async move {
+                    match call_get_bookmarks(&server_name, &api_key, user_id, episode_id).await {
+                        Ok(bookmarks) => audio_dispatch.reduce_mut(move |audio_state| {
+                            audio_state.bookmarks.insert(episode_id, bookmarks);
+                        }),
+                        Err(e) => web_sys::console::log_1(
+                            &format!("Error loading bookmarks: {}", e).into(),
+                        ),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_clip_length_change = {
+        let clip_length = clip_length.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                clip_length.set(select.value().parse::<u32>().unwrap_or(60));
+            }
+        })
+    };
+
+    let bookmarks = audio_state
+        .bookmarks
+        .get(&props.episode_id)
+        .cloned()
+        .unwrap_or_default();
+
+    html! {
+        <div class="bookmark-list">
+            <div class="bookmark-list-header">
+                <p class="item_container-text text-lg font-bold">{"Bookmarks"}</p>
+                if !bookmarks.is_empty() {
+                    <label class="item_container-text text-sm">
+                        {"Clip length "}
+                        <select onchange={on_clip_length_change} class="theme-select-dropdown appearance-none border px-2 py-1 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                            { for CLIP_LENGTHS.iter().map(|(seconds, label)| html! {
+                                <option value={seconds.to_string()} selected={*clip_length == *seconds}>{ *label }</option>
+                            })}
+                        </select>
+                    </label>
+                }
+            </div>
+            if bookmarks.is_empty() {
+                <p class="item_container-text text-sm">{"No bookmarks yet. Use the bookmark button in the full player to mark a moment in this episode."}</p>
+            }
+            { for bookmarks.into_iter().map(|bookmark| {
+                let start = bookmark.timestamp as f64;
+                let end = (start + *clip_length as f64).min(if props.duration > 0 { props.duration as f64 } else { f64::MAX });
+                let clip = ClipRange { start, end: Some(end) };
+
+                let on_play = {
+                    let on_play = props.on_play.clone();
+                    Callback::from(move |_: MouseEvent| on_play.emit(ClipRange::starting_at(start)))
+                };
+                let on_play_clip = {
+                    let on_play = props.on_play.clone();
+                    Callback::from(move |_: MouseEvent| on_play.emit(clip))
+                };
+                let on_share = {
+                    let shared = shared.clone();
+                    let audio_dispatch = audio_dispatch.clone();
+                    let server_name = server_name.clone().unwrap_or_default();
+                    let episode_id = props.episode_id;
+                    let bookmark_id = bookmark.bookmark_id;
+                    Callback::from(move |_: MouseEvent| {
+                        let link = clip_link(&server_name, episode_id, &clip);
+                        let copied = copy_to_clipboard(&link);
+                        shared.set(Some((bookmark_id, link)));
+                        if copied {
+                            audio_dispatch.reduce_mut(|audio_state| {
+                                audio_state.info_message = Some("Clip link copied".to_string())
+                            });
+                        }
+                    })
+                };
+                let on_delete = {
+                    let audio_dispatch = audio_dispatch.clone();
+                    let api_key = api_key.clone();
+                    let server_name = server_name.clone();
+                    let episode_id = props.episode_id;
+                    let bookmark_id = bookmark.bookmark_id;
+                    Callback::from(move |_: MouseEvent| {
+                        let (server_name, user_id) = match (server_name.clone(), user_id) {
+                            (Some(server_name), Some(user_id)) => (server_name, user_id),
+                            _ => return,
+                        };
+                        let api_key = api_key.clone();
+                        let audio_dispatch = audio_dispatch.clone();
+                        spawn_local(async move {
+                            match call_delete_bookmark(&server_name, &api_key, user_id, bookmark_id).await {
+                                Ok(_) => audio_dispatch.reduce_mut(move |audio_state| {
+                                    if let Some(bookmarks) = audio_state.bookmarks.get_mut(&episode_id) {
+                                        bookmarks.retain(|bookmark| bookmark.bookmark_id != bookmark_id);
+                                    }
+                                }),
+                                Err(e) => audio_dispatch.reduce_mut(|audio_state| {
+                                    audio_state.error_message = Some(format!("Error removing bookmark: {}", e))
+                                }),
+                            }
+                        });
+                    })
+                };
+
+                let shared_link = shared
+                    .as_ref()
+                    .filter(|(shared_id, _)| *shared_id == bookmark.bookmark_id)
+                    .map(|(_, link)| link.clone());
+
+                html! {
+                    <div class="bookmark-row">
+                        <div class="bookmark-row-main">
+                            <button onclick={on_play} title="Play from here" class="bookmark-time">
+                                { format_time(start) }
+                            </button>
+                            <span class="item_container-text bookmark-note">
+                                { bookmark.note.clone().unwrap_or_default() }
+                            </span>
+                            <button onclick={on_play_clip} title={format!("Play {}", clip.label())} class="bookmark-action">
+                                <span class="material-icons">{"play_circle"}</span>
+                            </button>
+                            <button onclick={on_share} title="Share clip" class="bookmark-action">
+                                <span class="material-icons">{"share"}</span>
+                            </button>
+                            <button onclick={on_delete} title="Remove bookmark" class="bookmark-action">
+                                <span class="material-icons">{"delete"}</span>
+                            </button>
+                        </div>
+                        if let Some(link) = shared_link {
+                            <input type="text" readonly=true class="search-bar-input border text-sm rounded-lg p-2 bookmark-link" value={link} />
+                        }
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
@@ -1,6 +1,8 @@
 use crate::components::context::AppState;
+use crate::components::episode_browser::EpisodeView;
+use crate::components::gen_components::EPISODE_PAGE_SIZE;
 use crate::components::podcast_layout::ClickedFeedURL;
-use crate::requests::pod_req::{call_check_podcast, call_get_podcast_id};
+use crate::requests::pod_req::{call_check_podcast, call_get_podcast_id, Page};
 use crate::requests::search_pods::{call_get_podcast_episodes, call_parse_podcast_url};
 use std::collections::HashMap;
 use web_sys::MouseEvent;
@@ -79,11 +81,16 @@ pub fn create_on_title_click(
                                     &format!("Podcast IDs: {:?}", podcast_id).into(),
                                 );
 
+                                // The view the podcast was last left in, whether the show is
+                                // serial is only known once its feed is read
+                                let filter = EpisodeView::load(&podcast_url_call).filter(false);
                                 match call_get_podcast_episodes(
                                     &server_clone,
                                     &api_clone,
                                     &user_id,
                                     &podcast_id,
+                                    &filter,
+                                    Page::first(EPISODE_PAGE_SIZE),
                                 )
                                 .await
                                 {
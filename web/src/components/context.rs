@@ -1,19 +1,28 @@
 use crate::components::audio::AudioPlayerProps;
+use crate::components::audio_graph;
+use crate::components::bookmarks::ClipRange;
+use crate::components::gen_funcs::format_time;
+use crate::components::keybindings::KeyBindings;
 use crate::components::podcast_layout::ClickedFeedURL;
+use crate::components::sleep_timer::{SleepTimer, SleepTimerMode};
+use crate::requests::chapters::Chapter;
 use crate::requests::login_requests::AddUserRequest;
 use crate::requests::login_requests::GetUserDetails;
 use crate::requests::login_requests::LoginServerRequest;
 use crate::requests::login_requests::{GetApiDetails, TimeZoneInfo};
 use crate::requests::pod_req::{
-    Episode, EpisodeDownloadResponse, EpisodeMetadataResponse, HistoryDataResponse, Podcast,
-    PodcastResponse, QueuedEpisodesResponse, RecentEps, SavedEpisodesResponse,
+    Bookmark, Episode, EpisodeDownloadResponse, EpisodeMetadataResponse, HistoryDataResponse,
+    PlaybackProfile, Podcast, PodcastResponse, QueuedEpisodesResponse, RecentEps,
+    SavedEpisodesResponse,
 };
 use crate::requests::search_pods::{PodcastFeedResult, PodcastSearchResult, SearchResponse};
-use crate::requests::setting_reqs::{AddSettingsUserRequest, EditSettingsUserRequest};
+use crate::requests::setting_reqs::{
+    AddSettingsUserRequest, AudioProcessing, EditSettingsUserRequest, QueueSettings,
+};
 use crate::requests::stat_reqs::UserStats;
 use serde::Deserialize;
 use serde_json::{from_str, json};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
@@ -117,6 +126,35 @@ pub struct SettingsState {
     pub active_tab: Option<String>,
 }
 
+#[derive(Clone, Copy, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+    Failed,
+}
+
+// A desktop episode download waiting in or working through the app's queue
+#[derive(Clone, Deserialize, PartialEq, Debug)]
+pub struct DownloadJob {
+    pub id: i64,
+    pub episodeid: i32,
+    pub title: String,
+    pub filename: String,
+    pub position: i64,
+    pub state: JobState,
+    pub attempts: u32,
+    // Unix time before which a retry doesn't start
+    pub nextattempt: i64,
+    pub error: Option<String>,
+    // Kept up to date from the download-progress events while the job runs
+    #[serde(skip)]
+    pub downloaded: u64,
+    #[serde(skip)]
+    pub total: Option<u64>,
+}
+
 #[derive(Default, Clone, PartialEq, Store, Debug)]
 pub struct UIState {
     pub audio_playing: Option<bool>,
@@ -134,9 +172,30 @@ pub struct UIState {
     pub audio_volume: f64,
     pub start_skip_sec: f64,
     pub end_skip_sec: f64,
+    // Whether the server was unreachable on the last write, see requests::outbox
     pub offline: Option<bool>,
     pub app_offline_mode: Option<bool>,
     pub local_download_increment: Option<i32>,
+    pub chapters: Option<Vec<Chapter>>,
+    pub sleep_timer: Option<SleepTimer>,
+    pub keybindings: KeyBindings,
+    pub shortcut_help_open: bool,
+    pub playback_profile: Option<PlaybackProfile>,
+    pub smart_speed: bool,
+    // Set when Smart Speed was asked for but the host won't let the audio be analyzed
+    pub smart_speed_unavailable: bool,
+    // Loaded from the user's settings the first time an episode plays
+    pub audio_processing: Option<AudioProcessing>,
+    // Bookmarks by episode ID, for the episodes whose list has been loaded
+    pub bookmarks: HashMap<i32, Vec<Bookmark>>,
+    // Set while a clip is playing so playback pauses at its end
+    pub active_clip: Option<ClipRange>,
+    // Loaded from the user's settings the first time an episode finishes
+    pub queue_settings: Option<QueueSettings>,
+    // Changes waiting in the outbox for the server to come back
+    pub outbox_pending: usize,
+    // The desktop app's download queue in order, see downloads_tauri
+    pub download_jobs: Vec<DownloadJob>,
 }
 
 impl UIState {
@@ -161,7 +220,75 @@ impl UIState {
         }
     }
 
+    // The profile of the podcast that's playing, falling back to the defaults
+    pub fn active_playback_profile(&self) -> PlaybackProfile {
+        self.playback_profile.clone().unwrap_or_default()
+    }
+
+    // Must run before `set_audio_source`. Audio that goes through the Web Audio graph has to be
+    // loaded with CORS, and an element that's already routed can't play from hosts without it,
+    // so it's swapped for a fresh one.
+    pub fn prepare_audio_element(&mut self, route_through_graph: bool) {
+        if let Some(audio) = &self.audio_element {
+            if !route_through_graph && audio_graph::is_routed(audio) {
+                let _ = audio.pause();
+                self.audio_element = None;
+                audio_graph::detach();
+            }
+        }
+        self.ensure_audio_element();
+        if let Some(audio) = &self.audio_element {
+            audio.set_cross_origin(route_through_graph.then_some("anonymous"));
+        }
+    }
+
+    // Reloads the current episode with CORS so it can go through the Web Audio graph, keeping the
+    // position and speed
+    pub fn reload_through_graph(&mut self) {
+        let src = match &self.currently_playing {
+            Some(playing) => playing.src.clone(),
+            None => return,
+        };
+        let (position, was_playing) = self.audio_element.as_ref().map_or((0.0, false), |audio| {
+            (audio.current_time(), !audio.paused())
+        });
+        self.prepare_audio_element(true);
+        self.set_audio_source(src);
+        self.apply_audio_graph();
+        if let Some(audio) = &self.audio_element {
+            audio.set_current_time(position);
+            if self.playback_speed > 0.0 {
+                audio.set_default_playback_rate(self.playback_speed);
+                audio.set_playback_rate(self.playback_speed);
+            }
+            if was_playing {
+                let _ = audio.play();
+            }
+        }
+    }
+
+    // Routes the current element through the Web Audio graph and applies the volume boost,
+    // processing chain and Smart Speed to it. The element must have been loaded with CORS.
+    pub fn apply_audio_graph(&self) {
+        if let Some(audio) = &self.audio_element {
+            if !audio_graph::route(audio) {
+                return;
+            }
+            audio_graph::set_volume_gain(audio, self.active_playback_profile().volume_gain);
+            audio_graph::set_player_volume(audio, self.audio_volume / 100.0);
+            audio_graph::set_processing(audio, &self.audio_processing.clone().unwrap_or_default());
+            audio_graph::set_smart_speed(audio, self.smart_speed);
+        }
+    }
+
     pub fn set_audio_source(&mut self, src: String) {
+        self.ensure_audio_element();
+        if let Some(audio) = &self.audio_element {
+            audio.set_src(&src);
+        }
+    }
+
+    fn ensure_audio_element(&mut self) {
         if self.audio_element.is_none() {
             self.audio_element = HtmlAudioElement::new().ok();
             if let Some(audio) = &self.audio_element {
@@ -172,10 +299,75 @@ impl UIState {
                     .add_event_listener_with_callback("canplay", closure.as_ref().unchecked_ref())
                     .unwrap();
                 closure.forget(); // Prevents the closure from being garbage collected
+
+                // Episodes from feeds without a duration start out at zero or an estimate
+                let metadata = Closure::wrap(Box::new(move || {
+                    Dispatch::<UIState>::global().reduce_mut(|state| state.sync_media_duration());
+                }) as Box<dyn Fn()>);
+                audio
+                    .add_event_listener_with_callback(
+                        "loadedmetadata",
+                        metadata.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                metadata.forget();
             }
         }
+    }
+
+    // The media's own duration beats whatever the feed said
+    fn sync_media_duration(&mut self) {
+        let Some(duration) = self.audio_element.as_ref().map(|audio| audio.duration()) else {
+            return;
+        };
+        if !duration.is_finite() || duration <= 0.0 {
+            return;
+        }
+        if let Some(props) = self.currently_playing.as_mut() {
+            if (props.duration_sec - duration).abs() >= 1.0 {
+                props.duration_sec = duration;
+                props.duration = format_time(duration);
+            }
+        }
+    }
+
+    pub fn seek_to(&mut self, time_seconds: f64) {
         if let Some(audio) = &self.audio_element {
-            audio.set_src(&src);
+            audio.set_current_time(time_seconds);
+        }
+        self.update_current_time(time_seconds);
+    }
+
+    pub fn start_sleep_timer(&mut self, mode: SleepTimerMode) {
+        self.restore_sleep_volume();
+        self.sleep_timer = Some(SleepTimer::start(mode, self));
+    }
+
+    pub fn cancel_sleep_timer(&mut self) {
+        self.restore_sleep_volume();
+        self.sleep_timer = None;
+    }
+
+    // Called when the listener shakes the device or interacts during the fade out. Countdowns
+    // start over, while end-of-episode timers are dropped since the listener is clearly awake.
+    pub fn restart_sleep_timer(&mut self) {
+        if let Some(timer) = self.sleep_timer.clone() {
+            self.restore_sleep_volume();
+            self.sleep_timer = match timer.mode {
+                SleepTimerMode::EndOfEpisode => None,
+                mode => Some(SleepTimer::start(mode, self)),
+            };
+        }
+    }
+
+    fn restore_sleep_volume(&mut self) {
+        if let Some(timer) = self.sleep_timer.as_mut() {
+            if timer.fading {
+                if let Some(audio) = &self.audio_element {
+                    audio_graph::set_player_volume(audio, self.audio_volume / 100.0);
+                }
+                timer.fading = false;
+            }
         }
     }
 
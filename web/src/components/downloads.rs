@@ -1,6 +1,7 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::{
     download_episode_item, empty_message, on_shownotes_click, Search_nav, UseScrollToTop,
+    VirtualList,
 };
 use crate::components::audio::on_play_click;
 use crate::components::audio::AudioPlayer;
@@ -455,6 +456,115 @@ pub fn render_podcast_with_episodes(
     let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
     let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
 
+    // Podcasts can have hundreds of downloaded episodes, only the ones near the screen are drawn
+    let episode_count = episodes.len();
+    let episodes = Rc::new(episodes);
+    let render_episode = Callback::from(move |index: usize| {
+        let episode = episodes[index].clone();
+        let id_string = &episode.episodeid.to_string();
+
+        let dispatch = dispatch.clone();
+
+        let episode_url_clone = episode.episodeurl.clone();
+        let episode_title_clone = episode.episodetitle.clone();
+        let episode_artwork_clone = episode.episodeartwork.clone();
+        let episode_duration_clone = episode.episodeduration.clone();
+        let episode_id_clone = episode.episodeid.clone();
+        let episode_listened_clone = episode.listenduration.clone();
+        let _completed = episode.completed;
+        let desc_expanded = desc_rc.expanded_descriptions.contains(id_string);
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = window)]
+            fn toggleDescription(guid: &str, expanded: bool);
+        }
+        let toggle_expanded = {
+            let desc_dispatch = desc_state.clone();
+            let episode_guid = episode.episodeid.clone().to_string();
+
+            Callback::from(move |_: MouseEvent| {
+                let guid = episode_guid.clone();
+                desc_dispatch.reduce_mut(move |state| {
+                    if state.expanded_descriptions.contains(&guid) {
+                        state.expanded_descriptions.remove(&guid); // Collapse the description
+                        toggleDescription(&guid, false); // Call JavaScript function
+                    } else {
+                        state.expanded_descriptions.insert(guid.clone()); // Expand the description
+                        toggleDescription(&guid, true); // Call JavaScript function
+                    }
+                });
+            })
+        };
+
+        let episode_url_for_closure = episode_url_clone.clone();
+        let episode_title_for_closure = episode_title_clone.clone();
+        let episode_artwork_for_closure = episode_artwork_clone.clone();
+        let episode_duration_for_closure = episode_duration_clone.clone();
+        let listener_duration_for_closure = episode_listened_clone.clone();
+        let episode_id_for_closure = episode_id_clone.clone();
+        let user_id_play = user_id.clone();
+        let server_name_play = server_name.clone();
+        let api_key_play = api_key.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        let is_local = Option::from(true);
+
+        let on_play_click = on_play_click(
+            episode_url_for_closure.clone(),
+            episode_title_for_closure.clone(),
+            episode_artwork_for_closure.clone(),
+            episode_duration_for_closure.clone(),
+            episode_id_for_closure.clone(),
+            listener_duration_for_closure.clone(),
+            api_key_play.unwrap().unwrap(),
+            user_id_play.unwrap(),
+            server_name_play.unwrap(),
+            audio_dispatch.clone(),
+            audio_state.clone(),
+            is_local,
+            None,
+        );
+
+        let on_shownotes_click = on_shownotes_click(
+            history_clone.clone(),
+            dispatch.clone(),
+            episode_id_for_closure.clone(),
+        );
+
+        let date_format = match_date_format(state.date_format.as_deref());
+        let datetime = parse_date(&episode.episodepubdate, &state.user_tz);
+        let format_release = format!(
+            "{}",
+            format_datetime(&datetime, &state.hour_preference, date_format)
+        );
+        let on_checkbox_change_cloned = on_checkbox_change.clone();
+        let episode_url_for_ep_item = episode_url_clone.clone();
+        let sanitized_description =
+            sanitize_html_with_blank_target(&episode.episodedescription.clone());
+
+        let check_episode_id = &episode.episodeid.clone();
+        let is_completed = state
+            .completed_episodes
+            .as_ref()
+            .unwrap_or(&vec![])
+            .contains(&check_episode_id);
+        download_episode_item(
+            Box::new(episode),
+            sanitized_description.clone(),
+            desc_expanded,
+            &format_release,
+            on_play_click,
+            on_shownotes_click,
+            toggle_expanded,
+            episode_duration_clone,
+            episode_listened_clone,
+            "downloads",
+            on_checkbox_change_cloned, // Add this line
+            is_delete_mode,            // Add this line
+            episode_url_for_ep_item,
+            is_completed,
+        )
+    });
+
     html! {
         <div key={podcast.podcastid}>
             <div class="item-container border-solid border flex items-start mb-4 shadow-md rounded-lg h-full" onclick={toggle_pod_expanded}>
@@ -476,106 +586,7 @@ pub fn render_podcast_with_episodes(
             { if is_expanded {
                 html! {
                     <div class="episodes-dropdown, pl-4">
-                        { for episodes.into_iter().map(|episode| {
-                            let id_string = &episode.episodeid.to_string();
-
-                            let dispatch = dispatch.clone();
-
-                            let episode_url_clone = episode.episodeurl.clone();
-                            let episode_title_clone = episode.episodetitle.clone();
-                            let episode_artwork_clone = episode.episodeartwork.clone();
-                            let episode_duration_clone = episode.episodeduration.clone();
-                            let episode_id_clone = episode.episodeid.clone();
-                            let episode_listened_clone = episode.listenduration.clone();
-                            let _completed = episode.completed;
-                            let desc_expanded = desc_rc.expanded_descriptions.contains(id_string);
-                            #[wasm_bindgen]
-                            extern "C" {
-                                #[wasm_bindgen(js_namespace = window)]
-                                fn toggleDescription(guid: &str, expanded: bool);
-                            }
-                            let toggle_expanded = {
-                                let desc_dispatch = desc_state.clone();
-                                let episode_guid = episode.episodeid.clone().to_string();
-
-                                Callback::from(move |_: MouseEvent| {
-                                    let guid = episode_guid.clone();
-                                    desc_dispatch.reduce_mut(move |state| {
-                                        if state.expanded_descriptions.contains(&guid) {
-                                            state.expanded_descriptions.remove(&guid); // Collapse the description
-                                            toggleDescription(&guid, false); // Call JavaScript function
-                                        } else {
-                                            state.expanded_descriptions.insert(guid.clone()); // Expand the description
-                                            toggleDescription(&guid, true); // Call JavaScript function
-                                        }
-                                    });
-                                })
-                            };
-
-                            let episode_url_for_closure = episode_url_clone.clone();
-                            let episode_title_for_closure = episode_title_clone.clone();
-                            let episode_artwork_for_closure = episode_artwork_clone.clone();
-                            let episode_duration_for_closure = episode_duration_clone.clone();
-                            let listener_duration_for_closure = episode_listened_clone.clone();
-                            let episode_id_for_closure = episode_id_clone.clone();
-                            let user_id_play = user_id.clone();
-                            let server_name_play = server_name.clone();
-                            let api_key_play = api_key.clone();
-                            let audio_dispatch = audio_dispatch.clone();
-                            let is_local = Option::from(true);
-
-                            let on_play_click = on_play_click(
-                                episode_url_for_closure.clone(),
-                                episode_title_for_closure.clone(),
-                                episode_artwork_for_closure.clone(),
-                                episode_duration_for_closure.clone(),
-                                episode_id_for_closure.clone(),
-                                listener_duration_for_closure.clone(),
-                                api_key_play.unwrap().unwrap(),
-                                user_id_play.unwrap(),
-                                server_name_play.unwrap(),
-                                audio_dispatch.clone(),
-                                audio_state.clone(),
-                                is_local,
-                            );
-
-                            let on_shownotes_click = on_shownotes_click(
-                                history_clone.clone(),
-                                dispatch.clone(),
-                                episode_id_for_closure.clone(),
-                            );
-
-                            let date_format = match_date_format(state.date_format.as_deref());
-                            let datetime = parse_date(&episode.episodepubdate, &state.user_tz);
-                            let format_release = format!("{}", format_datetime(&datetime, &state.hour_preference, date_format));
-                            let on_checkbox_change_cloned = on_checkbox_change.clone();
-                            let episode_url_for_ep_item = episode_url_clone.clone();
-                            let sanitized_description =
-                                sanitize_html_with_blank_target(&episode.episodedescription.clone());
-
-                            let check_episode_id = &episode.episodeid.clone();
-                            let is_completed = state
-                                .completed_episodes
-                                .as_ref()
-                                .unwrap_or(&vec![])
-                                .contains(&check_episode_id);
-                            download_episode_item(
-                                Box::new(episode),
-                                sanitized_description.clone(),
-                                desc_expanded,
-                                &format_release,
-                                on_play_click,
-                                on_shownotes_click,
-                                toggle_expanded,
-                                episode_duration_clone,
-                                episode_listened_clone,
-                                "downloads",
-                                on_checkbox_change_cloned, // Add this line
-                                is_delete_mode, // Add this line
-                                episode_url_for_ep_item,
-                                is_completed,
-                            )
-                        }) }
+                        <VirtualList item_count={episode_count} render_item={render_episode} />
                     </div>
                 }
             } else {
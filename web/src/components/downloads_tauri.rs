@@ -1,13 +1,15 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::{
     download_episode_item, empty_message, on_shownotes_click, Search_nav, UseScrollToTop,
+    VirtualList,
 };
 use crate::components::audio::on_play_click_offline;
 use crate::components::audio::AudioPlayer;
-use crate::components::context::{AppState, ExpandedDescriptions, UIState};
+use crate::components::context::{AppState, DownloadJob, ExpandedDescriptions, JobState, UIState};
 use crate::components::gen_funcs::{
     format_datetime, match_date_format, parse_date, sanitize_html_with_blank_target,
 };
+use crate::requests::chapters::{normalize_chapters, Chapter};
 use crate::requests::pod_req::{
     call_remove_downloaded_episode, DownloadEpisodeRequest, EpisodeDownload,
     EpisodeDownloadResponse, EpisodeInfo, Podcast, PodcastDetails, PodcastResponse,
@@ -19,8 +21,10 @@ use yewdux::prelude::*;
 // use crate::components::gen_funcs::check_auth;
 use crate::components::episodes_layout::UIStateMsg;
 use crate::requests::login_requests::use_check_authentication;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::rc::Rc;
 use tauri_sys::tauri;
@@ -41,104 +45,265 @@ fn group_episodes_by_podcast(episodes: Vec<EpisodeDownload>) -> HashMap<i32, Vec
     grouped
 }
 
-pub async fn download_file(url: String, filename: String) -> Result<(), JsValue> {
-    // Create a wrapper struct to explicitly define the argument names
-    #[derive(Serialize)]
-    struct DownloadFileArgs {
-        url: String,
-        filename: String,
-    }
+// Sent by the app as its queue changes and while a job downloads, see src-tauri/src/download_queue.rs
+const JOBS_EVENT: &str = "download-jobs";
+const PROGRESS_EVENT: &str = "download-progress";
 
-    // Wrap the parameters in the struct with the expected argument names
-    let args = DownloadFileArgs { url, filename };
+#[derive(Deserialize, Debug)]
+struct DownloadProgress {
+    filename: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
 
-    // Serialize and invoke the Tauri command
-    let serialized_data = serde_wasm_bindgen::to_value(&args).unwrap();
-    web_sys::console::log_1(&serialized_data); // Debug print
+// How the app works through its download queue, see DownloadSettings in src-tauri/src/local_db.rs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DownloadSettings {
+    pub maxconcurrent: u32,
+    pub hostinterval: u32,
+    pub maxattempts: u32,
+    pub schedule: String,
+    pub nightstart: u32,
+    pub nightend: u32,
+    pub unmeteredonly: bool,
+}
 
-    tauri::invoke::<_, ()>("download_file", &args)
-        .await
-        .map_err(|e| JsValue::from_str(&format!("Failed to invoke download: {}", e)))
+thread_local! {
+    static LISTENING_FOR_DOWNLOADS: Cell<bool> = Cell::new(false);
 }
 
-pub async fn start_local_file_server(file_path: &str) -> Result<String, JsValue> {
-    #[derive(Serialize)]
-    struct StartFileServerArgs {
-        filepath: String,
+// Keeps UIState::download_jobs in step with the app's queue from the first time it's needed on
+fn listen_for_downloads() {
+    if LISTENING_FOR_DOWNLOADS.with(|listening| listening.replace(true)) {
+        return;
     }
+    wasm_bindgen_futures::spawn_local(async {
+        match tauri_sys::event::listen::<Vec<DownloadJob>>(JOBS_EVENT).await {
+            Ok(mut events) => {
+                while let Some(event) = events.next().await {
+                    Dispatch::<UIState>::global()
+                        .reduce_mut(move |state| replace_download_jobs(state, event.payload));
+                }
+            }
+            Err(e) => web_sys::console::log_1(
+                &format!("Unable to follow the download queue: {:?}", e).into(),
+            ),
+        }
+    });
+    wasm_bindgen_futures::spawn_local(async {
+        match tauri_sys::event::listen::<DownloadProgress>(PROGRESS_EVENT).await {
+            Ok(mut events) => {
+                while let Some(event) = events.next().await {
+                    let progress = event.payload;
+                    Dispatch::<UIState>::global().reduce_mut(move |state| {
+                        if let Some(job) = state
+                            .download_jobs
+                            .iter_mut()
+                            .find(|job| job.filename == progress.filename)
+                        {
+                            job.downloaded = progress.downloaded;
+                            job.total = progress.total;
+                        }
+                    });
+                }
+            }
+            Err(e) => web_sys::console::log_1(
+                &format!("Unable to follow download progress: {:?}", e).into(),
+            ),
+        }
+    });
+}
 
-    let args = StartFileServerArgs {
-        filepath: file_path.to_string(),
-    };
+// Progress isn't part of the job list, it carries over from the jobs being replaced. A job that
+// dropped out finished or was removed, either way the downloaded episodes get reloaded.
+fn replace_download_jobs(state: &mut UIState, mut jobs: Vec<DownloadJob>) {
+    for job in jobs.iter_mut() {
+        if let Some(previous) = state
+            .download_jobs
+            .iter()
+            .find(|previous| previous.id == job.id)
+        {
+            job.downloaded = previous.downloaded;
+            job.total = previous.total;
+        }
+    }
+    let finished = state
+        .download_jobs
+        .iter()
+        .any(|previous| !jobs.iter().any(|job| job.id == previous.id));
+    state.download_jobs = jobs;
+    if finished {
+        *state.local_download_increment.get_or_insert(0) += 1;
+    }
+}
 
-    tauri::invoke::<_, String>("start_file_server", &args)
+pub async fn fetch_download_jobs() -> Result<(), JsValue> {
+    listen_for_downloads();
+    let jobs = tauri::invoke::<_, Vec<DownloadJob>>("get_download_jobs", &())
         .await
-        .map_err(|e| JsValue::from_str(&format!("Failed to start local file server: {}", e)))
+        .map_err(|e| JsValue::from_str(&format!("Failed to fetch download jobs: {:?}", e)))?;
+    Dispatch::<UIState>::global().reduce_mut(move |state| replace_download_jobs(state, jobs));
+    Ok(())
 }
 
-pub async fn update_local_database(episode_info: EpisodeInfo) -> Result<(), JsValue> {
-    // Create a wrapper struct to explicitly define the argument name
+// expected_size is the enclosure length from the feed, the app checks the finished file against
+// it when the server doesn't say how big the file is. The enclosure type goes along so the file
+// gets the right extension when neither the server nor the file itself says what it is.
+pub async fn queue_episode_download(
+    episode_info: EpisodeInfo,
+    podcast_details: PodcastDetails,
+    expected_size: Option<u64>,
+) -> Result<(), JsValue> {
+    listen_for_downloads();
+
     #[derive(Serialize)]
     #[allow(non_snake_case)]
-    struct UpdateLocalDbArgs {
+    struct QueueEpisodeDownloadArgs {
         episodeInfo: EpisodeInfo,
+        podcastDetails: PodcastDetails,
+        expectedSize: Option<u64>,
+        mediaType: Option<String>,
     }
 
-    // Wrap the episode_info in the struct with the expected argument name
-    let args = UpdateLocalDbArgs {
+    let args = QueueEpisodeDownloadArgs {
+        mediaType: episode_info.enclosuretype.clone(),
         episodeInfo: episode_info,
+        podcastDetails: podcast_details,
+        expectedSize: expected_size,
     };
 
-    // Serialize and invoke the Tauri command
-    let serialized_data = serde_wasm_bindgen::to_value(&args).unwrap();
-    web_sys::console::log_1(&serialized_data); // Debug print
+    tauri::invoke::<_, ()>("queue_episode_download", &args)
+        .await
+        .map_err(|e| JsValue::from_str(&format!("Failed to queue download: {}", e)))
+}
+
+async fn control_download_job(command: &str, id: i64) -> Result<(), JsValue> {
+    #[derive(Serialize)]
+    struct DownloadJobArgs {
+        id: i64,
+    }
 
-    tauri::invoke::<_, ()>("update_local_db", &args)
+    tauri::invoke::<_, ()>(command, &DownloadJobArgs { id })
         .await
-        .map_err(|e| JsValue::from_str(&format!("Failed to update local DB: {}", e)))
+        .map_err(|e| JsValue::from_str(&format!("Failed to {}: {}", command.replace('_', " "), e)))
 }
 
-pub async fn remove_episode_from_local_db(episode_id: i32) -> Result<(), JsValue> {
+pub async fn pause_download_job(id: i64) -> Result<(), JsValue> {
+    control_download_job("pause_download_job", id).await
+}
+
+pub async fn resume_download_job(id: i64) -> Result<(), JsValue> {
+    control_download_job("resume_download_job", id).await
+}
+
+// Drops the job along with whatever it had downloaded so far
+pub async fn remove_download_job(id: i64) -> Result<(), JsValue> {
+    control_download_job("remove_download_job", id).await
+}
+
+// position counts from 0 at the front of the queue
+pub async fn move_download_job(id: i64, position: usize) -> Result<(), JsValue> {
     #[derive(Serialize)]
-    struct RemoveEpisodeFromLocalDbArgs {
+    struct MoveDownloadJobArgs {
+        id: i64,
+        position: usize,
+    }
+
+    tauri::invoke::<_, ()>("move_download_job", &MoveDownloadJobArgs { id, position })
+        .await
+        .map_err(|e| JsValue::from_str(&format!("Failed to move download: {}", e)))
+}
+
+async fn control_downloads(command: &str) -> Result<(), JsValue> {
+    tauri::invoke::<_, ()>(command, &())
+        .await
+        .map_err(|e| JsValue::from_str(&format!("Failed to {}: {}", command.replace('_', " "), e)))
+}
+
+pub async fn pause_all_downloads() -> Result<(), JsValue> {
+    control_downloads("pause_all_downloads").await
+}
+
+pub async fn resume_all_downloads() -> Result<(), JsValue> {
+    control_downloads("resume_all_downloads").await
+}
+
+pub async fn clear_failed_downloads() -> Result<(), JsValue> {
+    control_downloads("clear_failed_downloads").await
+}
+
+pub async fn fetch_download_settings() -> Result<DownloadSettings, JsValue> {
+    tauri::invoke::<_, DownloadSettings>("get_download_settings", &())
+        .await
+        .map_err(|e| JsValue::from_str(&format!("Failed to fetch download settings: {:?}", e)))
+}
+
+pub async fn save_download_settings(settings: DownloadSettings) -> Result<(), JsValue> {
+    #[derive(Serialize)]
+    struct SaveDownloadSettingsArgs {
+        settings: DownloadSettings,
+    }
+
+    tauri::invoke::<_, ()>(
+        "save_download_settings",
+        &SaveDownloadSettingsArgs { settings },
+    )
+    .await
+    .map_err(|e| JsValue::from_str(&format!("Failed to save download settings: {}", e)))
+}
+
+// The app's media server URL for a downloaded episode, it stays the same until the app closes
+pub async fn fetch_local_episode_url(episode_id: i32) -> Result<String, JsValue> {
+    #[derive(Serialize)]
+    struct EpisodeMediaUrlArgs {
         episodeid: i32,
     }
 
-    let args = RemoveEpisodeFromLocalDbArgs {
+    let args = EpisodeMediaUrlArgs {
         episodeid: episode_id,
     };
 
-    tauri::invoke::<_, ()>("remove_from_local_db", &args)
+    tauri::invoke::<_, String>("get_episode_media_url", &args)
         .await
-        .map_err(|e| JsValue::from_str(&format!("Failed to start local file server: {}", e)))
+        .map_err(|e| JsValue::from_str(&format!("Failed to get local episode URL: {}", e)))
 }
 
-pub async fn fetch_local_episodes() -> Result<Vec<EpisodeDownload>, JsValue> {
-    tauri::invoke::<_, Vec<EpisodeDownload>>("get_local_episodes", &())
+// Reads ID3 CHAP frames from a downloaded episode
+pub async fn fetch_local_chapters(episode_id: i32) -> Result<Vec<Chapter>, JsValue> {
+    #[derive(Serialize)]
+    struct GetLocalChaptersArgs {
+        episodeid: i32,
+    }
+
+    let args = GetLocalChaptersArgs {
+        episodeid: episode_id,
+    };
+
+    tauri::invoke::<_, Vec<Chapter>>("get_local_chapters", &args)
         .await
-        .map_err(|e| JsValue::from_str(&format!("Failed to fetch local episodes: {:?}", e)))
+        .map(normalize_chapters)
+        .map_err(|e| JsValue::from_str(&format!("Failed to read local chapters: {:?}", e)))
 }
 
-pub async fn update_podcast_database(podcast_details: PodcastDetails) -> Result<(), JsValue> {
-    // Create a wrapper struct to explicitly define the argument name
+pub async fn remove_episode_from_local_db(episode_id: i32) -> Result<(), JsValue> {
     #[derive(Serialize)]
-    #[allow(non_snake_case)]
-    struct UpdatePodcastDbArgs {
-        podcastDetails: PodcastDetails,
+    struct RemoveEpisodeFromLocalDbArgs {
+        episodeid: i32,
     }
 
-    // Wrap the podcast_details in the struct with the expected argument name
-    let args = UpdatePodcastDbArgs {
-        podcastDetails: podcast_details,
+    let args = RemoveEpisodeFromLocalDbArgs {
+        episodeid: episode_id,
     };
 
-    // Serialize and invoke the Tauri command
-    let serialized_data = serde_wasm_bindgen::to_value(&args).unwrap();
-    web_sys::console::log_1(&serialized_data); // Debug print
+    tauri::invoke::<_, ()>("remove_from_local_db", &args)
+        .await
+        .map_err(|e| JsValue::from_str(&format!("Failed to start local file server: {}", e)))
+}
 
-    tauri::invoke::<_, ()>("update_podcast_db", &args)
+pub async fn fetch_local_episodes() -> Result<Vec<EpisodeDownload>, JsValue> {
+    tauri::invoke::<_, Vec<EpisodeDownload>>("get_local_episodes", &())
         .await
-        .map_err(|e| JsValue::from_str(&format!("Failed to update podcast DB: {:?}", e)))
+        .map_err(|e| JsValue::from_str(&format!("Failed to fetch local episodes: {:?}", e)))
 }
 
 pub async fn fetch_local_podcasts() -> Result<Vec<Podcast>, JsValue> {
@@ -558,6 +723,7 @@ pub fn downloads() -> Html {
                                         }
                                     }
                                 </div>
+                                <DownloadJobs />
                             </div>
                         }
                     }
@@ -644,6 +810,295 @@ pub fn downloads() -> Html {
     }
 }
 
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+}
+
+// Runs a queue action, showing its error in the snackbar if it fails
+fn run_queue_action(action: impl std::future::Future<Output = Result<(), JsValue>> + 'static) {
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = action.await {
+            Dispatch::<UIState>::global()
+                .reduce_mut(|state| state.error_message = Some(format!("{:?}", e)));
+        }
+    });
+}
+
+fn job_status(job: &DownloadJob, now: i64) -> String {
+    let amount = match job.total {
+        Some(total) => format!(
+            "{} of {}",
+            format_megabytes(job.downloaded),
+            format_megabytes(total)
+        ),
+        None => format_megabytes(job.downloaded),
+    };
+    match job.state {
+        JobState::Running => amount,
+        JobState::Paused => format!("Paused, {}", amount),
+        JobState::Queued if job.nextattempt > now => format!(
+            "Retrying in {} min after attempt {}: {}",
+            (job.nextattempt - now + 59) / 60,
+            job.attempts,
+            job.error.clone().unwrap_or_default()
+        ),
+        JobState::Queued => "Waiting".to_string(),
+        JobState::Failed => format!(
+            "Failed after {} attempts: {}",
+            job.attempts,
+            job.error.clone().unwrap_or_default()
+        ),
+    }
+}
+
+// The desktop app's download queue, with its order, controls and settings
+#[function_component(DownloadJobs)]
+fn download_jobs() -> Html {
+    let (ui_state, _ui_dispatch) = use_store::<UIState>();
+    let settings = use_state(|| None::<DownloadSettings>);
+    let settings_open = use_state(|| false);
+
+    {
+        let settings = settings.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = fetch_download_jobs().await {
+                    web_sys::console::log_1(&format!("{:?}", e).into());
+                }
+                match fetch_download_settings().await {
+                    Ok(fetched) => settings.set(Some(fetched)),
+                    Err(e) => web_sys::console::log_1(&format!("{:?}", e).into()),
+                }
+            });
+            || ()
+        });
+    }
+
+    let jobs = &ui_state.download_jobs;
+    let now = (js_sys::Date::now() / 1000.0) as i64;
+    let any_active = jobs
+        .iter()
+        .any(|job| matches!(job.state, JobState::Queued | JobState::Running));
+    let any_paused = jobs.iter().any(|job| job.state == JobState::Paused);
+    let any_failed = jobs.iter().any(|job| job.state == JobState::Failed);
+
+    let on_pause_all = Callback::from(|_: MouseEvent| run_queue_action(pause_all_downloads()));
+    let on_resume_all = Callback::from(|_: MouseEvent| run_queue_action(resume_all_downloads()));
+    let on_clear_failed =
+        Callback::from(|_: MouseEvent| run_queue_action(clear_failed_downloads()));
+    let on_toggle_settings = {
+        let settings_open = settings_open.clone();
+        Callback::from(move |_: MouseEvent| settings_open.set(!*settings_open))
+    };
+
+    let job_count = jobs.len();
+    let rows = jobs.iter().enumerate().map(|(index, job)| {
+        let id = job.id;
+        let percent = job
+            .total
+            .filter(|total| *total > 0)
+            .map(|total| (job.downloaded as f64 / total as f64 * 100.0).min(100.0))
+            .unwrap_or(0.0);
+        let on_move_up = Callback::from(move |_: MouseEvent| {
+            run_queue_action(move_download_job(id, index.saturating_sub(1)))
+        });
+        let on_move_down =
+            Callback::from(move |_: MouseEvent| run_queue_action(move_download_job(id, index + 1)));
+        let paused = matches!(job.state, JobState::Paused | JobState::Failed);
+        let on_toggle_pause = Callback::from(move |_: MouseEvent| {
+            if paused {
+                run_queue_action(resume_download_job(id))
+            } else {
+                run_queue_action(pause_download_job(id))
+            }
+        });
+        let on_remove = Callback::from(move |_: MouseEvent| run_queue_action(remove_download_job(id)));
+
+        html! {
+            <div key={job.id} class="mb-3">
+                <div class="flex items-center">
+                    <p class="item_container-text flex-grow truncate">{ &job.title }</p>
+                    <button class="item-container-button selector-button font-bold py-1 px-2 rounded-full flex items-center justify-center" disabled={index == 0} onclick={on_move_up}>
+                        <span class="material-icons">{"arrow_upward"}</span>
+                    </button>
+                    <button class="item-container-button selector-button font-bold py-1 px-2 rounded-full flex items-center justify-center" disabled={index + 1 == job_count} onclick={on_move_down}>
+                        <span class="material-icons">{"arrow_downward"}</span>
+                    </button>
+                    <button class="item-container-button selector-button font-bold py-1 px-2 rounded-full flex items-center justify-center" onclick={on_toggle_pause}>
+                        <span class="material-icons">{ if paused { "play_arrow" } else { "pause" } }</span>
+                    </button>
+                    <button class="item-container-button selector-button font-bold py-1 px-2 rounded-full flex items-center justify-center" onclick={on_remove}>
+                        <span class="material-icons">{"close"}</span>
+                    </button>
+                </div>
+                if job.state == JobState::Running || job.state == JobState::Paused {
+                    <div class="progress-bar-container">
+                        <div class="progress-bar" style={ format!("width: {}%;", percent) }></div>
+                    </div>
+                }
+                <p class={ if job.state == JobState::Failed { "import-status import-status-failed" } else { "import-status" } }>{ job_status(job, now) }</p>
+            </div>
+        }
+    });
+
+    html! {
+        <div class="item-container border-solid border rounded-lg shadow-md p-4 my-4">
+            <div class="flex items-center mb-2">
+                <p class="item_container-text text-lg font-semibold flex-grow">{"Download Queue"}</p>
+                if any_active {
+                    <button class="download-button font-bold py-1 px-3 rounded inline-flex items-center" onclick={on_pause_all}>
+                        <span class="material-icons icon-space">{"pause"}</span>
+                        <span>{"Pause All"}</span>
+                    </button>
+                }
+                if any_paused {
+                    <button class="download-button font-bold py-1 px-3 rounded inline-flex items-center" onclick={on_resume_all}>
+                        <span class="material-icons icon-space">{"play_arrow"}</span>
+                        <span>{"Resume All"}</span>
+                    </button>
+                }
+                if any_failed {
+                    <button class="download-button font-bold py-1 px-3 rounded inline-flex items-center" onclick={on_clear_failed}>
+                        <span class="material-icons icon-space">{"delete_sweep"}</span>
+                        <span>{"Clear Failed"}</span>
+                    </button>
+                }
+                <button class="download-button font-bold py-1 px-3 rounded inline-flex items-center" onclick={on_toggle_settings}>
+                    <span class="material-icons">{"settings"}</span>
+                </button>
+            </div>
+            if *settings_open {
+                if let Some(current) = (*settings).clone() {
+                    <DownloadSettingsForm settings={current} on_saved={
+                        let settings = settings.clone();
+                        Callback::from(move |saved| settings.set(Some(saved)))
+                    } />
+                }
+            }
+            if jobs.is_empty() {
+                <p class="item_container-text">{"Nothing queued. Episodes you download locally wait here until they're done."}</p>
+            }
+            { for rows }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct DownloadSettingsFormProps {
+    settings: DownloadSettings,
+    on_saved: Callback<DownloadSettings>,
+}
+
+#[function_component(DownloadSettingsForm)]
+fn download_settings_form(props: &DownloadSettingsFormProps) -> Html {
+    let draft = use_state(|| props.settings.clone());
+
+    let number_input = |update: fn(&mut DownloadSettings, u32)| {
+        let draft = draft.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                if let Ok(value) = input.value().parse::<u32>() {
+                    let mut next = (*draft).clone();
+                    update(&mut next, value);
+                    draft.set(next);
+                }
+            }
+        })
+    };
+    let select_input = |update: fn(&mut DownloadSettings, String)| {
+        let draft = draft.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                let mut next = (*draft).clone();
+                update(&mut next, select.value());
+                draft.set(next);
+            }
+        })
+    };
+    let on_unmetered = {
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*draft).clone();
+            next.unmeteredonly = !next.unmeteredonly;
+            draft.set(next);
+        })
+    };
+    let on_save = {
+        let draft = draft.clone();
+        let on_saved = props.on_saved.clone();
+        Callback::from(move |_: MouseEvent| {
+            let settings = (*draft).clone();
+            let on_saved = on_saved.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = save_download_settings(settings.clone()).await;
+                Dispatch::<UIState>::global().reduce_mut(|state| match result {
+                    Ok(()) => state.info_message = Some("Download settings saved".to_string()),
+                    Err(e) => state.error_message = Some(format!("{:?}", e)),
+                });
+                on_saved.emit(settings);
+            });
+        })
+    };
+    let hour_options = |selected: u32| {
+        (0..24)
+            .map(|hour| {
+                html! {
+                    <option value={hour.to_string()} selected={hour == selected}>{ format!("{:02}:00", hour) }</option>
+                }
+            })
+            .collect::<Html>()
+    };
+
+    html! {
+        <div class="mb-4">
+            <div class="audio-processing-row">
+                <label for="downloads-concurrent" class="item_container-text">{"Simultaneous Downloads"}</label>
+                <input type="number" id="downloads-concurrent" min="1" max="10" value={draft.maxconcurrent.to_string()}
+                    oninput={number_input(|settings, value| settings.maxconcurrent = value.clamp(1, 10))} />
+            </div>
+            <div class="audio-processing-row">
+                <label for="downloads-host-interval" class="item_container-text">{"Seconds Between Downloads From One Host"}</label>
+                <input type="number" id="downloads-host-interval" min="0" max="600" value={draft.hostinterval.to_string()}
+                    oninput={number_input(|settings, value| settings.hostinterval = value.min(600))} />
+            </div>
+            <div class="audio-processing-row">
+                <label for="downloads-attempts" class="item_container-text">{"Attempts Before Giving Up"}</label>
+                <input type="number" id="downloads-attempts" min="1" max="20" value={draft.maxattempts.to_string()}
+                    oninput={number_input(|settings, value| settings.maxattempts = value.clamp(1, 20))} />
+            </div>
+            <div class="audio-processing-row">
+                <label for="downloads-schedule" class="item_container-text">{"Start Downloads"}</label>
+                <select id="downloads-schedule" onchange={select_input(|settings, value| settings.schedule = value)} class="theme-select-dropdown appearance-none border px-4 py-2 pr-8 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    <option value="anytime" selected={draft.schedule != "night"}>{"Any Time"}</option>
+                    <option value="night" selected={draft.schedule == "night"}>{"At Night"}</option>
+                </select>
+            </div>
+            if draft.schedule == "night" {
+                <div class="audio-processing-row">
+                    <label for="downloads-night-start" class="item_container-text">{"Night Starts"}</label>
+                    <select id="downloads-night-start" onchange={select_input(|settings, value| settings.nightstart = value.parse().unwrap_or(settings.nightstart))} class="theme-select-dropdown appearance-none border px-4 py-2 pr-8 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                        { hour_options(draft.nightstart) }
+                    </select>
+                </div>
+                <div class="audio-processing-row">
+                    <label for="downloads-night-end" class="item_container-text">{"Night Ends"}</label>
+                    <select id="downloads-night-end" onchange={select_input(|settings, value| settings.nightend = value.parse().unwrap_or(settings.nightend))} class="theme-select-dropdown appearance-none border px-4 py-2 pr-8 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                        { hour_options(draft.nightend) }
+                    </select>
+                </div>
+            }
+            <div class="audio-processing-row">
+                <label for="downloads-unmetered" class="item_container-text">{"Only On Unmetered Connections"}</label>
+                <input type="checkbox" id="downloads-unmetered" checked={draft.unmeteredonly} onclick={on_unmetered} />
+            </div>
+            <p class="item_container-text text-sm mb-2">{"The schedule only holds back downloads that haven't started yet. Whether a connection is metered comes from the system on Linux and Windows, elsewhere downloads aren't held back."}</p>
+            <button onclick={on_save} class="theme-submit-button font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                {"Save"}
+            </button>
+        </div>
+    }
+}
+
 pub fn render_podcast_with_episodes(
     podcast: &Podcast,
     episodes: Vec<EpisodeDownload>,
@@ -659,6 +1114,90 @@ pub fn render_podcast_with_episodes(
 ) -> Html {
     let history_clone = BrowserHistory::new();
 
+    // Podcasts can have hundreds of downloaded episodes, only the ones near the screen are drawn
+    let episode_count = episodes.len();
+    let episodes = Rc::new(episodes);
+    let render_episode = Callback::from(move |index: usize| {
+        let episode = episodes[index].clone();
+        let id_string = &episode.episodeid.to_string();
+
+        let dispatch = dispatch.clone();
+
+        let episode_url_clone = episode.episodeurl.clone();
+        let episode_duration_clone = episode.episodeduration.clone();
+        let episode_id_clone = episode.episodeid.clone();
+        let episode_listened_clone = episode.listenduration.clone();
+        let desc_expanded = desc_rc.expanded_descriptions.contains(id_string);
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = window)]
+            fn toggleDescription(guid: &str, expanded: bool);
+        }
+        let toggle_expanded = {
+            let desc_dispatch = desc_state.clone();
+            let episode_guid = episode.episodeid.clone().to_string();
+
+            Callback::from(move |_: MouseEvent| {
+                let guid = episode_guid.clone();
+                desc_dispatch.reduce_mut(move |state| {
+                    if state.expanded_descriptions.contains(&guid) {
+                        state.expanded_descriptions.remove(&guid); // Collapse the description
+                        toggleDescription(&guid, false); // Call JavaScript function
+                    } else {
+                        state.expanded_descriptions.insert(guid.clone()); // Expand the description
+                        toggleDescription(&guid, true); // Call JavaScript function
+                    }
+                });
+            })
+        };
+
+        let episode_id_for_closure = episode_id_clone.clone();
+        let audio_dispatch = audio_dispatch.clone();
+
+        let on_play_click = on_play_click_offline(episode.clone(), audio_dispatch);
+
+        let on_shownotes_click = on_shownotes_click(
+            history_clone.clone(),
+            dispatch.clone(),
+            episode_id_for_closure.clone(),
+        );
+
+        let date_format = match_date_format(state.date_format.as_deref());
+        let datetime = parse_date(&episode.episodepubdate, &state.user_tz);
+        let format_release = format!(
+            "{}",
+            format_datetime(&datetime, &state.hour_preference, date_format)
+        );
+        let on_checkbox_change_cloned = on_checkbox_change.clone();
+        let episode_url_for_ep_item = episode_url_clone.clone();
+        let sanitized_description =
+            sanitize_html_with_blank_target(&episode.episodedescription.clone());
+
+        let check_episode_id = &episode.episodeid.clone();
+        let is_completed = state
+            .completed_episodes
+            .as_ref()
+            .unwrap_or(&vec![])
+            .contains(&check_episode_id);
+        download_episode_item(
+            Box::new(episode),
+            sanitized_description.clone(),
+            desc_expanded,
+            &format_release,
+            on_play_click,
+            on_shownotes_click,
+            toggle_expanded,
+            episode_duration_clone,
+            episode_listened_clone,
+            "local_downloads",
+            on_checkbox_change_cloned, // Add this line
+            is_delete_mode,            // Add this line
+            episode_url_for_ep_item,
+            is_completed,
+        )
+    });
+
     html! {
         <div key={podcast.podcastid}>
             <div class="item-container border-solid border flex items-start mb-4 shadow-md rounded-lg h-full" onclick={toggle_expanded}>
@@ -680,82 +1219,7 @@ pub fn render_podcast_with_episodes(
             { if is_expanded {
                 html! {
                     <div class="episodes-dropdown, pl-4">
-                        { for episodes.into_iter().map(|episode| {
-                            let id_string = &episode.episodeid.to_string();
-
-                            let dispatch = dispatch.clone();
-
-                            let episode_url_clone = episode.episodeurl.clone();
-                            let episode_duration_clone = episode.episodeduration.clone();
-                            let episode_id_clone = episode.episodeid.clone();
-                            let episode_listened_clone = episode.listenduration.clone();
-                            let desc_expanded = desc_rc.expanded_descriptions.contains(id_string);
-
-                            #[wasm_bindgen]
-                            extern "C" {
-                                #[wasm_bindgen(js_namespace = window)]
-                                fn toggleDescription(guid: &str, expanded: bool);
-                            }
-                            let toggle_expanded = {
-                                let desc_dispatch = desc_state.clone();
-                                let episode_guid = episode.episodeid.clone().to_string();
-
-                                Callback::from(move |_: MouseEvent| {
-                                    let guid = episode_guid.clone();
-                                    desc_dispatch.reduce_mut(move |state| {
-                                        if state.expanded_descriptions.contains(&guid) {
-                                            state.expanded_descriptions.remove(&guid); // Collapse the description
-                                            toggleDescription(&guid, false); // Call JavaScript function
-                                        } else {
-                                            state.expanded_descriptions.insert(guid.clone()); // Expand the description
-                                            toggleDescription(&guid, true); // Call JavaScript function
-                                        }
-                                    });
-                                })
-                            };
-
-                            let episode_id_for_closure = episode_id_clone.clone();
-                            let audio_dispatch = audio_dispatch.clone();
-
-                            let on_play_click = on_play_click_offline(episode.clone(), audio_dispatch);
-
-                            let on_shownotes_click = on_shownotes_click(
-                                history_clone.clone(),
-                                dispatch.clone(),
-                                episode_id_for_closure.clone(),
-                            );
-
-                            let date_format = match_date_format(state.date_format.as_deref());
-                            let datetime = parse_date(&episode.episodepubdate, &state.user_tz);
-                            let format_release = format!("{}", format_datetime(&datetime, &state.hour_preference, date_format));
-                            let on_checkbox_change_cloned = on_checkbox_change.clone();
-                            let episode_url_for_ep_item = episode_url_clone.clone();
-                            let sanitized_description =
-                                sanitize_html_with_blank_target(&episode.episodedescription.clone());
-
-                            let check_episode_id = &episode.episodeid.clone();
-                            let is_completed = state
-                                .completed_episodes
-                                .as_ref()
-                                .unwrap_or(&vec![])
-                                .contains(&check_episode_id);
-                            download_episode_item(
-                                Box::new(episode),
-                                sanitized_description.clone(),
-                                desc_expanded,
-                                &format_release,
-                                on_play_click,
-                                on_shownotes_click,
-                                toggle_expanded,
-                                episode_duration_clone,
-                                episode_listened_clone,
-                                "local_downloads",
-                                on_checkbox_change_cloned, // Add this line
-                                is_delete_mode, // Add this line
-                                episode_url_for_ep_item,
-                                is_completed,
-                            )
-                        }) }
+                        <VirtualList item_count={episode_count} render_item={render_episode} />
                     </div>
                 }
             } else {
@@ -2,19 +2,26 @@ use super::app_drawer::App_drawer;
 use super::gen_components::{empty_message, Search_nav, UseScrollToTop};
 use crate::components::audio::on_play_click;
 use crate::components::audio::AudioPlayer;
+use crate::components::bookmarks::{clip_from_location, BookmarkList, ClipRange};
 use crate::components::click_events::create_on_title_click;
 use crate::components::context::{AppState, UIState};
 use crate::components::episodes_layout::SafeHtml;
 use crate::components::episodes_layout::UIStateMsg;
+use crate::components::feed_details::EpisodeExtras;
 use crate::components::gen_funcs::{
     format_datetime, format_time, match_date_format, parse_date, sanitize_html_with_blank_target,
 };
+use crate::components::queue::queue_episode_at;
+use crate::components::transcript::TranscriptPane;
 use crate::requests::login_requests::use_check_authentication;
 use crate::requests::pod_req;
 use crate::requests::pod_req::{
     call_download_episode, call_queue_episode, call_save_episode, DownloadEpisodeRequest,
-    EpisodeMetadataResponse, EpisodeRequest, QueuePodcastRequest, SavePodcastRequest,
+    EpisodeMetadataResponse, EpisodeRequest, QueuePodcastRequest, QueuePositionRequest,
+    SavePodcastRequest,
 };
+use crate::requests::search_pods::{Episode as FeedEpisode, PodcastInfo};
+use crate::requests::transcripts::{call_get_transcript, preferred_transcript, TranscriptCue};
 use std::collections::HashMap;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
@@ -78,6 +85,8 @@ pub fn epsiode() -> Html {
     let error_message = audio_state.error_message.clone();
     let info_message = audio_state.info_message.clone();
     let history = BrowserHistory::new();
+    // Clip links carry the episode and range in the query string
+    let shared_clip = use_state(clip_from_location);
 
     {
         let ui_dispatch = audio_dispatch.clone();
@@ -119,7 +128,9 @@ pub fn epsiode() -> Html {
             .map(|ud| ud.server_name.clone());
         let effect_dispatch = dispatch.clone();
 
-        let episode_id = state.selected_episode_id.clone();
+        let episode_id = (*shared_clip)
+            .map(|(episode_id, _)| episode_id)
+            .or(state.selected_episode_id.clone());
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
@@ -163,6 +174,74 @@ pub fn epsiode() -> Html {
         );
     }
 
+    // Transcript links, people and soundbites come from the feed. A podcast that isn't added yet
+    // is already parsed in the feed results, the server keeps them for an added one.
+    let transcript_cues: UseStateHandle<Option<Vec<TranscriptCue>>> = use_state(|| None);
+    let feed_episode: UseStateHandle<Option<(FeedEpisode, Option<PodcastInfo>)>> =
+        use_state(|| None);
+    {
+        let transcript_cues = transcript_cues.clone();
+        let feed_episode = feed_episode.clone();
+        let podcast_feed_results = state.podcast_feed_results.clone();
+        let fetched_episode = state
+            .fetched_episode
+            .as_ref()
+            .map(|fetched| fetched.episode.clone());
+        let api_key = api_key.clone().flatten();
+        let server_name = server_name.clone();
+
+        use_effect_with(
+            fetched_episode.as_ref().map(|episode| episode.episodeid),
+            move |_| {
+                transcript_cues.set(None);
+                feed_episode.set(None);
+                if let Some(episode) = fetched_episode {
+                    let from_feed = podcast_feed_results.as_ref().and_then(|feed| {
+                        feed.podcast.as_ref()?;
+                        feed.episodes
+                            .iter()
+                            .find(|feed_episode| {
+                                feed_episode.enclosure_url.as_deref()
+                                    == Some(episode.episodeurl.as_str())
+                            })
+                            .map(|feed_episode| (feed_episode.clone(), feed.podcast.clone()))
+                    });
+                    let found = from_feed.unwrap_or_else(|| {
+                        let stored = FeedEpisode {
+                            enclosure_url: Some(episode.episodeurl.clone()),
+                            season: episode.season,
+                            episode_number: episode.episodenumber,
+                            episode_type: episode.episodetype.clone(),
+                            ..FeedEpisode::default()
+                        }
+                        .with_stored_extras(episode.feedextras.as_deref());
+                        let podcast =
+                            PodcastInfo::from_stored_extras(episode.podcastextras.as_deref());
+                        (stored, podcast)
+                    });
+                    let link = preferred_transcript(&found.0.transcripts).cloned();
+                    feed_episode.set(Some(found));
+                    if let (Some(link), Some(api_key), Some(server_name)) =
+                        (link, api_key, server_name)
+                    {
+                        wasm_bindgen_futures::spawn_local(async move {
+                            match call_get_transcript(&server_name, &Some(api_key), &link).await {
+                                Ok(cues) if !cues.is_empty() => transcript_cues.set(Some(cues)),
+                                Ok(_) => {}
+                                Err(e) => {
+                                    web_sys::console::log_1(
+                                        &format!("Error loading transcript: {}", e).into(),
+                                    );
+                                }
+                            }
+                        });
+                    }
+                }
+                || ()
+            },
+        );
+    }
+
     html! {
         <>
         <div class="main-container">
@@ -206,8 +285,42 @@ pub fn epsiode() -> Html {
                         audio_dispatch.clone(),
                         audio_state.clone(),
                         None,
+                        None,
                     );
 
+                    let play_clip = {
+                        let api_key = api_key.clone();
+                        let user_id = user_id.clone();
+                        let server_name = server_name.clone();
+                        let audio_dispatch = audio_dispatch.clone();
+                        let audio_state = audio_state.clone();
+                        let episode_url = episode_url_clone.clone();
+                        let episode_title = episode_title_clone.clone();
+                        let episode_artwork = episode_artwork_clone.clone();
+                        // The play handler above shadows on_play_click here
+                        Callback::from(move |clip: ClipRange| {
+                            crate::components::audio::on_play_click(
+                                episode_url.clone(),
+                                episode_title.clone(),
+                                episode_artwork.clone(),
+                                episode_duration_clone,
+                                episode_id_clone,
+                                episode_listened_clone,
+                                api_key.clone().unwrap().unwrap(),
+                                user_id.unwrap(),
+                                server_name.clone().unwrap(),
+                                audio_dispatch.clone(),
+                                audio_state.clone(),
+                                None,
+                                Some(clip),
+                            )
+                            .emit(MouseEvent::new("click").unwrap());
+                        })
+                    };
+                    let shared_clip_here = (*shared_clip)
+                        .filter(|(clip_episode_id, _)| *clip_episode_id == episode_id_clone)
+                        .map(|(_, clip)| clip);
+
                     let user_id_queue = user_id.clone();
                     let server_name_queue = server_name.clone();
                     let api_key_queue = api_key.clone();
@@ -242,6 +355,31 @@ pub fn epsiode() -> Html {
                         })
                     };
 
+                    let on_play_next = {
+                        let server_name = server_name.clone();
+                        let api_key = api_key.clone();
+                        let audio_dispatch = audio_dispatch.clone();
+                        let app_dispatch = dispatch.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let playing_episode_id = audio_dispatch
+                                .get()
+                                .currently_playing
+                                .as_ref()
+                                .filter(|props| !props.offline && props.episode_id != episode_id_for_closure)
+                                .map(|props| props.episode_id);
+                            if let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) {
+                                queue_episode_at(
+                                    QueuePositionRequest::play_next(episode_id_for_closure, user_id, playing_episode_id),
+                                    server_name,
+                                    api_key.clone().flatten(),
+                                    app_dispatch.clone(),
+                                    audio_dispatch.clone(),
+                                    Some("Episode will play next".to_string()),
+                                );
+                            }
+                        })
+                    };
+
                     let saved_server_name = server_name.clone();
                     let saved_api_key = api_key.clone();
                     let save_post = audio_dispatch.clone();
@@ -397,6 +535,10 @@ pub fn epsiode() -> Html {
                                             <i class="material-icons">{ "playlist_add" }</i>
                                             {"Queue"}
                                         </button>
+                                        <button onclick={on_play_next} class="queue-button">
+                                            <i class="material-icons">{ "queue_play_next" }</i>
+                                            {"Play Next"}
+                                        </button>
                                         <button onclick={on_save_episode} class="save-button">
                                             <i class="material-icons">{ "favorite" }</i>
                                             {"Save"}
@@ -416,6 +558,21 @@ pub fn epsiode() -> Html {
                                 }
                             }
                             </div>
+                            if let (Some(clip), true) = (shared_clip_here, should_show_buttons) {
+                                <div class="shared-clip-banner">
+                                    <span class="item_container-text">{ format!("Shared clip: {}", clip.label()) }</span>
+                                    <button onclick={{
+                                        let play_clip = play_clip.clone();
+                                        Callback::from(move |_: MouseEvent| play_clip.emit(clip))
+                                    }} class="play-button">
+                                        <i class="material-icons">{ "play_arrow" }</i>
+                                        {"Play Clip"}
+                                    </button>
+                                </div>
+                            }
+                            if let Some((feed_episode, podcast)) = (*feed_episode).clone() {
+                                <EpisodeExtras episode={feed_episode} podcast={podcast} on_play_clip={play_clip.clone()} />
+                            }
                             <hr class="episode-divider" />
                             <div class="episode-single-desc episode-description">
                             // <p>{ description }</p>
@@ -423,6 +580,14 @@ pub fn epsiode() -> Html {
                                 <SafeHtml html={description} />
                             </div>
                             </div>
+                            if should_show_buttons {
+                                <hr class="episode-divider" />
+                                <BookmarkList episode_id={episode_id_clone} duration={episode_duration_clone} on_play={play_clip.clone()} />
+                            }
+                            if let Some(cues) = (*transcript_cues).clone() {
+                                <hr class="episode-divider" />
+                                <TranscriptPane cues={cues} episode_id={episode_id_clone} />
+                            }
                         </div>
                     }
                     // item
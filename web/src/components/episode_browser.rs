@@ -0,0 +1,316 @@
+use crate::components::gen_funcs::convert_time_to_seconds;
+use crate::requests::search_pods::Episode;
+use pinepods_client::{EpisodeFilter, EpisodeOrder, EpisodeType, PodcastSeasons};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use web_sys::{window, HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+// How each podcast's episode list was last sorted and filtered, by feed URL
+const PODCAST_VIEWS_STORAGE_KEY: &str = "pinepods_podcast_views";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum EpisodeSort {
+    Newest,
+    Oldest,
+    Longest,
+    Shortest,
+}
+
+impl EpisodeSort {
+    const ALL: [EpisodeSort; 4] = [
+        EpisodeSort::Newest,
+        EpisodeSort::Oldest,
+        EpisodeSort::Longest,
+        EpisodeSort::Shortest,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            EpisodeSort::Newest => "newest",
+            EpisodeSort::Oldest => "oldest",
+            EpisodeSort::Longest => "longest",
+            EpisodeSort::Shortest => "shortest",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            EpisodeSort::Newest => "Newest first",
+            EpisodeSort::Oldest => "Oldest first",
+            EpisodeSort::Longest => "Longest first",
+            EpisodeSort::Shortest => "Shortest first",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        EpisodeSort::ALL.into_iter().find(|sort| sort.key() == key)
+    }
+}
+
+// Trailers and bonus episodes get their own tabs, the season tabs only hold full episodes
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub enum EpisodeTab {
+    #[default]
+    All,
+    Season(i32),
+    Trailers,
+    Bonus,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct EpisodeView {
+    pub tab: EpisodeTab,
+    // `None` follows the show, serial shows start from the first episode
+    pub sort: Option<EpisodeSort>,
+    pub unplayed_only: bool,
+    pub downloaded_only: bool,
+}
+
+impl EpisodeView {
+    pub fn load(feed_url: &str) -> Self {
+        load_views().remove(feed_url).unwrap_or_default()
+    }
+
+    pub fn store(&self, feed_url: &str) {
+        let mut views = load_views();
+        if *self == EpisodeView::default() {
+            views.remove(feed_url);
+        } else {
+            views.insert(feed_url.to_string(), self.clone());
+        }
+        if let Some(storage) = window().and_then(|window| window.local_storage().ok().flatten()) {
+            if let Ok(serialized) = serde_json::to_string(&views) {
+                let _ = storage.set_item(PODCAST_VIEWS_STORAGE_KEY, &serialized);
+            }
+        }
+    }
+
+    pub fn sort_for(&self, serial: bool) -> EpisodeSort {
+        self.sort.unwrap_or(if serial {
+            EpisodeSort::Oldest
+        } else {
+            EpisodeSort::Newest
+        })
+    }
+
+    // What to ask the server for, an added podcast's episodes are sorted and filtered there
+    pub fn filter(&self, serial: bool) -> EpisodeFilter {
+        EpisodeFilter {
+            sort: match self.sort_for(serial) {
+                EpisodeSort::Newest => EpisodeOrder::Newest,
+                EpisodeSort::Oldest => EpisodeOrder::Oldest,
+                EpisodeSort::Longest => EpisodeOrder::Longest,
+                EpisodeSort::Shortest => EpisodeOrder::Shortest,
+            },
+            season: match self.tab {
+                EpisodeTab::Season(season) => Some(season),
+                _ => None,
+            },
+            episode_type: Some(match self.tab {
+                EpisodeTab::Trailers => EpisodeType::Trailer,
+                EpisodeTab::Bonus => EpisodeType::Bonus,
+                EpisodeTab::All | EpisodeTab::Season(_) => EpisodeType::Full,
+            }),
+            unplayed: self.unplayed_only,
+            downloaded: self.downloaded_only,
+        }
+    }
+
+    // The episodes of a parsed feed this view shows, in the order it shows them
+    pub fn apply(&self, serial: bool, episodes: Vec<Episode>) -> Vec<Episode> {
+        let mut episodes: Vec<Episode> = episodes
+            .into_iter()
+            .filter(|episode| match self.tab {
+                EpisodeTab::All => !episode.is_trailer() && !episode.is_bonus(),
+                EpisodeTab::Season(season) => {
+                    episode.season == Some(season) && !episode.is_trailer() && !episode.is_bonus()
+                }
+                EpisodeTab::Trailers => episode.is_trailer(),
+                EpisodeTab::Bonus => episode.is_bonus(),
+            })
+            .filter(|episode| !self.unplayed_only || episode.completed != Some(true))
+            .filter(|episode| !self.downloaded_only || episode.downloaded == Some(true))
+            .collect();
+        // Sorts are stable, so episodes without a date or duration keep the server's order
+        match self.sort_for(serial) {
+            EpisodeSort::Newest => {
+                episodes.sort_by_key(|episode| std::cmp::Reverse(pub_timestamp(episode)))
+            }
+            EpisodeSort::Oldest => {
+                episodes.reverse();
+                episodes.sort_by_key(|episode| {
+                    (
+                        pub_timestamp(episode).is_none(),
+                        pub_timestamp(episode),
+                        episode.episode_number.map(|number| (number * 100.0) as i64),
+                    )
+                });
+            }
+            EpisodeSort::Longest => {
+                episodes.sort_by_key(|episode| std::cmp::Reverse(duration_seconds(episode)))
+            }
+            EpisodeSort::Shortest => episodes.sort_by_key(|episode| {
+                let duration = duration_seconds(episode);
+                (duration.is_none(), duration)
+            }),
+        }
+        episodes
+    }
+}
+
+fn load_views() -> HashMap<String, EpisodeView> {
+    window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(PODCAST_VIEWS_STORAGE_KEY).ok().flatten())
+        .and_then(|stored| serde_json::from_str(&stored).ok())
+        .unwrap_or_default()
+}
+
+// Feeds use RFC 2822 dates, the server sends them without a zone
+fn pub_timestamp(episode: &Episode) -> Option<i64> {
+    pinepods_client::parse_feed_date(episode.pub_date.as_deref()?).map(|date| date.timestamp())
+}
+
+fn duration_seconds(episode: &Episode) -> Option<u32> {
+    convert_time_to_seconds(episode.duration.as_deref()?.trim()).ok()
+}
+
+// What the tab row needs to know about the episodes
+#[derive(Clone, PartialEq, Default)]
+pub struct EpisodeTabs {
+    pub seasons: Vec<i32>,
+    pub trailers: bool,
+    pub bonus: bool,
+}
+
+impl EpisodeTabs {
+    pub fn of(episodes: &[Episode]) -> Self {
+        let seasons: BTreeSet<i32> = episodes
+            .iter()
+            .filter(|episode| !episode.is_trailer() && !episode.is_bonus())
+            .filter_map(|episode| episode.season)
+            .collect();
+        EpisodeTabs {
+            seasons: seasons.into_iter().collect(),
+            trailers: episodes.iter().any(|episode| episode.is_trailer()),
+            bonus: episodes.iter().any(|episode| episode.is_bonus()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.seasons.len() < 2 && !self.trailers && !self.bonus
+    }
+}
+
+impl From<PodcastSeasons> for EpisodeTabs {
+    fn from(seasons: PodcastSeasons) -> Self {
+        EpisodeTabs {
+            seasons: seasons.seasons,
+            trailers: seasons.trailers,
+            bonus: seasons.bonus,
+        }
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct EpisodeBrowserProps {
+    pub view: EpisodeView,
+    pub tabs: EpisodeTabs,
+    pub serial: bool,
+    // Played and downloaded are only known for podcasts that have been added
+    pub show_filters: bool,
+    pub on_change: Callback<EpisodeView>,
+}
+
+// Season tabs plus the sort and filter controls above a podcast's episodes
+#[function_component(EpisodeBrowser)]
+pub fn episode_browser(props: &EpisodeBrowserProps) -> Html {
+    let tab_button = |tab: EpisodeTab, label: String| {
+        let on_click = {
+            let on_change = props.on_change.clone();
+            let view = props.view.clone();
+            Callback::from(move |_: MouseEvent| {
+                on_change.emit(EpisodeView {
+                    tab,
+                    ..view.clone()
+                })
+            })
+        };
+        let class = classes!(
+            "episode-tab",
+            (props.view.tab == tab).then_some("episode-tab-active")
+        );
+        html! { <button {class} onclick={on_click}>{ label }</button> }
+    };
+
+    let on_sort_change = {
+        let on_change = props.on_change.clone();
+        let view = props.view.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            on_change.emit(EpisodeView {
+                sort: EpisodeSort::from_key(&select.value()),
+                ..view.clone()
+            });
+        })
+    };
+    let on_unplayed_change = {
+        let on_change = props.on_change.clone();
+        let view = props.view.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_change.emit(EpisodeView {
+                unplayed_only: input.checked(),
+                ..view.clone()
+            });
+        })
+    };
+    let on_downloaded_change = {
+        let on_change = props.on_change.clone();
+        let view = props.view.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_change.emit(EpisodeView {
+                downloaded_only: input.checked(),
+                ..view.clone()
+            });
+        })
+    };
+
+    let sort = props.view.sort_for(props.serial);
+    html! {
+        <div class="episode-browser">
+            if !props.tabs.is_empty() {
+                <div class="episode-tabs">
+                    { tab_button(EpisodeTab::All, "All".to_string()) }
+                    { for props.tabs.seasons.iter().map(|season| tab_button(EpisodeTab::Season(*season), format!("Season {}", season))) }
+                    if props.tabs.trailers {
+                        { tab_button(EpisodeTab::Trailers, "Trailers".to_string()) }
+                    }
+                    if props.tabs.bonus {
+                        { tab_button(EpisodeTab::Bonus, "Bonus".to_string()) }
+                    }
+                </div>
+            }
+            <div class="episode-browser-controls item_container-text">
+                <select onchange={on_sort_change} class="theme-select-dropdown appearance-none border px-2 py-1 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    { for EpisodeSort::ALL.iter().map(|option| html! {
+                        <option value={option.key()} selected={*option == sort}>{ option.label() }</option>
+                    }) }
+                </select>
+                if props.show_filters {
+                    <label class="episode-browser-filter">
+                        <input type="checkbox" checked={props.view.unplayed_only} onchange={on_unplayed_change} />
+                        { "Unplayed only" }
+                    </label>
+                    <label class="episode-browser-filter">
+                        <input type="checkbox" checked={props.view.downloaded_only} onchange={on_downloaded_change} />
+                        { "Downloaded only" }
+                    </label>
+                }
+            </div>
+        </div>
+    }
+}
@@ -1,20 +1,31 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::ContextButton;
-use super::gen_components::{EpisodeTrait, Search_nav, UseScrollToTop};
+use super::gen_components::{
+    may_have_more, EpisodeTrait, Search_nav, UseScrollToTop, VirtualList, EPISODE_PAGE_SIZE,
+};
 use super::gen_funcs::{format_datetime, match_date_format, parse_date};
 use crate::components::audio::{on_play_click, AudioPlayer};
 use crate::components::context::{AppState, UIState};
+use crate::components::episode_browser::{
+    EpisodeBrowser, EpisodeSort, EpisodeTab, EpisodeTabs, EpisodeView,
+};
+use crate::components::feed_details::{episode_badges, season_heading, PodcastExtras};
 use crate::components::gen_funcs::format_time;
 use crate::components::gen_funcs::{
     convert_time_to_seconds, sanitize_html_with_blank_target, truncate_description,
 };
 use crate::requests::login_requests::use_check_authentication;
 use crate::requests::pod_req::{
-    call_add_podcast, call_adjust_skip_times, call_check_podcast, call_download_all_podcast,
-    call_enable_auto_download, call_get_auto_download_status, call_get_auto_skip_times,
+    call_add_podcast, call_adjust_playback_profile, call_adjust_skip_times, call_check_podcast,
+    call_download_all_podcast, call_enable_auto_download, call_get_auto_download_status,
+    call_get_auto_skip_times, call_get_playback_profile, call_get_podcast_details,
     call_get_podcast_id_from_ep, call_get_podcast_id_from_ep_name, call_remove_podcasts_name,
-    AutoDownloadRequest, DownloadAllPodcastRequest, PodcastValues, RemovePodcastValuesName,
-    SkipTimesRequest,
+    AutoDownloadRequest, DownloadAllPodcastRequest, Page, PlaybackProfile, PlaybackProfileRequest,
+    PodcastValues, RemovePodcastValuesName, SkipTimesRequest,
+};
+use crate::requests::podcast_ns::Trailer;
+use crate::requests::search_pods::{
+    call_get_podcast_episodes, call_get_podcast_seasons, PodcastInfo,
 };
 use htmlentity::entity::decode;
 use htmlentity::entity::ICodedDataTrait;
@@ -244,6 +255,7 @@ pub fn episode_layout() -> Html {
     let podcast_id = use_state(|| 0);
     let start_skip = use_state(|| 0);
     let end_skip = use_state(|| 0);
+    let playback_profile = use_state(PlaybackProfile::default);
 
     {
         let api_key = api_key.clone();
@@ -252,9 +264,14 @@ pub fn episode_layout() -> Html {
         let download_status = download_status.clone();
         let episode_name = episode_name_pre.clone();
         let episode_url = episode_url_pre.clone();
+        let known_podcast_id = search_state
+            .podcast_feed_results
+            .as_ref()
+            .and_then(|results| results.podcast_id);
         let user_id = search_state.user_details.as_ref().map(|ud| ud.UserID);
         let effect_start_skip = start_skip.clone();
         let effect_end_skip = end_skip.clone();
+        let effect_playback_profile = playback_profile.clone();
         let effect_added = is_added.clone();
 
         use_effect_with(effect_added.clone(), move |_| {
@@ -272,15 +289,22 @@ pub fn episode_layout() -> Html {
                     if let (Some(api_key), Some(server_name)) =
                         (api_key.as_ref(), server_name.as_ref())
                     {
-                        match call_get_podcast_id_from_ep_name(
-                            &server_name,
-                            &api_key,
-                            episode_name.unwrap(),
-                            episode_url.unwrap(),
-                            user_id,
-                        )
-                        .await
-                        {
+                        // Episodes from the server come with their podcast, otherwise it's
+                        // looked up by the first episode
+                        let id = match known_podcast_id {
+                            Some(id) => Ok(id),
+                            None => {
+                                call_get_podcast_id_from_ep_name(
+                                    &server_name,
+                                    &api_key,
+                                    episode_name.unwrap(),
+                                    episode_url.unwrap(),
+                                    user_id,
+                                )
+                                .await
+                            }
+                        };
+                        match id {
                             Ok(id) => {
                                 podcast_id.set(id);
 
@@ -320,6 +344,24 @@ pub fn episode_layout() -> Html {
                                         );
                                     }
                                 }
+                                match call_get_playback_profile(
+                                    &server_name,
+                                    &Some(api_key.clone().unwrap()),
+                                    user_id,
+                                    id,
+                                )
+                                .await
+                                {
+                                    Ok(profile) => {
+                                        effect_playback_profile.set(profile);
+                                    }
+                                    Err(e) => {
+                                        web_sys::console::log_1(
+                                            &format!("Error getting playback profile: {}", e)
+                                                .into(),
+                                        );
+                                    }
+                                }
                             }
                             Err(e) => {
                                 web_sys::console::log_1(
@@ -768,6 +810,95 @@ pub fn episode_layout() -> Html {
         })
     };
 
+    // Save the playback speed, volume boost and skip steps used for this podcast
+    let save_playback_profile = {
+        let playback_profile = playback_profile.clone();
+        let api_key = api_key.clone();
+        let user_id = user_id.clone();
+        let server_name = server_name.clone();
+        let podcast_id = podcast_id.clone();
+        let profile_dispatch = _dispatch.clone();
+
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let profile_dispatch = profile_dispatch.clone();
+            let profile = (*playback_profile).clone();
+            let api_key = api_key.clone();
+            let user_id = user_id.clone().unwrap();
+            let server_name = server_name.clone();
+            let podcast_id = *podcast_id;
+
+            wasm_bindgen_futures::spawn_local(async move {
+                if let (Some(api_key), Some(server_name)) = (api_key.as_ref(), server_name.as_ref())
+                {
+                    let request = PlaybackProfileRequest {
+                        podcast_id,
+                        user_id,
+                        profile,
+                    };
+
+                    match call_adjust_playback_profile(&server_name, &api_key, &request).await {
+                        Ok(_) => {
+                            profile_dispatch.reduce_mut(|state| {
+                                state.info_message =
+                                    Option::from("Playback Settings Adjusted".to_string())
+                            });
+                        }
+                        Err(e) => {
+                            web_sys::console::log_1(
+                                &format!("Error updating playback profile: {}", e).into(),
+                            );
+                            profile_dispatch.reduce_mut(|state| {
+                                state.error_message =
+                                    Option::from("Error Adjusting Playback Settings".to_string())
+                            });
+                        }
+                    }
+                }
+            });
+        })
+    };
+
+    // Each input edits one field of the profile
+    let on_profile_input = |update: fn(&mut PlaybackProfile, &str)| {
+        let playback_profile = playback_profile.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                let mut profile = (*playback_profile).clone();
+                update(&mut profile, &input.value());
+                playback_profile.set(profile);
+            }
+        })
+    };
+    let on_speed_input = on_profile_input(|profile, value| {
+        if let Ok(speed) = value.parse::<f64>() {
+            profile.playback_speed = speed.clamp(0.5, 2.0);
+        }
+    });
+    let on_gain_input = on_profile_input(|profile, value| {
+        if let Ok(gain) = value.parse::<f64>() {
+            profile.volume_gain = gain.clamp(1.0, 3.0);
+        }
+    });
+    let on_skip_forward_input = on_profile_input(|profile, value| {
+        if let Ok(step) = value.parse::<i32>() {
+            profile.skip_forward = step.max(1);
+        }
+    });
+    let on_smart_speed_toggle = {
+        let playback_profile = playback_profile.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut profile = (*playback_profile).clone();
+            profile.smart_speed = !profile.smart_speed;
+            playback_profile.set(profile);
+        })
+    };
+    let on_skip_backward_input = on_profile_input(|profile, value| {
+        if let Ok(step) = value.parse::<i32>() {
+            profile.skip_backward = step.max(1);
+        }
+    });
+
     // Define the modal components
     let podcast_option_model = html! {
         <div id="podcast_option_model" tabindex="-1" aria-hidden="true" class="fixed top-0 right-0 left-0 z-50 flex justify-center items-center w-full h-[calc(100%-1rem)] max-h-full bg-black bg-opacity-25">
@@ -834,6 +965,71 @@ pub fn episode_layout() -> Html {
                                     </button>
                                 </div>
                             </div>
+                            <div class="mt-4">
+                                <label for="playback-profile" class="block mb-2 text-sm font-medium">{"Playback Settings:"}</label>
+                                <div class="flex items-center space-x-2 mb-2">
+                                    <label for="profile-speed" class="block text-sm font-medium">{format!("Speed: {:.1}x", playback_profile.playback_speed)}</label>
+                                    <input
+                                        type="range"
+                                        id="profile-speed"
+                                        min="0.5"
+                                        max="2.0"
+                                        step="0.1"
+                                        value={playback_profile.playback_speed.to_string()}
+                                        oninput={on_speed_input}
+                                    />
+                                </div>
+                                <div class="flex items-center space-x-2 mb-2">
+                                    <label for="profile-gain" class="block text-sm font-medium">{format!("Volume Boost: {:.0}%", playback_profile.volume_gain * 100.0)}</label>
+                                    <input
+                                        type="range"
+                                        id="profile-gain"
+                                        min="1.0"
+                                        max="3.0"
+                                        step="0.1"
+                                        value={playback_profile.volume_gain.to_string()}
+                                        oninput={on_gain_input}
+                                    />
+                                </div>
+                                <div class="flex items-center space-x-2 mb-2">
+                                    <label for="profile-smart-speed" class="block text-sm font-medium">{"Smart Speed (speed through silences):"}</label>
+                                    <label class="inline-flex relative items-center cursor-pointer">
+                                        <input type="checkbox" id="profile-smart-speed" checked={playback_profile.smart_speed} class="sr-only peer" onclick={on_smart_speed_toggle} />
+                                        <div class="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full rtl:peer-checked:after:-translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:start-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600"></div>
+                                    </label>
+                                </div>
+                                <div class="flex items-center space-x-2">
+                                    <div class="flex items-center space-x-2">
+                                        <label for="profile-skip-back" class="block text-sm font-medium">{"Skip Back (seconds):"}</label>
+                                        <input
+                                            type="number"
+                                            id="profile-skip-back"
+                                            min="1"
+                                            value={playback_profile.skip_backward.to_string()}
+                                            class="email-input border text-sm rounded-lg p-2.5 w-16"
+                                            oninput={on_skip_backward_input}
+                                        />
+                                    </div>
+                                    <div class="flex items-center space-x-2">
+                                        <label for="profile-skip-forward" class="block text-sm font-medium">{"Skip Forward (seconds):"}</label>
+                                        <input
+                                            type="number"
+                                            id="profile-skip-forward"
+                                            min="1"
+                                            value={playback_profile.skip_forward.to_string()}
+                                            class="email-input border text-sm rounded-lg p-2.5 w-16"
+                                            oninput={on_skip_forward_input}
+                                        />
+                                    </div>
+                                    <button
+                                        class="download-button font-bold py-2 px-4 rounded"
+                                        onclick={save_playback_profile}
+                                    >
+                                        {"Confirm"}
+                                    </button>
+                                </div>
+                                <p class="text-xs mt-2">{"Volume boost and Smart Speed only work for podcasts whose host allows cross-origin audio."}</p>
+                            </div>
                             // <div>
                             //     <label for="tag-adjust" class="block mb-2 text-sm font-medium">{"Adjust Tags Associated with this Podcast"}</label>
                             //     <input placeholder="my_S3creT_P@$$" type="password" id="password" name="password" class="search-bar-input border text-sm rounded-lg block w-full p-2.5" required=true />
@@ -859,6 +1055,262 @@ pub fn episode_layout() -> Html {
         fn toggle_description(guid: &str);
     }
 
+    // Hosts, trailers and support links for an added podcast come from what the server kept of
+    // its feed
+    let stored_details: UseStateHandle<Option<PodcastInfo>> = use_state(|| None);
+    {
+        let stored_details = stored_details.clone();
+        let api_key = api_key.clone().flatten();
+        let user_id = user_id.clone();
+        let server_name = server_name.clone();
+        let needs_details = podcast_added
+            && search_state
+                .podcast_feed_results
+                .as_ref()
+                .is_some_and(|results| results.podcast.is_none());
+        use_effect_with(
+            (*podcast_id, needs_details),
+            move |(podcast_id, needs_details)| {
+                stored_details.set(None);
+                if let (true, Some(api_key), Some(user_id), Some(server_name)) = (
+                    *needs_details && *podcast_id != 0,
+                    api_key,
+                    user_id,
+                    server_name,
+                ) {
+                    let podcast_id = *podcast_id;
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match call_get_podcast_details(&server_name, &api_key, user_id, &podcast_id)
+                            .await
+                        {
+                            Ok(details) => stored_details.set(PodcastInfo::from_stored_extras(
+                                details.feedextras.as_deref(),
+                            )),
+                            Err(e) => web_sys::console::log_1(
+                                &format!("Error loading the podcast's details: {}", e).into(),
+                            ),
+                        }
+                    });
+                }
+                || ()
+            },
+        );
+    }
+    let podcast_details = search_state
+        .podcast_feed_results
+        .as_ref()
+        .and_then(|results| results.podcast.clone())
+        .or_else(|| (*stored_details).clone());
+
+    // Trailers aren't episodes, they play like an episode of a podcast that isn't added
+    let on_play_trailer = {
+        let api_key = api_key.clone().flatten();
+        let user_id = user_id.clone();
+        let server_name = server_name.clone();
+        let audio_dispatch = _dispatch.clone();
+        let audio_state = state.clone();
+        let artwork = clicked_podcast_info
+            .as_ref()
+            .map(|info| info.podcast_artwork.clone())
+            .unwrap_or_default();
+        Callback::from(move |trailer: Trailer| {
+            if let (Some(api_key), Some(user_id), Some(server_name)) =
+                (api_key.clone(), user_id, server_name.clone())
+            {
+                on_play_click(
+                    trailer.url,
+                    trailer.title,
+                    artwork.clone(),
+                    0,
+                    0,
+                    Some(0),
+                    api_key,
+                    user_id,
+                    server_name,
+                    audio_dispatch.clone(),
+                    audio_state.clone(),
+                    None,
+                    None,
+                )
+                .emit(MouseEvent::new("click").unwrap());
+            }
+        })
+    };
+
+    // Only the episodes of an added podcast come from the server a page at a time, a feed
+    // that isn't added yet is parsed in full
+    let loading_more = use_state(|| false);
+    let on_load_more = {
+        let dispatch = _dispatch.clone();
+        let search_dispatch = _search_dispatch.clone();
+        let loading_more = loading_more.clone();
+        let api_key = api_key.clone().flatten();
+        let user_id = user_id.clone();
+        let server_name = server_name.clone();
+        let podcast_id = *podcast_id;
+        let (loaded, filter) = search_state
+            .podcast_feed_results
+            .as_ref()
+            .map_or((0, None), |results| {
+                (results.episodes.len(), results.filter.clone())
+            });
+        Callback::from(move |_: ()| {
+            if let (Some(user_id), Some(server_name), Some(filter)) =
+                (user_id, server_name.clone(), filter.clone())
+            {
+                let api_key = api_key.clone();
+                let dispatch = dispatch.clone();
+                let search_dispatch = search_dispatch.clone();
+                let loading_more = loading_more.clone();
+                loading_more.set(true);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let page = Page::after(loaded, EPISODE_PAGE_SIZE);
+                    match call_get_podcast_episodes(
+                        &server_name,
+                        &api_key,
+                        &user_id,
+                        &podcast_id,
+                        &filter,
+                        page,
+                    )
+                    .await
+                    {
+                        Ok(more) => search_dispatch.reduce_mut(move |state| {
+                            // A page for a view that has since been left is dropped
+                            if let Some(results) = state
+                                .podcast_feed_results
+                                .as_mut()
+                                .filter(|results| results.filter == more.filter)
+                            {
+                                let more: Vec<_> = more
+                                    .episodes
+                                    .into_iter()
+                                    .filter(|ep| {
+                                        !results
+                                            .episodes
+                                            .iter()
+                                            .any(|e| e.episode_id == ep.episode_id)
+                                    })
+                                    .collect();
+                                results.episodes.extend(more);
+                            }
+                        }),
+                        Err(e) => dispatch.reduce_mut(|state| {
+                            state.error_message =
+                                Option::from(format!("Failed to load more episodes: {}", e))
+                        }),
+                    }
+                    loading_more.set(false);
+                });
+            }
+        })
+    };
+
+    // Seasons, sorting and filters, remembered per podcast
+    let feed_url = clicked_podcast_info
+        .as_ref()
+        .map(|info| info.podcast_url.clone());
+    let view = use_state(EpisodeView::default);
+    {
+        let view = view.clone();
+        use_effect_with(feed_url.clone(), move |feed_url| {
+            view.set(
+                feed_url
+                    .as_deref()
+                    .map(EpisodeView::load)
+                    .unwrap_or_default(),
+            );
+            || ()
+        });
+    }
+    let on_view_change = {
+        let view = view.clone();
+        let feed_url = feed_url.clone();
+        Callback::from(move |new_view: EpisodeView| {
+            if let Some(feed_url) = &feed_url {
+                new_view.store(feed_url);
+            }
+            view.set(new_view);
+        })
+    };
+    let serial = podcast_details
+        .as_ref()
+        .is_some_and(|info| info.is_serial());
+    // The server sorts and filters an added podcast's episodes, so a new view starts over from
+    // the first page of it
+    {
+        let dispatch = _dispatch.clone();
+        let search_dispatch = _search_dispatch.clone();
+        let api_key = api_key.clone().flatten();
+        let user_id = user_id.clone();
+        let server_name = server_name.clone();
+        let podcast_id = *podcast_id;
+        let wanted = view.filter(serial);
+        let listed = search_state
+            .podcast_feed_results
+            .as_ref()
+            .and_then(|results| results.filter.clone());
+        use_effect_with((wanted, listed), move |(wanted, listed)| {
+            if let (Some(listed), Some(user_id), Some(server_name)) = (listed, user_id, server_name)
+            {
+                if listed != wanted && podcast_id != 0 {
+                    let wanted = wanted.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match call_get_podcast_episodes(
+                            &server_name,
+                            &api_key,
+                            &user_id,
+                            &podcast_id,
+                            &wanted,
+                            Page::first(EPISODE_PAGE_SIZE),
+                        )
+                        .await
+                        {
+                            Ok(first) => search_dispatch.reduce_mut(move |state| {
+                                if let Some(results) = state.podcast_feed_results.as_mut() {
+                                    results.episodes = first.episodes;
+                                    results.filter = first.filter;
+                                }
+                            }),
+                            Err(e) => dispatch.reduce_mut(|state| {
+                                state.error_message =
+                                    Option::from(format!("Failed to load episodes: {}", e))
+                            }),
+                        }
+                    });
+                }
+            }
+            || ()
+        });
+    }
+    // The tabs cover every episode, not just the loaded ones
+    let server_tabs: UseStateHandle<Option<EpisodeTabs>> = use_state(|| None);
+    {
+        let server_tabs = server_tabs.clone();
+        let api_key = api_key.clone().flatten();
+        let user_id = user_id.clone();
+        let server_name = server_name.clone();
+        use_effect_with(*podcast_id, move |podcast_id| {
+            server_tabs.set(None);
+            if let (true, Some(user_id), Some(server_name)) =
+                (*podcast_id != 0, user_id, server_name)
+            {
+                let podcast_id = *podcast_id;
+                wasm_bindgen_futures::spawn_local(async move {
+                    match call_get_podcast_seasons(&server_name, &api_key, user_id, podcast_id)
+                        .await
+                    {
+                        Ok(seasons) => server_tabs.set(Some(seasons.into())),
+                        Err(e) => web_sys::console::log_1(
+                            &format!("Error getting the podcast's seasons: {}", e).into(),
+                        ),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
     html! {
         <div class="main-container">
             <Search_nav />
@@ -953,6 +1405,9 @@ pub fn episode_layout() -> Html {
                                         }
                                     }
                                 </div>
+                                if let Some(info) = podcast_details.clone() {
+                                    <PodcastExtras info={info} on_play_trailer={on_play_trailer.clone()} />
+                                }
 
                             </div>
                         </div>
@@ -964,9 +1419,40 @@ pub fn episode_layout() -> Html {
         }
         {
                 if let Some(results) = podcast_feed_results {
-                    html! {
-                        <div>
-                            { for results.episodes.iter().map(|episode| {
+                    let loaded = results.episodes.len();
+                    let paged = results.filter.is_some();
+                    // Episodes the publisher blocked stay out of the list
+                    let episodes: Vec<_> = results
+                        .episodes
+                        .into_iter()
+                        .filter(|episode| !episode.block)
+                        .collect();
+                    // Episodes from the server come sorted and filtered, only a parsed feed is
+                    // sorted and filtered here
+                    let (tabs, episodes) = if paged {
+                        ((*server_tabs).clone().unwrap_or_default(), episodes)
+                    } else {
+                        (EpisodeTabs::of(&episodes), view.apply(serial, episodes))
+                    };
+                    // Season headings only make sense while the list is in release order
+                    let show_seasons = view.tab == EpisodeTab::All
+                        && matches!(view.sort_for(serial), EpisodeSort::Newest | EpisodeSort::Oldest);
+                    let count = episodes.len();
+                    let episodes = Rc::new(episodes);
+                    let _dispatch = _dispatch.clone();
+                    let _search_dispatch = _search_dispatch.clone();
+                    let search_state = search_state.clone();
+                    let server_name = server_name.clone();
+                    let user_id = user_id.clone();
+                    let api_key = api_key.clone();
+                    let state = state.clone();
+                    let render_episode = Callback::from(move |index: usize| {
+                                let episode = &episodes[index];
+                                let heading = if show_seasons && (index == 0 || episodes[index - 1].season != episode.season) {
+                                    season_heading(episode)
+                                } else {
+                                    html! {}
+                                };
                                 let dispatch = _dispatch.clone();
                                 let search_dispatch = _search_dispatch.clone();
                                 let search_state_clone = search_state.clone(); // Clone search_state
@@ -1034,6 +1520,7 @@ pub fn episode_layout() -> Html {
                                     dispatch.clone(),
                                     state.clone(),
                                     None,
+                                    None,
                                 );
 
                                 let description_class = if is_expanded {
@@ -1051,6 +1538,8 @@ pub fn episode_layout() -> Html {
                                 let episode_url_for_ep_item = episode_url_clone.clone();
                                 let should_show_buttons = !episode_url_for_ep_item.is_empty();
                                 html! {
+                                    <>
+                                    { heading }
                                     <div class="item-container flex items-center mb-4 shadow-md rounded-lg">
                                         <img src={episode.artwork.clone().unwrap_or_default()} alt={format!("Cover for {}", &episode.title.clone().unwrap_or_default())} class="object-cover align-top-cover w-full item-container img"/>
                                         <div class="flex flex-col p-4 space-y-2 flex-grow md:w-7/12">
@@ -1068,6 +1557,7 @@ pub fn episode_layout() -> Html {
                                                     </div>
                                                 }
                                             }
+                                            { episode_badges(episode) }
                                             <span class="episode-time-badge inline-flex items-center px-2.5 py-0.5 rounded me-2">
                                                 <svg class="time-icon w-2.5 h-2.5 me-1.5" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" fill="currentColor" viewBox="0 0 20 20">
                                                     <path d="M10 0a10 10 0 1 0 10 10A10.011 10.011 0 0 0 10 0Zm3.982 13.982a1 1 0 0 1-1.414 0l-3.274-3.274A1.012 1.012 0 0 1 9 10V6a1 1 0 0 1 2 0v3.586l2.982 2.982a1 1 0 0 1 0 1.414Z"/>
@@ -1125,9 +1615,29 @@ pub fn episode_layout() -> Html {
 
 
                                     </div>
+                                    </>
                                 }
-                            })}
-                        </div>
+                            });
+                    html! {
+                        <>
+                        <EpisodeBrowser
+                            view={(*view).clone()}
+                            tabs={tabs}
+                            serial={serial}
+                            show_filters={podcast_added}
+                            on_change={on_view_change.clone()}
+                        />
+                        if count == 0 && !*loading_more {
+                            <p class="item_container-text text-center">{ "No episodes match these filters" }</p>
+                        }
+                        <VirtualList
+                            item_count={count}
+                            render_item={render_episode}
+                            has_more={paged && *podcast_id != 0 && may_have_more(loaded)}
+                            loading_more={*loading_more}
+                            on_load_more={on_load_more.clone()}
+                        />
+                        </>
                     }
                 } else {
                     html! {
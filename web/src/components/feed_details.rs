@@ -0,0 +1,292 @@
+use crate::components::bookmarks::ClipRange;
+use crate::components::gen_funcs::format_time;
+use crate::requests::podcast_ns::{Funding, Location, Person, Trailer, Value};
+use crate::requests::search_pods::{Episode as FeedEpisode, PodcastInfo};
+use yew::prelude::*;
+
+// What the Podcasting 2.0 tags add to the podcast and episode pages
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct PeopleListProps {
+    pub title: String,
+    pub people: Vec<Person>,
+}
+
+#[function_component(PeopleList)]
+pub fn people_list(props: &PeopleListProps) -> Html {
+    if props.people.is_empty() {
+        return html! {};
+    }
+    html! {
+        <div class="feed-section">
+            <p class="item_container-text font-bold">{ &props.title }</p>
+            <div class="feed-people">
+                { for props.people.iter().map(|person| {
+                    let name = match person.href.as_deref().and_then(safe_link) {
+                        Some(href) => html! {
+                            <a href={href} target="_blank" rel="noopener noreferrer" class="link hover:underline">{ &person.name }</a>
+                        },
+                        None => html! { <span>{ &person.name }</span> },
+                    };
+                    html! {
+                        <div class="feed-person">
+                            if let Some(img) = &person.img {
+                                <img src={img.clone()} alt={person.name.clone()} class="feed-person-img" />
+                            } else {
+                                <i class="material-icons feed-person-img">{ "person" }</i>
+                            }
+                            <div class="feed-person-text item_container-text">
+                                { name }
+                                <span class="feed-person-role">{ capitalize(&person.role) }</span>
+                            </div>
+                        </div>
+                    }
+                }) }
+            </div>
+        </div>
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Feed provided links only go in an href when they're plain web links, never javascript: or data:
+fn safe_link(url: &str) -> Option<String> {
+    let url = url.trim();
+    let lower = url.to_ascii_lowercase();
+    if lower.starts_with("https://") || lower.starts_with("http://") {
+        Some(url.to_string())
+    } else {
+        None
+    }
+}
+
+fn funding_links(funding: &[Funding]) -> Html {
+    if !funding
+        .iter()
+        .any(|funding| safe_link(&funding.url).is_some())
+    {
+        return html! {};
+    }
+    html! {
+        <div class="feed-section">
+            <p class="item_container-text font-bold">{ "Support the show" }</p>
+            <div class="feed-links">
+                { for funding.iter().filter_map(|funding| {
+                    let url = safe_link(&funding.url)?;
+                    Some(html! {
+                        <a href={url} target="_blank" rel="noopener noreferrer" class="feed-link">
+                            <i class="material-icons">{ "volunteer_activism" }</i>
+                            { funding.label.clone().unwrap_or_else(|| "Support".to_string()) }
+                        </a>
+                    })
+                }) }
+            </div>
+        </div>
+    }
+}
+
+fn location_line(location: &Option<Location>) -> Html {
+    let location = match location {
+        Some(location) => location,
+        None => return html! {},
+    };
+    let name = match location.map_url().as_deref().and_then(safe_link) {
+        Some(url) => html! {
+            <a href={url} target="_blank" rel="noopener noreferrer" class="link hover:underline">{ &location.name }</a>
+        },
+        None => html! { <span>{ &location.name }</span> },
+    };
+    html! {
+        <p class="header-text feed-location">
+            <i class="material-icons">{ "place" }</i>
+            { name }
+        </p>
+    }
+}
+
+fn value_recipients(value: &Option<Value>) -> Html {
+    let value = match value {
+        Some(value) if !value.recipients.is_empty() => value,
+        _ => return html! {},
+    };
+    let total: u32 = value
+        .recipients
+        .iter()
+        .map(|recipient| recipient.split)
+        .sum();
+    html! {
+        <div class="feed-section">
+            <p class="item_container-text font-bold">{ format!("Value for value ({})", value.kind) }</p>
+            <ul class="feed-value item_container-text text-sm">
+                { for value.recipients.iter().filter(|recipient| !recipient.fee).map(|recipient| {
+                    let share = if total > 0 {
+                        format!("{}%", recipient.split * 100 / total)
+                    } else {
+                        String::new()
+                    };
+                    html! {
+                        <li>
+                            <span>{ recipient.name.clone().unwrap_or_else(|| "Unnamed recipient".to_string()) }</span>
+                            <span class="feed-value-split">{ share }</span>
+                        </li>
+                    }
+                }) }
+            </ul>
+        </div>
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct PodcastExtrasProps {
+    pub info: PodcastInfo,
+    pub on_play_trailer: Callback<Trailer>,
+}
+
+// Hosts, credits, support links and trailers for the podcast header
+#[function_component(PodcastExtras)]
+pub fn podcast_extras(props: &PodcastExtrasProps) -> Html {
+    let info = &props.info;
+    let hosts: Vec<Person> = info.hosts().into_iter().cloned().collect();
+    let hosts_title = if hosts.len() == 1 { "Host" } else { "Hosts" };
+    let credits: Vec<Person> = info
+        .persons
+        .iter()
+        .filter(|person| !person.is_host())
+        .cloned()
+        .collect();
+
+    html! {
+        <div class="feed-extras">
+            { location_line(&info.location) }
+            if info.locked {
+                <p class="header-text">
+                    <i class="material-icons">{ "lock" }</i>
+                    { "The publisher has locked this feed against moving to another host" }
+                </p>
+            }
+            if !info.trailers.is_empty() {
+                <div class="feed-links">
+                    { for info.trailers.iter().map(|trailer| {
+                        let on_click = {
+                            let on_play_trailer = props.on_play_trailer.clone();
+                            let trailer = trailer.clone();
+                            Callback::from(move |_: MouseEvent| on_play_trailer.emit(trailer.clone()))
+                        };
+                        html! {
+                            <button onclick={on_click} class="feed-link">
+                                <i class="material-icons">{ "play_circle" }</i>
+                                { match trailer.season {
+                                    Some(season) => format!("{} (Season {})", trailer.title, season),
+                                    None => trailer.title.clone(),
+                                } }
+                            </button>
+                        }
+                    }) }
+                </div>
+            }
+            <PeopleList title={hosts_title} people={hosts} />
+            <PeopleList title="Credits" people={credits} />
+            { funding_links(&info.funding) }
+            { value_recipients(&info.value) }
+        </div>
+    }
+}
+
+// The season and episode number and the trailer or bonus marker for an episode row
+pub fn episode_badges(episode: &FeedEpisode) -> Html {
+    let number = episode.number_label();
+    if number.is_none() && !episode.is_trailer() && !episode.is_bonus() {
+        return html! {};
+    }
+    html! {
+        <div class="feed-badges">
+            if let Some(number) = number {
+                <span class="feed-badge">{ number }</span>
+            }
+            if episode.is_trailer() {
+                <span class="feed-badge feed-badge-accent">{ "Trailer" }</span>
+            }
+            if episode.is_bonus() {
+                <span class="feed-badge feed-badge-accent">{ "Bonus" }</span>
+            }
+        </div>
+    }
+}
+
+// Shown above the first episode of each season in a list
+pub fn season_heading(episode: &FeedEpisode) -> Html {
+    match (episode.season, &episode.season_name) {
+        (Some(season), Some(name)) => html! {
+            <h3 class="season-heading item_container-text">{ format!("Season {}: {}", season, name) }</h3>
+        },
+        (Some(season), None) => html! {
+            <h3 class="season-heading item_container-text">{ format!("Season {}", season) }</h3>
+        },
+        _ => html! {},
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct EpisodeExtrasProps {
+    pub episode: FeedEpisode,
+    // Podcast level details, used where the episode has none of its own
+    #[prop_or_default]
+    pub podcast: Option<PodcastInfo>,
+    pub on_play_clip: Callback<ClipRange>,
+}
+
+// People, soundbites, place and support links for the episode page
+#[function_component(EpisodeExtras)]
+pub fn episode_extras(props: &EpisodeExtrasProps) -> Html {
+    let episode = &props.episode;
+    let podcast = props.podcast.clone().unwrap_or_default();
+    // Episode level people replace the podcast's, hosts usually aren't repeated on every item
+    let people = if episode.persons.is_empty() {
+        podcast.persons.clone()
+    } else {
+        episode.persons.clone()
+    };
+    let value = episode.value.clone().or(podcast.value.clone());
+
+    html! {
+        <div class="feed-extras">
+            { episode_badges(episode) }
+            if let Some(name) = &episode.season_name {
+                <p class="header-text">{ name }</p>
+            }
+            { location_line(&episode.location) }
+            <PeopleList title="People" people={people} />
+            if !episode.soundbites.is_empty() {
+                <div class="feed-section">
+                    <p class="item_container-text font-bold">{ "Soundbites" }</p>
+                    <div class="feed-links">
+                        { for episode.soundbites.iter().map(|soundbite| {
+                            let clip = ClipRange {
+                                start: soundbite.start_time,
+                                end: Some(soundbite.start_time + soundbite.duration),
+                            };
+                            let on_click = {
+                                let on_play_clip = props.on_play_clip.clone();
+                                Callback::from(move |_: MouseEvent| on_play_clip.emit(clip))
+                            };
+                            html! {
+                                <button onclick={on_click} class="feed-link">
+                                    <i class="material-icons">{ "play_circle" }</i>
+                                    { soundbite.title.clone().unwrap_or_else(|| format_time(soundbite.start_time)) }
+                                </button>
+                            }
+                        }) }
+                    </div>
+                </div>
+            }
+            { funding_links(&podcast.funding) }
+            { value_recipients(&value) }
+        </div>
+    }
+}
@@ -1,15 +1,14 @@
 use crate::components::context::{AppState, UIState};
 #[cfg(not(feature = "server_build"))]
-use crate::components::downloads_tauri::{
-    download_file, remove_episode_from_local_db, update_local_database, update_podcast_database,
-};
+use crate::components::downloads_tauri::{queue_episode_download, remove_episode_from_local_db};
 use crate::components::episodes_layout::SafeHtml;
 use crate::components::gen_funcs::format_time;
+use crate::components::queue::queue_episode_at;
 use crate::requests::pod_req::{
     call_download_episode, call_mark_episode_completed, call_mark_episode_uncompleted,
-    call_queue_episode, call_remove_downloaded_episode, call_remove_queued_episode,
-    call_remove_saved_episode, call_save_episode, DownloadEpisodeRequest, Episode, EpisodeDownload,
-    HistoryEpisode, MarkEpisodeCompletedRequest, QueuePodcastRequest, QueuedEpisode,
+    call_remove_downloaded_episode, call_remove_queued_episode, call_remove_saved_episode,
+    call_save_episode, DownloadEpisodeRequest, Episode, EpisodeDownload, HistoryEpisode,
+    MarkEpisodeCompletedRequest, Page, QueuePodcastRequest, QueuePositionRequest, QueuedEpisode,
     SavePodcastRequest, SavedEpisode,
 };
 #[cfg(not(feature = "server_build"))]
@@ -19,13 +18,17 @@ use crate::requests::pod_req::{
 use crate::requests::search_pods::Episode as SearchNewEpisode;
 use crate::requests::search_pods::SearchEpisode;
 use crate::requests::search_pods::{call_get_podcast_info, test_connection};
+use gloo_events::EventListener;
 use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::rc::Rc;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
-use web_sys::{console, window, HtmlInputElement, MouseEvent};
+use web_sys::{console, window, Element, HtmlInputElement, MouseEvent};
 use yew::prelude::*;
 use yew::Callback;
 use yew_router::history::{BrowserHistory, History};
@@ -54,6 +57,237 @@ pub fn use_scroll_to_top() -> Html {
     html! {}
 }
 
+// How many episodes the paginated lists load at a time
+pub const EPISODE_PAGE_SIZE: usize = 50;
+
+// A list loaded in whole pages may have more after it, a short last page means it doesn't.
+// When the total is an exact multiple one extra empty page gets asked for.
+pub fn may_have_more(loaded: usize) -> bool {
+    loaded > 0 && loaded % EPISODE_PAGE_SIZE == 0
+}
+
+// Refreshing a list keeps as many rows as were already loaded, so the scroll position holds
+pub fn refresh_page(loaded: usize) -> Page {
+    Page::first(loaded.max(EPISODE_PAGE_SIZE))
+}
+
+// Rows drawn past the edges of the screen, so a quick scroll doesn't show blank space
+const VIRTUAL_LIST_OVERSCAN_PX: f64 = 1000.0;
+// How close to the end of the loaded rows the next page is asked for
+const VIRTUAL_LIST_LOAD_MORE_PX: f64 = 2000.0;
+// Drawn before anything has been measured
+const VIRTUAL_LIST_INITIAL_ROWS: usize = 20;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct VirtualListProps {
+    pub item_count: usize,
+    pub render_item: Callback<usize, Html>,
+    // Assumed for rows that haven't been drawn yet
+    #[prop_or(200.0)]
+    pub estimated_row_height: f64,
+    // Asked for the next page once the user scrolls near the end
+    #[prop_or_default]
+    pub on_load_more: Option<Callback<()>>,
+    #[prop_or_default]
+    pub has_more: bool,
+    #[prop_or_default]
+    pub loading_more: bool,
+}
+
+struct VirtualListState {
+    // Measured heights by row index
+    heights: HashMap<usize, f64>,
+    estimate: f64,
+    range: Option<Range<usize>>,
+    // The item count the last page was asked for at, so one scroll doesn't ask twice
+    requested_at: Option<usize>,
+}
+
+impl VirtualListState {
+    fn height(&self, index: usize) -> f64 {
+        self.heights.get(&index).copied().unwrap_or(self.estimate)
+    }
+
+    fn total(&self, rows: Range<usize>) -> f64 {
+        rows.map(|index| self.height(index)).sum()
+    }
+
+    // Records the heights of the rows on screen. Rows not drawn yet are assumed to be as tall
+    // as the average drawn one.
+    fn measure(&mut self, list: &Element) {
+        let rows = match list.query_selector_all(":scope > .virtual-list-row") {
+            Ok(rows) => rows,
+            Err(_) => return,
+        };
+        for i in 0..rows.length() {
+            let row = match rows
+                .item(i)
+                .and_then(|node| node.dyn_into::<Element>().ok())
+            {
+                Some(row) => row,
+                None => continue,
+            };
+            let index = row
+                .get_attribute("data-index")
+                .and_then(|index| index.parse::<usize>().ok());
+            if let Some(index) = index {
+                self.heights
+                    .insert(index, row.get_bounding_client_rect().height());
+            }
+        }
+        if !self.heights.is_empty() {
+            self.estimate = self.heights.values().sum::<f64>() / self.heights.len() as f64;
+        }
+    }
+
+    // The rows overlapping the screen, and whether the end of the list is close
+    fn visible(&self, list: &Element, item_count: usize) -> (Range<usize>, bool) {
+        let viewport = window()
+            .and_then(|window| window.inner_height().ok())
+            .and_then(|height| height.as_f64())
+            .unwrap_or(800.0);
+        let scrolled = -list.get_bounding_client_rect().top();
+        let from = scrolled - VIRTUAL_LIST_OVERSCAN_PX;
+        let to = scrolled + viewport + VIRTUAL_LIST_OVERSCAN_PX;
+
+        let mut start = None;
+        let mut end = item_count;
+        let mut offset = 0.0;
+        for index in 0..item_count {
+            if offset > to {
+                end = index;
+                break;
+            }
+            let height = self.height(index);
+            if start.is_none() && offset + height >= from {
+                start = Some(index);
+            }
+            offset += height;
+        }
+        let start = start.unwrap_or(end).min(end);
+        let near_end = scrolled + viewport + VIRTUAL_LIST_LOAD_MORE_PX >= self.total(0..item_count);
+        (start..end, near_end)
+    }
+}
+
+// Works out which rows to draw after a scroll, resize or render, and asks for the next page
+// when the end is close
+fn refresh_virtual_list(
+    list_ref: &NodeRef,
+    state: &RefCell<VirtualListState>,
+    props: &VirtualListProps,
+    redraw: &UseForceUpdateHandle,
+) {
+    let list = match list_ref.cast::<Element>() {
+        Some(list) => list,
+        None => return,
+    };
+    // Redrawing or loading can render straight away, so the state is let go of first
+    let (moved, load_more) = {
+        let mut state = state.borrow_mut();
+        state.measure(&list);
+        let (visible, near_end) = state.visible(&list, props.item_count);
+        let moved = state.range.as_ref() != Some(&visible);
+        state.range = Some(visible);
+        let load_more = near_end
+            && props.has_more
+            && !props.loading_more
+            && state.requested_at != Some(props.item_count);
+        if load_more {
+            state.requested_at = Some(props.item_count);
+        }
+        (moved, load_more)
+    };
+    if moved {
+        redraw.force_update();
+    }
+    if load_more {
+        if let Some(on_load_more) = &props.on_load_more {
+            on_load_more.emit(());
+        }
+    }
+}
+
+// A list that only puts the rows near the screen in the DOM, with spacers standing in for the
+// rest. Rows can be any height, they're measured once drawn.
+#[function_component(VirtualList)]
+pub fn virtual_list(props: &VirtualListProps) -> Html {
+    let list_ref = use_node_ref();
+    let state = {
+        let estimate = props.estimated_row_height;
+        use_mut_ref(move || VirtualListState {
+            heights: HashMap::new(),
+            estimate,
+            range: None,
+            requested_at: None,
+        })
+    };
+    let redraw = use_force_update();
+    // The listeners outlive this render, they read the props from here
+    let latest_props = use_mut_ref(|| props.clone());
+    *latest_props.borrow_mut() = props.clone();
+
+    {
+        let list_ref = list_ref.clone();
+        let state = state.clone();
+        let latest_props = latest_props.clone();
+        let redraw = redraw.clone();
+        use_effect_with((), move |_| {
+            let window = window().unwrap();
+            let listeners: Vec<EventListener> = ["scroll", "resize"]
+                .into_iter()
+                .map(|event| {
+                    let list_ref = list_ref.clone();
+                    let state = state.clone();
+                    let latest_props = latest_props.clone();
+                    let redraw = redraw.clone();
+                    EventListener::new(&window, event, move |_| {
+                        let props = latest_props.borrow().clone();
+                        refresh_virtual_list(&list_ref, &state, &props, &redraw);
+                    })
+                })
+                .collect();
+            move || drop(listeners)
+        });
+    }
+    {
+        // Rows just drawn may not be the height that was guessed
+        let list_ref = list_ref.clone();
+        let state = state.clone();
+        let props = props.clone();
+        let redraw = redraw.clone();
+        use_effect(move || {
+            refresh_virtual_list(&list_ref, &state, &props, &redraw);
+            || ()
+        });
+    }
+
+    let count = props.item_count;
+    let state = state.borrow();
+    let range = state
+        .range
+        .clone()
+        .unwrap_or(0..count.min(VIRTUAL_LIST_INITIAL_ROWS));
+    let (start, end) = (range.start.min(count), range.end.min(count));
+    let above = state.total(0..start);
+    let below = state.total(end..count);
+
+    html! {
+        <div class="virtual-list" ref={list_ref}>
+            <div style={format!("height: {}px;", above)}></div>
+            { for (start..end).map(|index| html! {
+                <div class="virtual-list-row" key={index} data-index={index.to_string()}>
+                    { props.render_item.emit(index) }
+                </div>
+            }) }
+            <div style={format!("height: {}px;", below)}></div>
+            if props.loading_more {
+                <div class="virtual-list-loading">{ "Loading more episodes..." }</div>
+            }
+        </div>
+    }
+}
+
 #[function_component(ErrorMessage)]
 pub fn error_message(props: &ErrorMessageProps) -> Html {
     // Your existing logic here...
@@ -416,39 +650,48 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let queue_api_key = api_key.clone();
     let queue_server_name = server_name.clone();
     let queue_post = audio_dispatch.clone();
-    // let server_name = server_name.clone();
-    let on_add_to_queue = {
-        let episode = props.episode.clone();
+    let queue_app_dispatch = post_dispatch.clone();
+    // Puts the episode straight after whatever is playing, or at the front of the queue
+    let on_play_next = {
+        let episode_id = props.episode.get_episode_id();
+        let server_name = queue_server_name.clone();
+        let api_key = queue_api_key.clone();
+        let queue_post = queue_post.clone();
+        let app_dispatch = queue_app_dispatch.clone();
         Callback::from(move |_| {
-            let server_name_copy = queue_server_name.clone();
-            let api_key_copy = queue_api_key.clone();
-            let queue_post = queue_post.clone();
-            let request = QueuePodcastRequest {
-                episode_id: episode.get_episode_id(),
-                user_id: user_id.unwrap(), // replace with the actual user ID
-            };
-            let server_name = server_name_copy; // replace with the actual server name
-            let api_key = api_key_copy; // replace with the actual API key
-            let future = async move {
-                // let _ = call_queue_episode(&server_name.unwrap(), &api_key.flatten(), &request).await;
-                // queue_post.reduce_mut(|state| state.info_message = Option::from(format!("Episode added to Queue!")));
-                match call_queue_episode(&server_name.unwrap(), &api_key.flatten(), &request).await
-                {
-                    Ok(success_message) => {
-                        queue_post.reduce_mut(|state| {
-                            state.info_message = Option::from(format!("{}", success_message))
-                        });
-                    }
-                    Err(e) => {
-                        queue_post.reduce_mut(|state| {
-                            state.error_message = Option::from(format!("{}", e))
-                        });
-                        // Handle error, e.g., display the error message
-                    }
-                }
-            };
-            wasm_bindgen_futures::spawn_local(future);
-            // dropdown_open.set(false);
+            // Offline episodes aren't in the server's queue so there's nothing to follow
+            let playing_episode_id = queue_post
+                .get()
+                .currently_playing
+                .as_ref()
+                .filter(|props| !props.offline && props.episode_id != episode_id)
+                .map(|props| props.episode_id);
+            if let (Some(server_name), Some(user_id)) = (server_name.clone(), user_id) {
+                queue_episode_at(
+                    QueuePositionRequest::play_next(episode_id, user_id, playing_episode_id),
+                    server_name,
+                    api_key.clone().flatten(),
+                    app_dispatch.clone(),
+                    queue_post.clone(),
+                    Some("Episode will play next".to_string()),
+                );
+            }
+        })
+    };
+
+    let on_play_last = {
+        let episode_id = props.episode.get_episode_id();
+        Callback::from(move |_| {
+            if let (Some(server_name), Some(user_id)) = (queue_server_name.clone(), user_id) {
+                queue_episode_at(
+                    QueuePositionRequest::play_last(episode_id, user_id),
+                    server_name,
+                    queue_api_key.clone().flatten(),
+                    queue_app_dispatch.clone(),
+                    queue_post.clone(),
+                    Some("Episode added to the end of the Queue".to_string()),
+                );
+            }
         })
     };
 
@@ -652,43 +895,13 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
             let future = async move {
                 match call_get_episode_metadata(&server_name, ep_api_key, &request).await {
                     Ok(episode_info) => {
-                        let audio_url = episode_info.episodeurl.clone();
-                        let artwork_url = episode_info.episodeartwork.clone();
-                        let podcast_id = episode_info.podcastid.clone();
-                        let filename = format!("episode_{}.mp3", episode_id);
-                        let artwork_filename = format!("artwork_{}.jpg", episode_id);
-                        post_state.reduce_mut(|state| {
-                            state.info_message = Some(format!("Episode download queued!"))
-                        });
-                        // Download audio
-                        match download_file(audio_url, filename.clone()).await {
-                            Ok(_) => {}
-                            Err(e) => {
-                                post_state.reduce_mut(|state| {
-                                    state.error_message =
-                                        Some(format!("Failed to download episode audio: {:?}", e))
-                                });
-                            }
-                        }
+                        let podcast_id = episode_info.podcastid;
+                        let expected_size = episode_info
+                            .enclosurelength
+                            .and_then(|length| u64::try_from(length).ok());
 
-                        // Download artwork
-                        if let Err(e) = download_file(artwork_url, artwork_filename.clone()).await {
-                            post_state.reduce_mut(|state| {
-                                state.error_message =
-                                    Some(format!("Failed to download episode artwork: {:?}", e))
-                            });
-                        }
-
-                        // Update local JSON database
-                        if let Err(e) = update_local_database(episode_info).await {
-                            post_state.reduce_mut(|state| {
-                                state.error_message =
-                                    Some(format!("Failed to update local database: {:?}", e))
-                            });
-                        }
-
-                        // Fetch and update local podcast metadata
-                        match call_get_podcast_details(
+                        // The podcast is stored alongside the episode once it's downloaded
+                        let podcast_details = match call_get_podcast_details(
                             &server_name,
                             &api_key.unwrap(),
                             user_id_copy.unwrap(),
@@ -696,22 +909,27 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
                         )
                         .await
                         {
-                            Ok(podcast_details) => {
-                                if let Err(e) = update_podcast_database(podcast_details).await {
-                                    post_state.reduce_mut(|state| {
-                                        state.error_message = Some(format!(
-                                            "Failed to update podcast database: {:?}",
-                                            e
-                                        ))
-                                    });
-                                }
-                            }
+                            Ok(podcast_details) => podcast_details,
                             Err(e) => {
                                 post_state.reduce_mut(|state| {
                                     state.error_message =
                                         Some(format!("Failed to fetch podcast metadata: {:?}", e))
                                 });
+                                return;
                             }
+                        };
+
+                        // The app's queue takes it from here, progress shows on the downloads page
+                        match queue_episode_download(episode_info, podcast_details, expected_size)
+                            .await
+                        {
+                            Ok(()) => post_state.reduce_mut(|state| {
+                                state.info_message = Some(format!("Episode download queued!"))
+                            }),
+                            Err(e) => post_state.reduce_mut(|state| {
+                                state.error_message =
+                                    Some(format!("Failed to queue episode download: {:?}", e))
+                            }),
                         }
                     }
                     Err(e) => {
@@ -735,14 +953,12 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
             let episode_id = episode.get_episode_id();
 
             let future = async move {
-                let filename = format!("episode_{}.mp3", episode_id);
-
                 // Download audio
                 match remove_episode_from_local_db(episode_id).await {
                     Ok(_) => {
                         post_state.reduce_mut(|state| {
                             state.info_message =
-                                Some(format!("Episode {} downloaded locally!", filename));
+                                Some(format!("Episode {} removed locally!", episode_id));
                             if let Some(increment) = state.local_download_increment.as_mut() {
                                 *increment += 1;
                             } else {
@@ -970,7 +1186,8 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     #[cfg(not(feature = "server_build"))]
     let local_download_options = html! {
         <>
-            <li class="dropdown-option" onclick={on_add_to_queue.clone()}>{ "Queue Episode" }</li>
+            <li class="dropdown-option" onclick={on_play_next.clone()}>{ "Play Next" }</li>
+                <li class="dropdown-option" onclick={on_play_last.clone()}>{ "Play Last" }</li>
             <li class="dropdown-option" onclick={on_save_episode.clone()}>{ "Save Episode" }</li>
             <li class="dropdown-option" onclick={on_remove_locally_downloaded_episode.clone()}>{ "Remove Downloaded Episode" }</li>
             <li class="dropdown-option" onclick={on_toggle_complete.clone()}>{ if is_completed { "Mark Episode Incomplete" } else { "Mark Episode Complete" } }</li>
@@ -983,7 +1200,8 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
     let action_buttons = match props.page_type.as_str() {
         "saved" => html! {
             <>
-                <li class="dropdown-option" onclick={on_add_to_queue.clone()}>{ "Queue Episode" }</li>
+                <li class="dropdown-option" onclick={on_play_next.clone()}>{ "Play Next" }</li>
+                <li class="dropdown-option" onclick={on_play_last.clone()}>{ "Play Last" }</li>
                 <li class="dropdown-option" onclick={on_remove_saved_episode.clone()}>{ "Remove Saved Episode" }</li>
                 {
                     download_button.clone()
@@ -993,6 +1211,8 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
         },
         "queue" => html! {
             <>
+                <li class="dropdown-option" onclick={on_play_next.clone()}>{ "Play Next" }</li>
+                <li class="dropdown-option" onclick={on_play_last.clone()}>{ "Move to End of Queue" }</li>
                 <li class="dropdown-option" onclick={on_save_episode.clone()}>{ "Save Episode" }</li>
                 <li class="dropdown-option" onclick={on_remove_queued_episode.clone()}>{ "Remove from Queue" }</li>
                 {
@@ -1003,7 +1223,8 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
         },
         "downloads" => html! {
             <>
-                <li class="dropdown-option" onclick={on_add_to_queue.clone()}>{ "Queue Episode" }</li>
+                <li class="dropdown-option" onclick={on_play_next.clone()}>{ "Play Next" }</li>
+                <li class="dropdown-option" onclick={on_play_last.clone()}>{ "Play Last" }</li>
                 <li class="dropdown-option" onclick={on_save_episode.clone()}>{ "Save Episode" }</li>
                 <li class="dropdown-option" onclick={on_remove_downloaded_episode.clone()}>{ "Remove Downloaded Episode" }</li>
                 <li class="dropdown-option" onclick={on_toggle_complete.clone()}>{ if is_completed { "Mark Episode Incomplete" } else { "Mark Episode Complete" } }</li>
@@ -1016,7 +1237,8 @@ pub fn context_button(props: &ContextButtonProps) -> Html {
         _ => html! {
             // Default set of buttons for other page types
             <>
-                <li class="dropdown-option" onclick={on_add_to_queue.clone()}>{ "Queue Episode" }</li>
+                <li class="dropdown-option" onclick={on_play_next.clone()}>{ "Play Next" }</li>
+                <li class="dropdown-option" onclick={on_play_last.clone()}>{ "Play Last" }</li>
                 <li class="dropdown-option" onclick={on_save_episode.clone()}>{ "Save Episode" }</li>
                 {
                     download_button.clone()
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use ammonia::Builder;
 use web_sys::{DomParser, SupportedType};
 use wasm_bindgen::JsCast;
@@ -9,7 +9,7 @@ use argon2::{
     },
     Argon2
 };
-use chrono::{DateTime, NaiveDateTime, Utc, TimeZone};
+use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
 use std::str::FromStr;
 
@@ -47,11 +47,10 @@ pub fn match_date_format(date_format: Option<&str>) -> DateFormat {
 }
 
 pub fn parse_date(date_str: &str, user_tz: &Option<String>) -> DateTime<Tz> {
-    let naive_datetime = NaiveDateTime::parse_from_str(date_str, "%a, %d %b %Y %H:%M:%S %z")
-        .or_else(|_| NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S"))
-        .unwrap_or_else(|_| Utc::now().naive_utc());
-
-    let datetime_utc = Utc.from_utc_datetime(&naive_datetime);
+    // Feeds write dates every which way, the zone they give is kept instead of dropped
+    let datetime_utc = pinepods_client::parse_feed_date(date_str)
+        .map(|date| date.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
     let tz: Tz = user_tz.as_ref().and_then(|tz| Tz::from_str(tz).ok()).unwrap_or_else(|| chrono_tz::UTC);
     datetime_utc.with_timezone(&tz)
 }
@@ -174,7 +173,15 @@ pub fn validate_email(email: &str) -> Vec<ValidationError> {
 }
 
 
-pub fn parse_opml(opml_content: &str) -> Vec<(String, String)> {
+// A feed from an OPML file, with the folders it was nested in, outermost first
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpmlFeed {
+    pub title: String,
+    pub xml_url: String,
+    pub folders: Vec<String>,
+}
+
+pub fn parse_opml(opml_content: &str) -> Vec<OpmlFeed> {
     let parser = DomParser::new().unwrap();
     let doc = parser.parse_from_string(opml_content, SupportedType::TextXml)
         .unwrap()
@@ -182,17 +189,52 @@ pub fn parse_opml(opml_content: &str) -> Vec<(String, String)> {
         .unwrap();
 
     let mut podcasts = Vec::new();
-    let outlines = doc.query_selector_all("outline").unwrap();
-    for i in 0..outlines.length() {
-        if let Some(outline) = outlines.item(i).and_then(|o| o.dyn_into::<web_sys::Element>().ok()) {
-            let title = outline.get_attribute("title").unwrap_or_default();
-            let xml_url = outline.get_attribute("xmlUrl").unwrap_or_default();
-            podcasts.push((title, xml_url));
-        }
+    if let Some(root) = doc.document_element() {
+        collect_outlines(&root, &[], &mut podcasts);
     }
+    // Exports from some apps list a feed once per folder it's in, the first one wins
+    let mut seen = HashSet::new();
+    podcasts.retain(|feed: &OpmlFeed| seen.insert(feed.xml_url.clone()));
     podcasts
 }
 
+// Outlines without an xmlUrl are folders, everything nested in them belongs to that folder
+fn collect_outlines(parent: &web_sys::Element, folders: &[String], podcasts: &mut Vec<OpmlFeed>) {
+    let mut child = parent.first_element_child();
+    while let Some(element) = child {
+        if !element.tag_name().eq_ignore_ascii_case("outline") {
+            collect_outlines(&element, folders, podcasts);
+        } else {
+            let title = element.get_attribute("title")
+                .filter(|title| !title.trim().is_empty())
+                .or_else(|| element.get_attribute("text"))
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let xml_url = element.get_attribute("xmlUrl").unwrap_or_default().trim().to_string();
+            if xml_url.is_empty() {
+                let mut nested = folders.to_vec();
+                if !title.is_empty() {
+                    nested.push(title);
+                }
+                collect_outlines(&element, &nested, podcasts);
+            } else {
+                // OPML 2.0 can also name categories as comma separated paths, "/Tech/News"
+                let mut feed_folders = folders.to_vec();
+                for category in element.get_attribute("category").unwrap_or_default().split(',') {
+                    for name in category.split('/').map(str::trim).filter(|name| !name.is_empty()) {
+                        if !feed_folders.iter().any(|folder| folder == name) {
+                            feed_folders.push(name.to_string());
+                        }
+                    }
+                }
+                podcasts.push(OpmlFeed { title, xml_url, folders: feed_folders });
+            }
+        }
+        child = element.next_element_sibling();
+    }
+}
+
 pub fn format_time(time_in_seconds: f64) -> String {
     let hours = (time_in_seconds / 3600.0).floor() as i32;
     let minutes = ((time_in_seconds % 3600.0) / 60.0).floor() as i32;
@@ -207,25 +249,7 @@ pub fn format_time_mins(time_in_minutes: i32) -> String {
     format!("{:02}:{:02}", hours, minutes)
 }
 
+// Takes the fractional, overflowing and ISO 8601 durations feeds write too, see parse_duration
 pub fn convert_time_to_seconds(time: &str) -> Result<u32, Box<dyn std::error::Error>> {
-    let parts: Vec<&str> = time.split(':').collect();
-
-    match parts.len() {
-        3 => {
-            let hours: u32 = parts[0].parse()?;
-            let minutes: u32 = parts[1].parse()?;
-            let seconds: u32 = parts[2].parse()?;
-            Ok(hours * 3600 + minutes * 60 + seconds)
-        }
-        2 => {
-            let minutes: u32 = parts[0].parse()?;
-            let seconds: u32 = parts[1].parse()?;
-            Ok(minutes * 60 + seconds)
-        }
-        1 => {
-            let seconds: u32 = parts[0].parse()?;
-            Ok(seconds)
-        }
-        _ => Err("Invalid time format".into()),
-    }
+    pinepods_client::parse_duration(time).ok_or_else(|| format!("Invalid time format: {}", time).into())
 }
\ No newline at end of file
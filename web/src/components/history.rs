@@ -1,6 +1,7 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::{
-    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop,
+    empty_message, episode_item, may_have_more, on_shownotes_click, refresh_page, Search_nav,
+    UseScrollToTop, VirtualList, EPISODE_PAGE_SIZE,
 };
 use crate::components::audio::on_play_click;
 use crate::components::audio::AudioPlayer;
@@ -10,8 +11,10 @@ use crate::components::gen_funcs::{
     format_datetime, match_date_format, parse_date, sanitize_html_with_blank_target,
     truncate_description,
 };
+use crate::requests::cache::{self, CachedList};
 use crate::requests::login_requests::use_check_authentication;
-use crate::requests::pod_req::{self, HistoryDataResponse};
+use crate::requests::pod_req::{self, HistoryDataResponse, HistoryEpisode, Page};
+use std::rc::Rc;
 use yew::prelude::*;
 use yew::{function_component, html, Html};
 use yew_router::history::BrowserHistory;
@@ -31,7 +34,8 @@ pub fn history() -> Html {
     let dropdown_open = use_state(|| false);
     let session_dispatch = _post_dispatch.clone();
     let session_state = post_state.clone();
-    let loading = use_state(|| true);
+    // A copy already in AppState shows straight away while it refreshes
+    let loading = use_state(|| post_state.episode_history.is_none());
 
     use_effect_with((), move |_| {
         // Check if the page reload action has already occurred to prevent redundant execution
@@ -91,6 +95,9 @@ pub fn history() -> Html {
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
+        let has_cached = post_state.episode_history.is_some();
+        let loaded = loaded_episodes(&post_state);
+
         use_effect_with(
             (api_key.clone(), user_id.clone(), server_name.clone()),
             move |_| {
@@ -101,7 +108,29 @@ pub fn history() -> Html {
                     let dispatch = effect_dispatch.clone();
 
                     wasm_bindgen_futures::spawn_local(async move {
-                        match pod_req::call_get_user_history(&server_name, &api_key, &user_id).await
+                        if has_cached && cache::is_fresh(&server_name, user_id, CachedList::History)
+                        {
+                            dispatch.reduce_mut(|state| {
+                                state.completed_episodes =
+                                    state.episode_history.as_ref().map(|history| {
+                                        history
+                                            .data
+                                            .iter()
+                                            .filter(|ep| ep.completed)
+                                            .map(|ep| ep.episodeid)
+                                            .collect()
+                                    });
+                            });
+                            loading_ep.set(false);
+                            return;
+                        }
+                        match pod_req::call_get_user_history(
+                            &server_name,
+                            &api_key,
+                            &user_id,
+                            refresh_page(loaded),
+                        )
+                        .await
                         {
                             Ok(fetched_episodes) => {
                                 let completed_episode_ids: Vec<i32> = fetched_episodes
@@ -115,6 +144,7 @@ pub fn history() -> Html {
                                     });
                                     state.completed_episodes = Some(completed_episode_ids);
                                 });
+                                cache::mark_fetched(&server_name, user_id, CachedList::History);
                                 loading_ep.set(false);
                             }
                             Err(e) => {
@@ -129,6 +159,62 @@ pub fn history() -> Html {
         );
     }
 
+    let loading_more = use_state(|| false);
+    let on_load_more = {
+        let dispatch = dispatch.clone();
+        let error = error.clone();
+        let loading_more = loading_more.clone();
+        let api_key = state
+            .auth_details
+            .as_ref()
+            .and_then(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let loaded = loaded_episodes(&state);
+        Callback::from(move |_| {
+            if let (Some(api_key), Some(user_id), Some(server_name)) =
+                (api_key.clone(), user_id, server_name.clone())
+            {
+                let dispatch = dispatch.clone();
+                let error = error.clone();
+                let loading_more = loading_more.clone();
+                loading_more.set(true);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let page = Page::after(loaded, EPISODE_PAGE_SIZE);
+                    match pod_req::call_get_user_history(
+                        &server_name,
+                        &Some(api_key),
+                        &user_id,
+                        page,
+                    )
+                    .await
+                    {
+                        Ok(more) => dispatch.reduce_mut(move |state| {
+                            let episodes = &mut state
+                                .episode_history
+                                .get_or_insert(HistoryDataResponse { data: Vec::new() })
+                                .data;
+                            // Listening to something moves it to the top and shifts the pages
+                            let more: Vec<HistoryEpisode> = more
+                                .into_iter()
+                                .filter(|ep| !episodes.iter().any(|e| e.episodeid == ep.episodeid))
+                                .collect();
+                            let completed =
+                                more.iter().filter(|ep| ep.completed).map(|ep| ep.episodeid);
+                            state
+                                .completed_episodes
+                                .get_or_insert_with(Vec::new)
+                                .extend(completed);
+                            episodes.extend(more);
+                        }),
+                        Err(e) => error.set(Some(e.to_string())),
+                    }
+                    loading_more.set(false);
+                });
+            }
+        })
+    };
+
     html! {
         <>
         <div class="main-container">
@@ -168,7 +254,16 @@ pub fn history() -> Html {
                                     )
                                 } else {
 
-                                    history_eps.data.into_iter().map(|episode| {
+                                    let count = history_eps.data.len();
+                                    let episodes = Rc::new(history_eps.data);
+                                    let post_state = post_state.clone();
+                                    let history = history.clone();
+                                    let state = state.clone();
+                                    let dispatch = dispatch.clone();
+                                    let audio_dispatch = audio_dispatch.clone();
+                                    let audio_state = audio_state.clone();
+                                    let render_episode = Callback::from(move |index: usize| {
+                                        let episode = episodes[index].clone();
                                         let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
                                         let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
                                         let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
@@ -235,6 +330,7 @@ pub fn history() -> Html {
                                             audio_dispatch.clone(),
                                             audio_state.clone(),
                                             None,
+                                            None,
                                         );
 
                                         let on_shownotes_click = on_shownotes_click(
@@ -271,7 +367,16 @@ pub fn history() -> Html {
                                         );
 
                                         item
-                                    }).collect::<Html>()
+                                    });
+                                    html! {
+                                        <VirtualList
+                                            item_count={count}
+                                            render_item={render_episode}
+                                            has_more={may_have_more(count)}
+                                            loading_more={*loading_more}
+                                            on_load_more={on_load_more.clone()}
+                                        />
+                                    }
                                 }
 
                             } else {
@@ -295,3 +400,10 @@ pub fn history() -> Html {
         </>
     }
 }
+
+fn loaded_episodes(state: &AppState) -> usize {
+    state
+        .episode_history
+        .as_ref()
+        .map_or(0, |history| history.data.len())
+}
@@ -1,6 +1,7 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::{
-    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop,
+    empty_message, episode_item, may_have_more, on_shownotes_click, refresh_page, Search_nav,
+    UseScrollToTop, VirtualList, EPISODE_PAGE_SIZE,
 };
 use crate::components::audio::on_play_click;
 use crate::components::audio::AudioPlayer;
@@ -8,9 +9,11 @@ use crate::components::context::{AppState, ExpandedDescriptions, UIState};
 use crate::components::gen_funcs::{
     format_datetime, parse_date, sanitize_html_with_blank_target, DateFormat,
 };
+use crate::requests::cache::{self, CachedList};
 use crate::requests::pod_req;
 use crate::requests::pod_req::Episode as EpisodeData;
-use crate::requests::pod_req::RecentEps;
+use crate::requests::pod_req::{Page, RecentEps};
+use std::rc::Rc;
 use yew::prelude::*;
 use yew::{function_component, html, Html};
 use yew_router::history::BrowserHistory;
@@ -69,7 +72,8 @@ pub fn home() -> Html {
     let (audio_state, audio_dispatch) = use_store::<UIState>();
     let error_message = audio_state.error_message.clone();
     let info_message = audio_state.info_message.clone();
-    let loading = use_state(|| true);
+    // A copy already in AppState shows straight away while it refreshes
+    let loading = use_state(|| post_state.server_feed_results.is_none());
 
     {
         let ui_dispatch = audio_dispatch.clone();
@@ -115,6 +119,9 @@ pub fn home() -> Html {
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
+        let has_cached = post_state.server_feed_results.is_some();
+        let loaded = loaded_episodes(&post_state);
+
         use_effect_with(
             (api_key.clone(), user_id.clone(), server_name.clone()),
             move |_| {
@@ -125,7 +132,33 @@ pub fn home() -> Html {
                     let dispatch = effect_dispatch.clone();
 
                     wasm_bindgen_futures::spawn_local(async move {
-                        match pod_req::call_get_recent_eps(&server_name, &api_key, &user_id).await {
+                        if has_cached
+                            && cache::is_fresh(&server_name, user_id, CachedList::RecentEpisodes)
+                        {
+                            dispatch.reduce_mut(|state| {
+                                state.completed_episodes = state
+                                    .server_feed_results
+                                    .as_ref()
+                                    .and_then(|recent| recent.episodes.as_ref())
+                                    .map(|episodes| {
+                                        episodes
+                                            .iter()
+                                            .filter(|ep| ep.completed)
+                                            .map(|ep| ep.episodeid)
+                                            .collect()
+                                    });
+                            });
+                            loading_ep.set(false);
+                            return;
+                        }
+                        match pod_req::call_get_recent_eps(
+                            &server_name,
+                            &api_key,
+                            &user_id,
+                            refresh_page(loaded),
+                        )
+                        .await
+                        {
                             Ok(fetched_episodes) => {
                                 let completed_episode_ids: Vec<i32> = fetched_episodes
                                     .iter()
@@ -139,6 +172,11 @@ pub fn home() -> Html {
                                     });
                                     state.completed_episodes = Some(completed_episode_ids);
                                 });
+                                cache::mark_fetched(
+                                    &server_name,
+                                    user_id,
+                                    CachedList::RecentEpisodes,
+                                );
                                 loading_ep.set(false);
                             }
                             Err(e) => {
@@ -153,6 +191,58 @@ pub fn home() -> Html {
         );
     }
 
+    let loading_more = use_state(|| false);
+    let on_load_more = {
+        let dispatch = dispatch.clone();
+        let error = error.clone();
+        let loading_more = loading_more.clone();
+        let api_key = state
+            .auth_details
+            .as_ref()
+            .and_then(|ud| ud.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let loaded = loaded_episodes(&state);
+        Callback::from(move |_| {
+            if let (Some(api_key), Some(user_id), Some(server_name)) =
+                (api_key.clone(), user_id, server_name.clone())
+            {
+                let dispatch = dispatch.clone();
+                let error = error.clone();
+                let loading_more = loading_more.clone();
+                loading_more.set(true);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let page = Page::after(loaded, EPISODE_PAGE_SIZE);
+                    match pod_req::call_get_recent_eps(&server_name, &Some(api_key), &user_id, page)
+                        .await
+                    {
+                        Ok(more) => dispatch.reduce_mut(move |state| {
+                            let episodes = state
+                                .server_feed_results
+                                .get_or_insert(RecentEps { episodes: None })
+                                .episodes
+                                .get_or_insert_with(Vec::new);
+                            // New episodes arriving at the top shift the pages, skip repeats
+                            let more: Vec<EpisodeData> = more
+                                .into_iter()
+                                .filter(|ep| !episodes.iter().any(|e| e.episodeid == ep.episodeid))
+                                .collect();
+                            let completed =
+                                more.iter().filter(|ep| ep.completed).map(|ep| ep.episodeid);
+                            state
+                                .completed_episodes
+                                .get_or_insert_with(Vec::new)
+                                .extend(completed);
+                            episodes.extend(more);
+                        }),
+                        Err(e) => error.set(Some(e.to_string())),
+                    }
+                    loading_more.set(false);
+                });
+            }
+        })
+    };
+
     html! {
         <>
         <div class="main-container">
@@ -182,13 +272,24 @@ pub fn home() -> Html {
                                     "You can add new podcasts by using the search bar above. Search for your favorite podcast and click the plus button to add it."
                                 )
                             } else {
-                                episodes.into_iter().map(|episode| {
+                                let count = episodes.len();
+                                let episodes = Rc::new(episodes);
+                                let render_episode = Callback::from(move |index: usize| {
                                     html! {
                                         <Episode
-                                            episode={episode.clone()}
+                                            episode={episodes[index].clone()}
                                         />
                                     }
-                                }).collect::<Html>()
+                                });
+                                html! {
+                                    <VirtualList
+                                        item_count={count}
+                                        render_item={render_episode}
+                                        has_more={may_have_more(count)}
+                                        loading_more={*loading_more}
+                                        on_load_more={on_load_more.clone()}
+                                    />
+                                }
                             }
                         } else {
                             empty_message(
@@ -224,6 +325,14 @@ pub fn home() -> Html {
     }
 }
 
+fn loaded_episodes(state: &AppState) -> usize {
+    state
+        .server_feed_results
+        .as_ref()
+        .and_then(|recent| recent.episodes.as_ref())
+        .map_or(0, |episodes| episodes.len())
+}
+
 #[derive(Properties, PartialEq, Clone)]
 pub struct EpisodeProps {
     pub episode: EpisodeData, // Assuming EpisodeData contains all episode details
@@ -311,6 +420,7 @@ pub fn episode(props: &EpisodeProps) -> Html {
         audio_dispatch.clone(),
         audio_state.clone(),
         None,
+        None,
     );
 
     let on_shownotes_click = on_shownotes_click(
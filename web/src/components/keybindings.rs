@@ -0,0 +1,596 @@
+use crate::components::audio::play_next_in_queue;
+use crate::components::audio_graph;
+use crate::components::context::{AppState, UIState};
+use crate::components::routes::Route;
+use crate::requests::setting_reqs::call_get_keybindings;
+use gloo_events::EventListener;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{window, HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+use yew::{function_component, html, Html};
+use yew_router::prelude::*;
+use yewdux::prelude::*;
+
+const KEYBINDINGS_STORAGE_KEY: &str = "keybindings";
+
+// How long to wait for the second key of a sequence like `g h`
+const SEQUENCE_TIMEOUT_MS: f64 = 1500.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyAction {
+    TogglePlayback,
+    SeekBackward,
+    SeekForward,
+    SkipBackward,
+    SkipForward,
+    VolumeUp,
+    VolumeDown,
+    SpeedUp,
+    SpeedDown,
+    NextInQueue,
+    GoHome,
+    GoPodcasts,
+    GoQueue,
+    GoSaved,
+    GoHistory,
+    GoDownloads,
+    GoSearch,
+    GoUserStats,
+    GoSettings,
+    ShowHelp,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 20] = [
+        KeyAction::TogglePlayback,
+        KeyAction::SeekBackward,
+        KeyAction::SeekForward,
+        KeyAction::SkipBackward,
+        KeyAction::SkipForward,
+        KeyAction::VolumeUp,
+        KeyAction::VolumeDown,
+        KeyAction::SpeedUp,
+        KeyAction::SpeedDown,
+        KeyAction::NextInQueue,
+        KeyAction::GoHome,
+        KeyAction::GoPodcasts,
+        KeyAction::GoQueue,
+        KeyAction::GoSaved,
+        KeyAction::GoHistory,
+        KeyAction::GoDownloads,
+        KeyAction::GoSearch,
+        KeyAction::GoUserStats,
+        KeyAction::GoSettings,
+        KeyAction::ShowHelp,
+    ];
+
+    // Stable id used when storing rebound shortcuts
+    pub fn id(&self) -> &'static str {
+        match self {
+            KeyAction::TogglePlayback => "toggle_playback",
+            KeyAction::SeekBackward => "seek_backward",
+            KeyAction::SeekForward => "seek_forward",
+            KeyAction::SkipBackward => "skip_backward",
+            KeyAction::SkipForward => "skip_forward",
+            KeyAction::VolumeUp => "volume_up",
+            KeyAction::VolumeDown => "volume_down",
+            KeyAction::SpeedUp => "speed_up",
+            KeyAction::SpeedDown => "speed_down",
+            KeyAction::NextInQueue => "next_in_queue",
+            KeyAction::GoHome => "go_home",
+            KeyAction::GoPodcasts => "go_podcasts",
+            KeyAction::GoQueue => "go_queue",
+            KeyAction::GoSaved => "go_saved",
+            KeyAction::GoHistory => "go_history",
+            KeyAction::GoDownloads => "go_downloads",
+            KeyAction::GoSearch => "go_search",
+            KeyAction::GoUserStats => "go_user_stats",
+            KeyAction::GoSettings => "go_settings",
+            KeyAction::ShowHelp => "show_help",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<KeyAction> {
+        KeyAction::ALL
+            .iter()
+            .copied()
+            .find(|action| action.id() == id)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            KeyAction::TogglePlayback => "Play / pause",
+            KeyAction::SeekBackward => "Rewind 10 seconds",
+            KeyAction::SeekForward => "Fast forward 10 seconds",
+            KeyAction::SkipBackward => "Skip back by the podcast's skip step",
+            KeyAction::SkipForward => "Skip forward by the podcast's skip step",
+            KeyAction::VolumeUp => "Volume up",
+            KeyAction::VolumeDown => "Volume down",
+            KeyAction::SpeedUp => "Increase playback speed",
+            KeyAction::SpeedDown => "Decrease playback speed",
+            KeyAction::NextInQueue => "Play next episode in queue",
+            KeyAction::GoHome => "Go to home",
+            KeyAction::GoPodcasts => "Go to podcasts",
+            KeyAction::GoQueue => "Go to queue",
+            KeyAction::GoSaved => "Go to saved episodes",
+            KeyAction::GoHistory => "Go to history",
+            KeyAction::GoDownloads => "Go to downloads",
+            KeyAction::GoSearch => "Go to search",
+            KeyAction::GoUserStats => "Go to user stats",
+            KeyAction::GoSettings => "Go to settings",
+            KeyAction::ShowHelp => "Show keyboard shortcuts",
+        }
+    }
+
+    pub fn category(&self) -> &'static str {
+        match self {
+            KeyAction::GoHome
+            | KeyAction::GoPodcasts
+            | KeyAction::GoQueue
+            | KeyAction::GoSaved
+            | KeyAction::GoHistory
+            | KeyAction::GoDownloads
+            | KeyAction::GoSearch
+            | KeyAction::GoUserStats
+            | KeyAction::GoSettings => "Navigation",
+            KeyAction::ShowHelp => "General",
+            _ => "Playback",
+        }
+    }
+
+    // Bindings are key names joined by `+` for modifiers, with sequences separated by spaces
+    pub fn default_bindings(&self) -> &'static [&'static str] {
+        match self {
+            KeyAction::TogglePlayback => &["Space", "k"],
+            KeyAction::SeekBackward => &["j"],
+            KeyAction::SeekForward => &["l"],
+            KeyAction::SkipBackward => &["ArrowLeft"],
+            KeyAction::SkipForward => &["ArrowRight"],
+            KeyAction::VolumeUp => &["ArrowUp"],
+            KeyAction::VolumeDown => &["ArrowDown"],
+            KeyAction::SpeedUp => &[">"],
+            KeyAction::SpeedDown => &["<"],
+            KeyAction::NextInQueue => &["n"],
+            KeyAction::GoHome => &["g h"],
+            KeyAction::GoPodcasts => &["g p"],
+            KeyAction::GoQueue => &["g q"],
+            KeyAction::GoSaved => &["g s"],
+            KeyAction::GoHistory => &["g y"],
+            KeyAction::GoDownloads => &["g d"],
+            KeyAction::GoSearch => &["g f"],
+            KeyAction::GoUserStats => &["g u"],
+            KeyAction::GoSettings => &["g o"],
+            KeyAction::ShowHelp => &["?"],
+        }
+    }
+
+    fn route(&self) -> Option<Route> {
+        match self {
+            KeyAction::GoHome => Some(Route::Home),
+            KeyAction::GoPodcasts => Some(Route::Podcasts),
+            KeyAction::GoQueue => Some(Route::Queue),
+            KeyAction::GoSaved => Some(Route::Saved),
+            KeyAction::GoHistory => Some(Route::PodHistory),
+            KeyAction::GoDownloads => Some(Route::Downloads),
+            KeyAction::GoSearch => Some(Route::Search),
+            KeyAction::GoUserStats => Some(Route::UserStats),
+            KeyAction::GoSettings => Some(Route::Settings),
+            _ => None,
+        }
+    }
+}
+
+// The user's shortcuts. Only rebound actions are kept, everything else uses its defaults.
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct KeyBindings {
+    overrides: HashMap<String, String>,
+}
+
+impl KeyBindings {
+    pub fn from_overrides(overrides: HashMap<String, String>) -> Self {
+        let overrides = overrides
+            .into_iter()
+            .filter(|(id, binding)| KeyAction::from_id(id).is_some() && !binding.trim().is_empty())
+            .collect();
+        KeyBindings { overrides }
+    }
+
+    pub fn overrides(&self) -> &HashMap<String, String> {
+        &self.overrides
+    }
+
+    pub fn bindings_for(&self, action: KeyAction) -> Vec<String> {
+        match self.overrides.get(action.id()) {
+            Some(binding) => vec![binding.clone()],
+            None => action
+                .default_bindings()
+                .iter()
+                .map(|binding| binding.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn is_rebound(&self, action: KeyAction) -> bool {
+        self.overrides.contains_key(action.id())
+    }
+
+    pub fn rebind(&mut self, action: KeyAction, binding: String) {
+        self.overrides.insert(action.id().to_string(), binding);
+    }
+
+    pub fn reset(&mut self, action: KeyAction) {
+        self.overrides.remove(action.id());
+    }
+
+    pub fn action_for(&self, sequence: &str) -> Option<KeyAction> {
+        KeyAction::ALL
+            .iter()
+            .copied()
+            .find(|action| self.bindings_for(*action).iter().any(|b| b == sequence))
+    }
+
+    // Other actions already using `binding`, so the settings page can warn about clashes
+    pub fn conflicts(&self, action: KeyAction, binding: &str) -> Vec<KeyAction> {
+        KeyAction::ALL
+            .iter()
+            .copied()
+            .filter(|other| *other != action)
+            .filter(|other| self.bindings_for(*other).iter().any(|b| b == binding))
+            .collect()
+    }
+
+    fn is_sequence_prefix(&self, sequence: &str) -> bool {
+        let prefix = format!("{} ", sequence);
+        KeyAction::ALL.iter().any(|action| {
+            self.bindings_for(*action)
+                .iter()
+                .any(|binding| binding.starts_with(&prefix))
+        })
+    }
+
+    pub fn load_local() -> Self {
+        let overrides = window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(KEYBINDINGS_STORAGE_KEY).ok().flatten())
+            .and_then(|stored| serde_json::from_str(&stored).ok())
+            .unwrap_or_default();
+        KeyBindings::from_overrides(overrides)
+    }
+
+    pub fn store_local(&self) {
+        if let Some(storage) = window().and_then(|window| window.local_storage().ok().flatten()) {
+            if let Ok(serialized) = serde_json::to_string(&self.overrides) {
+                let _ = storage.set_item(KEYBINDINGS_STORAGE_KEY, &serialized);
+            }
+        }
+    }
+}
+
+// Turns a key press into the name used in bindings, or None for a lone modifier key
+pub fn key_from_event(event: &KeyboardEvent) -> Option<String> {
+    let key = event.key();
+    if matches!(
+        key.as_str(),
+        "Shift" | "Control" | "Alt" | "Meta" | "CapsLock" | "Dead" | "Unidentified"
+    ) {
+        return None;
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    if event.ctrl_key() {
+        parts.push("Ctrl".to_string());
+    }
+    if event.alt_key() {
+        parts.push("Alt".to_string());
+    }
+    if event.meta_key() {
+        parts.push("Meta".to_string());
+    }
+    // Shift is already part of printable keys like `?` or `N`
+    let printable = key.chars().count() == 1;
+    if event.shift_key() && !printable {
+        parts.push("Shift".to_string());
+    }
+    parts.push(if key == " " { "Space".to_string() } else { key });
+    Some(parts.join("+"))
+}
+
+// Shortcuts never fire while the user is typing
+fn is_typing_target(event: &KeyboardEvent) -> bool {
+    event
+        .target()
+        .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        .map_or(false, |element| {
+            let tag = element.tag_name();
+            tag.eq_ignore_ascii_case("input")
+                || tag.eq_ignore_ascii_case("textarea")
+                || tag.eq_ignore_ascii_case("select")
+                || element.is_content_editable()
+        })
+}
+
+// Keys the browser scrolls the page with, they're only taken over while something is playing
+fn scrolls_page(key: &str) -> bool {
+    matches!(
+        key,
+        "Space" | "ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight"
+    )
+}
+
+fn seek_by(state: &mut UIState, offset: f64) {
+    let new_time = state
+        .audio_element
+        .as_ref()
+        .map(|audio| (audio.current_time() + offset).max(0.0));
+    if let Some(new_time) = new_time {
+        state.seek_to(new_time);
+    }
+}
+
+fn change_speed(state: &mut UIState, step: f64) {
+    let speed = if state.playback_speed > 0.0 {
+        state.playback_speed
+    } else {
+        1.0
+    };
+    // Same bounds as the speed slider in the player
+    let speed = ((speed + step) * 10.0).round() / 10.0;
+    state.playback_speed = speed.clamp(0.5, 2.0);
+    if let Some(audio) = &state.audio_element {
+        audio.set_playback_rate(state.playback_speed);
+    }
+}
+
+fn change_volume(state: &mut UIState, step: f64) {
+    state.audio_volume = (state.audio_volume + step).clamp(1.0, 100.0);
+    if let Some(audio) = &state.audio_element {
+        audio_graph::set_player_volume(audio, state.audio_volume / 100.0);
+    }
+}
+
+fn run_action(
+    action: KeyAction,
+    app_state: &AppState,
+    audio_dispatch: &Dispatch<UIState>,
+    navigator: &Option<Navigator>,
+) {
+    if let Some(route) = action.route() {
+        if let Some(navigator) = navigator {
+            navigator.push(&route);
+        }
+        return;
+    }
+
+    match action {
+        KeyAction::ShowHelp => {
+            audio_dispatch.reduce_mut(|state| state.shortcut_help_open = !state.shortcut_help_open)
+        }
+        KeyAction::TogglePlayback => audio_dispatch.reduce_mut(UIState::toggle_playback),
+        KeyAction::SeekBackward => audio_dispatch.reduce_mut(|state| seek_by(state, -10.0)),
+        KeyAction::SeekForward => audio_dispatch.reduce_mut(|state| seek_by(state, 10.0)),
+        KeyAction::SkipBackward => audio_dispatch.reduce_mut(|state| {
+            let step = state.active_playback_profile().skip_backward as f64;
+            seek_by(state, -step)
+        }),
+        KeyAction::SkipForward => audio_dispatch.reduce_mut(|state| {
+            let step = state.active_playback_profile().skip_forward as f64;
+            seek_by(state, step)
+        }),
+        KeyAction::VolumeUp => audio_dispatch.reduce_mut(|state| change_volume(state, 10.0)),
+        KeyAction::VolumeDown => audio_dispatch.reduce_mut(|state| change_volume(state, -10.0)),
+        KeyAction::SpeedUp => audio_dispatch.reduce_mut(|state| change_speed(state, 0.1)),
+        KeyAction::SpeedDown => audio_dispatch.reduce_mut(|state| change_speed(state, -0.1)),
+        KeyAction::NextInQueue => {
+            let audio_state = audio_dispatch.get();
+            let playing = audio_state
+                .currently_playing
+                .as_ref()
+                .filter(|playing| !playing.offline)
+                .map(|playing| playing.episode_id);
+            let server_name = app_state
+                .auth_details
+                .as_ref()
+                .map(|auth| auth.server_name.clone());
+            let api_key = app_state
+                .auth_details
+                .as_ref()
+                .and_then(|auth| auth.api_key.clone());
+            let user_id = app_state.user_details.as_ref().map(|user| user.UserID);
+            if let (Some(episode_id), Some(server_name), Some(user_id)) =
+                (playing, server_name, user_id)
+            {
+                play_next_in_queue(
+                    server_name,
+                    api_key,
+                    user_id,
+                    episode_id,
+                    audio_dispatch.clone(),
+                    audio_state,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+// Listens for shortcuts on every page and shows the "?" overlay
+#[function_component(KeyboardShortcuts)]
+pub fn keyboard_shortcuts() -> Html {
+    let (state, dispatch) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let navigator = use_navigator();
+
+    // Shortcuts saved on this device apply straight away, the server copy wins once signed in
+    {
+        let audio_dispatch = audio_dispatch.clone();
+        use_effect_with((), move |_| {
+            let keybindings = KeyBindings::load_local();
+            audio_dispatch.reduce_mut(move |state| state.keybindings = keybindings);
+            || ()
+        });
+    }
+    {
+        let audio_dispatch = audio_dispatch.clone();
+        let server_name = state
+            .auth_details
+            .as_ref()
+            .map(|auth| auth.server_name.clone());
+        let api_key = state
+            .auth_details
+            .as_ref()
+            .and_then(|auth| auth.api_key.clone());
+        let user_id = state.user_details.as_ref().map(|user| user.UserID);
+        use_effect_with(
+            (server_name, api_key, user_id),
+            move |(server_name, api_key, user_id)| {
+                if let (Some(server_name), Some(api_key), Some(user_id)) =
+                    (server_name.clone(), api_key.clone(), *user_id)
+                {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match call_get_keybindings(server_name, api_key, &user_id).await {
+                            Ok(overrides) => {
+                                let keybindings = KeyBindings::from_overrides(overrides);
+                                keybindings.store_local();
+                                audio_dispatch
+                                    .reduce_mut(move |state| state.keybindings = keybindings);
+                            }
+                            Err(e) => web_sys::console::log_1(
+                                &format!("Error getting keyboard shortcuts: {}", e).into(),
+                            ),
+                        }
+                    });
+                }
+                || ()
+            },
+        );
+    }
+
+    {
+        let audio_dispatch = audio_dispatch.clone();
+        let navigator = navigator.clone();
+        use_effect_with((), move |_| {
+            let pending: Rc<RefCell<Option<(String, f64)>>> = Rc::new(RefCell::new(None));
+            let document = window().unwrap().document().unwrap();
+            let listener = EventListener::new(&document, "keydown", move |event| {
+                let event = match event.dyn_ref::<KeyboardEvent>() {
+                    Some(event) => event,
+                    None => return,
+                };
+                if is_typing_target(event) {
+                    return;
+                }
+                let key = match key_from_event(event) {
+                    Some(key) => key,
+                    None => return,
+                };
+
+                let ui_state = audio_dispatch.get();
+                if ui_state.shortcut_help_open && key == "Escape" {
+                    audio_dispatch.reduce_mut(|state| state.shortcut_help_open = false);
+                    return;
+                }
+                if ui_state.currently_playing.is_none() && scrolls_page(&key) {
+                    return;
+                }
+
+                let now = js_sys::Date::now();
+                let previous = pending
+                    .borrow_mut()
+                    .take()
+                    .filter(|(_, pressed_at)| now - pressed_at < SEQUENCE_TIMEOUT_MS)
+                    .map(|(sequence, _)| sequence);
+                let keybindings = &ui_state.keybindings;
+                // Finish a started sequence first, otherwise treat the key on its own
+                let candidates = previous
+                    .map(|previous| format!("{} {}", previous, key))
+                    .into_iter()
+                    .chain(std::iter::once(key.clone()));
+                for sequence in candidates {
+                    if let Some(action) = keybindings.action_for(&sequence) {
+                        event.prevent_default();
+                        run_action(action, &dispatch.get(), &audio_dispatch, &navigator);
+                        return;
+                    }
+                    if keybindings.is_sequence_prefix(&sequence) {
+                        event.prevent_default();
+                        *pending.borrow_mut() = Some((sequence, now));
+                        return;
+                    }
+                }
+            });
+            move || drop(listener)
+        });
+    }
+
+    let close_help = {
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            audio_dispatch.reduce_mut(|state| state.shortcut_help_open = false);
+        })
+    };
+
+    if !audio_state.shortcut_help_open {
+        return html! {};
+    }
+
+    let keybindings = &audio_state.keybindings;
+    let categories = ["Playback", "Navigation", "General"];
+    html! {
+        <div class="fixed top-0 right-0 left-0 z-50 flex justify-center items-center w-full h-full max-h-full bg-black bg-opacity-25" onclick={close_help.clone()}>
+            <div class="modal-container relative p-4 w-full max-w-lg max-h-full rounded-lg shadow" onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}>
+                <div class="flex items-center justify-between p-4 md:p-5 border-b rounded-t">
+                    <h3 class="text-xl font-semibold">{"Keyboard Shortcuts"}</h3>
+                    <button onclick={close_help} class="end-2.5 text-gray-400 bg-transparent hover:bg-gray-200 hover:text-gray-900 rounded-lg text-sm w-8 h-8 ms-auto inline-flex justify-center items-center">
+                        <span class="material-icons">{"close"}</span>
+                    </button>
+                </div>
+                <div class="shortcut-help-body p-4 md:p-5">
+                    { for categories.iter().map(|category| html! {
+                        <div class="shortcut-help-category">
+                            <h4 class="shortcut-help-heading">{ *category }</h4>
+                            { for KeyAction::ALL.iter().filter(|action| action.category() == *category).map(|action| html! {
+                                <div class="shortcut-row">
+                                    <span>{ action.description() }</span>
+                                    <ShortcutKeys bindings={keybindings.bindings_for(*action)} />
+                                </div>
+                            })}
+                        </div>
+                    })}
+                    <p class="shortcut-help-hint">{"Shortcuts can be changed under Settings → Keyboard Shortcuts."}</p>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ShortcutKeysProps {
+    pub bindings: Vec<String>,
+}
+
+// Renders bindings as <kbd> keys, e.g. `g h` as "g then h"
+#[function_component(ShortcutKeys)]
+pub fn shortcut_keys(props: &ShortcutKeysProps) -> Html {
+    html! {
+        <span class="shortcut-keys">
+            { for props.bindings.iter().enumerate().map(|(index, binding)| html! {
+                <>
+                    if index > 0 {
+                        <span class="shortcut-separator">{"or"}</span>
+                    }
+                    { for binding.split(' ').enumerate().map(|(step, key)| html! {
+                        <>
+                            if step > 0 {
+                                <span class="shortcut-separator">{"then"}</span>
+                            }
+                            <kbd class="shortcut-key">{ key }</kbd>
+                        </>
+                    })}
+                </>
+            })}
+        </span>
+    }
+}
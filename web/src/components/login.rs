@@ -1,6 +1,7 @@
 use crate::components::context::{AppState, UIState};
 use crate::components::episodes_layout::UIStateMsg;
 use crate::components::gen_funcs::{encode_password, validate_user_input, ValidationError};
+use crate::requests::cache;
 use crate::requests::login_requests::{self, call_check_mfa_enabled};
 use crate::requests::login_requests::{call_add_login_user, AddUserRequest};
 use crate::requests::login_requests::{
@@ -390,8 +391,8 @@ pub fn login() -> Html {
                                     )
                                     .await
                                     {
-                                        Ok(response) => {
-                                            if response.mfa_enabled {
+                                        Ok(mfa_enabled) => {
+                                            if mfa_enabled {
                                                 page_state.set(PageState::MFAPrompt);
                                             } else {
                                                 let theme_api = api_key.clone();
@@ -1044,8 +1045,8 @@ pub fn login() -> Html {
                             )
                             .await
                             {
-                                Ok(response) => {
-                                    if response.mfa_enabled {
+                                Ok(mfa_enabled) => {
+                                    if mfa_enabled {
                                         page_state.set(PageState::MFAPrompt);
                                     } else {
                                         history.push("/home"); // Use the route path
@@ -1186,8 +1187,8 @@ pub fn login() -> Html {
                 )
                 .await
                 {
-                    Ok(response) => {
-                        if response.verified {
+                    Ok(verified) => {
+                        if verified {
                             page_state.set(PageState::Default);
                             let theme_api = api_key.clone();
                             let theme_server = server_name.clone();
@@ -1544,8 +1545,8 @@ pub fn login() -> Html {
                                     )
                                     .await
                                     {
-                                        Ok(response) => {
-                                            if response.mfa_enabled {
+                                        Ok(mfa_enabled) => {
+                                            if mfa_enabled {
                                                 page_state.set(PageState::MFAPrompt);
                                             } else {
                                                 let theme_api = api_key.clone();
@@ -1752,8 +1753,8 @@ pub fn login() -> Html {
                             )
                             .await
                             {
-                                Ok(response) => {
-                                    if response.mfa_enabled {
+                                Ok(mfa_enabled) => {
+                                    if mfa_enabled {
                                         page_state.set(PageState::MFAPrompt);
                                     } else {
                                         history.push("/home"); // Use the route path
@@ -1888,8 +1889,8 @@ pub fn login() -> Html {
                 )
                 .await
                 {
-                    Ok(response) => {
-                        if response.verified {
+                    Ok(verified) => {
+                        if verified {
                             page_state.set(PageState::Default);
                             let theme_api = api_key.clone();
                             let theme_server = server_name.clone();
@@ -2070,6 +2071,9 @@ pub fn logout() -> Html {
         .get_item("selected_theme")
         .expect("failed to get 'selected_theme'");
 
+    // Nothing fetched with the old key should be shown to whoever signs in next
+    cache::clear();
+
     // Clear storages
     local_storage.clear().expect("failed to clear localStorage");
     session_storage
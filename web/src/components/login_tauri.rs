@@ -1,5 +1,6 @@
 use crate::components::context::{AppState, UIState};
 use crate::components::episodes_layout::UIStateMsg;
+use crate::requests::cache;
 use crate::requests::login_requests::{self, call_check_mfa_enabled};
 use crate::requests::login_requests::{
     call_first_login_done, call_get_time_info, call_self_service_login_status,
@@ -391,8 +392,8 @@ pub fn login() -> Html {
                                     )
                                     .await
                                     {
-                                        Ok(response) => {
-                                            if response.mfa_enabled {
+                                        Ok(mfa_enabled) => {
+                                            if mfa_enabled {
                                                 page_state.set(PageState::MFAPrompt);
                                             } else {
                                                 let theme_api = api_key.clone();
@@ -609,8 +610,8 @@ pub fn login() -> Html {
                             )
                             .await
                             {
-                                Ok(response) => {
-                                    if response.mfa_enabled {
+                                Ok(mfa_enabled) => {
+                                    if mfa_enabled {
                                         page_state.set(PageState::MFAPrompt);
                                     } else {
                                         history.push("/home"); // Use the route path
@@ -745,8 +746,8 @@ pub fn login() -> Html {
                 )
                 .await
                 {
-                    Ok(response) => {
-                        if response.verified {
+                    Ok(verified) => {
+                        if verified {
                             page_state.set(PageState::Default);
                             let theme_api = api_key.clone();
                             let theme_server = server_name.clone();
@@ -930,6 +931,9 @@ pub fn logout() -> Html {
         .get_item("selected_theme")
         .expect("failed to get 'selected_theme'");
 
+    // Nothing fetched with the old key should be shown to whoever signs in next
+    cache::clear();
+
     // Clear storages
     local_storage.clear().expect("failed to clear localStorage");
     session_storage
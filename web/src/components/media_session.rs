@@ -0,0 +1,126 @@
+use js_sys::{Array, Function, Object, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::window;
+
+// The Media Session bindings in web-sys are still behind `web_sys_unstable_apis`, so
+// `navigator.mediaSession` is driven through Reflect instead. Every helper quietly does nothing
+// in browsers and webviews that don't support it.
+
+pub const MEDIA_SESSION_ACTIONS: [&str; 6] = [
+    "play",
+    "pause",
+    "seekbackward",
+    "seekforward",
+    "seekto",
+    "nexttrack",
+];
+
+fn media_session() -> Option<JsValue> {
+    let navigator = window()?.navigator();
+    Reflect::get(&navigator, &JsValue::from_str("mediaSession"))
+        .ok()
+        .filter(|session| !session.is_undefined() && !session.is_null())
+}
+
+fn set_property(target: &Object, key: &str, value: &JsValue) {
+    let _ = Reflect::set(target, &JsValue::from_str(key), value);
+}
+
+pub fn set_metadata(title: &str, podcast_name: Option<&str>, artwork_url: &str) {
+    let session = match media_session() {
+        Some(session) => session,
+        None => return,
+    };
+    let constructor = match window()
+        .and_then(|window| Reflect::get(&window, &JsValue::from_str("MediaMetadata")).ok())
+        .and_then(|constructor| constructor.dyn_into::<Function>().ok())
+    {
+        Some(constructor) => constructor,
+        None => return,
+    };
+
+    let init = Object::new();
+    set_property(&init, "title", &JsValue::from_str(title));
+    if let Some(podcast_name) = podcast_name {
+        set_property(&init, "artist", &JsValue::from_str(podcast_name));
+        set_property(&init, "album", &JsValue::from_str(podcast_name));
+    }
+    if !artwork_url.is_empty() {
+        let image = Object::new();
+        set_property(&image, "src", &JsValue::from_str(artwork_url));
+        set_property(&init, "artwork", &Array::of1(&image));
+    }
+
+    if let Ok(metadata) = Reflect::construct(&constructor, &Array::of1(&init)) {
+        let _ = Reflect::set(&session, &JsValue::from_str("metadata"), &metadata);
+    }
+}
+
+pub fn set_playback_state(playing: bool) {
+    if let Some(session) = media_session() {
+        let state = if playing { "playing" } else { "paused" };
+        let _ = Reflect::set(
+            &session,
+            &JsValue::from_str("playbackState"),
+            &JsValue::from_str(state),
+        );
+    }
+}
+
+// Keeps the scrubber in OS media overlays in sync with the player
+pub fn set_position_state(duration: f64, position: f64, playback_rate: f64) {
+    // setPositionState throws on a missing duration or a position past the end
+    if !duration.is_finite() || duration <= 0.0 || !position.is_finite() || playback_rate <= 0.0 {
+        return;
+    }
+    let session = match media_session() {
+        Some(session) => session,
+        None => return,
+    };
+    let set_position = match Reflect::get(&session, &JsValue::from_str("setPositionState"))
+        .ok()
+        .and_then(|function| function.dyn_into::<Function>().ok())
+    {
+        Some(set_position) => set_position,
+        None => return,
+    };
+
+    let state = Object::new();
+    set_property(&state, "duration", &JsValue::from_f64(duration));
+    set_property(
+        &state,
+        "position",
+        &JsValue::from_f64(position.clamp(0.0, duration)),
+    );
+    set_property(&state, "playbackRate", &JsValue::from_f64(playback_rate));
+    let _ = set_position.call1(&session, &state);
+}
+
+// Registers `handler` for a media session action, or removes it when `handler` is None
+pub fn set_action_handler(action: &str, handler: Option<&Function>) {
+    let session = match media_session() {
+        Some(session) => session,
+        None => return,
+    };
+    if let Some(set_handler) = Reflect::get(&session, &JsValue::from_str("setActionHandler"))
+        .ok()
+        .and_then(|function| function.dyn_into::<Function>().ok())
+    {
+        let handler = handler.map_or(JsValue::NULL, |handler| JsValue::from(handler.clone()));
+        // Browsers throw for actions they don't implement
+        let _ = set_handler.call2(&session, &JsValue::from_str(action), &handler);
+    }
+}
+
+pub fn clear_action_handlers() {
+    for action in MEDIA_SESSION_ACTIONS.iter() {
+        set_action_handler(action, None);
+    }
+}
+
+// Reads a numeric field (`seekOffset`, `seekTime`) from the details passed to an action handler
+pub fn action_detail(details: &JsValue, key: &str) -> Option<f64> {
+    Reflect::get(details, &JsValue::from_str(key))
+        .ok()
+        .and_then(|value| value.as_f64())
+}
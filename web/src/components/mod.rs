@@ -12,19 +12,28 @@ pub(crate) mod user_stats;
 
 mod audio;
 mod click_events;
+pub(crate) mod audio_graph;
+pub(crate) mod bookmarks;
 pub(crate) mod context;
 pub(crate) mod desc_impl;
 pub mod downloads;
 pub(crate) mod episode;
+pub(crate) mod episode_browser;
 pub(crate) mod episodes_layout;
+pub(crate) mod feed_details;
 pub(crate) mod gen_components;
 pub mod gen_funcs;
+pub(crate) mod keybindings;
 #[cfg(feature = "server_build")]
 pub mod login;
+pub(crate) mod media_session;
+pub(crate) mod outbox_sync;
 pub(crate) mod podcast_layout;
 pub(crate) mod podcasts;
 pub(crate) mod search_new;
 pub mod setting_components;
+pub(crate) mod sleep_timer;
+pub(crate) mod transcript;
 
 #[cfg(not(feature = "server_build"))]
 pub mod downloads_tauri;
@@ -0,0 +1,123 @@
+use crate::components::context::{AppState, UIState};
+use crate::requests::outbox;
+use gloo_events::EventListener;
+use gloo_timers::callback::Interval;
+use web_sys::window;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+// How often to look for outbox entries whose backoff has run out
+const CHECK_INTERVAL_MS: u32 = 5_000;
+
+// Sends changes kept while the server was unreachable, and says how many are still waiting
+#[function_component(OutboxSync)]
+pub fn outbox_sync() -> Html {
+    let (state, _) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+
+    let server_name = state
+        .auth_details
+        .as_ref()
+        .map(|auth| auth.server_name.clone());
+    let api_key = state
+        .auth_details
+        .as_ref()
+        .and_then(|auth| auth.api_key.clone());
+
+    {
+        let audio_dispatch = audio_dispatch.clone();
+        use_effect_with(
+            (server_name.clone(), api_key.clone()),
+            move |(server_name, api_key)| {
+                let pending = outbox::pending_count();
+                audio_dispatch.reduce_mut(move |state| state.outbox_pending = pending);
+
+                let mut listeners = Vec::new();
+                let mut interval = None;
+                if let Some(server_name) = server_name.clone() {
+                    let flush = {
+                        let api_key = api_key.clone();
+                        move |force: bool| {
+                            let server_name = server_name.clone();
+                            let api_key = api_key.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                outbox::flush(server_name, api_key, force).await;
+                            });
+                        }
+                    };
+                    if pending > 0 {
+                        flush(true);
+                    }
+                    let window = window().unwrap();
+                    {
+                        let flush = flush.clone();
+                        // The browser noticed the network is back, don't wait out the backoff
+                        listeners.push(EventListener::new(&window, "online", move |_| flush(true)));
+                    }
+                    listeners.push(EventListener::new(&window, "offline", move |_| {
+                        Dispatch::<UIState>::global()
+                            .reduce_mut(|state| state.offline = Some(true));
+                    }));
+                    interval = Some(Interval::new(CHECK_INTERVAL_MS, move || {
+                        if Dispatch::<UIState>::global().get().outbox_pending > 0 {
+                            flush(false);
+                        }
+                    }));
+                }
+                move || {
+                    drop(listeners);
+                    drop(interval);
+                }
+            },
+        );
+    }
+
+    // Another request got through, so whatever is waiting can go now too
+    {
+        let server_name = server_name.clone();
+        let api_key = api_key.clone();
+        let back_online = audio_state.offline == Some(false);
+        use_effect_with(back_online, move |back_online| {
+            if let (true, Some(server_name)) = (*back_online, server_name) {
+                if outbox::pending_count() > 0 {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        outbox::flush(server_name, api_key, true).await;
+                    });
+                }
+            }
+            || ()
+        });
+    }
+
+    let retry_now = Callback::from(move |_: MouseEvent| {
+        if let Some(server_name) = server_name.clone() {
+            let api_key = api_key.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                outbox::flush(server_name, api_key, true).await;
+            });
+        }
+    });
+
+    let offline = audio_state.offline == Some(true);
+    let pending = audio_state.outbox_pending;
+    if !offline && pending == 0 {
+        return html! {};
+    }
+
+    let label = match (offline, pending) {
+        (true, 0) => "Can't reach the server".to_string(),
+        (true, 1) => "Offline, 1 change waiting to sync".to_string(),
+        (true, count) => format!("Offline, {} changes waiting to sync", count),
+        (false, 1) => "Syncing 1 change".to_string(),
+        (false, count) => format!("Syncing {} changes", count),
+    };
+    html! {
+        <div class={classes!("outbox-status", offline.then_some("outbox-status-offline"))}>
+            <i class="material-icons">{ if offline { "cloud_off" } else { "cloud_sync" } }</i>
+            <span>{ label }</span>
+            if offline && pending > 0 {
+                <button class="outbox-retry" onclick={retry_now}>{ "Retry now" }</button>
+            }
+        </div>
+    }
+}
@@ -7,6 +7,7 @@ use crate::components::click_events::create_on_title_click;
 use crate::components::context::{AppState, ExpandedDescriptions, UIState};
 use crate::components::episodes_layout::SafeHtml;
 use crate::components::gen_components::{Search_nav, UseScrollToTop};
+use crate::requests::cache::{self, CachedList};
 use crate::requests::login_requests::use_check_authentication;
 use crate::requests::pod_req;
 use crate::requests::pod_req::{call_remove_podcasts, PodcastResponse, RemovePodcastValues};
@@ -107,6 +108,9 @@ pub fn podcasts() -> Html {
         let api_key_effect = api_key.clone();
         let effect_dispatch = dispatch.clone();
 
+        // Shown from AppState while it refreshes, not refetched at all while fresh
+        let has_cached = state.podcast_feed_return.is_some();
+
         use_effect_with(
             (api_key_effect, user_id_effect, server_name_effect),
             move |_| {
@@ -119,6 +123,11 @@ pub fn podcasts() -> Html {
                     let dispatch = effect_dispatch.clone();
 
                     wasm_bindgen_futures::spawn_local(async move {
+                        if has_cached
+                            && cache::is_fresh(&server_name, user_id, CachedList::Podcasts)
+                        {
+                            return;
+                        }
                         match pod_req::call_get_podcasts(&server_name, &api_key, &user_id).await {
                             Ok(fetched_podcasts) => {
                                 dispatch.reduce_mut(move |state| {
@@ -126,6 +135,7 @@ pub fn podcasts() -> Html {
                                         pods: Some(fetched_podcasts),
                                     });
                                 });
+                                cache::mark_fetched(&server_name, user_id, CachedList::Podcasts);
                             }
                             Err(e) => console::log_1(
                                 &format!("Unable to parse Podcasts: {:?}", &e).into(),
@@ -1,6 +1,6 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::{
-    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop,
+    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop, VirtualList,
 };
 use crate::components::audio::on_play_click;
 use crate::components::audio::AudioPlayer;
@@ -10,8 +10,13 @@ use crate::components::gen_funcs::{
     format_datetime, match_date_format, parse_date, sanitize_html_with_blank_target,
     truncate_description,
 };
+use crate::requests::cache::{self, CachedList};
 use crate::requests::pod_req;
-use crate::requests::pod_req::QueuedEpisodesResponse;
+use crate::requests::pod_req::{
+    call_get_queued_episodes, call_queue_episode_at, QueuePositionRequest, QueuedEpisodesResponse,
+};
+use std::rc::Rc;
+use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yew::{function_component, html, Html};
 use yew_router::history::BrowserHistory;
@@ -23,6 +28,38 @@ use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
 use web_sys::window;
 
+// Puts an episode at a spot in the queue, then reloads the queue page's list if it was loaded
+pub fn queue_episode_at(
+    request: QueuePositionRequest,
+    server_name: String,
+    api_key: Option<String>,
+    app_dispatch: Dispatch<AppState>,
+    audio_dispatch: Dispatch<UIState>,
+    success_message: Option<String>,
+) {
+    spawn_local(async move {
+        match call_queue_episode_at(&server_name, &api_key, &request).await {
+            Ok(_) => {
+                if let Some(message) = success_message {
+                    audio_dispatch.reduce_mut(|state| state.info_message = Some(message));
+                }
+                if app_dispatch.get().queued_episodes.is_some() {
+                    if let Ok(episodes) =
+                        call_get_queued_episodes(&server_name, &api_key, &request.user_id).await
+                    {
+                        app_dispatch.reduce_mut(move |state| {
+                            state.queued_episodes = Some(QueuedEpisodesResponse { episodes });
+                        });
+                    }
+                }
+            }
+            Err(e) => audio_dispatch.reduce_mut(|state| {
+                state.error_message = Some(format!("{}", e));
+            }),
+        }
+    });
+}
+
 #[function_component(Queue)]
 pub fn queue() -> Html {
     let (state, dispatch) = use_store::<AppState>();
@@ -38,7 +75,8 @@ pub fn queue() -> Html {
 
     let session_dispatch = _post_dispatch.clone();
     let session_state = post_state.clone();
-    let loading = use_state(|| true);
+    // A copy already in AppState shows straight away while it refreshes
+    let loading = use_state(|| post_state.queued_episodes.is_none());
 
     use_effect_with((), move |_| {
         // Check if the page reload action has already occurred to prevent redundant execution
@@ -115,6 +153,8 @@ pub fn queue() -> Html {
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
+        let has_cached = post_state.queued_episodes.is_some();
+
         use_effect_with(
             (api_key.clone(), user_id.clone(), server_name.clone()),
             move |_| {
@@ -125,6 +165,21 @@ pub fn queue() -> Html {
                     let dispatch = effect_dispatch.clone();
 
                     wasm_bindgen_futures::spawn_local(async move {
+                        if has_cached && cache::is_fresh(&server_name, user_id, CachedList::Queue) {
+                            dispatch.reduce_mut(|state| {
+                                state.completed_episodes =
+                                    state.queued_episodes.as_ref().map(|queued| {
+                                        queued
+                                            .episodes
+                                            .iter()
+                                            .filter(|ep| ep.completed)
+                                            .map(|ep| ep.episodeid)
+                                            .collect()
+                                    });
+                            });
+                            loading_ep.set(false);
+                            return;
+                        }
                         match pod_req::call_get_queued_episodes(&server_name, &api_key, &user_id)
                             .await
                         {
@@ -140,6 +195,7 @@ pub fn queue() -> Html {
                                     });
                                     state.completed_episodes = Some(completed_episode_ids);
                                 });
+                                cache::mark_fetched(&server_name, user_id, CachedList::Queue);
                                 loading_ep.set(false);
                                 // web_sys::console::log_1(&format!("State after update: {:?}", state).into()); // Log state after update
                             }
@@ -155,6 +211,49 @@ pub fn queue() -> Html {
         );
     }
 
+    // The index of the episode being dragged and of the row it's over
+    let dragging: UseStateHandle<Option<usize>> = use_state(|| None);
+    let drag_over: UseStateHandle<Option<usize>> = use_state(|| None);
+
+    // Moves the episode at `from` to `to`, in the list straight away and then on the server
+    let move_episode = {
+        let dispatch = dispatch.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        let api_key = post_state
+            .auth_details
+            .as_ref()
+            .and_then(|ud| ud.api_key.clone());
+        let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID);
+        let server_name = post_state
+            .auth_details
+            .as_ref()
+            .map(|ud| ud.server_name.clone());
+        Callback::from(move |(from, to): (usize, usize)| {
+            let mut moved = None;
+            dispatch.reduce_mut(|state| {
+                if let Some(queued) = state.queued_episodes.as_mut() {
+                    if from < queued.episodes.len() && to < queued.episodes.len() && from != to {
+                        let episode = queued.episodes.remove(from);
+                        moved = Some(episode.episodeid);
+                        queued.episodes.insert(to, episode);
+                    }
+                }
+            });
+            if let (Some(episode_id), Some(user_id), Some(server_name)) =
+                (moved, user_id, server_name.clone())
+            {
+                queue_episode_at(
+                    QueuePositionRequest::move_to(episode_id, user_id, to as i32 + 1),
+                    server_name,
+                    api_key.clone(),
+                    dispatch.clone(),
+                    audio_dispatch.clone(),
+                    None,
+                );
+            }
+        })
+    };
+
     html! {
         <>
         <div class="main-container">
@@ -188,10 +287,22 @@ pub fn queue() -> Html {
                                 // Render "No Queued Episodes Found" if episodes list is empty
                                 empty_message(
                                     "No Queued Episodes Found",
-                                    "You can queue episodes by clicking the context button on each episode and clicking 'Queue Episode'. Doing this will play episodes in order of the queue after the currently playing episode is complete."
+                                    "You can queue episodes by clicking the context button on each episode and clicking 'Play Next' or 'Play Last'. Doing this will play episodes in order of the queue after the currently playing episode is complete. Drag episodes here, or use Alt and the arrow keys, to reorder them."
                                 )
                             } else {
-                                queued_eps.episodes.into_iter().map(|episode| {
+                                let queue_length = queued_eps.episodes.len();
+                                let episodes = Rc::new(queued_eps.episodes);
+                                let post_state = post_state.clone();
+                                let history = history.clone();
+                                let state = state.clone();
+                                let dispatch = dispatch.clone();
+                                let audio_dispatch = audio_dispatch.clone();
+                                let audio_state = audio_state.clone();
+                                let dragging = dragging.clone();
+                                let drag_over = drag_over.clone();
+                                let move_episode = move_episode.clone();
+                                let render_episode = Callback::from(move |index: usize| {
+                                    let episode = episodes[index].clone();
                             let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
                             let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
                             let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
@@ -259,6 +370,7 @@ pub fn queue() -> Html {
                                 audio_dispatch.clone(),
                                 audio_state.clone(),
                                 None,
+                                None,
                             );
 
                             let on_shownotes_click = on_shownotes_click(
@@ -293,14 +405,122 @@ pub fn queue() -> Html {
                                 is_completed
                             );
 
-                            item
-                        }).collect::<Html>()
+                            let on_drag_start = {
+                                let dragging = dragging.clone();
+                                Callback::from(move |e: DragEvent| {
+                                    // Firefox only starts a drag when it carries some data
+                                    if let Some(data_transfer) = e.data_transfer() {
+                                        let _ = data_transfer.set_data("text/plain", &index.to_string());
+                                    }
+                                    dragging.set(Some(index));
+                                })
+                            };
+                            let on_drag_over = {
+                                let drag_over = drag_over.clone();
+                                Callback::from(move |e: DragEvent| {
+                                    // Rows only accept drops when the default is prevented
+                                    e.prevent_default();
+                                    if *drag_over != Some(index) {
+                                        drag_over.set(Some(index));
+                                    }
+                                })
+                            };
+                            let on_drop = {
+                                let dragging = dragging.clone();
+                                let drag_over = drag_over.clone();
+                                let move_episode = move_episode.clone();
+                                Callback::from(move |e: DragEvent| {
+                                    e.prevent_default();
+                                    if let Some(from) = *dragging {
+                                        move_episode.emit((from, index));
+                                    }
+                                    dragging.set(None);
+                                    drag_over.set(None);
+                                })
+                            };
+                            let on_drag_end = {
+                                let dragging = dragging.clone();
+                                let drag_over = drag_over.clone();
+                                Callback::from(move |_: DragEvent| {
+                                    dragging.set(None);
+                                    drag_over.set(None);
+                                })
+                            };
+                            // Alt + the arrow keys move the focused episode, plain arrows stay volume shortcuts
+                            let on_key_down = {
+                                let move_episode = move_episode.clone();
+                                Callback::from(move |e: KeyboardEvent| {
+                                    if !e.alt_key() {
+                                        return;
+                                    }
+                                    match e.key().as_str() {
+                                        "ArrowUp" if index > 0 => {
+                                            e.prevent_default();
+                                            move_episode.emit((index, index - 1));
+                                        }
+                                        "ArrowDown" if index + 1 < queue_length => {
+                                            e.prevent_default();
+                                            move_episode.emit((index, index + 1));
+                                        }
+                                        _ => {}
+                                    }
+                                })
+                            };
+                            let on_move_up = {
+                                let move_episode = move_episode.clone();
+                                Callback::from(move |_: MouseEvent| move_episode.emit((index, index.saturating_sub(1))))
+                            };
+                            let on_move_down = {
+                                let move_episode = move_episode.clone();
+                                Callback::from(move |_: MouseEvent| move_episode.emit((index, index + 1)))
+                            };
+
+                            let row_class = classes!(
+                                "queue-item",
+                                (*dragging == Some(index)).then_some("queue-item-dragging"),
+                                (*drag_over == Some(index) && *dragging != Some(index)).then_some("queue-item-drop-target"),
+                            );
+
+                            html! {
+                                <div
+                                    key={episode_id_clone}
+                                    class={row_class}
+                                    draggable="true"
+                                    tabindex="0"
+                                    aria-label="Queued episode. Press Alt and the up or down arrow to move it."
+                                    ondragstart={on_drag_start}
+                                    ondragover={on_drag_over}
+                                    ondrop={on_drop}
+                                    ondragend={on_drag_end}
+                                    onkeydown={on_key_down}
+                                >
+                                    <div class="queue-reorder-controls">
+                                        <button onclick={on_move_up} disabled={index == 0} title="Move up" class="queue-reorder-button">
+                                            <span class="material-icons">{"keyboard_arrow_up"}</span>
+                                        </button>
+                                        <span class="material-icons queue-drag-handle" title="Drag to reorder">{"drag_indicator"}</span>
+                                        <button onclick={on_move_down} disabled={index + 1 == queue_length} title="Move down" class="queue-reorder-button">
+                                            <span class="material-icons">{"keyboard_arrow_down"}</span>
+                                        </button>
+                                    </div>
+                                    <div class="queue-item-content">
+                                        { item }
+                                    </div>
+                                </div>
+                            }
+                        });
+                                html! {
+                                    <VirtualList
+                                        item_count={queue_length}
+                                        render_item={render_episode}
+                                    />
+                                }
                         }
 
                     } else {
                         empty_message(
                             "No Queued Episodes Found - State is None",
-                            "You can queue episodes by clicking the context button on each episode and clicking 'Queue Episode'. Doing this will play episodes in order of the queue after the currently playing episode is complete."
+                            "You can queue episodes by clicking the context button on each episode and clicking 'Play Next' or 'Play Last'. Doing this will play episodes in order of the queue after the currently playing episode is complete. Drag episodes here, or use Alt and the arrow keys, to reorder them."
                         )
                     }
                 }
@@ -1,6 +1,6 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::{
-    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop,
+    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop, VirtualList,
 };
 use crate::components::audio::on_play_click;
 use crate::components::audio::AudioPlayer;
@@ -10,8 +10,10 @@ use crate::components::gen_funcs::{
     format_datetime, match_date_format, parse_date, sanitize_html_with_blank_target,
     truncate_description,
 };
+use crate::requests::cache::{self, CachedList};
 use crate::requests::pod_req;
 use crate::requests::pod_req::SavedEpisodesResponse;
+use std::rc::Rc;
 use yew::prelude::*;
 use yew::{function_component, html, Html};
 use yew_router::history::BrowserHistory;
@@ -39,7 +41,8 @@ pub fn saved() -> Html {
 
     let session_dispatch = _post_dispatch.clone();
     let session_state = post_state.clone();
-    let loading = use_state(|| true);
+    // A copy already in AppState shows straight away while it refreshes
+    let loading = use_state(|| post_state.saved_episodes.is_none());
 
     use_effect_with((), move |_| {
         // Check if the page reload action has already occurred to prevent redundant execution
@@ -124,6 +127,8 @@ pub fn saved() -> Html {
 
         // fetch_episodes(api_key.flatten(), user_id, server_name, dispatch, error, pod_req::call_get_recent_eps);
 
+        let has_cached = post_state.saved_episodes.is_some();
+
         use_effect_with(
             (api_key.clone(), user_id.clone(), server_name.clone()),
             move |_| {
@@ -134,6 +139,21 @@ pub fn saved() -> Html {
                     let dispatch = effect_dispatch.clone();
 
                     wasm_bindgen_futures::spawn_local(async move {
+                        if has_cached && cache::is_fresh(&server_name, user_id, CachedList::Saved) {
+                            dispatch.reduce_mut(|state| {
+                                state.completed_episodes =
+                                    state.saved_episodes.as_ref().map(|saved| {
+                                        saved
+                                            .episodes
+                                            .iter()
+                                            .filter(|ep| ep.completed)
+                                            .map(|ep| ep.episodeid)
+                                            .collect()
+                                    });
+                            });
+                            loading_ep.set(false);
+                            return;
+                        }
                         match pod_req::call_get_saved_episodes(&server_name, &api_key, &user_id)
                             .await
                         {
@@ -150,6 +170,7 @@ pub fn saved() -> Html {
                                     });
                                     state.completed_episodes = Some(completed_episode_ids);
                                 });
+                                cache::mark_fetched(&server_name, user_id, CachedList::Saved);
                                 loading_ep.set(false);
                                 // web_sys::console::log_1(&format!("State after update: {:?}", state).into()); // Log state after update
                             }
@@ -203,7 +224,16 @@ pub fn saved() -> Html {
                                 "You can save episodes by clicking the context button on each episode and clicking 'Save Episode'. Doing this will save episodes here for easy access when you want to return to them."
                             )
                         } else {
-                            saved_eps.episodes.into_iter().map(|episode| {
+                            let count = saved_eps.episodes.len();
+                            let episodes = Rc::new(saved_eps.episodes);
+                            let post_state = post_state.clone();
+                            let history = history.clone();
+                            let state = state.clone();
+                            let dispatch = dispatch.clone();
+                            let audio_dispatch = audio_dispatch.clone();
+                            let audio_state = audio_state.clone();
+                            let render_episode = Callback::from(move |index: usize| {
+                                let episode = episodes[index].clone();
                                 let api_key = post_state.auth_details.as_ref().map(|ud| ud.api_key.clone());
                                 let user_id = post_state.user_details.as_ref().map(|ud| ud.UserID.clone());
                                 let server_name = post_state.auth_details.as_ref().map(|ud| ud.server_name.clone());
@@ -271,6 +301,7 @@ pub fn saved() -> Html {
                                     audio_dispatch.clone(),
                                     audio_state.clone(),
                                     None,
+                                    None,
                                 );
 
                                 let on_shownotes_click = on_shownotes_click(
@@ -307,7 +338,13 @@ pub fn saved() -> Html {
                                 );
 
                                 item
-                            }).collect::<Html>()
+                            });
+                            html! {
+                                <VirtualList
+                                    item_count={count}
+                                    render_item={render_episode}
+                                />
+                            }
                         }
 
                     } else {
@@ -1,6 +1,6 @@
 use super::app_drawer::App_drawer;
 use super::gen_components::{
-    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop,
+    empty_message, episode_item, on_shownotes_click, Search_nav, UseScrollToTop, VirtualList,
 };
 use crate::components::audio::on_play_click;
 use crate::components::audio::AudioPlayer;
@@ -19,6 +19,7 @@ use yewdux::prelude::*;
 use crate::components::episodes_layout::UIStateMsg;
 use crate::requests::login_requests::use_check_authentication;
 use async_std::task::sleep;
+use std::rc::Rc;
 use std::time::Duration;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
@@ -226,7 +227,18 @@ pub fn search(_props: &SearchProps) -> Html {
                                     "Perhaps try again, but search for something slightly different :/"
                                 )
                             } else {
-                                episodes.into_iter().map(|episode| {
+                                let count = episodes.len();
+                                let episodes = Rc::new(episodes);
+                                let state = state.clone();
+                                let dispatch = dispatch.clone();
+                                let history = history.clone();
+                                let api_key = api_key.clone();
+                                let server_name = server_name.clone();
+                                let user_id = user_id.clone();
+                                let audio_dispatch = audio_dispatch.clone();
+                                let audio_state = audio_state.clone();
+                                let render_episode = Callback::from(move |index: usize| {
+                                    let episode = episodes[index].clone();
                                     let id_string = &episode.episodeid.to_string();
 
                                     let is_expanded = state.expanded_descriptions.contains(id_string);
@@ -290,6 +302,7 @@ pub fn search(_props: &SearchProps) -> Html {
                                         audio_dispatch.clone(),
                                         audio_state.clone(),
                                         None,
+                                        None,
                                     );
 
                                     let on_shownotes_click = on_shownotes_click(
@@ -326,7 +339,13 @@ pub fn search(_props: &SearchProps) -> Html {
                                     );
 
                                     item
-                                }).collect::<Html>()
+                                });
+                                html! {
+                                    <VirtualList
+                                        item_count={count}
+                                        render_item={render_episode}
+                                    />
+                                }
                             }
                     // } else {
                     //     empty_message(
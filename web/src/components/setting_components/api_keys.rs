@@ -39,8 +39,8 @@ pub fn api_keys() -> Html {
                 if let Some(api_key) = api_key_cloned {
                     if let Some(server_name) = server_name_cloned {
                         match call_get_api_info(server_name, user_id.unwrap(), api_key.unwrap()).await {
-                            Ok(response) => {
-                                api_infos.set(response.api_info);
+                            Ok(api_info) => {
+                                api_infos.set(api_info);
                             },
                             Err(e) => {
                                 audio_dispatch_effect.reduce_mut(|audio_state| audio_state.error_message = Option::from(format!("Error getting API Info: {}", e)));
@@ -91,8 +91,8 @@ pub fn api_keys() -> Html {
             let new_api_key = new_api_key.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 match call_create_api_key(&server_name.unwrap(), user_id.unwrap(), &api_key.unwrap().unwrap()).await {
-                    Ok(response) => {
-                        new_api_key.set(response.api_key);
+                    Ok(created_key) => {
+                        new_api_key.set(created_key);
                         page_state.set(PageState::Shown); // Move to the edit page state
                     },
                     Err(e) => {
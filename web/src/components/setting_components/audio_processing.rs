@@ -0,0 +1,236 @@
+use crate::components::audio::route_current_episode;
+use crate::components::audio_graph;
+use crate::components::context::{AppState, UIState};
+use crate::requests::setting_reqs::{
+    call_get_audio_processing, call_set_audio_processing, eq_bands_with_gains, AudioProcessing,
+    EqPreset, SetAudioProcessingRequest, EQ_BAND_LABELS,
+};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+// Loudness targets offered in the dropdown, in dB
+const LOUDNESS_TARGETS: [(f64, &str); 4] = [
+    (-14.0, "-14 dB (loud, like music streaming)"),
+    (-16.0, "-16 dB (podcast standard)"),
+    (-19.0, "-19 dB (quieter, mono podcasts)"),
+    (-23.0, "-23 dB (broadcast)"),
+];
+
+#[function_component(AudioProcessingOptions)]
+pub fn audio_processing_options() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let processing = use_state(|| audio_state.audio_processing.clone().unwrap_or_default());
+
+    {
+        let processing = processing.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        let api_key = state
+            .auth_details
+            .as_ref()
+            .and_then(|ud| ud.api_key.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let loaded = audio_state.audio_processing.is_some();
+        use_effect_with((), move |_| {
+            if let (false, Some(api_key), Some(server_name), Some(user_id)) =
+                (loaded, api_key, server_name, user_id)
+            {
+                spawn_local(async move {
+                    match call_get_audio_processing(server_name, api_key, &user_id).await {
+                        Ok(fetched) => {
+                            let fetched = fetched.unwrap_or_default();
+                            processing.set(fetched.clone());
+                            audio_dispatch
+                                .reduce_mut(move |state| state.audio_processing = Some(fetched));
+                        }
+                        Err(e) => web_sys::console::log_1(
+                            &format!("Error getting audio processing settings: {}", e).into(),
+                        ),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let update = {
+        let processing = processing.clone();
+        move |change: Box<dyn Fn(&mut AudioProcessing)>| {
+            let mut updated = (*processing).clone();
+            change(&mut updated);
+            processing.set(updated);
+        }
+    };
+
+    let on_toggle_enabled = {
+        let update = update.clone();
+        Callback::from(move |_: MouseEvent| {
+            update(Box::new(|processing| {
+                processing.enabled = !processing.enabled
+            }))
+        })
+    };
+    let on_toggle_compressor = {
+        let update = update.clone();
+        Callback::from(move |_: MouseEvent| {
+            update(Box::new(|processing| {
+                processing.compressor = !processing.compressor
+            }))
+        })
+    };
+    let on_loudness_change = {
+        let update = update.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                let target = select.value().parse::<f64>().ok();
+                update(Box::new(move |processing| {
+                    processing.loudness_target = target
+                }))
+            }
+        })
+    };
+    let on_preset_change = {
+        let update = update.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                let preset = EqPreset::from_id(&select.value());
+                update(Box::new(move |processing| {
+                    processing.eq_preset = preset;
+                    // Custom keeps whatever the bands were set to
+                    if let Some(gains) = preset.gains() {
+                        processing.eq_bands = eq_bands_with_gains(gains);
+                    }
+                }))
+            }
+        })
+    };
+
+    let on_submit = {
+        let processing = processing.clone();
+        let state = state.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let processing = (*processing).clone();
+            let api_key = state
+                .auth_details
+                .as_ref()
+                .and_then(|ud| ud.api_key.clone());
+            let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+            let user_id = match state.user_details.as_ref().map(|ud| ud.UserID) {
+                Some(user_id) => user_id,
+                None => return,
+            };
+            let request = SetAudioProcessingRequest {
+                user_id,
+                audio_processing: processing.clone(),
+            };
+
+            let audio_dispatch = audio_dispatch.clone();
+            spawn_local(async move {
+                match call_set_audio_processing(&server_name, &api_key, &request).await {
+                    Ok(_) => {
+                        let enabled = processing.enabled;
+                        audio_dispatch.reduce_mut(move |audio_state| {
+                            audio_state.audio_processing = Some(processing);
+                            audio_state.info_message =
+                                Some("Audio Processing Updated!".to_string());
+                        });
+                        // Apply the new chain to whatever is playing right now
+                        let current = audio_dispatch.get();
+                        let routed = current
+                            .audio_element
+                            .as_ref()
+                            .map_or(false, audio_graph::is_routed);
+                        if current.currently_playing.is_some()
+                            && (enabled || routed)
+                            && !route_current_episode(&audio_dispatch).await
+                        {
+                            audio_dispatch.reduce_mut(|audio_state| {
+                                audio_state.info_message = Some("Audio Processing Updated! The current episode's host doesn't allow cross-origin audio, so it plays unprocessed.".to_string())
+                            });
+                        }
+                    }
+                    Err(e) => audio_dispatch.reduce_mut(|audio_state| {
+                        audio_state.error_message =
+                            Some(format!("Error Updating Audio Processing: {}", e))
+                    }),
+                }
+            });
+        })
+    };
+
+    let current_target = processing.loudness_target;
+    let bands = processing.bands();
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{"Audio Processing:"}</p>
+            <p class="item_container-text text-md mb-4">{"Even out the loudness of the shows you listen to and shape their sound. The compressor narrows the gap between loud and quiet voices, the loudness target brings every episode to a similar level and the equalizer boosts or cuts parts of the sound. These settings are saved to your user settings and only apply to podcasts whose host allows cross-origin audio, along with your downloads in the app."}</p>
+
+            <div class="audio-processing-row">
+                <label for="processing-enabled" class="item_container-text">{"Enable Audio Processing"}</label>
+                <input type="checkbox" id="processing-enabled" checked={processing.enabled} onclick={on_toggle_enabled} />
+            </div>
+            <div class="audio-processing-row">
+                <label for="processing-compressor" class="item_container-text">{"Compressor"}</label>
+                <input type="checkbox" id="processing-compressor" checked={processing.compressor} disabled={!processing.enabled} onclick={on_toggle_compressor} />
+            </div>
+            <div class="audio-processing-row">
+                <label for="processing-loudness" class="item_container-text">{"Loudness Target"}</label>
+                <select id="processing-loudness" disabled={!processing.enabled} onchange={on_loudness_change} class="theme-select-dropdown appearance-none border px-4 py-2 pr-8 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    <option value="off" selected={current_target.is_none()}>{"Off"}</option>
+                    { for LOUDNESS_TARGETS.iter().map(|(target, label)| html! {
+                        <option value={target.to_string()} selected={current_target == Some(*target)}>{ *label }</option>
+                    })}
+                </select>
+            </div>
+            <div class="audio-processing-row">
+                <label for="processing-eq" class="item_container-text">{"Equalizer Preset"}</label>
+                <select id="processing-eq" disabled={!processing.enabled} onchange={on_preset_change} class="theme-select-dropdown appearance-none border px-4 py-2 pr-8 rounded shadow leading-tight focus:outline-none focus:shadow-outline">
+                    { for EqPreset::ALL.iter().map(|preset| html! {
+                        <option value={preset.id()} selected={processing.eq_preset == *preset}>{ preset.label() }</option>
+                    })}
+                </select>
+            </div>
+            { for bands.iter().enumerate().map(|(index, band)| {
+                let on_gain_input = {
+                    let update = update.clone();
+                    Callback::from(move |e: InputEvent| {
+                        if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                            let gain = input.value_as_number();
+                            update(Box::new(move |processing| {
+                                let mut bands = processing.bands();
+                                bands[index].gain = gain;
+                                processing.eq_bands = bands;
+                                processing.eq_preset = EqPreset::Custom;
+                            }))
+                        }
+                    })
+                };
+                html! {
+                    <div class="audio-processing-row">
+                        <label class="item_container-text">
+                            { format!("{} ({} Hz): {:+.0} dB", EQ_BAND_LABELS[index], band.frequency, band.gain) }
+                        </label>
+                        <input
+                            type="range"
+                            min="-12"
+                            max="12"
+                            step="1"
+                            disabled={!processing.enabled}
+                            value={band.gain.to_string()}
+                            oninput={on_gain_input}
+                        />
+                    </div>
+                }
+            })}
+
+            <button onclick={on_submit} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                {"Submit"}
+            </button>
+        </div>
+    }
+}
@@ -1,13 +1,16 @@
 use yew::prelude::*;
 use yewdux::prelude::*;
 use crate::components::context::AppState;
-use crate::requests::setting_reqs::call_add_custom_feed;
-use web_sys::HtmlInputElement;
+use crate::requests::setting_reqs::{call_add_custom_feed, FeedAuth};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use gloo_timers::callback::Timeout;
 
 #[function_component(CustomFeed)]
 pub fn custom_feed() -> Html {
     let feed_url = use_state(|| "".to_string());
+    // "none", "basic" or "header"
+    let auth_mode = use_state(|| "none".to_string());
+    let feed_auth = use_state(FeedAuth::default);
     let error_message = use_state(|| None::<String>);
     let info_message = use_state(|| None::<String>);
 
@@ -24,7 +27,32 @@ pub fn custom_feed() -> Html {
             let input: HtmlInputElement = e.target_dyn_into().unwrap();
             feed_url.set(input.value());
         })
-    };  
+    };
+
+    let on_auth_mode_change = {
+        let auth_mode = auth_mode.clone();
+        let feed_auth = feed_auth.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            auth_mode.set(select.value());
+            feed_auth.set(FeedAuth::default());
+        })
+    };
+    // Updates one of the credential fields, empty inputs count as not set
+    let update_auth = |set: fn(&mut FeedAuth, Option<String>)| {
+        let feed_auth = feed_auth.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut auth = (*feed_auth).clone();
+            set(&mut auth, Some(input.value()).filter(|value| !value.is_empty()));
+            feed_auth.set(auth);
+        })
+    };
+    let update_username = update_auth(|auth, value| auth.username = value);
+    let update_password = update_auth(|auth, value| auth.password = value);
+    let update_header_name = update_auth(|auth, value| auth.header_name = value);
+    let update_header_value = update_auth(|auth, value| auth.header_value = value);
+
     // Function to clear message
     let clear_error = {
         let error_message = error_message.clone();
@@ -46,6 +74,7 @@ pub fn custom_feed() -> Html {
         let server_name = server_name.unwrap_or_default();
         let user_id = user_id;
         let feed_url = (*feed_url).clone();
+        let feed_auth = (*feed_auth).clone();
         let error_message = error_message.clone();
         let info_message = info_message.clone();
         let clear_info = clear_info.clone();
@@ -56,10 +85,11 @@ pub fn custom_feed() -> Html {
             let server_name = server_name.clone();
             let api_key = api_key.clone();
             let feed_url = feed_url.clone();
+            let feed_auth = feed_auth.clone();
             let error_message = error_message.clone();
             let info_message = info_message.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                match call_add_custom_feed(&server_name, &feed_url, &user_id.unwrap(), &api_key.unwrap()).await {
+                match call_add_custom_feed(&server_name, &feed_url, &user_id.unwrap(), &api_key.unwrap(), &feed_auth).await {
                     Ok(message) => {
                         info_message.set(Some(message));
                         Timeout::new(5000, move || { clear_info.emit(()) }).forget();
@@ -83,6 +113,27 @@ pub fn custom_feed() -> Html {
                 <div>
                     <input id="feed_url" oninput={update_feed.clone()} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder="https://bestpodcast.com/feed.xml" />
                 </div>
+                <div class="mt-2">
+                    <label for="feed_auth_mode" class="item_container-text text-sm mr-2">{"Login:"}</label>
+                    <select id="feed_auth_mode" onchange={on_auth_mode_change} class="theme-select-dropdown border text-sm rounded-lg p-2">
+                        <option value="none" selected={*auth_mode == "none"}>{"None"}</option>
+                        <option value="basic" selected={*auth_mode == "basic"}>{"Username and password"}</option>
+                        <option value="header" selected={*auth_mode == "header"}>{"Custom header"}</option>
+                    </select>
+                </div>
+                // Stored apart from the feed URL, so they don't end up in exports
+                if *auth_mode == "basic" {
+                    <div class="mt-2 flex gap-2">
+                        <input oninput={update_username} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder="Username" autocomplete="off" />
+                        <input type="password" oninput={update_password} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder="Password" autocomplete="new-password" />
+                    </div>
+                }
+                if *auth_mode == "header" {
+                    <div class="mt-2 flex gap-2">
+                        <input oninput={update_header_name} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder="Authorization" autocomplete="off" />
+                        <input type="password" oninput={update_header_value} class="search-bar-input border text-sm rounded-lg block w-full p-2.5" placeholder="Bearer your-token" autocomplete="new-password" />
+                    </div>
+                }
                 // Display error message inline right below the text input
                 if let Some(error) = &*error_message {
                     <span class="text-red-600 text-xs">{ error }</span>
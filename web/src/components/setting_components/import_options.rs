@@ -1,20 +1,25 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
+use futures_util::stream::{self, StreamExt};
 use yew::prelude::*;
 use wasm_bindgen::JsCast;
 use yewdux::prelude::*;
 use crate::components::context::{UIState, AppState};
 use web_sys::{FileReader, HtmlInputElement};
 use wasm_bindgen::closure::Closure;
-use crate::components::gen_funcs::parse_opml;
-use crate::requests::pod_req::{call_add_podcast, PodcastValues};
+use crate::components::gen_funcs::{parse_opml, OpmlFeed};
+use crate::requests::pod_req::{call_add_podcast, call_check_podcast, PodcastValues};
 use crate::requests::search_pods::{call_parse_podcast_channel_info, PodcastInfo};
 
+// How many feeds are checked and added at once, a big OPML shouldn't open a connection per feed
+const IMPORT_CONCURRENCY: usize = 4;
 
 // use wasm_bindgen::JsValue;
 // use crate::requests::setting_reqs::{call_backup_user};
-fn transform_feed_result_to_values(feed_result: PodcastInfo, podcast_to_add: &PodcastToAdd, user_id: i32) -> PodcastValues {
-    let pod_title = podcast_to_add.title.clone();
-    let pod_feed_url = podcast_to_add.xml_url.clone();
+fn transform_feed_result_to_values(feed_result: PodcastInfo, feed: &OpmlFeed, user_id: i32) -> PodcastValues {
+    let pod_title = if feed.title.is_empty() { feed_result.title.clone() } else { feed.title.clone() };
+    let pod_feed_url = feed.xml_url.clone();
 
 
     // Simplified: Using first episode details or default values
@@ -26,8 +31,18 @@ fn transform_feed_result_to_values(feed_result: PodcastInfo, podcast_to_add: &Po
     let pod_episode_count = feed_result.episode_count;
 
 
-    // Placeholder for categories, as an example
-    let categories = HashMap::new();
+    // The folders the feed was filed under in the OPML become categories next to the feed's own
+    let mut category_names: Vec<String> = Vec::new();
+    for name in feed_result.categories.into_iter().chain(feed.folders.iter().cloned()) {
+        if !category_names.iter().any(|existing| existing.eq_ignore_ascii_case(&name)) {
+            category_names.push(name);
+        }
+    }
+    let categories = category_names
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| (index.to_string(), name))
+        .collect();
 
     PodcastValues {
         pod_title,
@@ -44,43 +59,127 @@ fn transform_feed_result_to_values(feed_result: PodcastInfo, podcast_to_add: &Po
 }
 
 
-#[derive(Debug, Clone)]
-pub struct PodcastToAdd {
-    title: String,
-    xml_url: String,
-}
-
 #[derive(Clone, Debug)]
 struct PodcastToImport {
-    title: String,
-    xml_url: String,
+    feed: OpmlFeed,
     selected: bool,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+enum ImportStatus {
+    Checking,
+    Adding,
+    Added,
+    Skipped,
+    Failed(String),
+}
 
-async fn add_podcasts(server_name: &str, api_key: &Option<String>, user_id: i32, podcasts: Vec<PodcastToAdd>) {
-    for podcast in podcasts.into_iter() {
-        // Parse podcast URL to get feed details
-        match call_parse_podcast_channel_info(&podcast.xml_url).await {
-            Ok(feed_result) => {
-                let add_podcast = PodcastToAdd {
-                    title: podcast.title.clone(),
-                    xml_url: podcast.xml_url.clone()
-                };
-                // Assuming you transform `feed_result` into `PodcastValues` needed by `call_add_podcast`
-                let podcast_values = transform_feed_result_to_values(feed_result, &add_podcast, user_id);
+impl ImportStatus {
+    fn label(&self) -> String {
+        match self {
+            ImportStatus::Checking => "Checking feed...".to_string(),
+            ImportStatus::Adding => "Adding...".to_string(),
+            ImportStatus::Added => "Added".to_string(),
+            ImportStatus::Skipped => "Already subscribed".to_string(),
+            ImportStatus::Failed(error) => format!("Failed: {}", error),
+        }
+    }
 
-                // Add podcast to the server
-                match call_add_podcast(server_name, api_key, user_id, &podcast_values).await {
-                    Ok(_) => log::info!("Podcast added successfully: {}", podcast.title.clone()),
-                    Err(e) => log::error!("Failed to add podcast {}: {:?}", podcast.title.clone(), e),
-                }
-            },
-            Err(e) => log::error!("Failed to parse podcast URL {}: {:?}", podcast.xml_url, e),
+    fn class(&self) -> &'static str {
+        match self {
+            ImportStatus::Checking | ImportStatus::Adding => "import-status",
+            ImportStatus::Added | ImportStatus::Skipped => "import-status import-status-done",
+            ImportStatus::Failed(_) => "import-status import-status-failed",
         }
     }
+
+    fn is_finished(&self) -> bool {
+        !matches!(self, ImportStatus::Checking | ImportStatus::Adding)
+    }
 }
 
+type ImportStatuses = Rc<RefCell<HashMap<String, ImportStatus>>>;
+
+async fn already_subscribed(server_name: &str, api_key: &str, user_id: i32, title: &str, xml_url: &str) -> bool {
+    if title.is_empty() {
+        return false;
+    }
+    call_check_podcast(server_name, api_key, user_id, title, xml_url)
+        .await
+        .map(|response| response.exists)
+        .unwrap_or(false)
+}
+
+async fn import_feed(
+    server_name: &str,
+    api_key: &str,
+    user_id: i32,
+    feed: &OpmlFeed,
+    set_status: &dyn Fn(ImportStatus),
+) -> ImportStatus {
+    set_status(ImportStatus::Checking);
+    if already_subscribed(server_name, api_key, user_id, &feed.title, &feed.xml_url).await {
+        return ImportStatus::Skipped;
+    }
+    let feed_result = match call_parse_podcast_channel_info(server_name, api_key, &feed.xml_url).await {
+        Ok(feed_result) => feed_result,
+        Err(e) => return ImportStatus::Failed(e.to_string()),
+    };
+    // Podcasts added from search are stored under the feed's own title rather than the OPML's
+    if feed_result.title != feed.title
+        && already_subscribed(server_name, api_key, user_id, &feed_result.title, &feed.xml_url).await
+    {
+        return ImportStatus::Skipped;
+    }
+    set_status(ImportStatus::Adding);
+    let podcast_values = transform_feed_result_to_values(feed_result, feed, user_id);
+    match call_add_podcast(server_name, &Some(api_key.to_string()), user_id, &podcast_values).await {
+        Ok(_) => ImportStatus::Added,
+        Err(e) => ImportStatus::Failed(e.to_string()),
+    }
+}
+
+async fn import_feeds(
+    server_name: String,
+    api_key: String,
+    user_id: i32,
+    feeds: Vec<OpmlFeed>,
+    statuses: ImportStatuses,
+    redraw: UseForceUpdateHandle,
+) {
+    stream::iter(feeds)
+        .for_each_concurrent(IMPORT_CONCURRENCY, |feed| {
+            let server_name = server_name.clone();
+            let api_key = api_key.clone();
+            let statuses = statuses.clone();
+            let redraw = redraw.clone();
+            async move {
+                let set_status = |status: ImportStatus| {
+                    statuses.borrow_mut().insert(feed.xml_url.clone(), status);
+                    redraw.force_update();
+                };
+                let status = import_feed(&server_name, &api_key, user_id, &feed, &set_status).await;
+                if let ImportStatus::Failed(error) = &status {
+                    log::error!("Failed to import {}: {}", feed.xml_url, error);
+                }
+                set_status(status);
+            }
+        })
+        .await;
+}
+
+// Feeds grouped by the folder path they were found under, in the order the file lists them
+fn group_by_folder(podcasts: &[PodcastToImport]) -> Vec<(String, Vec<usize>)> {
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for (index, podcast) in podcasts.iter().enumerate() {
+        let folder = podcast.feed.folders.join(" / ");
+        match groups.iter_mut().find(|(name, _)| *name == folder) {
+            Some((_, indexes)) => indexes.push(index),
+            None => groups.push((folder, vec![index])),
+        }
+    }
+    groups
+}
 
 
 #[function_component(ImportOptions)]
@@ -89,18 +188,26 @@ pub fn import_options() -> Html {
     let user_id = state.user_details.as_ref().map(|ud| ud.UserID.clone());
     let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
     let api_key = state.auth_details.as_ref().map(|ud| ud.api_key.clone());
-    let import_pods = use_state(|| Vec::new());
+    let import_pods = use_state(|| Vec::<PodcastToImport>::new());
     let show_verification = use_state(|| false);
+    let importing = use_state(|| false);
+    // Written to by every running import, so they live outside of the component state
+    let statuses: ImportStatuses = use_mut_ref(HashMap::new);
+    let redraw = use_force_update();
     let (_audio_state, audio_dispatch) = use_store::<UIState>();
 
 
     let onclick = {
         let import_pods = import_pods.clone();
         let show_verification = show_verification.clone();
+        let statuses = statuses.clone();
+        let audio_dispatch = audio_dispatch.clone();
         Callback::from(move |e: Event| {
             // let server_name = server_name.clone();
             let show_verification = show_verification.clone();
             let import_pods = import_pods.clone();
+            let statuses = statuses.clone();
+            let audio_dispatch = audio_dispatch.clone();
             let file_list = e.target_unchecked_into::<HtmlInputElement>().files();
             if let Some(files) = file_list {
                 if let Some(file) = files.get(0) {
@@ -111,10 +218,14 @@ pub fn import_options() -> Html {
                             let text = text.as_string().unwrap();
                             let import_data: Vec<PodcastToImport> = parse_opml(&text)
                                 .into_iter()
-                                .map(|(title, xml_url)| PodcastToImport { title, xml_url, selected: true })
+                                .map(|feed| PodcastToImport { feed, selected: true })
                                 .collect();
+                            if import_data.is_empty() {
+                                audio_dispatch.reduce_mut(|audio_state| audio_state.error_message = Some("No podcasts found in that file".to_string()));
+                            }
+                            statuses.borrow_mut().clear();
+                            show_verification.set(!import_data.is_empty());
                             import_pods.set(import_data);
-                            show_verification.set(true);
                         }
                     }) as Box<dyn FnMut(_)>);
                     reader.set_onload(Some(onload.as_ref().unchecked_ref()));
@@ -124,39 +235,87 @@ pub fn import_options() -> Html {
             }
         })
     };
-    
-    let server_name_confirm = server_name.clone();
-    let dispatch_wasm = _dispatch.clone();
-    let on_confirm = {
-        let import_pods = import_pods.clone();
-        let server_name = server_name_confirm.clone();
+
+    let start_import = {
+        let server_name = server_name.clone();
         let api_key = api_key.clone();
         let user_id = user_id.clone();
+        let importing = importing.clone();
+        let statuses = statuses.clone();
+        let redraw = redraw.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |feeds: Vec<OpmlFeed>| {
+            let (Some(server_name), Some(Some(api_key)), Some(user_id)) = (server_name.clone(), api_key.clone(), user_id) else {
+                return;
+            };
+            if feeds.is_empty() {
+                return;
+            }
+            importing.set(true);
+            let importing = importing.clone();
+            let statuses = statuses.clone();
+            let redraw = redraw.clone();
+            let audio_dispatch = audio_dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let urls: Vec<String> = feeds.iter().map(|feed| feed.xml_url.clone()).collect();
+                import_feeds(server_name, api_key, user_id, feeds, statuses.clone(), redraw).await;
+                let statuses = statuses.borrow();
+                let count = |matches: fn(&ImportStatus) -> bool| {
+                    urls.iter().filter(|url| statuses.get(*url).is_some_and(matches)).count()
+                };
+                let added = count(|status| *status == ImportStatus::Added);
+                let skipped = count(|status| *status == ImportStatus::Skipped);
+                let failed = count(|status| matches!(status, ImportStatus::Failed(_)));
+                let message = format!("Import finished: {} added, {} already subscribed, {} failed", added, skipped, failed);
+                audio_dispatch.reduce_mut(|audio_state| {
+                    if failed > 0 {
+                        audio_state.error_message = Some(message);
+                    } else {
+                        audio_state.info_message = Some(message);
+                    }
+                });
+                importing.set(false);
+            });
+        })
+    };
+
+    let on_confirm = {
+        let import_pods = import_pods.clone();
+        let statuses = statuses.clone();
+        let start_import = start_import.clone();
         Callback::from(move |_| {
-            dispatch_wasm.reduce_mut(|state| state.is_loading = Some(true));
+            statuses.borrow_mut().clear();
             // Filter for selected podcasts
-            let server_name = server_name.clone();
-            let api_key = api_key.clone();
-            let dispatch_wasm = dispatch_wasm.clone();
-            let audio_dispatch = audio_dispatch.clone();
-            let selected_podcasts: Vec<PodcastToAdd> = (*import_pods)
+            let selected_podcasts: Vec<OpmlFeed> = (*import_pods)
                 .iter()
                 .filter(|podcast| podcast.selected)
-                .map(|podcast| PodcastToAdd { title: podcast.title.clone(), xml_url: podcast.xml_url.clone() })
+                .map(|podcast| podcast.feed.clone())
                 .collect();
-    
-            wasm_bindgen_futures::spawn_local(async move {
-                // Your existing logic to add podcasts
-                if let (Some(server_name), Some(api_key), Some(user_id)) = (server_name.as_ref(), api_key.as_ref(), user_id) {
-                    add_podcasts(server_name, &Some(api_key.clone().unwrap()), user_id, selected_podcasts.clone()).await;
-                }
-                dispatch_wasm.reduce_mut(|state| state.is_loading = Some(false));
-                audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from("Selected Podcasts Added!".to_string()));
-
-            });
+            start_import.emit(selected_podcasts);
         })
     };
-    
+
+    let failed_feeds: Vec<OpmlFeed> = {
+        let statuses = statuses.borrow();
+        (*import_pods)
+            .iter()
+            .filter(|podcast| matches!(statuses.get(&podcast.feed.xml_url), Some(ImportStatus::Failed(_))))
+            .map(|podcast| podcast.feed.clone())
+            .collect()
+    };
+    let on_retry = {
+        let start_import = start_import.clone();
+        let failed_feeds = failed_feeds.clone();
+        Callback::from(move |_| start_import.emit(failed_feeds.clone()))
+    };
+
+    let progress = {
+        let statuses = statuses.borrow();
+        let started = statuses.len();
+        let finished = statuses.values().filter(|status| status.is_finished()).count();
+        (started > 0).then(|| format!("{} of {} feeds done", finished, started))
+    };
+
 
     html! {
         <div class="p-4">
@@ -164,7 +323,7 @@ pub fn import_options() -> Html {
             <p class="item_container-text text-md mb-4">{"You can Import an OPML of podcasts here. If you're migrating from a different podcast app this is probably the solution you want. Most podcast apps allow you to export a backup of your saved podcasts to an OPML file and this option can easily import them into Pinepods."}</p>
             // <input class="settings-button" type="file" accept=".opml" onchange={onclick} />
             <label class="input-button-label" for="fileInput">{ "Choose File" }</label>
-            <input id="fileInput" class="input-button" type="file" accept=".opml" onchange={onclick} />
+            <input id="fileInput" class="input-button" type="file" accept=".opml,.xml" onchange={onclick} />
             // Optionally display the content of the OPML file for debugging
             {
                 if *show_verification {
@@ -172,31 +331,83 @@ pub fn import_options() -> Html {
                         <div class="import-box">
                             <div>
                                 <p class="item_container-text">
-                                    {"The following podcasts were found. Please unselect any podcasts you don't want to add, and then click the button below. A large amount of podcasts will take a little while to parse all the feeds and add them. The loading animation will disappear once all complete. Be patient!"}
+                                    {"The following podcasts were found. Please unselect any podcasts you don't want to add, and then click the button below. Podcasts you're already subscribed to are skipped, and folders from the file are kept as categories. Each feed shows how its import went as it finishes."}
                                 </p>
-                                <button class="settings-button" onclick={on_confirm}>{"Add them!"}</button>
+                                <button class="settings-button" onclick={on_confirm} disabled={*importing}>{"Add them!"}</button>
+                                {
+                                    if !*importing && !failed_feeds.is_empty() {
+                                        html! {
+                                            <button class="settings-button ml-2" onclick={on_retry}>
+                                                {format!("Retry {} failed", failed_feeds.len())}
+                                            </button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                {
+                                    if let Some(progress) = progress {
+                                        html! { <p class="item_container-text mt-2">{progress}</p> }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
                             </div>
                             {
-                                for (*import_pods).iter().enumerate().map(|(index, podcast)| {
-                                    let toggle_selection = {
+                                for group_by_folder(&import_pods).into_iter().map(|(folder, indexes)| {
+                                    let all_selected = indexes.iter().all(|index| import_pods[*index].selected);
+                                    let toggle_folder = {
                                         let import_pods = import_pods.clone();
+                                        let indexes = indexes.clone();
                                         Callback::from(move |_| {
                                             let mut new_import_pods = (*import_pods).clone();
-                                            new_import_pods[index].selected = !new_import_pods[index].selected;
+                                            for index in &indexes {
+                                                new_import_pods[*index].selected = !all_selected;
+                                            }
                                             import_pods.set(new_import_pods);
                                         })
                                     };
-                                
                                     html! {
-                                        <div class="podcast import-list">
-                                            <label onclick={toggle_selection}>
-                                                <input type="checkbox" checked={podcast.selected} />
-                                                <span class="item_container-text">{format!("{} - {}", podcast.title, podcast.xml_url)}</span>
+                                        <div class="import-folder">
+                                            <label class="import-list import-folder-title">
+                                                <input type="checkbox" checked={all_selected} disabled={*importing} onclick={toggle_folder} />
+                                                <span class="item_container-text font-bold">
+                                                    {if folder.is_empty() { "Not in a folder".to_string() } else { folder }}
+                                                </span>
                                             </label>
+                                            {
+                                                for indexes.into_iter().map(|index| {
+                                                    let podcast = &import_pods[index];
+                                                    let toggle_selection = {
+                                                        let import_pods = import_pods.clone();
+                                                        Callback::from(move |_| {
+                                                            let mut new_import_pods = (*import_pods).clone();
+                                                            new_import_pods[index].selected = !new_import_pods[index].selected;
+                                                            import_pods.set(new_import_pods);
+                                                        })
+                                                    };
+                                                    let status = statuses.borrow().get(&podcast.feed.xml_url).cloned();
+
+                                                    html! {
+                                                        <div class="podcast import-list">
+                                                            <label>
+                                                                <input type="checkbox" checked={podcast.selected} disabled={*importing} onclick={toggle_selection} />
+                                                                <span class="item_container-text">{format!("{} - {}", podcast.feed.title, podcast.feed.xml_url)}</span>
+                                                            </label>
+                                                            {
+                                                                if let Some(status) = status {
+                                                                    html! { <span class={status.class()}>{status.label()}</span> }
+                                                                } else {
+                                                                    html! {}
+                                                                }
+                                                            }
+                                                        </div>
+                                                    }
+                                                })
+                                            }
                                         </div>
                                     }
-                                })                                
-                                
+                                })
                             }
                         </div>
                     }
@@ -205,6 +416,6 @@ pub fn import_options() -> Html {
                 }
             }
         </div>
-        
+
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,164 @@
+use crate::components::context::{AppState, UIState};
+use crate::components::keybindings::{key_from_event, KeyAction, ShortcutKeys};
+use crate::requests::setting_reqs::{call_set_keybindings, SetKeyBindingsRequest};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[function_component(KeyboardShortcutOptions)]
+pub fn keyboard_shortcut_options() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    // The action being rebound and the keys recorded for it so far
+    let editing: UseStateHandle<Option<(KeyAction, Vec<String>)>> = use_state(|| None);
+
+    let on_capture_keydown = {
+        let editing = editing.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            e.prevent_default();
+            if let Some((action, keys)) = (*editing).clone() {
+                let key = match key_from_event(&e) {
+                    Some(key) => key,
+                    None => return,
+                };
+                match key.as_str() {
+                    "Escape" => editing.set(None),
+                    "Backspace" => editing.set(Some((action, Vec::new()))),
+                    // Bindings are at most two keys long, like `g h`
+                    _ if keys.len() >= 2 => editing.set(Some((action, vec![key]))),
+                    _ => {
+                        let mut keys = keys;
+                        keys.push(key);
+                        editing.set(Some((action, keys)));
+                    }
+                }
+            }
+        })
+    };
+
+    let on_apply = {
+        let editing = editing.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some((action, keys)) = (*editing).clone() {
+                if !keys.is_empty() {
+                    let binding = keys.join(" ");
+                    audio_dispatch
+                        .reduce_mut(move |state| state.keybindings.rebind(action, binding));
+                }
+            }
+            editing.set(None);
+        })
+    };
+
+    let on_cancel = {
+        let editing = editing.clone();
+        Callback::from(move |_: MouseEvent| editing.set(None))
+    };
+
+    let on_submit = {
+        let state = state.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let keybindings = audio_dispatch.get().keybindings.clone();
+            keybindings.store_local();
+
+            let api_key = state
+                .auth_details
+                .as_ref()
+                .and_then(|ud| ud.api_key.clone());
+            let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+            let user_id = match state.user_details.as_ref().map(|ud| ud.UserID) {
+                Some(user_id) => user_id,
+                None => return,
+            };
+            let request = SetKeyBindingsRequest {
+                user_id,
+                keybindings: keybindings.overrides().clone(),
+            };
+
+            let audio_dispatch = audio_dispatch.clone();
+            spawn_local(async move {
+                match call_set_keybindings(&server_name, &api_key, &request).await {
+                    Ok(_) => audio_dispatch.reduce_mut(|audio_state| {
+                        audio_state.info_message = Some("Keyboard Shortcuts Updated!".to_string())
+                    }),
+                    Err(e) => audio_dispatch.reduce_mut(|audio_state| {
+                        audio_state.error_message =
+                            Some(format!("Error Updating Keyboard Shortcuts: {}", e))
+                    }),
+                }
+            });
+        })
+    };
+
+    let keybindings = &audio_state.keybindings;
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{"Keyboard Shortcuts:"}</p>
+            <p class="item_container-text text-md mb-4">{"Rebind any shortcut by pressing Change and then the new keys. Two keys pressed one after the other, like g then h, make a sequence. Press ? anywhere in the app to see every shortcut. Shortcuts are saved to your user settings alongside your theme."}</p>
+
+            <div class="shortcut-settings-list">
+                { for KeyAction::ALL.iter().map(|action| {
+                    let action = *action;
+                    let is_editing = editing.as_ref().map_or(false, |(editing_action, _)| *editing_action == action);
+
+                    let on_change = {
+                        let editing = editing.clone();
+                        Callback::from(move |_: MouseEvent| editing.set(Some((action, Vec::new()))))
+                    };
+                    let on_reset = {
+                        let audio_dispatch = audio_dispatch.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            audio_dispatch.reduce_mut(move |state| state.keybindings.reset(action))
+                        })
+                    };
+
+                    let conflicts: Vec<&str> = keybindings
+                        .bindings_for(action)
+                        .iter()
+                        .flat_map(|binding| keybindings.conflicts(action, binding))
+                        .map(|other| other.description())
+                        .collect();
+
+                    html! {
+                        <div class="shortcut-row shortcut-settings-row">
+                            <span class="item_container-text">{ action.description() }</span>
+                            if is_editing {
+                                <div class="shortcut-capture">
+                                    <input
+                                        type="text"
+                                        readonly=true
+                                        autofocus=true
+                                        class="search-bar-input border text-sm rounded-lg p-2 shortcut-capture-input"
+                                        placeholder="Press keys..."
+                                        value={editing.as_ref().map(|(_, keys)| keys.join(" ")).unwrap_or_default()}
+                                        onkeydown={on_capture_keydown.clone()}
+                                    />
+                                    <button onclick={on_apply.clone()} class="theme-submit-button font-bold py-1 px-3 rounded" type="button">{"Apply"}</button>
+                                    <button onclick={on_cancel.clone()} class="theme-submit-button font-bold py-1 px-3 rounded" type="button">{"Cancel"}</button>
+                                </div>
+                            } else {
+                                <div class="shortcut-capture">
+                                    <ShortcutKeys bindings={keybindings.bindings_for(action)} />
+                                    <button onclick={on_change} class="theme-submit-button font-bold py-1 px-3 rounded" type="button">{"Change"}</button>
+                                    if keybindings.is_rebound(action) {
+                                        <button onclick={on_reset} class="theme-submit-button font-bold py-1 px-3 rounded" type="button">{"Reset"}</button>
+                                    }
+                                </div>
+                            }
+                            if !conflicts.is_empty() {
+                                <span class="shortcut-conflict">{ format!("Also bound to: {}", conflicts.join(", ")) }</span>
+                            }
+                        </div>
+                    }
+                })}
+            </div>
+
+            <button onclick={on_submit} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                {"Submit"}
+            </button>
+        </div>
+    }
+}
@@ -168,8 +168,8 @@ pub fn mfa_options() -> Html {
                 )
                 .await
                 {
-                    Ok(response) => {
-                        if response.verified {
+                    Ok(verified) => {
+                        if verified {
                             // Handle successful verification, e.g., updating UI state or navigating
                             page_state.set(PageState::Hidden); // Example: hiding MFA prompt
                         } else {
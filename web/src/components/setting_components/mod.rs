@@ -12,4 +12,7 @@ pub mod email_settings;
 pub mod backup_server;
 pub mod restore_server;
 pub mod custom_feed;
+pub mod keyboard_shortcuts;
+pub mod audio_processing;
+pub mod queue_settings;
 // ...other submodule declarations if any...
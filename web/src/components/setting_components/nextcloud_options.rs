@@ -209,8 +209,8 @@ pub fn nextcloud_options() -> Html {
                                         )
                                         .await
                                         {
-                                            Ok(response) => {
-                                                if response.data {
+                                            Ok(sync_enabled) => {
+                                                if sync_enabled {
                                                     log::info!("gPodder settings have been set up");
                                                     audio_dispatch.reduce_mut(|audio_state| audio_state.info_message = Option::from("Nextcloud server has been authenticated successfully".to_string()));
                                                     break;
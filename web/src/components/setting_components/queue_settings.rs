@@ -0,0 +1,120 @@
+use crate::components::context::{AppState, UIState};
+use crate::requests::setting_reqs::{
+    call_get_queue_settings, call_set_queue_settings, QueueSettings, SetQueueSettingsRequest,
+};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use yewdux::prelude::*;
+
+#[function_component(QueueSettingsOptions)]
+pub fn queue_settings_options() -> Html {
+    let (state, _dispatch) = use_store::<AppState>();
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let settings = use_state(|| audio_state.queue_settings.unwrap_or_default());
+
+    {
+        let settings = settings.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        let api_key = state
+            .auth_details
+            .as_ref()
+            .and_then(|ud| ud.api_key.clone());
+        let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+        let user_id = state.user_details.as_ref().map(|ud| ud.UserID);
+        let loaded = audio_state.queue_settings.is_some();
+        use_effect_with((), move |_| {
+            if let (false, Some(api_key), Some(server_name), Some(user_id)) =
+                (loaded, api_key, server_name, user_id)
+            {
+                spawn_local(async move {
+                    match call_get_queue_settings(&server_name, &api_key, user_id).await {
+                        Ok(fetched) => {
+                            settings.set(fetched);
+                            audio_dispatch
+                                .reduce_mut(move |state| state.queue_settings = Some(fetched));
+                        }
+                        Err(e) => web_sys::console::log_1(
+                            &format!("Error getting queue settings: {}", e).into(),
+                        ),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_toggle_auto_advance = {
+        let settings = settings.clone();
+        Callback::from(move |_: MouseEvent| {
+            settings.set(QueueSettings {
+                auto_advance: !settings.auto_advance,
+                ..*settings
+            })
+        })
+    };
+    let on_toggle_remove_completed = {
+        let settings = settings.clone();
+        Callback::from(move |_: MouseEvent| {
+            settings.set(QueueSettings {
+                remove_completed: !settings.remove_completed,
+                ..*settings
+            })
+        })
+    };
+
+    let on_submit = {
+        let settings = settings.clone();
+        let state = state.clone();
+        let audio_dispatch = audio_dispatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            let queue_settings = *settings;
+            let api_key = state
+                .auth_details
+                .as_ref()
+                .and_then(|ud| ud.api_key.clone());
+            let server_name = state.auth_details.as_ref().map(|ud| ud.server_name.clone());
+            let user_id = match state.user_details.as_ref().map(|ud| ud.UserID) {
+                Some(user_id) => user_id,
+                None => return,
+            };
+            let request = SetQueueSettingsRequest {
+                user_id,
+                queue_settings,
+            };
+
+            let audio_dispatch = audio_dispatch.clone();
+            spawn_local(async move {
+                match call_set_queue_settings(&server_name, &api_key, &request).await {
+                    Ok(_) => audio_dispatch.reduce_mut(move |audio_state| {
+                        audio_state.queue_settings = Some(queue_settings);
+                        audio_state.info_message = Some("Queue Settings Updated!".to_string());
+                    }),
+                    Err(e) => audio_dispatch.reduce_mut(|audio_state| {
+                        audio_state.error_message =
+                            Some(format!("Error Updating Queue Settings: {}", e))
+                    }),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="p-4">
+            <p class="item_container-text text-lg font-bold mb-4">{"Queue:"}</p>
+            <p class="item_container-text text-md mb-4">{"Choose what happens when an episode from your queue finishes. With auto-advance on, the next episode in the queue starts right away. You can reorder the queue by dragging episodes on the Queue page, or by focusing one and pressing Alt with the up or down arrow."}</p>
+
+            <div class="audio-processing-row">
+                <label for="queue-auto-advance" class="item_container-text">{"Play the next queued episode automatically"}</label>
+                <input type="checkbox" id="queue-auto-advance" checked={settings.auto_advance} onclick={on_toggle_auto_advance} />
+            </div>
+            <div class="audio-processing-row">
+                <label for="queue-remove-completed" class="item_container-text">{"Remove episodes from the queue once they finish"}</label>
+                <input type="checkbox" id="queue-remove-completed" checked={settings.remove_completed} onclick={on_toggle_remove_completed} />
+            </div>
+
+            <button onclick={on_submit} class="theme-submit-button mt-4 font-bold py-2 px-4 rounded focus:outline-none focus:shadow-outline" type="button">
+                {"Submit"}
+            </button>
+        </div>
+    }
+}
@@ -254,6 +254,9 @@ pub fn settings() -> Html {
                         html! {
                         <div id="accordion-collapse" data-accordion="collapse" class="bg-custom-light">
                             <AccordionItem title="Change Theme" content={html!{ <setting_components::theme_options::ThemeOptions /> }} position={AccordionItemPosition::First}/>
+                            <AccordionItem title="Keyboard Shortcuts" content={html!{ <setting_components::keyboard_shortcuts::KeyboardShortcutOptions /> }} position={AccordionItemPosition::Middle}/>
+                            <AccordionItem title="Audio Processing" content={html!{ <setting_components::audio_processing::AudioProcessingOptions /> }} position={AccordionItemPosition::Middle}/>
+                            <AccordionItem title="Queue" content={html!{ <setting_components::queue_settings::QueueSettingsOptions /> }} position={AccordionItemPosition::Middle}/>
                             <AccordionItem title="MFA Settings" content={html!{ <setting_components::mfa_settings::MFAOptions /> }} position={AccordionItemPosition::Middle}/>
                             <AccordionItem title="Export/Backup Podcasts" content={html!{ <setting_components::export_settings::ExportOptions /> }} position={AccordionItemPosition::Middle}/>
                             <AccordionItem title="Import Podcasts" content={html!{ <setting_components::import_options::ImportOptions /> }} position={AccordionItemPosition::Middle}/>
@@ -0,0 +1,200 @@
+use crate::components::context::UIState;
+use crate::components::gen_funcs::format_time;
+use crate::requests::chapters::current_chapter_index;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew::{function_component, html, Html};
+use yewdux::prelude::*;
+
+// How long the volume takes to fade to silence before the timer stops playback
+pub const SLEEP_FADE_SECONDS: f64 = 30.0;
+
+const SLEEP_TIMER_MINUTES: [u32; 6] = [5, 10, 15, 30, 45, 60];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SleepTimerMode {
+    Minutes(u32),
+    EndOfEpisode,
+    EndOfChapters(u32),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum SleepDeadline {
+    // Milliseconds since the epoch, so the countdown keeps running while paused
+    WallClock(f64),
+    // Playback position in seconds
+    Position(f64),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SleepTimer {
+    pub mode: SleepTimerMode,
+    deadline: SleepDeadline,
+    pub fading: bool,
+}
+
+impl SleepTimer {
+    pub fn start(mode: SleepTimerMode, state: &UIState) -> Self {
+        let deadline = match mode {
+            SleepTimerMode::Minutes(minutes) => {
+                SleepDeadline::WallClock(js_sys::Date::now() + minutes as f64 * 60_000.0)
+            }
+            SleepTimerMode::EndOfEpisode => SleepDeadline::Position(episode_end(state)),
+            SleepTimerMode::EndOfChapters(count) => {
+                let chapters = state.chapters.as_deref().unwrap_or_default();
+                let target = current_chapter_index(chapters, state.current_time_seconds)
+                    .map(|index| index + count as usize)
+                    .and_then(|index| chapters.get(index))
+                    .map(|chapter| chapter.start_time);
+                SleepDeadline::Position(target.unwrap_or_else(|| episode_end(state)))
+            }
+        };
+        SleepTimer {
+            mode,
+            deadline,
+            fading: false,
+        }
+    }
+
+    // Seconds of listening left before the timer fires
+    pub fn remaining_seconds(&self, state: &UIState) -> f64 {
+        match self.deadline {
+            SleepDeadline::WallClock(deadline) => (deadline - js_sys::Date::now()) / 1000.0,
+            SleepDeadline::Position(position) => {
+                let speed = if state.playback_speed > 0.0 {
+                    state.playback_speed
+                } else {
+                    1.0
+                };
+                (position - state.current_time_seconds) / speed
+            }
+        }
+    }
+
+    pub fn label(&self, state: &UIState) -> String {
+        match self.mode {
+            SleepTimerMode::Minutes(_) => format_time(self.remaining_seconds(state).max(0.0)),
+            SleepTimerMode::EndOfEpisode => "End of episode".to_string(),
+            SleepTimerMode::EndOfChapters(1) => "End of chapter".to_string(),
+            SleepTimerMode::EndOfChapters(count) => format!("End of {} chapters", count),
+        }
+    }
+}
+
+// Where playback ends once the outro skip is taken into account
+fn episode_end(state: &UIState) -> f64 {
+    let duration = state
+        .audio_element
+        .as_ref()
+        .map(|audio| audio.duration())
+        .filter(|duration| duration.is_finite() && *duration > 0.0)
+        .or_else(|| state.currently_playing.as_ref().map(|props| props.duration_sec))
+        .unwrap_or_default();
+    let end_skip = state
+        .currently_playing
+        .as_ref()
+        .map(|props| props.end_pos_sec)
+        .unwrap_or_default();
+    duration - end_skip
+}
+
+#[function_component(SleepTimerControl)]
+pub fn sleep_timer_control() -> Html {
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let menu_open = use_state(|| false);
+    let chapter_count = use_state(|| 1u32);
+
+    let toggle_menu = {
+        let menu_open = menu_open.clone();
+        Callback::from(move |_: MouseEvent| menu_open.set(!*menu_open))
+    };
+
+    let start_timer = {
+        let audio_dispatch = audio_dispatch.clone();
+        let menu_open = menu_open.clone();
+        Callback::from(move |mode: SleepTimerMode| {
+            audio_dispatch.reduce_mut(|state| state.start_sleep_timer(mode));
+            menu_open.set(false);
+        })
+    };
+
+    let cancel_timer = {
+        let audio_dispatch = audio_dispatch.clone();
+        let menu_open = menu_open.clone();
+        Callback::from(move |_: MouseEvent| {
+            audio_dispatch.reduce_mut(|state| state.cancel_sleep_timer());
+            menu_open.set(false);
+        })
+    };
+
+    let on_chapter_count_input = {
+        let chapter_count = chapter_count.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                chapter_count.set(input.value().parse::<u32>().unwrap_or(1).max(1));
+            }
+        })
+    };
+
+    let has_chapters = audio_state
+        .chapters
+        .as_ref()
+        .map_or(false, |chapters| !chapters.is_empty());
+    let timer_label = audio_state
+        .sleep_timer
+        .as_ref()
+        .map(|timer| timer.label(&audio_state));
+
+    html! {
+        <div class="sleep-timer-control">
+            <button onclick={toggle_menu} title="Sleep timer" class="skip-button audio-top-button selector-button font-bold py-2 px-4 rounded-full w-10 h-10 flex items-center justify-center">
+                <span class="material-icons">{"bedtime"}</span>
+            </button>
+            if let Some(label) = timer_label.clone() {
+                <span class="sleep-timer-remaining">{ label }</span>
+            }
+            if *menu_open {
+                <div class="sleep-timer-menu">
+                    { for SLEEP_TIMER_MINUTES.iter().map(|minutes| {
+                        let start_timer = start_timer.clone();
+                        let minutes = *minutes;
+                        html! {
+                            <button class="sleep-timer-option" onclick={Callback::from(move |_: MouseEvent| start_timer.emit(SleepTimerMode::Minutes(minutes)))}>
+                                { format!("{} minutes", minutes) }
+                            </button>
+                        }
+                    })}
+                    <button class="sleep-timer-option" onclick={{
+                        let start_timer = start_timer.clone();
+                        Callback::from(move |_: MouseEvent| start_timer.emit(SleepTimerMode::EndOfEpisode))
+                    }}>
+                        {"End of episode"}
+                    </button>
+                    if has_chapters {
+                        <div class="sleep-timer-option sleep-timer-chapters">
+                            <button onclick={{
+                                let start_timer = start_timer.clone();
+                                let count = *chapter_count;
+                                Callback::from(move |_: MouseEvent| start_timer.emit(SleepTimerMode::EndOfChapters(count)))
+                            }}>
+                                {"End of chapters:"}
+                            </button>
+                            <input
+                                type="number"
+                                min="1"
+                                class="email-input border text-sm rounded-lg p-1 w-14"
+                                value={chapter_count.to_string()}
+                                oninput={on_chapter_count_input}
+                            />
+                        </div>
+                    }
+                    if timer_label.is_some() {
+                        <button class="sleep-timer-option sleep-timer-cancel" onclick={cancel_timer}>
+                            {"Turn off timer"}
+                        </button>
+                    }
+                </div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,155 @@
+use crate::components::context::UIState;
+use crate::components::gen_funcs::format_time;
+use crate::requests::transcripts::{current_cue_index, TranscriptCue};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement};
+use yew::prelude::*;
+use yew::{function_component, html, Html};
+use yewdux::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct TranscriptPaneProps {
+    pub cues: Vec<TranscriptCue>,
+    // Cues are only highlighted and seekable while this episode is the one playing
+    pub episode_id: i32,
+}
+
+// Wraps every case-insensitive occurrence of `query` in a <mark>
+fn highlight_matches(text: &str, query: &str) -> Html {
+    if query.is_empty() {
+        return html! { { text } };
+    }
+    // Offsets found in the lowercased text only line up with `text` when every char lowercases
+    // to a single char of the same byte length, fall back to plain text otherwise
+    if !text.chars().all(lowercases_in_place) {
+        return html! { { text } };
+    }
+    let lower_text = text.to_lowercase();
+    let lower_query = query.to_lowercase();
+
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for (index, _) in lower_text.match_indices(&lower_query) {
+        parts.push(html! { { &text[last..index] } });
+        parts.push(html! { <mark>{ &text[index..index + lower_query.len()] }</mark> });
+        last = index + lower_query.len();
+    }
+    parts.push(html! { { &text[last..] } });
+    html! { <>{ for parts }</> }
+}
+
+fn lowercases_in_place(c: char) -> bool {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower.len_utf8() == c.len_utf8(),
+        _ => false,
+    }
+}
+
+#[function_component(TranscriptPane)]
+pub fn transcript_pane(props: &TranscriptPaneProps) -> Html {
+    let (audio_state, audio_dispatch) = use_store::<UIState>();
+    let search_query = use_state(|| String::new());
+    let container_ref = use_node_ref();
+
+    let is_playing_episode = audio_state
+        .currently_playing
+        .as_ref()
+        .map_or(false, |playing| playing.episode_id == props.episode_id);
+    let current_cue = if is_playing_episode {
+        current_cue_index(&props.cues, audio_state.current_time_seconds)
+    } else {
+        None
+    };
+
+    // Keep the current cue in view while following along, but leave the scroll position alone
+    // while the user is searching
+    {
+        let container_ref = container_ref.clone();
+        let searching = !search_query.is_empty();
+        use_effect_with(current_cue, move |current_cue| {
+            if let (Some(index), false) = (current_cue, searching) {
+                if let Some(container) = container_ref.cast::<HtmlElement>() {
+                    if let Ok(Some(cue)) =
+                        container.query_selector(&format!("[data-cue-index='{}']", index))
+                    {
+                        if let Ok(cue) = cue.dyn_into::<HtmlElement>() {
+                            let target = cue.offset_top() - container.client_height() / 3;
+                            container.set_scroll_top(target.max(0));
+                        }
+                    }
+                }
+            }
+            || ()
+        });
+    }
+
+    let on_search_input = {
+        let search_query = search_query.clone();
+        Callback::from(move |e: InputEvent| {
+            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                search_query.set(input.value());
+            }
+        })
+    };
+
+    let query = search_query.trim().to_string();
+    let lower_query = query.to_lowercase();
+    let visible_cues: Vec<(usize, &TranscriptCue)> = props
+        .cues
+        .iter()
+        .enumerate()
+        .filter(|(_, cue)| lower_query.is_empty() || cue.text.to_lowercase().contains(&lower_query))
+        .collect();
+    let match_count = visible_cues.len();
+
+    html! {
+        <div class="transcript-pane">
+            <div class="transcript-header">
+                <h3 class="transcript-title">{"Transcript"}</h3>
+                <input
+                    type="search"
+                    class="search-bar-input transcript-search border text-sm rounded-lg p-2"
+                    placeholder="Search transcript"
+                    value={(*search_query).clone()}
+                    oninput={on_search_input}
+                />
+                if !query.is_empty() {
+                    <span class="transcript-match-count">{ format!("{} matches", match_count) }</span>
+                }
+            </div>
+            if !is_playing_episode {
+                <p class="transcript-hint">{"Play this episode to follow along and jump to a line."}</p>
+            }
+            <div class="transcript-cues" ref={container_ref}>
+                { for visible_cues.into_iter().map(|(index, cue)| {
+                    let start = cue.start;
+                    let on_cue_click = {
+                        let audio_dispatch = audio_dispatch.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            if is_playing_episode {
+                                audio_dispatch.reduce_mut(|state| state.seek_to(start));
+                            }
+                        })
+                    };
+                    let cue_class = if Some(index) == current_cue {
+                        classes!("transcript-cue", "current-cue")
+                    } else {
+                        classes!("transcript-cue")
+                    };
+                    html! {
+                        <div class={cue_class} data-cue-index={index.to_string()} onclick={on_cue_click}>
+                            <span class="transcript-cue-time">{ format_time(cue.start) }</span>
+                            <div class="transcript-cue-body">
+                                if let Some(speaker) = &cue.speaker {
+                                    <span class="transcript-speaker">{ speaker }</span>
+                                }
+                                <p class="transcript-text">{ highlight_matches(&cue.text, &query) }</p>
+                            </div>
+                        </div>
+                    }
+                })}
+            </div>
+        </div>
+    }
+}
@@ -70,6 +70,7 @@ pub fn user_stats() -> Html {
                             if let Some(stats) = user_stats {
                                 let formatted_date = format_date(&stats.UserCreated);
                                 let time_formatted = format_time_mins(stats.TimeListened);
+                                let time_saved_formatted = format_time_mins(stats.TimeSaved / 60);
                                 html! {
                                     <>
                                         <div class="stats-card">
@@ -101,6 +102,11 @@ pub fn user_stats() -> Html {
                                             <p class="stats-label">{"Episodes Downloaded"}</p>
                                             <p class="stats-value">{ &stats.EpisodesDownloaded }</p>
                                         </div>
+
+                                        <div class="stats-card">
+                                            <p class="stats-label">{"Time Saved by Smart Speed"}</p>
+                                            <p class="stats-value">{ &time_saved_formatted }</p>
+                                        </div>
                                         <div class="large-card col-span-1 md:col-span-3">
                                             <img src="static/assets/favicon.png" alt="Pinepods Logo" class="large-card-image"/>
                                                                      <p class="large-card-paragraph item_container-text">{"Current Version: 0.6.2"}</p>
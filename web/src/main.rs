@@ -11,6 +11,8 @@ use components::episode::Episode;
 use components::episodes_layout::EpisodeLayout;
 use components::history::PodHistory;
 use components::home::Home;
+use components::keybindings::KeyboardShortcuts;
+use components::outbox_sync::OutboxSync;
 use components::podcast_layout::PodLayout;
 use components::podcasts::Podcasts;
 use components::queue::Queue;
@@ -80,6 +82,8 @@ fn main_component() -> Html {
     html! {
         <BrowserRouter>
             <Switch<Route> render={switch} />
+            <KeyboardShortcuts />
+            <OutboxSync />
         </BrowserRouter>
     }
 }
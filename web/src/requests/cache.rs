@@ -0,0 +1,89 @@
+use pinepods_client::ResponseCache;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// The lists the main pages keep in AppState. A page shows what it already has straight away and
+// only refreshes it in the background once its TTL is up. Even then the response cache lets the
+// server answer an unchanged list with a 304.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CachedList {
+    RecentEpisodes,
+    Podcasts,
+    Queue,
+    Saved,
+    History,
+}
+
+pub const ALL_LISTS: [CachedList; 5] = [
+    CachedList::RecentEpisodes,
+    CachedList::Podcasts,
+    CachedList::Queue,
+    CachedList::Saved,
+    CachedList::History,
+];
+
+// Every list that shows an episode's progress or completed state
+pub const EPISODE_LISTS: [CachedList; 4] = [
+    CachedList::RecentEpisodes,
+    CachedList::Queue,
+    CachedList::Saved,
+    CachedList::History,
+];
+
+impl CachedList {
+    fn ttl_ms(self) -> f64 {
+        match self {
+            // Feeds refresh on the server in the background, new episodes can show up any time
+            CachedList::RecentEpisodes => 2.0 * 60_000.0,
+            CachedList::Podcasts => 10.0 * 60_000.0,
+            CachedList::Queue | CachedList::Saved | CachedList::History => 5.0 * 60_000.0,
+        }
+    }
+}
+
+thread_local! {
+    static RESPONSES: ResponseCache = ResponseCache::new();
+    // When each list was last fetched, by server and user
+    static FETCHED_AT: RefCell<HashMap<(String, i32, CachedList), f64>> =
+        RefCell::new(HashMap::new());
+}
+
+// Shared by every client `api_client` hands out
+pub fn responses() -> ResponseCache {
+    RESPONSES.with(|responses| responses.clone())
+}
+
+pub fn is_fresh(server_name: &str, user_id: i32, list: CachedList) -> bool {
+    FETCHED_AT.with(|fetched_at| {
+        fetched_at
+            .borrow()
+            .get(&(server_name.to_string(), user_id, list))
+            .is_some_and(|fetched| js_sys::Date::now() - fetched < list.ttl_ms())
+    })
+}
+
+pub fn mark_fetched(server_name: &str, user_id: i32, list: CachedList) {
+    FETCHED_AT.with(|fetched_at| {
+        fetched_at.borrow_mut().insert(
+            (server_name.to_string(), user_id, list),
+            js_sys::Date::now(),
+        );
+    });
+}
+
+// Makes the next visit to these lists refetch them. The stored responses stay, the server's
+// ETag decides whether they're still current.
+pub fn invalidate(server_name: &str, user_id: i32, lists: &[CachedList]) {
+    FETCHED_AT.with(|fetched_at| {
+        let mut fetched_at = fetched_at.borrow_mut();
+        for list in lists {
+            fetched_at.remove(&(server_name.to_string(), user_id, *list));
+        }
+    });
+}
+
+// On logout, nothing fetched with the old key should be shown again
+pub fn clear() {
+    RESPONSES.with(|responses| responses.clear());
+    FETCHED_AT.with(|fetched_at| fetched_at.borrow_mut().clear());
+}
@@ -0,0 +1,78 @@
+use crate::requests::pod_req::api_client;
+use crate::requests::search_pods::parse_error;
+use anyhow::Error;
+use pinepods_client::{ClientError, FeedAuth};
+use serde::{Deserialize, Serialize};
+
+fn default_toc() -> bool {
+    true
+}
+
+// A single chapter from the Podcasting 2.0 JSON chapters format
+// https://github.com/Podcastindex-org/podcast-namespace/blob/main/chapters/jsonChapters.md
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Chapter {
+    #[serde(rename = "startTime")]
+    pub start_time: f64,
+    #[serde(rename = "endTime", default)]
+    pub end_time: Option<f64>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub img: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    // Chapters marked `"toc": false` are silent markers and are kept out of the chapter list
+    #[serde(default = "default_toc")]
+    pub toc: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChaptersResponse {
+    #[serde(default)]
+    chapters: Vec<Chapter>,
+}
+
+// Drops hidden chapters and makes sure everything is sorted by start time
+pub fn normalize_chapters(chapters: Vec<Chapter>) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = chapters
+        .into_iter()
+        .filter(|chapter| chapter.toc && chapter.start_time >= 0.0)
+        .collect();
+    chapters.sort_by(|a, b| {
+        a.start_time
+            .partial_cmp(&b.start_time)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    chapters
+}
+
+pub fn parse_json_chapters(chapters_json: &str) -> Result<Vec<Chapter>, Error> {
+    let response: ChaptersResponse = serde_json::from_str(chapters_json)?;
+    Ok(normalize_chapters(response.chapters))
+}
+
+// Index of the chapter playing at `time_seconds`, if any
+pub fn current_chapter_index(chapters: &[Chapter], time_seconds: f64) -> Option<usize> {
+    chapters
+        .iter()
+        .rposition(|chapter| chapter.start_time <= time_seconds)
+        .filter(|index| {
+            chapters[*index]
+                .end_time
+                .map_or(true, |end_time| time_seconds < end_time)
+        })
+}
+
+pub async fn call_get_chapters(
+    server_name: &str,
+    api_key: &Option<String>,
+    chapters_url: &str,
+) -> Result<Vec<Chapter>, ClientError> {
+    // Chapter files are usually hosted without CORS headers, so fetch them through the server
+    // the same way feeds are fetched
+    let response_text = api_client(server_name, api_key)
+        .fetch_feed(chapters_url, &FeedAuth::default())
+        .await?;
+    parse_json_chapters(&response_text).map_err(parse_error)
+}
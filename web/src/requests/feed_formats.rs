@@ -0,0 +1,328 @@
+use crate::requests::search_pods::{
+    episode_duration, parse_rss, Episode, PodcastFeedResult, PodcastInfo,
+};
+use anyhow::Error;
+use atom_syndication::extension::ExtensionMap as AtomExtensionMap;
+use atom_syndication::{Entry, Feed, Link};
+use pinepods_client::parse_feed_date;
+use serde::Deserialize;
+
+// Custom feeds aren't always RSS. Atom and JSON Feed documents are read into the same
+// PodcastInfo and Episode the RSS parser produces, so the rest of the app doesn't care which
+// one a feed used.
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    JsonFeed,
+}
+
+// Decides from the document itself, servers label all three as text/xml or worse
+pub fn detect_format(body: &str) -> Option<FeedFormat> {
+    let body = body.trim_start_matches('\u{feff}').trim_start();
+    if body.starts_with('{') {
+        return Some(FeedFormat::JsonFeed);
+    }
+    // The first element that isn't the XML declaration, a comment or a doctype
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with('?') || rest.starts_with('!') {
+            continue;
+        }
+        let name: String = rest
+            .chars()
+            .take_while(|c| !c.is_whitespace() && *c != '>' && *c != '/')
+            .collect();
+        let local_name = name.rsplit(':').next().unwrap_or_default();
+        return match local_name {
+            "rss" | "RDF" => Some(FeedFormat::Rss),
+            "feed" => Some(FeedFormat::Atom),
+            _ => None,
+        };
+    }
+    None
+}
+
+pub fn parse_feed(body: &str) -> Result<PodcastFeedResult, Error> {
+    match detect_format(body) {
+        Some(FeedFormat::Rss) => parse_rss(body),
+        Some(FeedFormat::Atom) => parse_atom(body),
+        Some(FeedFormat::JsonFeed) => parse_json_feed(body),
+        None => Err(Error::msg("Not a feed, expected RSS, Atom or JSON Feed")),
+    }
+}
+
+// Dates are kept in the RFC 2822 form RSS uses, which is what the episode lists sort by
+fn to_rfc2822(date: &str) -> Option<String> {
+    parse_feed_date(date).map(|date| date.to_rfc2822())
+}
+
+// Atom feeds can carry the itunes tags too, atom_syndication keeps them by prefix like rss does
+fn atom_ext_text(extensions: &AtomExtensionMap, prefix: &str, name: &str) -> Option<String> {
+    extensions
+        .get(prefix)?
+        .get(name)?
+        .first()?
+        .value()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+fn atom_ext_attr(
+    extensions: &AtomExtensionMap,
+    prefix: &str,
+    name: &str,
+    attr: &str,
+) -> Option<String> {
+    extensions
+        .get(prefix)?
+        .get(name)?
+        .first()?
+        .attrs()
+        .get(attr)
+        .map(|value| value.to_string())
+}
+
+// A link with no rel is an alternate link
+fn atom_links<'a>(links: &'a [Link], rel: &'a str) -> impl Iterator<Item = &'a Link> {
+    links.iter().filter(move |link| link.rel() == rel)
+}
+
+fn parse_atom(body: &str) -> Result<PodcastFeedResult, Error> {
+    let feed: Feed = body.parse()?;
+    let extensions = feed.extensions();
+    let artwork_url = atom_ext_attr(extensions, "itunes", "image", "href")
+        .or_else(|| feed.logo().map(|logo| logo.to_string()))
+        .or_else(|| feed.icon().map(|icon| icon.to_string()));
+    let podcast = PodcastInfo {
+        title: feed.title().value.clone(),
+        description: feed
+            .subtitle()
+            .map(|subtitle| subtitle.value.clone())
+            .or_else(|| atom_ext_text(extensions, "itunes", "summary"))
+            .unwrap_or_default(),
+        artwork_url: artwork_url.clone(),
+        author: feed
+            .authors()
+            .first()
+            .map(|author| author.name().to_string())
+            .or_else(|| atom_ext_text(extensions, "itunes", "author"))
+            .unwrap_or_default(),
+        website: atom_links(feed.links(), "alternate")
+            .next()
+            .map(|link| link.href().to_string())
+            .unwrap_or_default(),
+        categories: feed
+            .categories()
+            .iter()
+            .map(|category| category.label().unwrap_or(category.term()).to_string())
+            .collect(),
+        explicit: atom_ext_text(extensions, "itunes", "explicit")
+            .is_some_and(|explicit| explicit == "yes" || explicit == "true"),
+        episode_count: feed.entries().len() as i32,
+        itunes_type: atom_ext_text(extensions, "itunes", "type")
+            .map(|itunes_type| itunes_type.to_ascii_lowercase()),
+        ..PodcastInfo::default()
+    };
+    let episodes = feed
+        .entries()
+        .iter()
+        .map(|entry| parse_atom_entry(entry, &artwork_url))
+        .collect();
+    Ok(PodcastFeedResult {
+        podcast: Some(podcast),
+        episodes,
+        podcast_id: None,
+        filter: None,
+    })
+}
+
+fn parse_atom_entry(entry: &Entry, podcast_artwork_url: &Option<String>) -> Episode {
+    let extensions = entry.extensions();
+    // Prefer something playable when an entry links more than one enclosure
+    let enclosure = atom_links(entry.links(), "enclosure")
+        .find(|link| {
+            link.mime_type()
+                .is_some_and(|mime| mime.starts_with("audio/") || mime.starts_with("video/"))
+        })
+        .or_else(|| atom_links(entry.links(), "enclosure").next());
+    Episode {
+        title: Some(entry.title().value.clone()),
+        description: entry.summary().map(|summary| summary.value.clone()),
+        pub_date: Some(entry.published().unwrap_or(entry.updated()).to_rfc2822()),
+        links: atom_links(entry.links(), "alternate")
+            .map(|link| link.href().to_string())
+            .collect(),
+        enclosure_url: enclosure.map(|link| link.href().to_string()),
+        enclosure_length: enclosure
+            .and_then(|link| link.length())
+            .map(|length| length.to_string()),
+        artwork: atom_ext_attr(extensions, "itunes", "image", "href")
+            .or_else(|| podcast_artwork_url.clone()),
+        content: entry
+            .content()
+            .and_then(|content| content.value())
+            .map(|content| content.to_string()),
+        authors: entry
+            .authors()
+            .iter()
+            .map(|author| author.name().to_string())
+            .collect(),
+        guid: Some(entry.id().to_string()),
+        duration: episode_duration(
+            atom_ext_text(extensions, "itunes", "duration").as_deref(),
+            enclosure.and_then(|link| link.length()),
+            enclosure.and_then(|link| link.mime_type()),
+        ),
+        episode_type: atom_ext_text(extensions, "itunes", "episodeType")
+            .map(|episode_type| episode_type.to_ascii_lowercase()),
+        ..Episode::default()
+    }
+}
+
+// JSON Feed 1.0 and 1.1, https://www.jsonfeed.org/version/1.1/. 1.0 had a single author
+// where 1.1 has a list, both are accepted.
+#[derive(Deserialize)]
+struct JsonFeed {
+    version: String,
+    title: String,
+    home_page_url: Option<String>,
+    description: Option<String>,
+    icon: Option<String>,
+    favicon: Option<String>,
+    #[serde(default)]
+    authors: Vec<JsonAuthor>,
+    author: Option<JsonAuthor>,
+    #[serde(default)]
+    items: Vec<JsonItem>,
+}
+
+#[derive(Deserialize)]
+struct JsonAuthor {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonItem {
+    // Should be a string, some generators write a number
+    id: serde_json::Value,
+    url: Option<String>,
+    external_url: Option<String>,
+    title: Option<String>,
+    content_html: Option<String>,
+    content_text: Option<String>,
+    summary: Option<String>,
+    image: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+    #[serde(default)]
+    authors: Vec<JsonAuthor>,
+    author: Option<JsonAuthor>,
+    #[serde(default)]
+    attachments: Vec<JsonAttachment>,
+}
+
+#[derive(Deserialize)]
+struct JsonAttachment {
+    url: String,
+    mime_type: Option<String>,
+    size_in_bytes: Option<u64>,
+    duration_in_seconds: Option<f64>,
+}
+
+fn author_names(authors: Vec<JsonAuthor>, author: Option<JsonAuthor>) -> Vec<String> {
+    authors
+        .into_iter()
+        .chain(author)
+        .filter_map(|author| author.name)
+        .collect()
+}
+
+fn parse_json_feed(body: &str) -> Result<PodcastFeedResult, Error> {
+    let feed: JsonFeed = serde_json::from_str(body)?;
+    if !feed.version.starts_with("https://jsonfeed.org/version/") {
+        return Err(Error::msg(format!(
+            "Unsupported JSON Feed version: {}",
+            feed.version
+        )));
+    }
+    let artwork_url = feed.icon.or(feed.favicon);
+    let podcast = PodcastInfo {
+        title: feed.title,
+        description: feed.description.unwrap_or_default(),
+        artwork_url: artwork_url.clone(),
+        author: author_names(feed.authors, feed.author)
+            .into_iter()
+            .next()
+            .unwrap_or_default(),
+        website: feed.home_page_url.unwrap_or_default(),
+        episode_count: feed.items.len() as i32,
+        ..PodcastInfo::default()
+    };
+    let episodes = feed
+        .items
+        .into_iter()
+        .map(|item| parse_json_item(item, &artwork_url))
+        .collect();
+    Ok(PodcastFeedResult {
+        podcast: Some(podcast),
+        episodes,
+        podcast_id: None,
+        filter: None,
+    })
+}
+
+fn parse_json_item(item: JsonItem, podcast_artwork_url: &Option<String>) -> Episode {
+    let attachment_index = item
+        .attachments
+        .iter()
+        .position(|attachment| {
+            attachment
+                .mime_type
+                .as_deref()
+                .is_some_and(|mime| mime.starts_with("audio/") || mime.starts_with("video/"))
+        })
+        .unwrap_or(0);
+    let attachment = item.attachments.into_iter().nth(attachment_index);
+    let duration = attachment.as_ref().and_then(|attachment| {
+        episode_duration(
+            attachment
+                .duration_in_seconds
+                .map(|seconds| seconds.to_string())
+                .as_deref(),
+            attachment
+                .size_in_bytes
+                .map(|size| size.to_string())
+                .as_deref(),
+            attachment.mime_type.as_deref(),
+        )
+    });
+    let guid = match item.id {
+        serde_json::Value::String(id) => Some(id),
+        serde_json::Value::Null => None,
+        id => Some(id.to_string()),
+    };
+    Episode {
+        title: item.title,
+        description: item.summary.or(item.content_text),
+        pub_date: item
+            .date_published
+            .or(item.date_modified)
+            .and_then(|date| to_rfc2822(&date)),
+        links: item.url.into_iter().chain(item.external_url).collect(),
+        enclosure_url: attachment.as_ref().map(|attachment| attachment.url.clone()),
+        enclosure_length: attachment
+            .as_ref()
+            .and_then(|attachment| attachment.size_in_bytes)
+            .map(|size| size.to_string()),
+        artwork: item.image.or_else(|| podcast_artwork_url.clone()),
+        content: item.content_html,
+        authors: author_names(item.authors, item.author),
+        guid,
+        duration,
+        ..Episode::default()
+    }
+}
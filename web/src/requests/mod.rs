@@ -1,7 +1,13 @@
+pub(crate) mod cache;
+pub(crate) mod chapters;
+pub(crate) mod feed_formats;
 pub(crate) mod login_requests;
 pub(crate) mod models;
+pub(crate) mod outbox;
 pub(crate) mod pod_req;
+pub(crate) mod podcast_ns;
 pub(crate) mod search_pods;
 
 pub(crate) mod stat_reqs;
+pub(crate) mod transcripts;
 pub(crate) mod setting_reqs;
\ No newline at end of file
@@ -0,0 +1,278 @@
+use crate::components::context::UIState;
+use crate::requests::pod_req::{
+    api_client, HistoryAddRequest, MarkEpisodeCompletedRequest, QueuePodcastRequest,
+    RecordListenDurationRequest,
+};
+use pinepods_client::{ClientError, PinepodsClient};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use web_sys::window;
+use yewdux::prelude::*;
+
+// Writes that couldn't reach the server wait here until it's back. They're kept in localStorage
+// so closing the app doesn't lose them. Reads aren't queued, they just fail as before.
+
+const OUTBOX_STORAGE_KEY: &str = "pinepods_outbox";
+
+const FIRST_RETRY_MS: f64 = 5_000.0;
+const MAX_RETRY_MS: f64 = 300_000.0;
+
+// What the wrappers hand back when a change was kept for later instead of sent
+pub const QUEUED_MESSAGE: &str = "You're offline, the change will sync when the server is back";
+
+thread_local! {
+    // A flush awaits between entries, don't let a second one start meanwhile
+    static FLUSHING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum OutboxAction {
+    ListenDuration {
+        episode_id: i32,
+        user_id: i32,
+        listen_duration: f64,
+    },
+    History {
+        episode_id: i32,
+        user_id: i32,
+        episode_pos: f32,
+    },
+    Completed {
+        episode_id: i32,
+        user_id: i32,
+        completed: bool,
+    },
+    Queue {
+        episode_id: i32,
+        user_id: i32,
+    },
+}
+
+impl OutboxAction {
+    fn target(&self) -> (i32, i32) {
+        match self {
+            OutboxAction::ListenDuration {
+                episode_id,
+                user_id,
+                ..
+            }
+            | OutboxAction::History {
+                episode_id,
+                user_id,
+                ..
+            }
+            | OutboxAction::Completed {
+                episode_id,
+                user_id,
+                ..
+            }
+            | OutboxAction::Queue {
+                episode_id,
+                user_id,
+            } => (*episode_id, *user_id),
+        }
+    }
+
+    // Only the latest position and the latest completed state for an episode matter
+    fn supersedes(&self, older: &OutboxAction) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(older)
+            && self.target() == older.target()
+    }
+
+    async fn send(&self, client: &PinepodsClient) -> Result<(), ClientError> {
+        match self.clone() {
+            OutboxAction::ListenDuration {
+                episode_id,
+                user_id,
+                listen_duration,
+            } => client
+                .record_listen_duration(&RecordListenDurationRequest {
+                    episode_id,
+                    user_id,
+                    listen_duration,
+                })
+                .await
+                .map(|_| ()),
+            OutboxAction::History {
+                episode_id,
+                user_id,
+                episode_pos,
+            } => {
+                client
+                    .add_history(&HistoryAddRequest {
+                        episode_id,
+                        episode_pos,
+                        user_id,
+                    })
+                    .await
+            }
+            OutboxAction::Completed {
+                episode_id,
+                user_id,
+                completed,
+            } => {
+                let request = MarkEpisodeCompletedRequest {
+                    episode_id,
+                    user_id,
+                };
+                if completed {
+                    client.mark_episode_completed(&request).await.map(|_| ())
+                } else {
+                    client.mark_episode_uncompleted(&request).await.map(|_| ())
+                }
+            }
+            OutboxAction::Queue {
+                episode_id,
+                user_id,
+            } => client
+                .queue_episode(&QueuePodcastRequest {
+                    episode_id,
+                    user_id,
+                })
+                .await
+                .map(|_| ()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OutboxEntry {
+    pub id: u64,
+    // No API key is stored, entries are sent with whatever key the user is signed in with
+    pub server_name: String,
+    pub action: OutboxAction,
+    pub attempts: u32,
+    // Milliseconds since the epoch before which the entry isn't retried
+    pub retry_at: f64,
+}
+
+fn load() -> Vec<OutboxEntry> {
+    window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(OUTBOX_STORAGE_KEY).ok().flatten())
+        .and_then(|stored| serde_json::from_str(&stored).ok())
+        .unwrap_or_default()
+}
+
+fn store(entries: &[OutboxEntry]) {
+    if let Some(storage) = window().and_then(|window| window.local_storage().ok().flatten()) {
+        if let Ok(serialized) = serde_json::to_string(entries) {
+            let _ = storage.set_item(OUTBOX_STORAGE_KEY, &serialized);
+        }
+    }
+    let pending = entries.len();
+    Dispatch::<UIState>::global().reduce_mut(move |state| state.outbox_pending = pending);
+}
+
+fn set_offline(offline: bool) {
+    let dispatch = Dispatch::<UIState>::global();
+    if dispatch.get().offline != Some(offline) {
+        dispatch.reduce_mut(move |state| state.offline = Some(offline));
+    }
+}
+
+// 5s, 10s, 20s... up to 5 minutes
+fn retry_delay_ms(attempts: u32) -> f64 {
+    (FIRST_RETRY_MS * 2f64.powi(attempts.min(16) as i32)).min(MAX_RETRY_MS)
+}
+
+pub fn pending_count() -> usize {
+    load().len()
+}
+
+pub fn enqueue(server_name: &str, action: OutboxAction) {
+    let mut entries = load();
+    entries.retain(|entry| !(entry.server_name == server_name && action.supersedes(&entry.action)));
+    let id = entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+    entries.push(OutboxEntry {
+        id,
+        server_name: server_name.to_string(),
+        action,
+        attempts: 0,
+        retry_at: js_sys::Date::now() + FIRST_RETRY_MS,
+    });
+    store(&entries);
+    set_offline(true);
+}
+
+// Passes a result through, except that a write the server never got is queued and reported
+// as `queued` so the caller carries on as if it went through
+pub fn or_queue<T>(
+    result: Result<T, ClientError>,
+    server_name: &str,
+    action: OutboxAction,
+    queued: T,
+) -> Result<T, ClientError> {
+    match result {
+        Err(error) if error.is_retryable() => {
+            enqueue(server_name, action);
+            Ok(queued)
+        }
+        Err(error) => Err(error),
+        Ok(value) => {
+            // The server now has something newer than what's waiting for the same episode
+            let mut entries = load();
+            let before = entries.len();
+            entries.retain(|entry| {
+                !(entry.server_name == server_name && action.supersedes(&entry.action))
+            });
+            if entries.len() != before {
+                store(&entries);
+            }
+            set_offline(false);
+            Ok(value)
+        }
+    }
+}
+
+// Sends what's waiting for `server_name`, oldest first. Stops at the first entry the server
+// still can't take so changes land in the order they were made. `force` ignores the backoff.
+pub async fn flush(server_name: String, api_key: Option<String>, force: bool) {
+    if api_key.is_none() || FLUSHING.with(|flushing| flushing.replace(true)) {
+        return;
+    }
+    let client = api_client(&server_name, &api_key);
+    loop {
+        // Reloaded every time, entries can be added or coalesced while a send is in flight
+        let next = load()
+            .into_iter()
+            .find(|entry| entry.server_name == server_name);
+        let entry = match next {
+            Some(entry) => entry,
+            None => {
+                set_offline(false);
+                break;
+            }
+        };
+        if !force && entry.retry_at > js_sys::Date::now() {
+            break;
+        }
+        match entry.action.send(&client).await {
+            // Signed out or the key was revoked, keep everything for the next sign-in
+            Err(ClientError::Unauthorized) => break,
+            Err(error) if error.is_retryable() => {
+                let mut entries = load();
+                if let Some(stored) = entries.iter_mut().find(|stored| stored.id == entry.id) {
+                    stored.attempts += 1;
+                    stored.retry_at = js_sys::Date::now() + retry_delay_ms(stored.attempts);
+                }
+                store(&entries);
+                set_offline(true);
+                break;
+            }
+            result => {
+                if let Err(error) = result {
+                    // The server answered but won't take it, e.g. the episode is gone
+                    web_sys::console::log_1(
+                        &format!("Dropping queued change {:?}: {}", entry.action, error).into(),
+                    );
+                }
+                let mut entries = load();
+                entries.retain(|stored| stored.id != entry.id);
+                store(&entries);
+            }
+        }
+    }
+    FLUSHING.with(|flushing| flushing.set(false));
+}
@@ -0,0 +1,267 @@
+use rss::extension::{Extension, ExtensionMap};
+use serde::{Deserialize, Serialize};
+
+// The Podcasting 2.0 namespace, https://podcastindex.org/namespace/1.0. The rss crate leaves
+// these tags in the extension map under the prefix the feed declared, which is `podcast` in
+// every feed we've seen.
+
+// Returns every `<podcast:NAME>` element from an item or channel's extensions
+pub fn podcast_exts<'a>(extensions: &'a ExtensionMap, name: &str) -> &'a [Extension] {
+    extensions
+        .get("podcast")
+        .and_then(|elements| elements.get(name))
+        .map(|values| values.as_slice())
+        .unwrap_or_default()
+}
+
+pub fn podcast_ext<'a>(extensions: &'a ExtensionMap, name: &str) -> Option<&'a Extension> {
+    podcast_exts(extensions, name).first()
+}
+
+// The tag's text, `None` when it's empty
+fn text(extension: &Extension) -> Option<String> {
+    extension
+        .value()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+fn attr(extension: &Extension, name: &str) -> Option<String> {
+    extension
+        .attrs()
+        .get(name)
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+// Nested tags, depending on the rss version they're keyed with or without the prefix
+fn children<'a>(extension: &'a Extension, name: &str) -> &'a [Extension] {
+    let children = extension.children();
+    children
+        .get(name)
+        .or_else(|| children.get(&format!("podcast:{}", name)))
+        .map(|values| values.as_slice())
+        .unwrap_or_default()
+}
+
+// <podcast:person>, a host, guest or anyone else who worked on the show or episode
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Person {
+    pub name: String,
+    // "host" and "cast" when the feed doesn't say
+    pub role: String,
+    pub group: String,
+    pub img: Option<String>,
+    pub href: Option<String>,
+}
+
+impl Person {
+    pub fn is_host(&self) -> bool {
+        self.role.eq_ignore_ascii_case("host")
+    }
+}
+
+// <podcast:funding>, where listeners can support the show
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Funding {
+    pub url: String,
+    pub label: Option<String>,
+}
+
+// <podcast:location>, what the show or episode is about, not where it was recorded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Location {
+    pub name: String,
+    pub geo: Option<String>,
+    pub osm: Option<String>,
+}
+
+impl Location {
+    // An OpenStreetMap link for the place, if the feed pinned it down
+    pub fn map_url(&self) -> Option<String> {
+        if let Some(osm) = &self.osm {
+            let kind = match osm.chars().next()?.to_ascii_uppercase() {
+                'R' => "relation",
+                'W' => "way",
+                'N' => "node",
+                _ => return None,
+            };
+            let id: String = osm[1..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if !id.is_empty() {
+                return Some(format!("https://www.openstreetmap.org/{}/{}", kind, id));
+            }
+        }
+        // geo:latitude,longitude with an optional altitude and ;u= uncertainty
+        let coords = self.geo.as_ref()?.strip_prefix("geo:")?;
+        let coords = coords.split(';').next()?;
+        let mut parts = coords.split(',');
+        let lat = parts.next()?.trim().parse::<f64>().ok()?;
+        let lon = parts.next()?.trim().parse::<f64>().ok()?;
+        Some(format!(
+            "https://www.openstreetmap.org/?mlat={}&mlon={}",
+            lat, lon
+        ))
+    }
+}
+
+// <podcast:soundbite>, a highlight of the episode chosen by the publisher
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Soundbite {
+    pub start_time: f64,
+    pub duration: f64,
+    pub title: Option<String>,
+}
+
+// <podcast:trailer>, a channel level teaser that isn't one of the episodes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Trailer {
+    pub title: String,
+    pub url: String,
+    pub pub_date: Option<String>,
+    pub length: Option<u64>,
+    pub mime_type: Option<String>,
+    pub season: Option<i32>,
+}
+
+// <podcast:value>, how listeners can stream payments to the people behind the show
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Value {
+    pub kind: String,
+    pub method: String,
+    pub suggested: Option<String>,
+    pub recipients: Vec<ValueRecipient>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ValueRecipient {
+    pub name: Option<String>,
+    pub kind: String,
+    pub address: String,
+    pub split: u32,
+    pub fee: bool,
+}
+
+pub fn persons(extensions: &ExtensionMap) -> Vec<Person> {
+    podcast_exts(extensions, "person")
+        .iter()
+        .filter_map(|person| {
+            Some(Person {
+                name: text(person)?,
+                role: attr(person, "role").unwrap_or_else(|| "host".to_string()),
+                group: attr(person, "group").unwrap_or_else(|| "cast".to_string()),
+                img: attr(person, "img"),
+                href: attr(person, "href"),
+            })
+        })
+        .collect()
+}
+
+pub fn funding(extensions: &ExtensionMap) -> Vec<Funding> {
+    podcast_exts(extensions, "funding")
+        .iter()
+        .filter_map(|funding| {
+            Some(Funding {
+                url: attr(funding, "url")?,
+                label: text(funding),
+            })
+        })
+        .collect()
+}
+
+pub fn location(extensions: &ExtensionMap) -> Option<Location> {
+    let location = podcast_ext(extensions, "location")?;
+    Some(Location {
+        name: text(location)?,
+        geo: attr(location, "geo"),
+        osm: attr(location, "osm"),
+    })
+}
+
+pub fn soundbites(extensions: &ExtensionMap) -> Vec<Soundbite> {
+    podcast_exts(extensions, "soundbite")
+        .iter()
+        .filter_map(|soundbite| {
+            Some(Soundbite {
+                start_time: attr(soundbite, "startTime")?.parse().ok()?,
+                duration: attr(soundbite, "duration")?.parse().ok()?,
+                title: text(soundbite),
+            })
+        })
+        .collect()
+}
+
+pub fn trailers(extensions: &ExtensionMap) -> Vec<Trailer> {
+    podcast_exts(extensions, "trailer")
+        .iter()
+        .filter_map(|trailer| {
+            Some(Trailer {
+                title: text(trailer).unwrap_or_else(|| "Trailer".to_string()),
+                url: attr(trailer, "url")?,
+                pub_date: attr(trailer, "pubdate"),
+                length: attr(trailer, "length").and_then(|length| length.parse().ok()),
+                mime_type: attr(trailer, "type"),
+                season: attr(trailer, "season").and_then(|season| season.parse().ok()),
+            })
+        })
+        .collect()
+}
+
+pub fn value(extensions: &ExtensionMap) -> Option<Value> {
+    let value = podcast_ext(extensions, "value")?;
+    let recipients = children(value, "valueRecipient")
+        .iter()
+        .filter_map(|recipient| {
+            Some(ValueRecipient {
+                name: attr(recipient, "name"),
+                kind: attr(recipient, "type").unwrap_or_default(),
+                address: attr(recipient, "address")?,
+                split: attr(recipient, "split")
+                    .and_then(|split| split.parse().ok())
+                    .unwrap_or(0),
+                fee: attr(recipient, "fee").is_some_and(|fee| fee.eq_ignore_ascii_case("true")),
+            })
+        })
+        .collect();
+    Some(Value {
+        kind: attr(value, "type")?,
+        method: attr(value, "method").unwrap_or_default(),
+        suggested: attr(value, "suggested"),
+        recipients,
+    })
+}
+
+// The show's permanent id, stays the same when the feed moves
+pub fn guid(extensions: &ExtensionMap) -> Option<String> {
+    podcast_ext(extensions, "guid").and_then(text)
+}
+
+// Whether the publisher has locked the feed against being imported by another host, and the
+// email of the owner who can unlock it
+pub fn locked(extensions: &ExtensionMap) -> (bool, Option<String>) {
+    match podcast_ext(extensions, "locked") {
+        Some(locked) => (is_yes(locked.value()), attr(locked, "owner")),
+        None => (false, None),
+    }
+}
+
+// The season number and its name, if it has one
+pub fn season(extensions: &ExtensionMap) -> Option<(i32, Option<String>)> {
+    let season = podcast_ext(extensions, "season")?;
+    Some((text(season)?.parse().ok()?, attr(season, "name")))
+}
+
+// The episode number, which may be a decimal like 4.5, and how the feed wants it shown
+pub fn episode(extensions: &ExtensionMap) -> Option<(f64, Option<String>)> {
+    let episode = podcast_ext(extensions, "episode")?;
+    Some((text(episode)?.parse().ok()?, attr(episode, "display")))
+}
+
+// itunes:block and podcast:locked both use "yes" for true
+pub fn is_yes(value: Option<&str>) -> bool {
+    value.is_some_and(|value| value.trim().eq_ignore_ascii_case("yes"))
+}
@@ -1,7 +1,17 @@
+use crate::requests::feed_formats::parse_feed;
+use crate::requests::pod_req::{api_client, Page, PinepodsClient};
+use crate::requests::podcast_ns::{
+    self, podcast_ext, podcast_exts, Funding, Location, Person, Soundbite, Trailer, Value,
+};
+use crate::requests::setting_reqs::FeedAuth;
 use anyhow::Error;
 use chrono::DateTime;
-use gloo_net::http::Request;
-use rss::Channel;
+use pinepods_client::{
+    estimate_duration, format_duration, parse_duration, ClientError, EpisodeFilter,
+    PodcastEpisode, PodcastIndexFeed, PodcastSeasons,
+};
+use rss::{Channel, Item};
+use crate::requests::transcripts::TranscriptLink;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,14 +20,7 @@ pub struct RecentEps {
     pub episodes: Vec<Episode>,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
-#[allow(non_snake_case)]
-pub struct PodcastSearchResult {
-    pub status: Option<String>,              // for PodcastIndex
-    pub resultCount: Option<i32>,            // for iTunes
-    pub feeds: Option<Vec<Podcast>>,         // for PodcastIndex
-    pub results: Option<Vec<ITunesPodcast>>, // for iTunes
-}
+pub use pinepods_client::{ITunesPodcast, PodcastSearchResult, SearchEpisode, SearchRequest};
 
 #[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
 #[allow(non_snake_case)]
@@ -42,9 +45,9 @@ pub struct UnifiedPodcast {
     pub(crate) episodeCount: i32,
 }
 
-// Implement conversions from Podcast and ITunesPodcast to UnifiedPodcast
-impl From<Podcast> for UnifiedPodcast {
-    fn from(podcast: Podcast) -> Self {
+// Implement conversions from PodcastIndexFeed and ITunesPodcast to UnifiedPodcast
+impl From<PodcastIndexFeed> for UnifiedPodcast {
+    fn from(podcast: PodcastIndexFeed) -> Self {
         UnifiedPodcast {
             id: podcast.id,
             title: podcast.title,
@@ -101,49 +104,6 @@ impl From<ITunesPodcast> for UnifiedPodcast {
     }
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
-#[allow(non_snake_case)]
-pub struct Podcast {
-    pub(crate) id: i64,
-    pub(crate) title: String,
-    pub(crate) url: String,
-    #[allow(non_snake_case)]
-    pub(crate) originalUrl: String,
-    pub(crate) link: String,
-    pub(crate) description: String,
-    pub(crate) author: String,
-    #[allow(non_snake_case)]
-    pub(crate) ownerName: String,
-    pub(crate) image: String,
-    pub(crate) artwork: String,
-    #[allow(non_snake_case)]
-    pub(crate) lastUpdateTime: i64,
-    pub(crate) categories: Option<HashMap<String, String>>,
-    pub(crate) explicit: bool,
-    #[allow(non_snake_case)]
-    pub(crate) episodeCount: i32,
-}
-
-#[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
-#[allow(non_snake_case)]
-pub struct ITunesPodcast {
-    pub wrapperType: String,
-    pub kind: String,
-    pub collectionId: i64,
-    pub trackId: i64,
-    pub(crate) artistName: String,
-    pub(crate) trackName: String,
-    pub(crate) collectionViewUrl: String,
-    pub(crate) feedUrl: String,
-    pub(crate) artworkUrl100: String,
-    pub(crate) releaseDate: String,
-    pub(crate) genres: Vec<String>,
-    pub(crate) collectionExplicitness: String,
-    #[allow(non_snake_case)]
-    pub(crate) trackCount: Option<i32>,
-    // add other fields as needed
-}
-
 fn deserialize_string_or_int<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -206,7 +166,7 @@ where
     deserializer.deserialize_option(StringOrIntVisitor)
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Episode {
     #[serde(rename = "Episodetitle")]
     pub title: Option<String>,
@@ -233,70 +193,166 @@ pub struct Episode {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "Episodeid")]
     pub episode_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub chapters_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub transcripts: Vec<TranscriptLink>,
+    // Episodes from the server get these from what it stored of the feed, see
+    // with_stored_extras
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub persons: Vec<Person>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub season: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub season_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub episode_number: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub episode_display: Option<String>,
+    // itunes:episodeType, "full", "trailer" or "bonus"
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub episode_type: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub block: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub location: Option<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub soundbites: Vec<Soundbite>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value: Option<Value>,
+    // Only known for episodes of an added podcast, which come from the server
+    #[serde(rename = "Completed", skip_serializing_if = "Option::is_none", default)]
+    pub completed: Option<bool>,
+    #[serde(
+        rename = "Downloaded",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub downloaded: Option<bool>,
+}
+
+impl Episode {
+    pub fn is_trailer(&self) -> bool {
+        self.episode_type.as_deref() == Some("trailer")
+    }
+
+    pub fn is_bonus(&self) -> bool {
+        self.episode_type.as_deref() == Some("bonus")
+    }
+
+    // "S2 E5", or however the feed asked for the number to be shown
+    pub fn number_label(&self) -> Option<String> {
+        if let Some(display) = &self.episode_display {
+            return Some(display.clone());
+        }
+        let episode = self.episode_number.map(|number| format!("E{}", number));
+        match (self.season, episode) {
+            (Some(season), Some(episode)) => Some(format!("S{} {}", season, episode)),
+            (Some(season), None) => Some(format!("S{}", season)),
+            (None, episode) => episode,
+        }
+    }
+
+    // Episodes loaded from the server have what it kept from the feed's Podcasting 2.0 tags as
+    // JSON, this fills it in
+    pub fn with_stored_extras(mut self, extras: Option<&str>) -> Self {
+        let extras: StoredEpisodeExtras = match extras.map(serde_json::from_str) {
+            Some(Ok(extras)) => extras,
+            _ => return self,
+        };
+        self.transcripts = extras.transcripts;
+        self.persons = extras.persons;
+        self.season_name = extras.season_name;
+        self.episode_display = extras.episode_display;
+        self.block = extras.block;
+        self.location = extras.location;
+        self.soundbites = extras.soundbites;
+        self.value = extras.value;
+        self
+    }
+}
+
+// What the server keeps from an item's Podcasting 2.0 tags, see feed_extras in its
+// app_functions.py
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct StoredEpisodeExtras {
+    transcripts: Vec<TranscriptLink>,
+    persons: Vec<Person>,
+    season_name: Option<String>,
+    episode_display: Option<String>,
+    block: bool,
+    location: Option<Location>,
+    soundbites: Vec<Soundbite>,
+    value: Option<Value>,
+}
+
+// And from the channel's
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct StoredPodcastExtras {
+    itunes_type: Option<String>,
+    locked: bool,
+    locked_owner: Option<String>,
+    persons: Vec<Person>,
+    funding: Vec<Funding>,
+    trailers: Vec<Trailer>,
+    location: Option<Location>,
+    value: Option<Value>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone, Serialize)]
 pub struct PodcastFeedResult {
-    // ... other fields ...
+    // Set when the result was parsed from the feed, episodes from the server come without it
+    #[serde(default)]
+    pub(crate) podcast: Option<PodcastInfo>,
     pub(crate) episodes: Vec<Episode>,
+    // For episodes from the server, the added podcast they belong to and how the server filtered
+    // and ordered them
+    #[serde(skip)]
+    pub(crate) podcast_id: Option<i32>,
+    #[serde(skip)]
+    pub(crate) filter: Option<EpisodeFilter>,
 }
 
 pub async fn call_get_podcast_info(
     podcast_value: &String,
     search_api_url: &Option<String>,
     search_index: &str,
-) -> Result<PodcastSearchResult, anyhow::Error> {
-    let url = if let Some(api_url) = search_api_url {
-        format!("{}?query={}&index={}", api_url, podcast_value, search_index)
-    } else {
-        return Err(anyhow::Error::msg("API URL is not provided"));
-    };
-
-    let response = Request::get(&url)
-        .send()
+) -> Result<PodcastSearchResult, ClientError> {
+    // The search API is its own service, it has no PinePods key to check
+    let api_url = search_api_url.as_deref().ok_or(ClientError::NotFound)?;
+    PinepodsClient::new(api_url, None)
+        .search_podcasts(podcast_value, search_index)
         .await
-        .map_err(|err| anyhow::Error::new(err))?;
-
-    if response.ok() {
-        let response_text = response
-            .text()
-            .await
-            .map_err(|err| anyhow::Error::new(err))?;
-
-        let search_results: PodcastSearchResult = serde_json::from_str(&response_text)?;
-        // web_sys::console::log_1(search_results.clone());
-        Ok(search_results)
-    } else {
-        Err(anyhow::Error::msg(format!(
-            "Failed to fetch podcast info: {}",
-            response.status_text()
-        )))
-    }
 }
 
-pub async fn test_connection(search_api_url: &Option<String>) -> Result<(), Error> {
-    let url = search_api_url
-        .as_ref()
-        .ok_or_else(|| Error::msg("API URL is missing"))?;
-
-    match Request::get(url).send().await {
-        Ok(response) => {
-            if response.ok() {
-                Ok(())
-            } else {
-                Err(Error::msg(format!(
-                    "HTTP error occurred: {}",
-                    response.status_text()
-                )))
-            }
-        }
-        Err(err) => Err(Error::new(err)),
-    }
+pub async fn test_connection(search_api_url: &Option<String>) -> Result<(), ClientError> {
+    let api_url = search_api_url.as_deref().ok_or(ClientError::NotFound)?;
+    PinepodsClient::new(api_url, None).check_search_api().await
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
-pub struct PodcastEpisodesResponse {
-    pub episodes: Vec<Episode>,
+impl From<PodcastEpisode> for Episode {
+    fn from(episode: PodcastEpisode) -> Self {
+        let extras = episode.Feedextras;
+        Episode {
+            title: episode.Episodetitle,
+            description: episode.Episodedescription,
+            pub_date: episode.Episodepubdate,
+            enclosure_url: episode.Episodeurl,
+            artwork: episode.Episodeartwork,
+            guid: Some(episode.Episodeid.to_string()),
+            duration: episode.Episodeduration.map(|duration| duration.to_string()),
+            episode_id: Some(episode.Episodeid),
+            completed: Some(episode.Completed),
+            downloaded: Some(episode.Downloaded),
+            season: episode.Season,
+            episode_number: episode.Episodenumber,
+            episode_type: episode.Episodetype,
+            ..Episode::default()
+        }
+        .with_stored_extras(extras.as_deref())
+    }
 }
 
 pub async fn call_get_podcast_episodes(
@@ -304,128 +360,157 @@ pub async fn call_get_podcast_episodes(
     api_key: &Option<String>,
     user_id: &i32,
     podcast_id: &i32,
-) -> Result<PodcastFeedResult, anyhow::Error> {
-    let url = format!(
-        "{}/api/data/podcast_episodes?user_id={}&podcast_id={}",
-        server_name, user_id, podcast_id
-    );
-    let api_key_ref = api_key
-        .as_deref()
-        .ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
-
-    let response = Request::get(&url)
-        .header("Api-Key", api_key_ref)
-        .send()
+    filter: &EpisodeFilter,
+    page: Page,
+) -> Result<PodcastFeedResult, ClientError> {
+    let episodes = api_client(server_name, api_key)
+        .podcast_episodes(*user_id, *podcast_id, filter, page)
         .await?;
+    Ok(PodcastFeedResult {
+        podcast: None,
+        episodes: episodes.into_iter().map(Episode::from).collect(),
+        podcast_id: Some(*podcast_id),
+        filter: Some(filter.clone()),
+    })
+}
 
-    if !response.ok() {
-        return Err(anyhow::Error::msg(format!(
-            "Failed to get podcast episodes: {}",
-            response.status_text()
-        )));
-    }
-
-    let response_text = response.text().await?;
-
-    let response_data: PodcastEpisodesResponse = serde_json::from_str(&response_text)?;
-
-    let episodes = response_data
-        .episodes
-        .into_iter()
-        .map(|mut episode| {
-            episode.guid = episode
-                .guid
-                .or_else(|| episode.episode_id.map(|id| id.to_string()));
-            episode
-        })
-        .collect::<Vec<_>>();
+pub async fn call_get_podcast_seasons(
+    server_name: &str,
+    api_key: &Option<String>,
+    user_id: i32,
+    podcast_id: i32,
+) -> Result<PodcastSeasons, ClientError> {
+    api_client(server_name, api_key)
+        .podcast_seasons(user_id, podcast_id)
+        .await
+}
 
-    Ok(PodcastFeedResult { episodes })
+// Feeds, chapters and transcripts all come back as text the web app parses itself
+pub(crate) fn parse_error(error: Error) -> ClientError {
+    ClientError::Decode(error.to_string())
 }
 
 pub async fn call_parse_podcast_url(
     server_name: String,
     api_key: &Option<String>,
     podcast_url: &str,
-) -> Result<PodcastFeedResult, Error> {
-    let encoded_podcast_url = urlencoding::encode(podcast_url);
-    let endpoint = format!(
-        "{}/api/data/fetch_podcast_feed?podcast_feed={}",
-        server_name, encoded_podcast_url
-    );
+) -> Result<PodcastFeedResult, ClientError> {
+    let feed = api_client(&server_name, api_key)
+        .fetch_feed(podcast_url, &FeedAuth::default())
+        .await?;
+    parse_feed(&feed).map_err(parse_error)
+}
 
-    let api_key_ref = api_key
-        .as_deref()
-        .ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
+pub fn parse_rss(body: &str) -> Result<PodcastFeedResult, Error> {
+    let channel = Channel::read_from(body.as_bytes())?;
+    let podcast = parse_channel_info(&channel);
+    let episodes = channel
+        .items()
+        .iter()
+        .map(|item| parse_item(item, &podcast.artwork_url))
+        .collect();
+    Ok(PodcastFeedResult {
+        podcast: Some(podcast),
+        episodes,
+        podcast_id: None,
+        filter: None,
+    })
+}
 
-    let request = Request::get(&endpoint)
-        .header("Content-Type", "application/json")
-        .header("Api-Key", api_key_ref)
-        .send()
-        .await?;
+// Feed durations come back as HH:MM:SS. A feed without a usable one gets an estimate from the
+// enclosure size, failing that it stays None and the player takes it from the media.
+pub fn episode_duration(
+    duration: Option<&str>,
+    length: Option<&str>,
+    mime_type: Option<&str>,
+) -> Option<String> {
+    duration
+        .and_then(parse_duration)
+        .filter(|seconds| *seconds > 0)
+        .or_else(|| estimate_duration(length?.trim().parse().ok()?, mime_type))
+        .map(format_duration)
+}
+
+// Everything we keep about a feed item
+fn parse_item(item: &Item, podcast_artwork_url: &Option<String>) -> Episode {
+    let itunes = item.itunes_ext();
+    let extensions = item.extensions();
+    let duration = episode_duration(
+        itunes.and_then(|ext| ext.duration()),
+        item.enclosure().map(|e| e.length()),
+        item.enclosure().map(|e| e.mime_type()),
+    );
+    // The podcast namespace tags win, the itunes ones are the fallback
+    let (season, season_name) = podcast_ns::season(extensions)
+        .map(|(season, name)| (Some(season), name))
+        .unwrap_or_else(|| {
+            let season = itunes
+                .and_then(|ext| ext.season())
+                .and_then(|season| season.trim().parse().ok());
+            (season, None)
+        });
+    let (episode_number, episode_display) = podcast_ns::episode(extensions)
+        .map(|(number, display)| (Some(number), display))
+        .unwrap_or_else(|| {
+            let number = itunes
+                .and_then(|ext| ext.episode())
+                .and_then(|number| number.trim().parse().ok());
+            (number, None)
+        });
 
-    if request.ok() {
-        let response_text = request.text().await?;
-        let channel = Channel::read_from(response_text.as_bytes())?;
-
-        let podcast_artwork_url = channel
-            .image()
-            .map(|img| img.url().to_string())
-            .or_else(|| {
-                channel
-                    .itunes_ext()
-                    .and_then(|ext| ext.image())
-                    .map(|url| url.to_string())
-            });
-
-        let episodes = channel
-            .items()
+    Episode {
+        title: item.title().map(|t| t.to_string()),
+        description: item.description().map(|d| d.to_string()),
+        content: item.content().map(|c| c.to_string()),
+        enclosure_url: item.enclosure().map(|e| e.url().to_string()),
+        enclosure_length: item.enclosure().map(|e| e.length().to_string()),
+        pub_date: item.pub_date().map(|p| p.to_string()),
+        authors: item
+            .author()
+            .map(|a| vec![a.to_string()])
+            .unwrap_or_default(),
+        links: item.link().map(|l| vec![l.to_string()]).unwrap_or_default(),
+        artwork: itunes
+            .and_then(|ext| ext.image())
+            .map(|url| url.to_string())
+            .or_else(|| podcast_artwork_url.clone()),
+        guid: item.guid().map(|g| g.value().to_string()),
+        duration,
+        episode_id: None,
+        chapters_url: podcast_ext(extensions, "chapters")
+            .and_then(|chapters| chapters.attrs().get("url"))
+            .map(|url| url.to_string()),
+        transcripts: podcast_exts(extensions, "transcript")
             .iter()
-            .map(|item| {
-                let duration = item
-                    .itunes_ext()
-                    .and_then(|ext| ext.duration())
-                    .map(|d| d.to_string());
-                if duration.is_none() {
-                    web_sys::console::log_1(
-                        &format!("Missing duration for episode: {:?}", item.title()).into(),
-                    );
-                }
-
-                Episode {
-                    title: item.title().map(|t| t.to_string()),
-                    description: item.description().map(|d| d.to_string()),
-                    content: item.content().map(|c| c.to_string()),
-                    enclosure_url: item.enclosure().map(|e| e.url().to_string()),
-                    enclosure_length: item.enclosure().map(|e| e.length().to_string()),
-                    pub_date: item.pub_date().map(|p| p.to_string()),
-                    authors: item
-                        .author()
-                        .map(|a| vec![a.to_string()])
-                        .unwrap_or_default(),
-                    links: item.link().map(|l| vec![l.to_string()]).unwrap_or_default(),
-                    artwork: item
-                        .itunes_ext()
-                        .and_then(|ext| ext.image())
-                        .map(|url| url.to_string())
-                        .or_else(|| podcast_artwork_url.clone()),
-                    guid: item.guid().map(|g| g.value().to_string()),
-                    duration: Some(duration.unwrap_or_else(|| "00:00:00".to_string())),
-                    episode_id: None,
-                }
+            .filter_map(|transcript| {
+                let attrs = transcript.attrs();
+                Some(TranscriptLink {
+                    url: attrs.get("url")?.to_string(),
+                    mime_type: attrs.get("type").cloned().unwrap_or_default(),
+                    language: attrs.get("language").cloned(),
+                    rel: attrs.get("rel").cloned(),
+                })
             })
-            .collect();
-
-        Ok(PodcastFeedResult { episodes })
-    } else {
-        Err(anyhow::Error::msg(format!(
-            "Failed to fetch podcast feed: HTTP {}",
-            request.status()
-        )))
+            .collect(),
+        persons: podcast_ns::persons(extensions),
+        season,
+        season_name,
+        episode_number,
+        episode_display,
+        episode_type: itunes
+            .and_then(|ext| ext.episode_type())
+            .map(|episode_type| episode_type.trim().to_ascii_lowercase()),
+        block: podcast_ns::is_yes(itunes.and_then(|ext| ext.block())),
+        location: podcast_ns::location(extensions),
+        soundbites: podcast_ns::soundbites(extensions),
+        value: podcast_ns::value(extensions),
+        completed: None,
+        downloaded: None,
     }
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[serde(default)]
 pub struct PodcastInfo {
     pub title: String,
     pub description: String,
@@ -435,131 +520,131 @@ pub struct PodcastInfo {
     pub categories: Vec<String>,
     pub explicit: bool,
     pub episode_count: i32,
+    // podcast:guid, stays the same when the feed moves
+    pub podcast_guid: Option<String>,
+    pub locked: bool,
+    pub locked_owner: Option<String>,
+    // itunes:block, the publisher asked directories not to list the show
+    pub block: bool,
+    // itunes:type, "episodic" or "serial"
+    pub itunes_type: Option<String>,
+    pub persons: Vec<Person>,
+    pub funding: Vec<Funding>,
+    pub trailers: Vec<Trailer>,
+    pub location: Option<Location>,
+    pub value: Option<Value>,
 }
 
-pub async fn call_parse_podcast_channel_info(podcast_url: &str) -> Result<PodcastInfo, Error> {
-    let response_text = Request::get(podcast_url).send().await?.text().await?;
-    let channel = Channel::read_from(response_text.as_bytes())?;
+impl PodcastInfo {
+    // Serial shows are meant to be listened to in order, from the first episode
+    pub fn is_serial(&self) -> bool {
+        self.itunes_type.as_deref() == Some("serial")
+    }
+
+    // For an added podcast, from what the server kept of its feed. Only the Podcasting 2.0
+    // details and the show's type are filled in, the rest is in the podcast's details.
+    pub fn from_stored_extras(extras: Option<&str>) -> Option<PodcastInfo> {
+        let extras: StoredPodcastExtras = serde_json::from_str(extras?).ok()?;
+        Some(PodcastInfo {
+            itunes_type: extras.itunes_type,
+            locked: extras.locked,
+            locked_owner: extras.locked_owner,
+            persons: extras.persons,
+            funding: extras.funding,
+            trailers: extras.trailers,
+            location: extras.location,
+            value: extras.value,
+            ..PodcastInfo::default()
+        })
+    }
 
+    pub fn hosts(&self) -> Vec<&Person> {
+        self.persons
+            .iter()
+            .filter(|person| person.is_host())
+            .collect()
+    }
+}
+
+fn parse_channel_info(channel: &Channel) -> PodcastInfo {
+    let itunes = channel.itunes_ext();
+    let extensions = channel.extensions();
     let podcast_artwork_url = channel
         .image()
         .map(|img| img.url().to_string())
         .or_else(|| {
-            channel
-                .itunes_ext()
+            itunes
                 .and_then(|ext| ext.image())
                 .map(|url| url.to_string())
         });
-    let podcast_title = channel.title().to_string();
-    let podcast_description = channel.description().to_string();
-    let podcast_authors = channel
-        .itunes_ext()
-        .and_then(|ext| ext.author())
-        .map(|a| a.to_string())
-        .unwrap_or_default();
-    let podcast_website = channel.link().to_string();
-    let podcast_categories = channel
-        .categories()
-        .iter()
-        .map(|c| c.name().to_string())
-        .collect::<Vec<_>>();
-    let podcast_explicit = channel.itunes_ext().map_or(false, |ext| {
+    let podcast_explicit = itunes.map_or(false, |ext| {
         ext.explicit()
             .map(|e| e.eq("yes") || e.eq("true"))
             .unwrap_or_default()
     });
+    let (locked, locked_owner) = podcast_ns::locked(extensions);
 
-    let podcast_episode_count = channel.items().len() as i32;
-
-    // Note: Add other podcast-level details as needed.
-
-    let podcast_info = PodcastInfo {
-        title: podcast_title,
-        description: podcast_description,
+    PodcastInfo {
+        title: channel.title().to_string(),
+        description: channel.description().to_string(),
         artwork_url: podcast_artwork_url,
-        author: podcast_authors,
-        website: podcast_website,
-        categories: podcast_categories,
+        author: itunes
+            .and_then(|ext| ext.author())
+            .map(|a| a.to_string())
+            .unwrap_or_default(),
+        website: channel.link().to_string(),
+        categories: channel
+            .categories()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect(),
         explicit: podcast_explicit,
-        episode_count: podcast_episode_count,
-        // Include other fields as necessary.
-    };
+        episode_count: channel.items().len() as i32,
+        podcast_guid: podcast_ns::guid(extensions),
+        locked,
+        locked_owner,
+        block: podcast_ns::is_yes(itunes.and_then(|ext| ext.block())),
+        itunes_type: itunes
+            .and_then(|ext| ext.r#type())
+            .map(|itunes_type| itunes_type.trim().to_ascii_lowercase()),
+        persons: podcast_ns::persons(extensions),
+        funding: podcast_ns::funding(extensions),
+        trailers: podcast_ns::trailers(extensions),
+        location: podcast_ns::location(extensions),
+        value: podcast_ns::value(extensions),
+    }
+}
 
-    Ok(podcast_info)
+// Read through the server, feed hosts rarely let browsers fetch them directly. Imported private
+// feeds often carry their login in the URL, it's passed on in headers instead.
+pub async fn call_parse_podcast_channel_info(
+    server_name: &str,
+    api_key: &str,
+    podcast_url: &str,
+) -> Result<PodcastInfo, ClientError> {
+    let (podcast_url, auth) = FeedAuth::split_url(podcast_url);
+    let feed = api_client(server_name, &Some(api_key.to_string()))
+        .fetch_feed(&podcast_url, &auth)
+        .await?;
+    parse_feed(&feed)
+        .map_err(parse_error)?
+        .podcast
+        .ok_or_else(|| ClientError::Decode("Feed has no podcast details".to_string()))
 }
 
 // In Databases
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct SearchRequest {
-    pub search_term: String,
-    pub user_id: i32,
-}
-
 #[derive(Deserialize, Debug, PartialEq, Clone)]
 pub struct SearchResponse {
     pub data: Vec<SearchEpisode>,
 }
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
-#[allow(non_snake_case)]
-#[serde(rename_all = "lowercase")]
-pub struct SearchEpisode {
-    pub podcastid: i32,
-    pub podcastname: String,
-    pub artworkurl: String,
-    pub author: String,
-    pub categories: String,
-    pub description: String,
-    pub episodecount: i32,
-    pub feedurl: String,
-    pub websiteurl: String,
-    pub explicit: i32,
-    pub userid: i32,
-    pub episodeid: i32,
-    pub episodetitle: String,
-    pub episodedescription: String,
-    pub episodeurl: String,
-    pub episodeartwork: String,
-    pub episodepubdate: String,
-    pub episodeduration: i32,
-    pub listenduration: Option<i32>,
-    pub completed: bool,
-}
-
 pub async fn call_search_database(
     server_name: &String,
     api_key: &Option<String>,
     request_data: &SearchRequest,
-) -> Result<Vec<SearchEpisode>, Error> {
-    let url = format!("{}/api/data/search_data", server_name);
-
-    // Convert Option<String> to Option<&str>
-    let api_key_ref = api_key
-        .as_deref()
-        .ok_or_else(|| anyhow::Error::msg("API key is missing"))?;
-
-    let request_body = serde_json::to_string(request_data)
-        .map_err(|e| anyhow::Error::msg(format!("Serialization Error: {}", e)))?;
-
-    let response = Request::post(&url)
-        .header("Api-Key", api_key_ref)
-        .header("Content-Type", "application/json")
-        .body(request_body)?
-        .send()
-        .await?;
-
-    if !response.ok() {
-        return Err(anyhow::Error::msg(format!(
-            "Failed to search database: {}",
-            response.status_text()
-        )));
-    }
-    // Deserialize the response body into a SearchResponse
-    let search_response: SearchResponse = response.json().await?;
-
-    // Extract the vector of episodes from the SearchResponse
-    let results = search_response.data;
-
-    Ok(results)
+) -> Result<Vec<SearchEpisode>, ClientError> {
+    api_client(server_name, api_key)
+        .search_data(request_data)
+        .await
 }
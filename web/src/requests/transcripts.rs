@@ -0,0 +1,225 @@
+use crate::requests::pod_req::api_client;
+use crate::requests::search_pods::parse_error;
+use anyhow::Error;
+use pinepods_client::{ClientError, FeedAuth};
+use serde::{Deserialize, Serialize};
+
+// A <podcast:transcript> link from a feed item
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TranscriptLink {
+    pub url: String,
+    pub mime_type: String,
+    pub language: Option<String>,
+    pub rel: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TranscriptFormat {
+    Srt,
+    WebVtt,
+    Json,
+}
+
+impl TranscriptLink {
+    pub fn format(&self) -> Option<TranscriptFormat> {
+        let mime_type = self.mime_type.to_ascii_lowercase();
+        let url = self.url.to_ascii_lowercase();
+        if mime_type.contains("json") {
+            Some(TranscriptFormat::Json)
+        } else if mime_type.contains("vtt") || url.ends_with(".vtt") {
+            Some(TranscriptFormat::WebVtt)
+        } else if mime_type.contains("srt") || mime_type.contains("subrip") || url.ends_with(".srt")
+        {
+            Some(TranscriptFormat::Srt)
+        } else {
+            None
+        }
+    }
+}
+
+// Picks the transcript we know how to display, preferring formats that carry speaker names
+pub fn preferred_transcript(links: &[TranscriptLink]) -> Option<&TranscriptLink> {
+    let rank = |link: &TranscriptLink| match link.format() {
+        Some(TranscriptFormat::Json) => 0,
+        Some(TranscriptFormat::WebVtt) => 1,
+        Some(TranscriptFormat::Srt) => 2,
+        None => 3,
+    };
+    links
+        .iter()
+        .filter(|link| link.format().is_some())
+        .min_by_key(|link| rank(link))
+}
+
+// The common model every transcript format is parsed into
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptCue {
+    pub start: f64,
+    pub end: f64,
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+// Parses `HH:MM:SS,mmm`, `HH:MM:SS.mmm` and `MM:SS.mmm` timestamps into seconds
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let timestamp = timestamp.trim().replace(',', ".");
+    let mut seconds = 0.0;
+    for part in timestamp.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+fn parse_timing_line(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    // WebVTT allows cue settings after the end timestamp
+    let end = rest.split_whitespace().next()?;
+    Some((parse_timestamp(start)?, parse_timestamp(end)?))
+}
+
+// Removes markup tags from cue text, returning the WebVTT voice (`<v Speaker>`) if there is one
+fn strip_cue_tags(text: &str) -> (Option<String>, String) {
+    let mut speaker = None;
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('<') {
+        stripped.push_str(&rest[..open]);
+        match rest[open..].find('>') {
+            Some(close) => {
+                let tag = &rest[open + 1..open + close];
+                // `<v Name>` or `<v.class Name>`, the name always follows the first space
+                if tag.starts_with("v ") || tag.starts_with("v.") {
+                    if let Some((_, name)) = tag.split_once(' ') {
+                        speaker.get_or_insert_with(|| name.trim().to_string());
+                    }
+                }
+                rest = &rest[open + close + 1..];
+            }
+            None => {
+                rest = &rest[open..];
+                break;
+            }
+        }
+    }
+    stripped.push_str(rest);
+    (speaker, stripped.trim().to_string())
+}
+
+// SRT and WebVTT share the same block structure, so both go through this
+fn parse_cue_blocks(content: &str) -> Vec<TranscriptCue> {
+    let content = content.replace("\r\n", "\n");
+    let mut cues = Vec::new();
+
+    for block in content.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let (start, end) = match lines.next().and_then(parse_timing_line) {
+            Some(timing) => timing,
+            None => continue, // Headers, NOTE, STYLE and REGION blocks have no timing line
+        };
+        let raw_text = lines.collect::<Vec<_>>().join(" ");
+        let (speaker, text) = strip_cue_tags(&raw_text);
+        if text.is_empty() {
+            continue;
+        }
+        cues.push(TranscriptCue {
+            start,
+            end,
+            speaker,
+            text,
+        });
+    }
+
+    cues
+}
+
+pub fn parse_srt(content: &str) -> Vec<TranscriptCue> {
+    parse_cue_blocks(content)
+}
+
+pub fn parse_vtt(content: &str) -> Vec<TranscriptCue> {
+    parse_cue_blocks(content)
+}
+
+#[derive(Deserialize, Debug)]
+struct JsonTranscript {
+    #[serde(default)]
+    segments: Vec<JsonSegment>,
+}
+
+#[derive(Deserialize, Debug)]
+struct JsonSegment {
+    #[serde(default)]
+    speaker: Option<String>,
+    #[serde(rename = "startTime")]
+    start_time: f64,
+    #[serde(rename = "endTime")]
+    end_time: f64,
+    body: String,
+}
+
+// JSON transcripts are often word-level, so consecutive segments from the same speaker are joined
+// into sentence-sized cues
+pub fn parse_json_transcript(content: &str) -> Result<Vec<TranscriptCue>, Error> {
+    let transcript: JsonTranscript = serde_json::from_str(content)?;
+    let mut cues: Vec<TranscriptCue> = Vec::new();
+
+    for segment in transcript.segments {
+        let body = segment.body.trim();
+        if body.is_empty() {
+            continue;
+        }
+        let continues_cue = cues.last().map_or(false, |cue| {
+            cue.speaker == segment.speaker
+                && !cue.text.ends_with(|c: char| matches!(c, '.' | '?' | '!'))
+        });
+        match cues.last_mut() {
+            Some(cue) if continues_cue => {
+                cue.text.push(' ');
+                cue.text.push_str(body);
+                cue.end = segment.end_time;
+            }
+            _ => cues.push(TranscriptCue {
+                start: segment.start_time,
+                end: segment.end_time,
+                speaker: segment.speaker,
+                text: body.to_string(),
+            }),
+        }
+    }
+
+    Ok(cues)
+}
+
+pub fn parse_transcript(
+    format: TranscriptFormat,
+    content: &str,
+) -> Result<Vec<TranscriptCue>, Error> {
+    match format {
+        TranscriptFormat::Srt => Ok(parse_srt(content)),
+        TranscriptFormat::WebVtt => Ok(parse_vtt(content)),
+        TranscriptFormat::Json => parse_json_transcript(content),
+    }
+}
+
+// Index of the cue being spoken at `time_seconds`, if any
+pub fn current_cue_index(cues: &[TranscriptCue], time_seconds: f64) -> Option<usize> {
+    cues.iter()
+        .rposition(|cue| cue.start <= time_seconds)
+        .filter(|index| time_seconds < cues[*index].end)
+}
+
+pub async fn call_get_transcript(
+    server_name: &str,
+    api_key: &Option<String>,
+    transcript: &TranscriptLink,
+) -> Result<Vec<TranscriptCue>, ClientError> {
+    let format = transcript
+        .format()
+        .ok_or_else(|| ClientError::Decode("Unsupported transcript format".to_string()))?;
+
+    // Transcript hosts rarely send CORS headers, so fetch through the server like feeds
+    let response_text = api_client(server_name, api_key)
+        .fetch_feed(&transcript.url, &FeedAuth::default())
+        .await?;
+    parse_transcript(format, &response_text).map_err(parse_error)
+}